@@ -0,0 +1,189 @@
+//! Derive macros for legion.
+//!
+//! `#[derive(LegionComponent)]` implements `legion::registry::RegisterComponent`, which registers
+//! the component type and its runtime hooks into a `Registry`. The hooks to register are selected
+//! with the `#[legion(...)]` attribute:
+//!
+//!  * `default`: Registers the type's `Default` constructor.
+//!  * `clone`: Registers the type's `Clone` implementation.
+//!  * `hash`: Registers the type's `Hash` implementation.
+//!  * `reflect`: Implements `Reflect` for the type's fields and registers it.
+//!  * `serde`: Registers the type's serde implementations (requires legion's `serialize` feature).
+//!
+//! ```ignore
+//! #[derive(Clone, Default, LegionComponent)]
+//! #[legion(default, clone, reflect)]
+//! struct Position {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! universe.registry.register_component::<Position>();
+//! ```
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::spanned::Spanned;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Fields;
+use syn::Index;
+use syn::LitStr;
+use syn::Meta;
+use syn::NestedMeta;
+
+#[derive(Default)]
+struct Options {
+    default: bool,
+    clone: bool,
+    hash: bool,
+    reflect: bool,
+    serde: bool,
+}
+
+#[proc_macro_derive(LegionComponent, attributes(legion))]
+pub fn derive_legion_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let options = parse_options(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut registrations = vec![quote! { registry.register::<Self>(); }];
+    if options.default {
+        registrations.push(quote! { registry.register_with_default::<Self>(); });
+    }
+    if options.clone {
+        registrations.push(quote! { registry.register_clone::<Self>(); });
+    }
+    if options.hash {
+        registrations.push(quote! { registry.register_hash::<Self>(); });
+    }
+    if options.reflect {
+        registrations.push(quote! { registry.register_reflect::<Self>(); });
+    }
+    if options.serde {
+        registrations.push(quote! { registry.register_serde::<Self>(); });
+    }
+
+    let reflect = if options.reflect {
+        expand_reflect(input)?
+    } else {
+        TokenStream2::new()
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::legion::registry::RegisterComponent for #name #ty_generics #where_clause {
+            fn register(registry: &mut ::legion::registry::Registry) {
+                #(#registrations)*
+            }
+        }
+
+        #reflect
+    })
+}
+
+fn parse_options(input: &DeriveInput) -> Result<Options, Error> {
+    let mut options = Options::default();
+    for attr in input.attrs.iter().filter(|a| a.path.is_ident("legion")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[legion(...)]`")),
+        };
+
+        for nested in list.nested.iter() {
+            let flag = match nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.get_ident(),
+                _ => None,
+            };
+            match flag.map(|ident| ident.to_string()).as_deref() {
+                Some("default") => options.default = true,
+                Some("clone") => options.clone = true,
+                Some("hash") => options.hash = true,
+                Some("reflect") => options.reflect = true,
+                Some("serde") => options.serde = true,
+                _ => {
+                    return Err(Error::new(
+                        nested.span(),
+                        "unknown legion option, expected one of `default`, `clone`, `hash`, `reflect` or `serde`",
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+fn expand_reflect(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "`#[legion(reflect)]` is only supported on structs",
+            ))
+        }
+    };
+
+    let (names, accessors): (Vec<_>, Vec<_>) = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                (LitStr::new(&ident.to_string(), ident.span()), quote! { #ident })
+            })
+            .unzip(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let index = Index {
+                    index: i as u32,
+                    span: f.span(),
+                };
+                (LitStr::new(&i.to_string(), f.span()), quote! { #index })
+            })
+            .unzip(),
+        Fields::Unit => (Vec::new(), Vec::new()),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::legion::reflect::Reflect for #name #ty_generics #where_clause {
+            fn field_names(&self) -> Vec<&'static str> { vec![#(#names),*] }
+
+            fn field(&self, name: &str) -> Option<&dyn ::legion::reflect::Reflect> {
+                match name {
+                    #(#names => Some(&self.#accessors),)*
+                    _ => None,
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn ::legion::reflect::Reflect> {
+                match name {
+                    #(#names => Some(&mut self.#accessors),)*
+                    _ => None,
+                }
+            }
+
+            fn as_any(&self) -> &dyn ::std::any::Any { self }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any { self }
+        }
+    })
+}
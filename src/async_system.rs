@@ -0,0 +1,329 @@
+//! Systems driven by futures.
+//!
+//! An async system is built with `SystemBuilder::build_async`. Its closure returns a future which
+//! may await IO (asset loads, network requests and so on) across several executions of the
+//! schedule. The future is polled once each time the system runs; while it is pending, the system
+//! resumes it on its next run rather than blocking the stage.
+//!
+//! The future may only touch the ECS through the `AsyncWorld` handle it was given, and only from
+//! within `AsyncWorld::with`. The world is lent to the handle for the duration of a single poll,
+//! so all ECS access happens while the scheduler considers the system to be running, and is
+//! validated against the system's declared resource and component access.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! # #[derive(Default)]
+//! # struct Score(usize);
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! # world.resources.insert(Score::default());
+//! let system = SystemBuilder::new("load")
+//!     .write_resource::<Score>()
+//!     .build_async(|handle| async move {
+//!         // await an asset load here...
+//!         handle.with(|_, _, score| score.0 += 1);
+//!     });
+//! ```
+
+use crate::borrow::{AtomicRefCell, Exclusive, RefMut};
+use crate::command::CommandBuffer;
+use crate::resource::{ResourceSet, ResourceTypeId};
+use crate::schedule::{ArchetypeAccess, Runnable};
+use crate::storage::ComponentTypeId;
+use crate::system::{PreparedWorld, SystemAccess, SystemId};
+use crate::world::World;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use tracing::{debug, span, Level};
+
+type BoxedFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+// Wrapper type for the system's in-flight future.
+struct Pending(Option<BoxedFuture>);
+// This is safe because systems are never called from 2 threads simultaneously.
+unsafe impl Sync for Pending {}
+
+/// Pointers to the data lent to an async system for the duration of a poll.
+struct PollScope<R: ResourceSet> {
+    // the poll which lent the data
+    generation: u64,
+    commands: *mut CommandBuffer,
+    world: *mut PreparedWorld,
+    resources: *mut R::PreparedResources,
+}
+
+// This is safe because the scope is only populated while the owning system is running, and the
+// system does not finish running until every `AsyncWorld::with` call using the scope returns.
+unsafe impl<R: ResourceSet> Send for PollScope<R> {}
+
+struct ScopeState<R: ResourceSet> {
+    // incremented each time a poll ends
+    generation: u64,
+    scope: Option<PollScope<R>>,
+    // set while a `with` call has taken the scope
+    in_use: bool,
+}
+
+/// The data lent to an async system's handles, shared between the system and its handles.
+struct SharedScope<R: ResourceSet> {
+    state: Mutex<ScopeState<R>>,
+    released: Condvar,
+}
+
+impl<R: ResourceSet> SharedScope<R> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(ScopeState {
+                generation: 0,
+                scope: None,
+                in_use: false,
+            }),
+            released: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<ScopeState<R>> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+// Returns a scope taken by `AsyncWorld::with`, even if the caller's closure panics.
+struct TakenScope<'a, R: ResourceSet> {
+    shared: &'a SharedScope<R>,
+    scope: Option<PollScope<R>>,
+}
+
+impl<'a, R: ResourceSet> Drop for TakenScope<'a, R> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        let scope = self.scope.take().unwrap();
+        // a scope from an earlier poll points at data which no longer exists
+        if scope.generation == state.generation {
+            state.scope = Some(scope);
+        }
+        state.in_use = false;
+        self.shared.released.notify_all();
+    }
+}
+
+// Withdraws the scope lent by `AsyncSystem::run` before the data it points to is dropped, even
+// if the system's future panics.
+struct LentScope<'a, R: ResourceSet> {
+    shared: &'a SharedScope<R>,
+}
+
+impl<'a, R: ResourceSet> Drop for LentScope<'a, R> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        while state.in_use {
+            state = self
+                .shared
+                .released
+                .wait(state)
+                .unwrap_or_else(|err| err.into_inner());
+        }
+        state.scope = None;
+        state.generation += 1;
+    }
+}
+
+/// Handle through which an async system's future accesses the world.
+pub struct AsyncWorld<R: ResourceSet> {
+    scope: Arc<SharedScope<R>>,
+}
+
+impl<R: ResourceSet> Clone for AsyncWorld<R> {
+    fn clone(&self) -> Self {
+        Self {
+            scope: self.scope.clone(),
+        }
+    }
+}
+
+impl<R: ResourceSet> AsyncWorld<R> {
+    /// Returns `true` if the system owning this handle is currently being polled.
+    pub fn is_available(&self) -> bool { self.scope.lock().scope.is_some() }
+
+    /// Runs `f` with the system's command buffer, world and resources.
+    ///
+    /// The handle may be used from another thread while the system is being polled, in which
+    /// case the system does not finish running until `f` returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a poll of the system's future, such as from a spawned task,
+    /// or while another call to `with` is in progress, such as re-entrantly from within `f`.
+    /// Component access made through the world panics if it was not declared on the system.
+    pub fn with<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut CommandBuffer, &mut PreparedWorld, &mut R::PreparedResources) -> T,
+    {
+        let taken = {
+            let mut state = self.scope.lock();
+            let scope = state
+                .scope
+                .take()
+                .expect("Attempted to access the world outside of the system's poll section");
+            state.in_use = true;
+            TakenScope {
+                shared: &self.scope,
+                scope: Some(scope),
+            }
+        };
+
+        let scope = taken.scope.as_ref().unwrap();
+        unsafe {
+            f(
+                &mut *scope.commands,
+                &mut *scope.world,
+                &mut *scope.resources,
+            )
+        }
+    }
+}
+
+/// A system whose body is a future, resumed each time the system runs.
+pub struct AsyncSystem<R: ResourceSet, F> {
+    name: SystemId,
+    resources: R,
+    run_fn: AtomicRefCell<F>,
+    pending: AtomicRefCell<Pending>,
+    scope: Arc<SharedScope<R>>,
+    archetypes: ArchetypeAccess,
+    access: SystemAccess,
+    command_buffer: AtomicRefCell<CommandBuffer>,
+}
+
+impl<R, F, Fut> AsyncSystem<R, F>
+where
+    R: ResourceSet,
+    F: FnMut(AsyncWorld<R>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    pub(crate) fn new(name: SystemId, resources: R, access: SystemAccess, run_fn: F) -> Self {
+        Self {
+            name,
+            resources,
+            run_fn: AtomicRefCell::new(run_fn),
+            pending: AtomicRefCell::new(Pending(None)),
+            scope: Arc::new(SharedScope::new()),
+            archetypes: ArchetypeAccess::All,
+            access,
+            command_buffer: AtomicRefCell::new(CommandBuffer::default()),
+        }
+    }
+
+    /// Returns `true` if the system has a future which has not yet completed.
+    pub fn is_pending(&self) -> bool { self.pending.get().0.is_some() }
+}
+
+impl<R, F, Fut> Runnable for AsyncSystem<R, F>
+where
+    R: ResourceSet,
+    F: FnMut(AsyncWorld<R>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn name(&self) -> &SystemId { &self.name }
+
+    fn reads(&self) -> (&[ResourceTypeId], &[ComponentTypeId]) {
+        (&self.access.resources.reads, &self.access.components.reads)
+    }
+
+    fn writes(&self) -> (&[ResourceTypeId], &[ComponentTypeId]) {
+        (
+            &self.access.resources.writes,
+            &self.access.components.writes,
+        )
+    }
+
+    fn prepare(&mut self, _: &World) {}
+
+    fn accesses_archetypes(&self) -> &ArchetypeAccess { &self.archetypes }
+
+    fn command_buffer_mut(&self) -> RefMut<Exclusive, CommandBuffer> {
+        self.command_buffer.get_mut()
+    }
+
+    fn run(&self, world: &World) {
+        let span = span!(Level::INFO, "AsyncSystem", system = %self.name);
+        let _guard = span.enter();
+
+        let mut pending = self.pending.get_mut();
+        let pending = &mut pending.0;
+        if pending.is_none() {
+            debug!("Starting");
+            let handle = AsyncWorld {
+                scope: self.scope.clone(),
+            };
+            *pending = Some(Box::pin((self.run_fn.get_mut())(handle)));
+        }
+
+        let mut resources = R::fetch(&world.resources);
+        let mut world_shim =
+            unsafe { PreparedWorld::new(world, &self.access.components, &self.archetypes) };
+        let mut commands = self.command_buffer.get_mut();
+
+        {
+            let mut state = self.scope.lock();
+            state.scope = Some(PollScope {
+                generation: state.generation,
+                commands: &mut *commands as *mut CommandBuffer,
+                world: &mut world_shim as *mut PreparedWorld,
+                resources: &mut resources as *mut R::PreparedResources,
+            });
+        }
+        // declared after the lent data, so that it is dropped first
+        let lent = LentScope {
+            shared: &self.scope,
+        };
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let poll = pending.as_mut().unwrap().as_mut().poll(&mut cx);
+
+        drop(lent);
+
+        if let Poll::Ready(()) = poll {
+            debug!("Completed");
+            *pending = None;
+        }
+    }
+
+    fn dispose(self: Box<Self>, _: &mut World) {}
+}
+
+/// A future which returns `Pending` once, allowing the runtime polling it to run other tasks.
+pub(crate) struct YieldNow(bool);
+
+impl YieldNow {
+    pub(crate) fn new() -> Self { YieldNow(false) }
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+// Async systems are re-polled each time they run, so their futures do not need to be woken.
+pub(crate) fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker { RawWaker::new(std::ptr::null(), &VTABLE) }
+    fn noop(_: *const ()) {}
+
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+}
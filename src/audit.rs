@@ -0,0 +1,256 @@
+//! Records the component types accessed within named scopes, such as system executions.
+//!
+//! This module is only available with the `access-audit` feature enabled. While a scope is
+//! active on a thread, every component slice borrowed on that thread (via queries or
+//! `World::get_component`) is recorded as either a read or a write. Systems automatically open
+//! a scope named after the system for the duration of their execution, and will log a warning
+//! if they access components which they did not declare.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! # use legion::audit;
+//! # #[derive(Copy, Clone, Debug, PartialEq)]
+//! # struct Position(f32);
+//! let universe = Universe::new();
+//! let mut world = universe.create_world();
+//! world.insert((), vec![(Position(0.0),)]);
+//!
+//! {
+//!     let _scope = audit::scope("movement");
+//!     let mut query = Write::<Position>::query();
+//!     for mut pos in query.iter(&mut world) {
+//!         pos.0 += 1.0;
+//!     }
+//! }
+//!
+//! let report = audit::report();
+//! println!("{}", report);
+//! ```
+
+use crate::storage::Component;
+use crate::storage::ComponentTypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use tracing::warn;
+
+thread_local! {
+    static SCOPES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+static ACCESSES: Mutex<Option<HashMap<String, ScopeAccess>>> = Mutex::new(None);
+
+/// The component types accessed within a scope.
+#[derive(Debug, Default, Clone)]
+pub struct ScopeAccess {
+    reads: HashMap<ComponentTypeId, &'static str>,
+    writes: HashMap<ComponentTypeId, &'static str>,
+}
+
+impl ScopeAccess {
+    /// Gets an iterator over the component types read within the scope.
+    pub fn reads(&self) -> impl Iterator<Item = (ComponentTypeId, &'static str)> + '_ {
+        self.reads.iter().map(|(t, n)| (*t, *n))
+    }
+
+    /// Gets an iterator over the component types written within the scope.
+    pub fn writes(&self) -> impl Iterator<Item = (ComponentTypeId, &'static str)> + '_ {
+        self.writes.iter().map(|(t, n)| (*t, *n))
+    }
+
+    /// Determines if the scope read the given component type.
+    pub fn is_read(&self, type_id: ComponentTypeId) -> bool { self.reads.contains_key(&type_id) }
+
+    /// Determines if the scope wrote the given component type.
+    pub fn is_written(&self, type_id: ComponentTypeId) -> bool {
+        self.writes.contains_key(&type_id)
+    }
+
+    /// Gets the names of the component types which cannot be accessed by both scopes
+    /// concurrently, as at least one of the scopes writes to them.
+    pub fn conflicts(&self, other: &ScopeAccess) -> Vec<&'static str> {
+        let mut conflicts = self
+            .writes
+            .iter()
+            .filter(|(t, _)| other.is_read(**t) || other.is_written(**t))
+            .chain(other.writes.iter().filter(|(t, _)| self.is_read(**t)))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        conflicts.sort();
+        conflicts.dedup();
+        conflicts
+    }
+}
+
+/// A snapshot of all recorded component accesses.
+#[derive(Debug, Default, Clone)]
+pub struct AccessReport {
+    scopes: HashMap<String, ScopeAccess>,
+}
+
+impl AccessReport {
+    /// Gets the recorded accesses of the named scope.
+    pub fn get(&self, scope: &str) -> Option<&ScopeAccess> { self.scopes.get(scope) }
+
+    /// Gets an iterator over all recorded scopes.
+    pub fn scopes(&self) -> impl Iterator<Item = (&str, &ScopeAccess)> {
+        self.scopes.iter().map(|(name, access)| (name.as_str(), access))
+    }
+
+    /// Gets the names of the component types on which the two named scopes conflict.
+    pub fn conflicts(&self, a: &str, b: &str) -> Vec<&'static str> {
+        match (self.get(a), self.get(b)) {
+            (Some(a), Some(b)) => a.conflicts(b),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Display for AccessReport {
+    /// Writes a conflict matrix, where each cell contains the number of component types on
+    /// which the row and column scopes conflict.
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let mut names = self.scopes.keys().collect::<Vec<_>>();
+        names.sort();
+
+        let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+        write!(f, "{:width$}", "", width = width)?;
+        for i in 0..names.len() {
+            write!(f, " {:>3}", i)?;
+        }
+        writeln!(f)?;
+
+        for (i, a) in names.iter().enumerate() {
+            write!(f, "{:width$}", a, width = width)?;
+            for b in names.iter() {
+                match self.conflicts(a, b).len() {
+                    0 => write!(f, " {:>3}", "-")?,
+                    n => write!(f, " {:>3}", n)?,
+                }
+            }
+            writeln!(f, "  ({})", i)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps an audit scope active until dropped.
+pub struct ScopeGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPES.with(|scopes| {
+            scopes.borrow_mut().pop();
+        });
+    }
+}
+
+/// Begins recording component accesses made on the current thread under the given scope name.
+///
+/// Scopes may be nested, in which case accesses are recorded against the innermost scope.
+pub fn scope<S: Into<String>>(name: S) -> ScopeGuard {
+    SCOPES.with(|scopes| scopes.borrow_mut().push(name.into()));
+    ScopeGuard {
+        _not_send: PhantomData,
+    }
+}
+
+/// Gets a snapshot of all component accesses recorded so far.
+pub fn report() -> AccessReport {
+    AccessReport {
+        scopes: ACCESSES.lock().unwrap().clone().unwrap_or_default(),
+    }
+}
+
+/// Clears all recorded component accesses.
+pub fn reset() { *ACCESSES.lock().unwrap() = None; }
+
+/// Records an access of component type `T` against the current scope, if there is one.
+pub(crate) fn record<T: Component>(write: bool) {
+    SCOPES.with(|scopes| {
+        if let Some(scope) = scopes.borrow().last() {
+            let mut accesses = ACCESSES.lock().unwrap();
+            let access = accesses
+                .get_or_insert_with(HashMap::new)
+                .entry(scope.clone())
+                .or_default();
+            let set = if write {
+                &mut access.writes
+            } else {
+                &mut access.reads
+            };
+            set.insert(ComponentTypeId::of::<T>(), std::any::type_name::<T>());
+        }
+    });
+}
+
+/// Logs a warning for each component type accessed within `scope` which is not
+/// included in the declared access.
+pub(crate) fn validate(
+    scope: &str,
+    declared_reads: &[ComponentTypeId],
+    declared_writes: &[ComponentTypeId],
+) {
+    let accesses = ACCESSES.lock().unwrap();
+    if let Some(access) = accesses.as_ref().and_then(|a| a.get(scope)) {
+        for (type_id, name) in access.reads() {
+            if !declared_reads.contains(&type_id) && !declared_writes.contains(&type_id) {
+                warn!(scope, component = name, "Undeclared component read");
+            }
+        }
+        for (type_id, name) in access.writes() {
+            if !declared_writes.contains(&type_id) {
+                warn!(scope, component = name, "Undeclared component write");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32);
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Vel(f32);
+
+    #[test]
+    fn record_conflicts() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.insert((), vec![(Pos(0.), Vel(1.))]);
+
+        {
+            let _scope = scope("audit_test_movement");
+            let mut query = <(Write<Pos>, Read<Vel>)>::query();
+            for (mut pos, vel) in query.iter(&mut world) {
+                pos.0 += vel.0;
+            }
+        }
+
+        {
+            let _scope = scope("audit_test_render");
+            let mut query = Read::<Pos>::query();
+            assert_eq!(1, query.iter(&mut world).count());
+        }
+
+        let report = report();
+        let movement = report.get("audit_test_movement").unwrap();
+        assert!(movement.is_written(ComponentTypeId::of::<Pos>()));
+        assert!(movement.is_read(ComponentTypeId::of::<Vel>()));
+
+        let conflicts = report.conflicts("audit_test_movement", "audit_test_render");
+        assert_eq!(vec![std::any::type_name::<Pos>()], conflicts);
+        assert!(report.to_string().contains("audit_test_render"));
+    }
+}
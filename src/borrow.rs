@@ -8,6 +8,10 @@ use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::atomic::AtomicIsize;
 
+// borrow states at or below this value are held by disjoint exclusive borrows
+#[cfg(debug_assertions)]
+const DISJOINT_BORROW: isize = isize::MIN / 2;
+
 /// A `RefCell` implementation which is thread safe. This type performs all the standard runtime
 /// borrow checking which would be familiar from using `RefCell`.
 ///
@@ -49,6 +53,14 @@ impl<T> AtomicRefCell<T> {
     /// Unwrap the value from the RefCell and kill it, returning the value.
     pub fn into_inner(self) -> T { self.value.into_inner() }
 
+    /// Gets a reference to the value without borrowing it.
+    ///
+    /// # Safety
+    ///
+    /// The value must not be modified while the reference is live.
+    #[inline(always)]
+    pub(crate) unsafe fn get_unchecked(&self) -> &T { &*self.value.get() }
+
     /// Retrieve an immutable `Ref` wrapped reference of `&T`. This is the safe version of `get`
     /// providing an error result on failure.
     ///
@@ -159,6 +171,64 @@ impl<T> AtomicRefCell<T> {
             &mut *self.value.get()
         }))
     }
+
+    /// Retrieves a reference to the value along with an exclusive borrow, which may be held
+    /// concurrently with other disjoint borrows of the same value but with no other borrows.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the value is already borrowed by anything other than a disjoint borrow.
+    ///
+    /// # Safety
+    ///
+    /// Each disjoint borrow must only be used to access parts of the value, or of the data it
+    /// points to, which are not accessed by any other disjoint borrow.
+    #[cfg(debug_assertions)]
+    pub(crate) unsafe fn try_get_disjoint<'a>(
+        &'a self,
+    ) -> Result<(Exclusive<'a>, &'a T), &'static str> {
+        loop {
+            let state = self.borrow_state.load(std::sync::atomic::Ordering::SeqCst);
+            let next = match state {
+                0 => DISJOINT_BORROW - 1,
+                x if x <= DISJOINT_BORROW => x - 1,
+                x if x < 0 => return Err("resource already borrowed as mutable"),
+                _ => return Err("resource already borrowed as immutable"),
+            };
+
+            if self
+                .borrow_state
+                .compare_exchange(
+                    state,
+                    next,
+                    std::sync::atomic::Ordering::SeqCst,
+                    std::sync::atomic::Ordering::SeqCst,
+                )
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        Ok((Exclusive::new(&self.borrow_state), &*self.value.get()))
+    }
+
+    /// Retrieves a reference to the value along with an exclusive borrow, which may be held
+    /// concurrently with other disjoint borrows of the same value but with no other borrows.
+    ///
+    /// # Safety
+    ///
+    /// Each disjoint borrow must only be used to access parts of the value, or of the data it
+    /// points to, which are not accessed by any other disjoint borrow.
+    ///
+    /// This release version of this function does not perform runtime borrow checking.
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    pub(crate) unsafe fn try_get_disjoint<'a>(
+        &'a self,
+    ) -> Result<(Exclusive<'a>, &'a T), &'static str> {
+        Ok((Exclusive::new(&self.borrow_state), &*self.value.get()))
+    }
 }
 
 unsafe impl<T: Send> Send for AtomicRefCell<T> {}
@@ -226,7 +296,19 @@ impl<'a> Exclusive<'a> {
 
 #[cfg(debug_assertions)]
 impl<'a> Drop for Exclusive<'a> {
-    fn drop(&mut self) { self.state.fetch_add(1, std::sync::atomic::Ordering::SeqCst); }
+    fn drop(&mut self) {
+        let state = self.state.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+
+        // the last of a group of disjoint borrows releases the value
+        if state == DISJOINT_BORROW {
+            let _ = self.state.compare_exchange(
+                DISJOINT_BORROW,
+                0,
+                std::sync::atomic::Ordering::SeqCst,
+                std::sync::atomic::Ordering::SeqCst,
+            );
+        }
+    }
 }
 
 impl<'a> UnsafeClone for Exclusive<'a> {
@@ -8,7 +8,14 @@ use crate::{
 use bit_set::BitSet;
 
 use derivative::Derivative;
-use std::{marker::PhantomData, sync::Arc};
+use parking_lot::Mutex;
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 #[cfg(feature = "par-schedule")]
 use crossbeam::queue::SegQueue;
@@ -337,6 +344,79 @@ impl CommandBuffer {
     }
 }
 
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A queue of command buffers which any thread may push into, such that threads outside of
+/// the system executor can request structural changes to a world.
+///
+/// The queue is split into shards, each protected by its own lock, and each thread always
+/// pushes into the same shard. Buffers pushed from a single thread are therefore written in the
+/// order they were pushed, but no ordering is guaranteed between buffers pushed from different
+/// threads.
+///
+/// Each world owns a queue, retrieved with `World::command_queue`, which is drained by
+/// `World::maintain`. Handles are cheap to clone and may be moved to other threads.
+#[derive(Clone)]
+pub struct CommandQueue {
+    shards: Arc<[Mutex<Vec<CommandBuffer>>]>,
+}
+
+impl CommandQueue {
+    /// Creates a new queue with the given number of shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn new(shards: usize) -> Self {
+        assert!(shards > 0, "command queue requires at least one shard");
+        CommandQueue {
+            shards: (0..shards).map(|_| Mutex::new(Vec::new())).collect(),
+        }
+    }
+
+    /// Pushes a command buffer into the queue.
+    pub fn push(&self, buffer: CommandBuffer) {
+        let shard = THREAD_SHARD.with(|shard| *shard) % self.shards.len();
+        self.shards[shard].lock().push(buffer);
+    }
+
+    /// Gets the number of command buffers in the queue.
+    pub fn len(&self) -> usize { self.shards.iter().map(|shard| shard.lock().len()).sum() }
+
+    /// Determines if the queue is empty.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Removes all command buffers from the queue, in shard order.
+    pub(crate) fn drain(&self) -> Vec<CommandBuffer> {
+        self.shards
+            .iter()
+            .flat_map(|shard| std::mem::take(&mut *shard.lock()))
+            .collect()
+    }
+}
+
+impl Default for CommandQueue {
+    fn default() -> Self {
+        let shards = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        CommandQueue::new(shards)
+    }
+}
+
+impl std::fmt::Debug for CommandQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("CommandQueue")
+            .field("shards", &self.shards.len())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -383,4 +463,51 @@ mod tests {
 
         assert_eq!(components_len, count);
     }
+
+    #[test]
+    fn command_queue_from_threads() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        let queue = world.command_queue();
+
+        let handles = (0..4)
+            .map(|i| {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    for j in 0..10 {
+                        let commands = CommandBuffer::default();
+                        commands.insert((), vec![(Pos(i as f32, j as f32, 0.),)]);
+                        queue.push(commands);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(40, queue.len());
+        let mut query = Read::<Pos>::query();
+        assert_eq!(0, query.iter(&mut world).count());
+
+        world.maintain();
+        assert!(queue.is_empty());
+        assert_eq!(40, query.iter(&mut world).count());
+
+        // buffers pushed from one thread are written in order
+        let positions = query
+            .iter(&mut world)
+            .map(|p| (p.0, p.1))
+            .collect::<Vec<_>>();
+        for i in 0..4 {
+            let order = positions
+                .iter()
+                .filter(|(x, _)| *x == i as f32)
+                .map(|(_, y)| *y)
+                .collect::<Vec<_>>();
+            assert_eq!((0..10).map(|j| j as f32).collect::<Vec<_>>(), order);
+        }
+    }
 }
@@ -0,0 +1,425 @@
+//! Diagnostics for detecting archetype explosion.
+//!
+//! Every distinct combination of component types creates an archetype, and every distinct
+//! combination of tag values within an archetype creates a chunk set with its own chunks. Tags
+//! which take many different values, such as a per-entity ID stored as a tag, therefore split
+//! entities across many sparsely filled chunks and defeat the cache locality chunks provide.
+//!
+//! `diagnose` inspects a world against a set of `Thresholds` and reports any archetypes which
+//! exceed them. An `ArchetypeMonitor` additionally records the world's archetype and chunk set
+//! counts each time it is sampled, so that growth over time can be observed.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! use legion::diagnostics::ArchetypeWarning;
+//! use legion::diagnostics::Thresholds;
+//!
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! struct Position(f32);
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct NetworkId(u32);
+//!
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! for i in 0..100 {
+//!     world.insert((NetworkId(i),), vec![(Position(0.0),)]);
+//! }
+//!
+//! let thresholds = Thresholds {
+//!     max_tag_values: 16,
+//!     ..Thresholds::default()
+//! };
+//! let diagnostics = legion::diagnostics::diagnose(&world, &thresholds);
+//! assert!(diagnostics.warnings.iter().any(|warning| match warning {
+//!     ArchetypeWarning::HighTagCardinality { distinct_values, .. } => *distinct_values == 100,
+//!     _ => false,
+//! }));
+//!
+//! for warning in &diagnostics.warnings {
+//!     println!("{}", warning);
+//! }
+//! ```
+use crate::storage::ArchetypeData;
+use crate::storage::ArchetypeId;
+use crate::world::World;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The limits above (or below) which `diagnose` reports a warning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    /// The maximum number of archetypes in the world.
+    pub max_archetypes: usize,
+    /// The maximum number of chunk sets in a single archetype.
+    pub max_chunksets: usize,
+    /// The maximum number of distinct values a single tag type may take within an archetype.
+    pub max_tag_values: usize,
+    /// The minimum average fraction of chunk capacity which is in use, for archetypes which
+    /// occupy more than one chunk.
+    pub min_occupancy: f32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            max_archetypes: 256,
+            max_chunksets: 64,
+            max_tag_values: 32,
+            min_occupancy: 0.25,
+        }
+    }
+}
+
+/// A problem found by `diagnose`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArchetypeWarning {
+    /// The world contains more archetypes than `Thresholds::max_archetypes`.
+    TooManyArchetypes { count: usize },
+    /// An archetype contains more chunk sets than `Thresholds::max_chunksets`.
+    TooManyChunksets {
+        archetype: ArchetypeId,
+        count: usize,
+    },
+    /// A tag type takes more distinct values within an archetype than
+    /// `Thresholds::max_tag_values`.
+    HighTagCardinality {
+        archetype: ArchetypeId,
+        tag: &'static str,
+        distinct_values: usize,
+    },
+    /// The chunks of an archetype are, on average, less full than `Thresholds::min_occupancy`.
+    LowOccupancy {
+        archetype: ArchetypeId,
+        occupancy: f32,
+    },
+}
+
+impl Display for ArchetypeWarning {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ArchetypeWarning::TooManyArchetypes { count } => write!(
+                f,
+                "world contains {} archetypes; consider whether rarely used component types could \
+                 be stored together",
+                count
+            ),
+            ArchetypeWarning::TooManyChunksets { archetype, count } => write!(
+                f,
+                "archetype {:?} contains {} chunk sets; its tags may take too many distinct values",
+                archetype, count
+            ),
+            ArchetypeWarning::HighTagCardinality {
+                archetype,
+                tag,
+                distinct_values,
+            } => write!(
+                f,
+                "tag `{}` takes {} distinct values in archetype {:?}; consider storing it as a \
+                 component instead",
+                tag, distinct_values, archetype
+            ),
+            ArchetypeWarning::LowOccupancy {
+                archetype,
+                occupancy,
+            } => write!(
+                f,
+                "chunks in archetype {:?} are only {:.0}% occupied on average",
+                archetype,
+                occupancy * 100.0
+            ),
+        }
+    }
+}
+
+/// The number of distinct values taken by a tag type within an archetype.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagCardinality {
+    /// The archetype containing the tag.
+    pub archetype: ArchetypeId,
+    /// The name of the tag type.
+    pub tag: &'static str,
+    /// The number of distinct values of the tag across the archetype's chunk sets.
+    pub distinct_values: usize,
+}
+
+/// The results of `diagnose`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchetypeDiagnostics {
+    /// The number of archetypes in the world.
+    pub archetypes: usize,
+    /// The total number of chunk sets across all archetypes.
+    pub chunksets: usize,
+    /// The average fraction of capacity in use across all chunks which hold entities.
+    pub average_occupancy: f32,
+    /// The number of distinct values taken by each tag type within each archetype, in
+    /// descending order.
+    pub tag_cardinality: Vec<TagCardinality>,
+    /// The thresholds which were exceeded.
+    pub warnings: Vec<ArchetypeWarning>,
+}
+
+/// Inspects the layout of a world, reporting where it exceeds the given thresholds.
+pub fn diagnose(world: &World, thresholds: &Thresholds) -> ArchetypeDiagnostics {
+    let archetypes = world.storage().archetypes();
+    let mut warnings = Vec::new();
+    let mut tag_cardinality = Vec::new();
+    let mut chunksets = 0;
+    let mut occupancy_sum = 0.0;
+    let mut chunk_count = 0;
+
+    if archetypes.len() > thresholds.max_archetypes {
+        warnings.push(ArchetypeWarning::TooManyArchetypes {
+            count: archetypes.len(),
+        });
+    }
+
+    for archetype in archetypes {
+        chunksets += archetype.len();
+        if archetype.len() > thresholds.max_chunksets {
+            warnings.push(ArchetypeWarning::TooManyChunksets {
+                archetype: archetype.id(),
+                count: archetype.len(),
+            });
+        }
+
+        for cardinality in tag_cardinalities(archetype) {
+            if cardinality.distinct_values > thresholds.max_tag_values {
+                warnings.push(ArchetypeWarning::HighTagCardinality {
+                    archetype: cardinality.archetype,
+                    tag: cardinality.tag,
+                    distinct_values: cardinality.distinct_values,
+                });
+            }
+            tag_cardinality.push(cardinality);
+        }
+
+        let capacity = archetype.layout().capacity() as f32;
+        let occupancy = archetype
+            .chunksets()
+            .iter()
+            .flat_map(|set| set.occupied())
+            .filter(|chunk| !chunk.is_empty())
+            .map(|chunk| chunk.len() as f32 / capacity)
+            .collect::<Vec<_>>();
+        if occupancy.len() > 1 {
+            let average = occupancy.iter().sum::<f32>() / occupancy.len() as f32;
+            if average < thresholds.min_occupancy {
+                warnings.push(ArchetypeWarning::LowOccupancy {
+                    archetype: archetype.id(),
+                    occupancy: average,
+                });
+            }
+        }
+        occupancy_sum += occupancy.iter().sum::<f32>();
+        chunk_count += occupancy.len();
+    }
+
+    tag_cardinality.sort_by_key(|c| Reverse(c.distinct_values));
+
+    ArchetypeDiagnostics {
+        archetypes: archetypes.len(),
+        chunksets,
+        average_occupancy: if chunk_count > 0 {
+            occupancy_sum / chunk_count as f32
+        } else {
+            0.0
+        },
+        tag_cardinality,
+        warnings,
+    }
+}
+
+fn tag_cardinalities(archetype: &ArchetypeData) -> Vec<TagCardinality> {
+    let description = archetype.description();
+    description
+        .tags()
+        .iter()
+        .zip(description.tag_names())
+        .filter_map(|((type_id, meta), name)| {
+            let storage = archetype.tags().get(*type_id)?;
+            let (ptr, element_size, count) = unsafe { storage.data_raw() };
+            let value = |i: usize| unsafe { ptr.as_ptr().add(i * element_size) as *const u8 };
+
+            let mut distinct: Vec<usize> = Vec::new();
+            for i in 0..count {
+                if !distinct.iter().any(|j| meta.equals(value(i), value(*j))) {
+                    distinct.push(i);
+                }
+            }
+
+            Some(TagCardinality {
+                archetype: archetype.id(),
+                tag: name,
+                distinct_values: distinct.len(),
+            })
+        })
+        .collect()
+}
+
+/// The archetype layout of a world at the time it was sampled by an `ArchetypeMonitor`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchetypeSample {
+    /// The number of archetypes in the world.
+    pub archetypes: usize,
+    /// The total number of chunk sets across all archetypes.
+    pub chunksets: usize,
+    /// The average fraction of capacity in use across all chunks which hold entities.
+    pub average_occupancy: f32,
+}
+
+/// Records archetype and chunk set counts over time.
+///
+/// The monitor is typically sampled once per frame, or once every few frames, and retains a
+/// bounded history of samples.
+#[derive(Debug, Clone)]
+pub struct ArchetypeMonitor {
+    thresholds: Thresholds,
+    history: VecDeque<ArchetypeSample>,
+    history_len: usize,
+}
+
+impl ArchetypeMonitor {
+    /// Creates a monitor which checks the given thresholds and retains up to `history_len`
+    /// samples.
+    pub fn new(thresholds: Thresholds, history_len: usize) -> Self {
+        ArchetypeMonitor {
+            thresholds,
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+        }
+    }
+
+    /// Gets the thresholds checked by the monitor.
+    pub fn thresholds(&self) -> &Thresholds { &self.thresholds }
+
+    /// Sets the thresholds checked by the monitor.
+    pub fn set_thresholds(&mut self, thresholds: Thresholds) { self.thresholds = thresholds; }
+
+    /// Inspects the world, recording a sample and returning its diagnostics.
+    pub fn sample(&mut self, world: &World) -> ArchetypeDiagnostics {
+        let diagnostics = diagnose(world, &self.thresholds);
+        if self.history_len > 0 {
+            if self.history.len() == self.history_len {
+                self.history.pop_front();
+            }
+            self.history.push_back(ArchetypeSample {
+                archetypes: diagnostics.archetypes,
+                chunksets: diagnostics.chunksets,
+                average_occupancy: diagnostics.average_occupancy,
+            });
+        }
+        diagnostics
+    }
+
+    /// Gets the recorded samples, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &ArchetypeSample> { self.history.iter() }
+
+    /// Gets the change in the number of chunk sets between the oldest and newest samples.
+    pub fn chunkset_growth(&self) -> isize {
+        match (self.history.front(), self.history.back()) {
+            (Some(first), Some(last)) => last.chunksets as isize - first.chunksets as isize,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Team(u32);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Id(u32);
+
+    #[test]
+    fn tag_cardinality() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        for i in 0..20 {
+            world.insert((Team(i % 2), Id(i)), vec![(Pos(0.),)]);
+        }
+
+        let thresholds = Thresholds {
+            max_chunksets: 10,
+            max_tag_values: 10,
+            min_occupancy: 0.0,
+            ..Thresholds::default()
+        };
+        let diagnostics = diagnose(&world, &thresholds);
+        assert_eq!(1, diagnostics.archetypes);
+        assert_eq!(20, diagnostics.chunksets);
+
+        let cardinality = diagnostics
+            .tag_cardinality
+            .iter()
+            .map(|c| (c.distinct_values, c.tag.ends_with("Id")))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(20, true), (2, false)], cardinality);
+
+        let archetype = world.storage().archetypes()[0].id();
+        assert_eq!(2, diagnostics.warnings.len());
+        assert!(diagnostics
+            .warnings
+            .contains(&ArchetypeWarning::TooManyChunksets {
+                archetype,
+                count: 20
+            }));
+        assert!(diagnostics.warnings.iter().any(|w| match w {
+            ArchetypeWarning::HighTagCardinality {
+                distinct_values, ..
+            } => *distinct_values == 20,
+            _ => false,
+        }));
+
+        // each of the archetype's chunks holds a single entity
+        let diagnostics = diagnose(
+            &world,
+            &Thresholds {
+                min_occupancy: 0.5,
+                ..thresholds
+            },
+        );
+        assert!(diagnostics.warnings.iter().any(|w| match w {
+            ArchetypeWarning::LowOccupancy { .. } => true,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn monitor_history() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        let thresholds = Thresholds {
+            min_occupancy: 0.0,
+            ..Thresholds::default()
+        };
+        let mut monitor = ArchetypeMonitor::new(thresholds, 2);
+
+        for i in 0..3 {
+            world.insert((Id(i),), vec![(Pos(0.),)]);
+            let diagnostics = monitor.sample(&world);
+            assert!(diagnostics.warnings.is_empty());
+            assert_eq!(
+                1.0 / world.storage().archetypes()[0].layout().capacity() as f32,
+                diagnostics.average_occupancy
+            );
+        }
+
+        let chunksets = monitor.history().map(|s| s.chunksets).collect::<Vec<_>>();
+        assert_eq!(vec![2, 3], chunksets);
+        assert_eq!(1, monitor.chunkset_growth());
+    }
+}
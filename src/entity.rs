@@ -1,24 +1,79 @@
-use parking_lot::Mutex;
+use crate::sync::Mutex;
 use std::fmt::Display;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::num::Wrapping;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use tracing::warn;
 
 pub(crate) type EntityIndex = u32;
 pub(crate) type EntityVersion = Wrapping<u32>;
 
+/// Identifies the `BlockAllocator` which issued an entity. Zero is never assigned, and marks
+/// entities of unknown origin, such as those reconstructed from FFI handles.
+type Namespace = u32;
+
+static NEXT_NAMESPACE: AtomicU32 = AtomicU32::new(1);
+
 /// A handle to an entity.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+///
+/// Each handle also records the universe which issued it, such that using an entity with a
+/// world from a different universe is detected rather than silently aliasing whichever of
+/// that world's entities shares its ID.
+#[derive(Copy, Clone)]
 pub struct Entity {
     index: EntityIndex,
     version: EntityVersion,
+    namespace: Namespace,
 }
 
 impl Entity {
     pub(crate) fn new(index: EntityIndex, version: EntityVersion) -> Entity {
-        Entity { index, version }
+        Entity {
+            index,
+            version,
+            namespace: 0,
+        }
+    }
+
+    fn in_namespace(mut self, namespace: Namespace) -> Entity {
+        self.namespace = namespace;
+        self
+    }
+
+    /// Determines if the entity may have been issued by the allocator with the given namespace.
+    fn issued_by(self, namespace: Namespace) -> bool {
+        self.namespace == 0 || self.namespace == namespace
     }
 
     pub(crate) fn index(self) -> EntityIndex { self.index }
+
+    pub(crate) fn version(self) -> EntityVersion { self.version }
+}
+
+// the namespace only guards lookups, and is not part of the entity's identity
+impl PartialEq for Entity {
+    fn eq(&self, other: &Self) -> bool { self.index == other.index && self.version == other.version }
+}
+
+impl Eq for Entity {}
+
+impl std::fmt::Debug for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Entity")
+            .field("index", &self.index)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+impl Hash for Entity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.version.hash(state);
+    }
 }
 
 impl Display for Entity {
@@ -59,51 +114,113 @@ impl EntityLocation {
     pub(crate) fn component(&self) -> usize { self.component_index }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct BlockAllocator {
+    namespace: Namespace,
+    block_size: usize,
     allocated: usize,
     free: Vec<EntityBlock>,
+    // index ranges whose memory has been released, with the version their entities resume from
+    released: Vec<(EntityIndex, EntityVersion)>,
 }
 
 impl BlockAllocator {
-    const BLOCK_SIZE: usize = 1024;
+    pub(crate) const DEFAULT_BLOCK_SIZE: usize = 1024;
 
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new() -> Self { Self::with_block_size(Self::DEFAULT_BLOCK_SIZE) }
+
+    pub(crate) fn with_block_size(block_size: usize) -> Self {
+        assert!(block_size > 0, "entity block size must be non-zero");
         BlockAllocator {
+            namespace: NEXT_NAMESPACE.fetch_add(1, Ordering::Relaxed),
+            block_size,
             allocated: 0,
             free: Vec::new(),
+            released: Vec::new(),
         }
     }
 
+    pub fn block_size(&self) -> usize { self.block_size }
+
     pub fn allocate(&mut self) -> EntityBlock {
         if let Some(block) = self.free.pop() {
             block
+        } else if let Some((start, version)) = self.released.pop() {
+            EntityBlock::with_base_version(start, self.block_size, version, self.namespace)
         } else {
-            let block = EntityBlock::new(self.allocated as EntityIndex, BlockAllocator::BLOCK_SIZE);
-            self.allocated += BlockAllocator::BLOCK_SIZE;
-            block
+            let start = self.allocated as EntityIndex;
+            self.allocated = self
+                .allocated
+                .checked_add(self.block_size)
+                .filter(|allocated| *allocated <= EntityIndex::MAX as usize + 1)
+                .expect("entity index space exhausted");
+            EntityBlock::with_base_version(start, self.block_size, Wrapping(1), self.namespace)
         }
     }
 
     pub fn free(&mut self, block: EntityBlock) { self.free.push(block); }
+
+    /// Releases the memory of all free blocks which contain no live entities.
+    ///
+    /// The index range of each released block is retained and will be handed out again by a
+    /// later allocation, with versions beyond any previously issued by the block.
+    ///
+    /// Returns the number of blocks released.
+    pub fn shrink(&mut self) -> usize {
+        let (empty, used): (Vec<_>, Vec<_>) = self
+            .free
+            .drain(..)
+            .partition(|block| block.free_slots() == block.len);
+        self.free = used;
+        self.free.shrink_to_fit();
+
+        let count = empty.len();
+        self.released.extend(
+            empty
+                .into_iter()
+                .map(|block| (block.start, block.next_version())),
+        );
+        count
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct EntityBlock {
     start: EntityIndex,
     len: usize,
+    base_version: EntityVersion,
+    namespace: Namespace,
     versions: Vec<EntityVersion>,
     free: Vec<EntityIndex>,
+    // whether `free` is sorted such that the lowest index is popped first
+    free_sorted: bool,
+    // whether the block is a copy of a block owned by another allocator, and so must not be
+    // returned to the block allocator
+    forked: bool,
     locations: Vec<EntityLocation>,
 }
 
 impl EntityBlock {
     pub fn new(start: EntityIndex, len: usize) -> EntityBlock {
+        Self::with_base_version(start, len, Wrapping(1), 0)
+    }
+
+    /// Creates a block whose entities are first allocated with the given version.
+    pub fn with_base_version(
+        start: EntityIndex,
+        len: usize,
+        base_version: EntityVersion,
+        namespace: Namespace,
+    ) -> EntityBlock {
         EntityBlock {
             start,
             len,
+            base_version,
+            namespace,
             versions: Vec::with_capacity(len),
             free: Vec::new(),
+            free_sorted: true,
+            forked: false,
             locations: std::iter::repeat(EntityLocation::new(0, 0, 0, 0))
                 .take(len)
                 .collect(),
@@ -112,6 +229,48 @@ impl EntityBlock {
 
     fn index(&self, index: EntityIndex) -> usize { (index - self.start) as usize }
 
+    /// Gets a version greater than that of every entity this block has issued.
+    fn next_version(&self) -> EntityVersion {
+        let max = self
+            .versions
+            .iter()
+            .map(|v| v.0)
+            .max()
+            .unwrap_or(self.base_version.0);
+        Wrapping(max.checked_add(1).unwrap_or(1))
+    }
+
+    /// Deletes every live entity in the block.
+    fn free_all(&mut self) {
+        let free = self
+            .free
+            .iter()
+            .copied()
+            .collect::<std::collections::HashSet<_>>();
+        for i in 0..self.versions.len() {
+            let index = self.start + i as EntityIndex;
+            if !free.contains(&index) {
+                self.versions[i] += Wrapping(1);
+                if self.versions[i].0 != 0 {
+                    self.free.push(index);
+                    self.free_sorted = false;
+                }
+            }
+        }
+    }
+
+    /// Gets the number of entities which can still be allocated from this block.
+    pub fn free_slots(&self) -> usize {
+        if self.forked {
+            0
+        } else {
+            self.free.len() + self.len - self.versions.len()
+        }
+    }
+
+    /// Ensures that deleting entities from this block will never allocate.
+    pub fn reserve_free_list(&mut self) { self.free.reserve_exact(self.len - self.free.len()); }
+
     pub fn in_range(&self, index: EntityIndex) -> bool {
         index >= self.start && index < (self.start + self.len as u32)
     }
@@ -119,31 +278,72 @@ impl EntityBlock {
     pub fn is_alive(&self, entity: Entity) -> Option<bool> {
         if entity.index >= self.start {
             let i = self.index(entity.index);
-            self.versions.get(i).map(|v| *v == entity.version)
+            self.versions
+                .get(i)
+                .map(|v| *v == entity.version && entity.issued_by(self.namespace))
         } else {
             None
         }
     }
 
-    pub fn allocate(&mut self) -> Option<Entity> {
+    pub fn allocate(&mut self) -> Option<Entity> { self.allocate_in(EntityAllocationOrder::Recent) }
+
+    /// Allocates an entity, recycling deleted indices in the given order.
+    ///
+    /// Forked blocks are read-only, as their indices may be issued by the allocator which owns
+    /// them, and so never allocate.
+    pub fn allocate_in(&mut self, order: EntityAllocationOrder) -> Option<Entity> {
+        if self.forked {
+            return None;
+        }
+
+        if order == EntityAllocationOrder::Clustered && !self.free_sorted {
+            self.free.sort_unstable_by(|a, b| b.cmp(a));
+            self.free_sorted = true;
+        }
+
         if let Some(index) = self.free.pop() {
             let i = self.index(index);
-            Some(Entity::new(index, self.versions[i]))
+            Some(Entity::new(index, self.versions[i]).in_namespace(self.namespace))
         } else if self.versions.len() < self.len {
             let index = self.start + self.versions.len() as EntityIndex;
-            self.versions.push(Wrapping(1));
-            Some(Entity::new(index, Wrapping(1)))
+            self.versions.push(self.base_version);
+            Some(Entity::new(index, self.base_version).in_namespace(self.namespace))
         } else {
             None
         }
     }
 
-    pub fn free(&mut self, entity: Entity) -> Option<EntityLocation> {
+    pub fn free(
+        &mut self,
+        entity: Entity,
+        on_wrap: VersionWrapBehavior,
+    ) -> Option<(EntityLocation, bool)> {
         if let Some(true) = self.is_alive(entity) {
             let i = self.index(entity.index);
             self.versions[i] += Wrapping(1);
-            self.free.push(entity.index);
-            self.get_location(entity.index)
+
+            let retired = if self.versions[i].0 == 0 {
+                match on_wrap {
+                    VersionWrapBehavior::Panic => {
+                        panic!("entity version counter wrapped for entity {}", entity)
+                    }
+                    VersionWrapBehavior::Log => {
+                        warn!(%entity, "Entity version counter wrapped");
+                        false
+                    }
+                    VersionWrapBehavior::Retire => true,
+                }
+            } else {
+                false
+            };
+
+            if !retired {
+                self.free.push(entity.index);
+                self.free_sorted = false;
+            }
+
+            self.get_location(entity.index).map(|l| (l, retired))
         } else {
             None
         }
@@ -165,12 +365,67 @@ impl EntityBlock {
     }
 }
 
+/// Determines how an `EntityAllocator` behaves when an entity index's version counter wraps.
+///
+/// Once a version counter wraps, a new entity may be allocated with the same ID as an
+/// entity which was deleted long ago, causing stale handles to be considered alive again.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum VersionWrapBehavior {
+    /// Panic when a version counter wraps.
+    Panic,
+    /// Log a warning and continue to recycle the index.
+    #[default]
+    Log,
+    /// Permanently retire the index, such that it is never allocated again.
+    Retire,
+}
+
+/// Determines the order in which an `EntityAllocator` recycles the indices of deleted entities.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum EntityAllocationOrder {
+    /// The most recently deleted index is recycled first.
+    #[default]
+    Recent,
+    /// The lowest free index is recycled first.
+    ///
+    /// Entities inserted together are allocated from one chunk at a time, so handing out
+    /// recycled indices in ascending order keeps the entities of each chunk close together in
+    /// the allocator's index to location table. This reduces cache misses in workloads which
+    /// look up many entities by ID, such as those dominated by `World::get_component`, at the
+    /// cost of sorting the free list when entities have been deleted since the last allocation.
+    Clustered,
+}
+
+/// Allocation statistics for an `EntityAllocator`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct EntityAllocatorStats {
+    /// The total number of entities ever allocated.
+    pub allocated_total: usize,
+    /// The number of entities which are currently alive.
+    pub live: usize,
+    /// The number of allocations which re-used the index of a deleted entity.
+    pub recycled: usize,
+    /// The number of indices which have been retired after their version counter wrapped.
+    pub retired: usize,
+    /// The number of entity blocks currently owned by the allocator.
+    pub blocks_in_use: usize,
+}
+
+/// The state of an `EntityAllocator` captured for a world snapshot.
+#[derive(Debug, Clone)]
+pub(crate) struct AllocatorSnapshot {
+    blocks: Vec<EntityBlock>,
+    stats: EntityAllocatorStats,
+}
+
 /// Manages the allocation and deletion of `Entity` IDs within a world.
 #[derive(Debug)]
 pub struct EntityAllocator {
     allocator: Arc<Mutex<BlockAllocator>>,
     blocks: Vec<EntityBlock>,
-    entity_buffer: Vec<Entity>,
+    stats: EntityAllocatorStats,
+    on_wrap: VersionWrapBehavior,
+    order: EntityAllocationOrder,
 }
 
 impl EntityAllocator {
@@ -178,10 +433,84 @@ impl EntityAllocator {
         EntityAllocator {
             allocator,
             blocks: Vec::new(),
-            entity_buffer: Vec::new(),
+            stats: EntityAllocatorStats::default(),
+            on_wrap: VersionWrapBehavior::default(),
+            order: EntityAllocationOrder::default(),
         }
     }
 
+    /// Gets allocation statistics for this allocator.
+    pub fn stats(&self) -> EntityAllocatorStats {
+        EntityAllocatorStats {
+            blocks_in_use: self.blocks.len(),
+            ..self.stats
+        }
+    }
+
+    /// Gets the behavior used when an entity index's version counter wraps.
+    pub fn version_wrap_behavior(&self) -> VersionWrapBehavior { self.on_wrap }
+
+    /// Sets the behavior used when an entity index's version counter wraps.
+    pub fn set_version_wrap_behavior(&mut self, behavior: VersionWrapBehavior) {
+        self.on_wrap = behavior;
+    }
+
+    /// Gets the order in which the indices of deleted entities are recycled.
+    pub fn allocation_order(&self) -> EntityAllocationOrder { self.order }
+
+    /// Sets the order in which the indices of deleted entities are recycled.
+    pub fn set_allocation_order(&mut self, order: EntityAllocationOrder) { self.order = order; }
+
+    /// Gets the number of entities which can be allocated without claiming another block.
+    pub fn free_slots(&self) -> usize { self.blocks.iter().map(|b| b.free_slots()).sum() }
+
+    /// Claims entity blocks until at least `additional` entities can be allocated without
+    /// allocating any further memory.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        let mut free = self.free_slots();
+        while free < additional {
+            let block = self.allocator.lock().allocate();
+            free += block.free_slots();
+            self.blocks.push(block);
+        }
+
+        for block in self.blocks.iter_mut() {
+            block.reserve_free_list();
+        }
+
+        self.update_gauges();
+    }
+
+    pub(crate) fn block_allocator(&self) -> Arc<Mutex<BlockAllocator>> { self.allocator.clone() }
+
+    /// Captures the state of every entity in the allocator.
+    pub(crate) fn snapshot(&self) -> AllocatorSnapshot {
+        AllocatorSnapshot {
+            blocks: self.blocks.clone(),
+            stats: self.stats,
+        }
+    }
+
+    /// Returns every entity to the state captured by `snapshot`. Blocks claimed since the
+    /// snapshot was taken are released.
+    pub(crate) fn restore(&mut self, snapshot: AllocatorSnapshot) {
+        let retained = snapshot
+            .blocks
+            .iter()
+            .map(|block| block.start)
+            .collect::<std::collections::HashSet<_>>();
+        for mut block in self.blocks.drain(..) {
+            if !retained.contains(&block.start) && !block.forked {
+                block.free_all();
+                self.allocator.lock().free(block);
+            }
+        }
+
+        self.blocks = snapshot.blocks;
+        self.stats = snapshot.stats;
+        self.update_gauges();
+    }
+
     pub(crate) fn get_block(&mut self) -> EntityBlock { self.allocator.lock().allocate() }
     pub(crate) fn push_block(&mut self, block: EntityBlock) { self.blocks.push(block); }
 
@@ -194,15 +523,40 @@ impl EntityAllocator {
             .unwrap_or(false)
     }
 
+    /// Gets an iterator over all entities in `entities` which are alive.
+    ///
+    /// This is faster than calling `is_alive` for each entity, as the allocator's blocks are
+    /// sorted once and then binary searched for each entity.
+    pub fn filter_alive<'a>(
+        &'a self,
+        entities: impl Iterator<Item = Entity> + 'a,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        let mut blocks = self.blocks.iter().collect::<Vec<_>>();
+        blocks.sort_by_key(|b| b.start);
+
+        entities.filter(move |entity| {
+            let i = match blocks.binary_search_by_key(&entity.index, |b| b.start) {
+                Ok(i) => i,
+                Err(0) => return false,
+                Err(i) => i - 1,
+            };
+            blocks[i].is_alive(*entity).unwrap_or(false)
+        })
+    }
+
     /// Allocates a new unused `Entity` ID.
     pub fn create_entity(&mut self) -> Entity {
+        let order = self.order;
         let entity = if let Some(entity) = self
             .blocks
             .iter_mut()
             .rev()
-            .filter_map(|b| b.allocate())
+            .filter_map(|b| b.allocate_in(order))
             .nth(0)
         {
+            if entity.version != Wrapping(1) {
+                self.stats.recycled += 1;
+            }
             entity
         } else {
             let mut block = self.allocator.lock().allocate();
@@ -211,12 +565,67 @@ impl EntityAllocator {
             entity
         };
 
-        self.entity_buffer.push(entity.clone());
+        self.stats.allocated_total += 1;
+        self.stats.live += 1;
+        self.update_gauges();
+
         entity
     }
 
     pub(crate) fn delete_entity(&mut self, entity: Entity) -> Option<EntityLocation> {
-        self.blocks.iter_mut().find_map(|b| b.free(entity))
+        let on_wrap = self.on_wrap;
+        let (location, retired) = self
+            .blocks
+            .iter_mut()
+            .find_map(|b| b.free(entity, on_wrap))?;
+
+        self.stats.live -= 1;
+        if retired {
+            self.stats.retired += 1;
+        }
+        self.update_gauges();
+
+        Some(location)
+    }
+
+    /// Deletes each of the given entities which are alive, returning their locations.
+    ///
+    /// Entities are grouped by the block they were allocated from, so that each block is found
+    /// once rather than once per entity, and the stats are updated once for the whole batch.
+    pub(crate) fn delete_entities(&mut self, entities: &[Entity]) -> Vec<(Entity, EntityLocation)> {
+        let mut sorted = entities.to_vec();
+        sorted.sort_unstable_by_key(|e| e.index);
+
+        let on_wrap = self.on_wrap;
+        let mut deleted = Vec::with_capacity(sorted.len());
+        let mut retired = 0;
+        for block in self.blocks.iter_mut() {
+            let start = sorted.partition_point(|e| e.index < block.start);
+            let end = sorted.partition_point(|e| e.index < block.start + block.len as EntityIndex);
+            for entity in &sorted[start..end] {
+                if let Some((location, was_retired)) = block.free(*entity, on_wrap) {
+                    deleted.push((*entity, location));
+                    if was_retired {
+                        retired += 1;
+                    }
+                }
+            }
+        }
+
+        self.stats.live -= deleted.len();
+        self.stats.retired += retired;
+        self.update_gauges();
+
+        deleted
+    }
+
+    fn update_gauges(&self) {
+        #[cfg(feature = "metrics")]
+        {
+            use std::convert::TryInto;
+            metrics::gauge!("entities_live", self.stats.live.try_into().unwrap());
+            metrics::gauge!("entity_blocks_in_use", self.blocks.len().try_into().unwrap());
+        }
     }
 
     pub(crate) fn set_location(&mut self, entity: EntityIndex, location: EntityLocation) {
@@ -235,19 +644,72 @@ impl EntityAllocator {
             .and_then(|b| b.get_location(entity))
     }
 
-    pub(crate) fn allocation_buffer(&self) -> &[Entity] { self.entity_buffer.as_slice() }
+    /// Creates a copy of the allocator, in which every entity alive in this allocator is alive.
+    ///
+    /// The copied blocks remain owned by this allocator, and so are never returned to the
+    /// block allocator by the copy. Entities can be deleted from the copied blocks, but new
+    /// entities are only allocated from blocks the copy claims for itself, so that their IDs
+    /// are never issued by this allocator.
+    pub(crate) fn fork(&self) -> EntityAllocator {
+        EntityAllocator {
+            allocator: self.allocator.clone(),
+            blocks: self
+                .blocks
+                .iter()
+                .cloned()
+                .map(|block| EntityBlock {
+                    forked: true,
+                    ..block
+                })
+                .collect(),
+            stats: self.stats,
+            on_wrap: self.on_wrap,
+            order: self.order,
+        }
+    }
 
-    pub(crate) fn clear_allocation_buffer(&mut self) { self.entity_buffer.clear(); }
+    /// Creates a copy of the allocator with its own copy of the block allocator, such that it
+    /// allocates the same entity IDs as this allocator would, given the same operations.
+    pub(crate) fn detach(&self) -> EntityAllocator {
+        EntityAllocator {
+            allocator: Arc::new(Mutex::new(self.allocator.lock().clone())),
+            blocks: self
+                .blocks
+                .iter()
+                .cloned()
+                .map(|block| EntityBlock {
+                    forked: false,
+                    ..block
+                })
+                .collect(),
+            stats: self.stats,
+            on_wrap: self.on_wrap,
+            order: self.order,
+        }
+    }
+
+    /// Determines if both allocators allocate entity blocks from the same universe.
+    pub(crate) fn shares_blocks(&self, other: &EntityAllocator) -> bool {
+        Arc::ptr_eq(&self.allocator, &other.allocator)
+    }
 
     pub(crate) fn merge(&mut self, mut other: EntityAllocator) {
         assert!(Arc::ptr_eq(&self.allocator, &other.allocator));
         self.blocks.append(&mut other.blocks);
+        self.stats.allocated_total += other.stats.allocated_total;
+        self.stats.live += other.stats.live;
+        self.stats.recycled += other.stats.recycled;
+        self.stats.retired += other.stats.retired;
+        self.update_gauges();
     }
 }
 
 impl Drop for EntityAllocator {
     fn drop(&mut self) {
-        for block in self.blocks.drain(..) {
+        // entities of a dropped world must not be considered alive by whichever world
+        // next claims its blocks
+        for mut block in self.blocks.drain(..).filter(|block| !block.forked) {
+            block.free_all();
             self.allocator.lock().free(block);
         }
     }
@@ -294,6 +756,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn create_entity_clustered() {
+        let mut allocator = EntityAllocator::new(Arc::from(Mutex::new(BlockAllocator::new())));
+        allocator.set_allocation_order(EntityAllocationOrder::Clustered);
+
+        let entities: Vec<Entity> = (0..8).map(|_| allocator.create_entity()).collect();
+        for i in &[5, 1, 6, 2] {
+            allocator.delete_entity(entities[*i]);
+        }
+
+        let recycled = (0..4)
+            .map(|_| allocator.create_entity().index())
+            .collect::<Vec<_>>();
+        let expected = [1, 2, 5, 6]
+            .iter()
+            .map(|i| entities[*i].index())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, recycled);
+    }
+
     #[test]
     fn is_alive_allocated() {
         let mut allocator = EntityAllocator::new(Arc::from(Mutex::new(BlockAllocator::new())));
@@ -344,6 +826,34 @@ mod tests {
         assert_eq!(None, allocator.delete_entity(entity));
     }
 
+    #[test]
+    fn filter_alive() {
+        let blocks = Arc::from(Mutex::new(BlockAllocator::new()));
+        let mut allocator_a = EntityAllocator::new(blocks.clone());
+        let mut allocator_b = EntityAllocator::new(blocks);
+
+        let mut entities = Vec::new();
+        for _ in 0..3 {
+            entities.extend((0..1500).map(|_| allocator_a.create_entity()));
+            entities.extend((0..1500).map(|_| allocator_b.create_entity()));
+        }
+
+        for e in entities.iter().step_by(3) {
+            allocator_a.delete_entity(*e);
+        }
+
+        let expected = entities
+            .iter()
+            .filter(|e| allocator_a.is_alive(**e))
+            .copied()
+            .collect::<Vec<_>>();
+        let alive = allocator_a
+            .filter_alive(entities.iter().copied())
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected, alive);
+    }
+
     #[test]
     fn multiple_allocators_unique_ids() {
         let blocks = Arc::from(Mutex::new(BlockAllocator::new()));
@@ -370,4 +880,91 @@ mod tests {
             assert_eq!(true, allocator_b.is_alive(e));
         }
     }
+
+    #[test]
+    fn stats() {
+        let mut allocator = EntityAllocator::new(Arc::from(Mutex::new(BlockAllocator::new())));
+
+        let entities: Vec<Entity> = (0..1500).map(|_| allocator.create_entity()).collect();
+        for e in entities.iter().rev().take(10) {
+            allocator.delete_entity(*e);
+        }
+        for _ in 0..5 {
+            allocator.create_entity();
+        }
+
+        let stats = allocator.stats();
+        assert_eq!(1505, stats.allocated_total);
+        assert_eq!(1495, stats.live);
+        assert_eq!(5, stats.recycled);
+        assert_eq!(0, stats.retired);
+        assert_eq!(2, stats.blocks_in_use);
+    }
+
+    #[test]
+    fn block_size() {
+        let blocks = Arc::from(Mutex::new(BlockAllocator::with_block_size(16)));
+        let mut allocator = EntityAllocator::new(blocks);
+
+        let entities: Vec<Entity> = (0..20).map(|_| allocator.create_entity()).collect();
+        assert_eq!(2, allocator.stats().blocks_in_use);
+        assert_eq!(12, allocator.free_slots());
+        assert_eq!(16, entities[16].index());
+    }
+
+    #[test]
+    fn shrink() {
+        let blocks = Arc::from(Mutex::new(BlockAllocator::with_block_size(16)));
+
+        let mut allocator = EntityAllocator::new(blocks.clone());
+        let stale: Vec<Entity> = (0..20).map(|_| allocator.create_entity()).collect();
+        for e in stale.iter().take(3) {
+            allocator.delete_entity(*e);
+        }
+        drop(allocator);
+
+        // the live entities of the dropped allocator were freed along with its blocks
+        assert_eq!(2, blocks.lock().shrink());
+        assert_eq!(0, blocks.lock().free.len());
+        assert_eq!(0, blocks.lock().shrink());
+
+        let mut allocator = EntityAllocator::new(blocks.clone());
+        let entities: Vec<Entity> = (0..20).map(|_| allocator.create_entity()).collect();
+        assert_eq!(32, blocks.lock().allocated);
+
+        // the released index ranges were reused
+        assert!(entities.iter().all(|e| e.index() < 32));
+        for e in stale.iter() {
+            assert_eq!(false, allocator.is_alive(*e));
+        }
+        for e in entities.iter() {
+            assert_eq!(true, allocator.is_alive(*e));
+        }
+    }
+
+    #[test]
+    fn version_wrap_retire() {
+        let mut allocator = EntityAllocator::new(Arc::from(Mutex::new(BlockAllocator::new())));
+        allocator.set_version_wrap_behavior(VersionWrapBehavior::Retire);
+
+        allocator.create_entity();
+        allocator.blocks[0].versions[0] = Wrapping(std::u32::MAX);
+        let entity = Entity::new(0, Wrapping(std::u32::MAX));
+        assert_eq!(true, allocator.delete_entity(entity).is_some());
+
+        let replacement = allocator.create_entity();
+        assert_ne!(entity.index(), replacement.index());
+        assert_eq!(1, allocator.stats().retired);
+    }
+
+    #[test]
+    #[should_panic]
+    fn version_wrap_panic() {
+        let mut allocator = EntityAllocator::new(Arc::from(Mutex::new(BlockAllocator::new())));
+        allocator.set_version_wrap_behavior(VersionWrapBehavior::Panic);
+
+        allocator.create_entity();
+        allocator.blocks[0].versions[0] = Wrapping(std::u32::MAX);
+        allocator.delete_entity(Entity::new(0, Wrapping(std::u32::MAX)));
+    }
 }
@@ -0,0 +1,91 @@
+//! Error types returned by fallible world operations.
+//!
+//! Methods such as `World::add_component_checked`, `World::delete_checked` and
+//! `World::insert_checked` report failures through `Error`, rather than silently doing nothing
+//! or panicking.
+use crate::entity::Entity;
+use crate::storage::ComponentTypeId;
+use crate::storage::TagTypeId;
+use crate::world::CapacityError;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// An error caused by the state of a specific entity.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EntityError {
+    /// The entity has been deleted, or was never alive in the world.
+    NotAlive(Entity),
+    /// The entity does not have a component of the given type.
+    MissingComponent(Entity, ComponentTypeId),
+}
+
+impl Display for EntityError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            EntityError::NotAlive(entity) => write!(f, "entity {} is not alive", entity),
+            EntityError::MissingComponent(entity, type_id) => {
+                write!(f, "entity {} does not have component {:?}", entity, type_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EntityError {}
+
+/// The error type for fallible world operations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The operation referred to an entity which could not be used.
+    Entity(EntityError),
+    /// A component layout contained the same component type more than once.
+    DuplicateComponent(ComponentTypeId),
+    /// A tag layout contained the same tag type more than once.
+    DuplicateTag(TagTypeId),
+    /// A fixed capacity world could not store the entities without allocating.
+    Capacity(CapacityError),
+    /// A component type could not be cloned, as it was not registered via
+    /// `Registry::register_clone` or `Registry::register_pod`.
+    NotCloneable(ComponentTypeId),
+    /// A component type could not be hashed, as it was not registered via
+    /// `Registry::register_hash` or `Registry::register_pod`.
+    NotHashable(ComponentTypeId),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Error::Entity(err) => err.fmt(f),
+            Error::DuplicateComponent(type_id) => {
+                write!(f, "component {:?} appears more than once", type_id)
+            }
+            Error::DuplicateTag(type_id) => {
+                write!(f, "tag {:?} appears more than once", type_id)
+            }
+            Error::Capacity(err) => write!(f, "insufficient capacity: {}", err),
+            Error::NotCloneable(type_id) => {
+                write!(f, "component {:?} is not registered as cloneable", type_id)
+            }
+            Error::NotHashable(type_id) => {
+                write!(f, "component {:?} is not registered as hashable", type_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Entity(err) => Some(err),
+            Error::Capacity(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<EntityError> for Error {
+    fn from(err: EntityError) -> Self { Error::Entity(err) }
+}
+
+impl From<CapacityError> for Error {
+    fn from(err: CapacityError) -> Self { Error::Capacity(err) }
+}
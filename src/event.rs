@@ -1,6 +1,13 @@
-use crate::{entity::Entity, filter::EntityFilter, world::WorldId};
+use crate::{
+    entity::Entity,
+    filter::EntityFilter,
+    schedule::Schedulable,
+    system::{SystemBuilder, SystemId},
+    world::WorldId,
+};
 use crossbeam::queue::{ArrayQueue, PushError};
 use derivative::Derivative;
+#[cfg(all(feature = "rayon", not(feature = "single-thread")))]
 use rayon::prelude::*;
 use shrinkwraprs::Shrinkwrap;
 use std::marker::PhantomData;
@@ -49,15 +56,32 @@ impl<T: Copy> Channel<T> {
     where
         T: Sync + Send,
     {
-        if !self
-            .bound_functions
-            .par_iter()
-            .map(|f| (f)(event))
-            .any(|e| e.is_none())
+        #[cfg(all(feature = "rayon", not(feature = "single-thread")))]
         {
-            self.queues
+            if !self
+                .bound_functions
                 .par_iter()
-                .for_each(|queue| queue.push(event).unwrap());
+                .map(|f| (f)(event))
+                .any(|e| e.is_none())
+            {
+                self.queues
+                    .par_iter()
+                    .for_each(|queue| queue.push(event).unwrap());
+            }
+        }
+
+        #[cfg(any(not(feature = "rayon"), feature = "single-thread"))]
+        {
+            if !self
+                .bound_functions
+                .iter()
+                .map(|f| (f)(event))
+                .any(|e| e.is_none())
+            {
+                self.queues
+                    .iter()
+                    .for_each(|queue| queue.push(event).unwrap());
+            }
         }
 
         Ok(())
@@ -88,8 +112,206 @@ pub enum EntityEvent {
     Deleted(Entity),
 }
 
+/// Events describing changes to a world as a whole, written to `World::world_channel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorldEvent {
+    /// The world has completed a `World::maintain` call.
+    Maintained(WorldId),
+    /// The world's entities have been replaced by a `World::replace_with` call.
+    Reset(WorldId),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EntityFilterEvent<F: EntityFilter> {
     InScope(Entity, PhantomData<F>),
     OutScope(Entity, PhantomData<F>),
 }
+
+/// A double buffered queue of events of type `T`, intended to be stored as a resource.
+///
+/// Events sent during one update remain readable through the following update, after which
+/// they are discarded. Each consumer tracks its own position in the queue with an
+/// `EventReader`, so multiple systems can read the same events independently.
+///
+/// `Events::update` must be called once per frame, such as via the system returned by
+/// `Events::update_system`.
+///
+/// ```
+/// # use legion::prelude::*;
+/// # use legion::event::{EventReader, Events};
+/// struct Collision(u32);
+///
+/// let mut events = Events::<Collision>::default();
+/// let mut reader = EventReader::default();
+///
+/// events.send(Collision(1));
+/// events.update();
+/// events.send(Collision(2));
+///
+/// let ids = reader.read(&events).map(|c| c.0).collect::<Vec<_>>();
+/// assert_eq!(vec![1, 2], ids);
+/// assert_eq!(0, reader.read(&events).count());
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug(bound = "T: std::fmt::Debug"), Default(bound = ""))]
+pub struct Events<T> {
+    previous: Vec<T>,
+    current: Vec<T>,
+    previous_start: usize,
+    current_start: usize,
+}
+
+impl<T: Send + Sync + 'static> Events<T> {
+    /// Sends an event, making it visible to all readers.
+    pub fn send(&mut self, event: T) { self.current.push(event); }
+
+    /// Sends all events from an iterator.
+    pub fn send_iter<I: IntoIterator<Item = T>>(&mut self, events: I) {
+        self.current.extend(events);
+    }
+
+    /// Discards the events sent before the previous update, and begins a new update.
+    pub fn update(&mut self) {
+        self.previous_start = self.current_start;
+        self.current_start += self.current.len();
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+
+    /// Discards all buffered events.
+    ///
+    /// Existing readers will not observe the discarded events.
+    pub fn clear(&mut self) {
+        self.update();
+        self.update();
+    }
+
+    /// Gets the number of buffered events.
+    pub fn len(&self) -> usize { self.previous.len() + self.current.len() }
+
+    /// Determines if there are no buffered events.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Creates a reader which will only observe events sent after this call.
+    pub fn reader(&self) -> EventReader<T> {
+        EventReader {
+            next: self.current_start + self.current.len(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Builds a system which calls `update` on the `Events<T>` resource each time it is run.
+    ///
+    /// The system should be scheduled once per frame, typically at the end of the frame.
+    pub fn update_system() -> Box<dyn Schedulable> {
+        SystemBuilder::<()>::new(SystemId::of::<Self>(Some(format!(
+            "Events<{}>::update",
+            std::any::type_name::<T>()
+        ))))
+        .write_resource::<Self>()
+        .build(|_, _, events, _| events.update())
+    }
+}
+
+/// Tracks a consumer's position in an `Events<T>` queue.
+///
+/// Readers are usually stored as system-local state, by capturing them in the system's
+/// closure. A default constructed reader observes all events which are still buffered.
+#[derive(Derivative)]
+#[derivative(Debug, Default(bound = ""))]
+pub struct EventReader<T> {
+    next: usize,
+    #[derivative(Debug = "ignore")]
+    _phantom: PhantomData<fn(T)>,
+}
+
+impl<T: Send + Sync + 'static> EventReader<T> {
+    /// Gets an iterator over all events this reader has not yet observed, and marks them
+    /// as observed.
+    ///
+    /// Events discarded by `Events::update` before they were read are skipped.
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T> + 'a {
+        let skip_previous = self.next.saturating_sub(events.previous_start);
+        let skip_current = self.next.saturating_sub(events.current_start);
+        let previous = &events.previous[skip_previous.min(events.previous.len())..];
+        let current = &events.current[skip_current.min(events.current.len())..];
+        self.next = events.current_start + events.current.len();
+        previous.iter().chain(current.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    struct TestEvent(usize);
+
+    #[test]
+    fn events_double_buffered() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut events = Events::<TestEvent>::default();
+        let mut early = EventReader::default();
+        let mut late = EventReader::default();
+
+        events.send(TestEvent(0));
+        assert_eq!(vec![&TestEvent(0)], early.read(&events).collect::<Vec<_>>());
+
+        events.update();
+        events.send(TestEvent(1));
+        assert_eq!(vec![&TestEvent(1)], early.read(&events).collect::<Vec<_>>());
+
+        events.update();
+        events.send(TestEvent(2));
+        // event 0 has been discarded before `late` read it
+        assert_eq!(
+            vec![&TestEvent(1), &TestEvent(2)],
+            late.read(&events).collect::<Vec<_>>()
+        );
+
+        let mut fresh = events.reader();
+        events.send(TestEvent(3));
+        assert_eq!(vec![&TestEvent(3)], fresh.read(&events).collect::<Vec<_>>());
+
+        events.clear();
+        assert!(events.is_empty());
+        assert_eq!(0, early.read(&events).count());
+    }
+
+    #[test]
+    fn events_between_systems() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.resources.insert(Events::<TestEvent>::default());
+
+        let send = SystemBuilder::<()>::new("send")
+            .write_resource::<Events<TestEvent>>()
+            .build(|_, _, events, _| events.send(TestEvent(1)));
+
+        let received = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = received.clone();
+        let mut reader = EventReader::<TestEvent>::default();
+        let receive = SystemBuilder::<()>::new("receive")
+            .read_resource::<Events<TestEvent>>()
+            .build(move |_, _, events, _| {
+                let count = reader.read(events).count();
+                counter.fetch_add(count, std::sync::atomic::Ordering::SeqCst);
+            });
+
+        let mut executor = StageExecutor::new(vec![
+            send,
+            receive,
+            Events::<TestEvent>::update_system(),
+        ]);
+        for _ in 0..3 {
+            executor.execute(&mut world);
+        }
+
+        assert_eq!(3, received.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(1, world.resources.get::<Events<TestEvent>>().unwrap().len());
+    }
+}
@@ -0,0 +1,724 @@
+//! A C ABI for driving legion from foreign hosts, such as Lua or C# scripting runtimes.
+//!
+//! Components created through this API are identified by a host-chosen `u32` type ID (see
+//! `ComponentTypeId::external`) and must be registered via `legion_world_register_component`
+//! before use. Such components are treated as plain data: they are copied bytewise into and
+//! out of chunks, and are never dropped.
+//!
+//! All functions taking pointers are `unsafe`; callers must pass pointers previously returned
+//! by this module (or valid arrays of the documented length), and must not use a world from
+//! multiple threads concurrently. Array pointers may be null when their length is zero.
+//!
+//! The functions are exported unmangled only with the `c-api` feature enabled.
+#![allow(clippy::missing_safety_doc)]
+
+use crate::entity::Entity;
+use crate::entity::EntityAllocator;
+use crate::filter::ArchetypeFilterData;
+use crate::filter::Filter;
+use crate::registry::ComponentRegistration;
+use crate::storage::ArchetypeDescription;
+use crate::storage::ComponentMeta;
+use crate::storage::ComponentStorage;
+use crate::storage::ComponentTypeId;
+use crate::storage::ExactTypeSetIter;
+use crate::world::ComponentLayout;
+use crate::world::ComponentSource;
+use crate::world::IntoComponentSource;
+use crate::world::Universe;
+use crate::world::World;
+use std::collections::HashSet;
+use std::num::Wrapping;
+use std::os::raw::c_void;
+use std::panic::AssertUnwindSafe;
+use std::ptr::NonNull;
+
+/// A C compatible representation of an `Entity`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EntityHandle {
+    pub index: u32,
+    pub version: u32,
+}
+
+impl From<Entity> for EntityHandle {
+    fn from(entity: Entity) -> Self {
+        EntityHandle {
+            index: entity.index(),
+            version: entity.version().0,
+        }
+    }
+}
+
+impl From<EntityHandle> for Entity {
+    fn from(handle: EntityHandle) -> Self { Entity::new(handle.index, Wrapping(handle.version)) }
+}
+
+/// Callback invoked by `legion_query_for_each` once per chunk.
+///
+/// `components` points to one array per requested component type, in the order the types were
+/// requested, each containing `count` elements.
+pub type ChunkCallback = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    entities: *const EntityHandle,
+    components: *const *mut u8,
+    count: usize,
+);
+
+/// Creates a new universe. The universe must be freed with `legion_universe_free`.
+#[no_mangle]
+pub extern "C" fn legion_universe_new() -> *mut Universe { Box::into_raw(Box::new(Universe::new())) }
+
+/// Frees a universe created with `legion_universe_new`.
+#[no_mangle]
+pub unsafe extern "C" fn legion_universe_free(universe: *mut Universe) {
+    if !universe.is_null() {
+        drop(Box::from_raw(universe));
+    }
+}
+
+/// Creates a new world within a universe. The world must be freed with `legion_world_free`.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_new(universe: *mut Universe) -> *mut World {
+    Box::into_raw(Box::new((&*universe).create_world()))
+}
+
+/// Frees a world created with `legion_world_new`.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_free(world: *mut World) {
+    if !world.is_null() {
+        drop(Box::from_raw(world));
+    }
+}
+
+/// Registers an external plain data component type with the given size and alignment.
+///
+/// Returns `false` if `align` is not a power of two, or `size` is not a multiple of `align`.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_register_component(
+    world: *mut World,
+    type_id: u32,
+    size: usize,
+    align: usize,
+) -> bool {
+    match ComponentMeta::raw(size, align) {
+        Some(meta) => {
+            (&mut *world).registry.register_raw(
+                ComponentTypeId::external(type_id),
+                ComponentRegistration::raw(meta, "external"),
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Inserts `entity_count` new entities with the given components.
+///
+/// `component_types` and `component_data` are arrays of `component_count` elements. Each entry
+/// in `component_data` points to an array of `entity_count` values of the corresponding type.
+/// The IDs of the new entities are written to `out_entities`, which may be null.
+///
+/// Returns `false` if any of the component types have not been registered or appear more than
+/// once, or if any required array is null.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_insert(
+    world: *mut World,
+    component_types: *const u32,
+    component_data: *const *const u8,
+    component_count: usize,
+    entity_count: usize,
+    out_entities: *mut EntityHandle,
+) -> bool {
+    let world = &mut *world;
+    let (types, data) = match (
+        array(component_types, component_count),
+        array(component_data, component_count),
+    ) {
+        (Some(types), Some(data)) => (types, data),
+        _ => return false,
+    };
+    if types.iter().collect::<HashSet<_>>().len() != types.len() {
+        return false;
+    }
+
+    let mut components = Vec::with_capacity(component_count);
+    for (type_id, ptr) in types.iter().zip(data.iter()) {
+        let type_id = ComponentTypeId::external(*type_id);
+        let meta = match world.registry.get(type_id) {
+            Some(registration) => *registration.meta(),
+            None => return false,
+        };
+        if ptr.is_null() && !meta.is_zero_sized() && entity_count > 0 {
+            return false;
+        }
+        components.push((type_id, meta, *ptr));
+    }
+
+    let entities = world.insert((), RawComponentSource::new(components, entity_count));
+    if !out_entities.is_null() {
+        for (i, entity) in entities.iter().enumerate() {
+            *out_entities.add(i) = EntityHandle::from(*entity);
+        }
+    }
+
+    true
+}
+
+/// Deletes an entity. Returns `false` if the entity was not alive.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_delete(world: *mut World, entity: EntityHandle) -> bool {
+    (&mut *world).delete(entity.into())
+}
+
+/// Determines if an entity is alive.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_is_alive(world: *const World, entity: EntityHandle) -> bool {
+    (&*world).is_alive(entity.into())
+}
+
+/// Gets a pointer to an entity's component, or null if the entity does not have the component.
+///
+/// The pointer is only valid until the world is next modified.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_get_component(
+    world: *const World,
+    entity: EntityHandle,
+    type_id: u32,
+) -> *const u8 {
+    match component_ptr(&*world, entity.into(), ComponentTypeId::external(type_id), false) {
+        Some(ptr) => ptr,
+        None => std::ptr::null(),
+    }
+}
+
+/// Overwrites an entity's component with the value at `data`.
+///
+/// Returns `false` if the entity does not have the component, or if `data` is null and the
+/// component is not zero sized.
+#[no_mangle]
+pub unsafe extern "C" fn legion_world_set_component(
+    world: *mut World,
+    entity: EntityHandle,
+    type_id: u32,
+    data: *const u8,
+) -> bool {
+    let world = &*world;
+    let type_id = ComponentTypeId::external(type_id);
+    let size = match world.registry.get(type_id) {
+        Some(registration) => registration.meta().size(),
+        None => return false,
+    };
+
+    if data.is_null() && size > 0 {
+        return false;
+    }
+
+    match component_ptr(world, entity.into(), type_id, true) {
+        Some(ptr) => {
+            if size > 0 {
+                std::ptr::copy_nonoverlapping(data, ptr, size);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Invokes `callback` for each chunk containing entities which have all of the given components.
+///
+/// All requested component types are borrowed mutably for the duration of each callback.
+///
+/// Returns `false` without invoking `callback` if a component type is requested more than once,
+/// or if `component_types` is null. Returns `false` if the query panics, such as when a component
+/// type is already borrowed.
+#[no_mangle]
+pub unsafe extern "C" fn legion_query_for_each(
+    world: *mut World,
+    component_types: *const u32,
+    component_count: usize,
+    callback: ChunkCallback,
+    user_data: *mut c_void,
+) -> bool {
+    let world = &mut *world;
+    let types = match array(component_types, component_count) {
+        Some(types) => types,
+        None => return false,
+    };
+    let types = types
+        .iter()
+        .map(|t| ComponentTypeId::external(*t))
+        .collect::<Vec<_>>();
+    if types.iter().collect::<HashSet<_>>().len() != types.len() {
+        return false;
+    }
+
+    // unwinding out of an `extern "C"` function is undefined behaviour
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+        query_for_each(world, &types, callback, user_data)
+    }))
+    .is_ok()
+}
+
+unsafe fn query_for_each(
+    world: &World,
+    types: &[ComponentTypeId],
+    callback: ChunkCallback,
+    user_data: *mut c_void,
+) {
+    let mut entities = Vec::new();
+    let mut pointers = Vec::with_capacity(types.len());
+    let mut borrows = Vec::with_capacity(types.len());
+
+    let archetypes = world.storage().archetypes().iter().filter(|archetype| {
+        let components = archetype.description().components();
+        types
+            .iter()
+            .all(|t| components.iter().any(|(c, _)| c == t))
+    });
+
+    for archetype in archetypes {
+        for chunk in archetype
+            .chunksets()
+            .iter()
+            .flat_map(|set| set.occupied())
+        {
+            if chunk.is_empty() {
+                continue;
+            }
+
+            entities.clear();
+            entities.extend(chunk.entities().iter().map(|e| EntityHandle::from(*e)));
+
+            for type_id in types.iter() {
+                let (ptr, _, _) = chunk.components(*type_id).unwrap().data_raw_mut();
+                pointers.push(*ptr);
+                borrows.push(ptr);
+            }
+
+            callback(user_data, entities.as_ptr(), pointers.as_ptr(), entities.len());
+
+            pointers.clear();
+            borrows.clear();
+        }
+    }
+}
+
+// Views a caller provided array, returning `None` if it is null but not empty.
+unsafe fn array<'a, T>(ptr: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(std::slice::from_raw_parts(ptr, len))
+    }
+}
+
+unsafe fn component_ptr(
+    world: &World,
+    entity: Entity,
+    type_id: ComponentTypeId,
+    write: bool,
+) -> Option<*mut u8> {
+    if !world.is_alive(entity) {
+        return None;
+    }
+
+    let location = world.entity_allocator.get_location(entity.index())?;
+    let archetype = world.storage().archetypes().get(location.archetype())?;
+    let chunk = archetype
+        .chunksets()
+        .get(location.set())?
+        .get(location.chunk())?;
+    let components = chunk.components(type_id)?;
+    let (ptr, element_size) = if write {
+        let (ptr, element_size, _) = components.data_raw_mut();
+        (*ptr, element_size)
+    } else {
+        let (ptr, element_size, _) = components.data_raw();
+        (*ptr, element_size)
+    };
+
+    Some(ptr.add(element_size * location.component()))
+}
+
+/// A `ComponentSource` which copies components out of caller provided arrays.
+struct RawComponentSource {
+    components: Vec<(ComponentTypeId, ComponentMeta, *const u8)>,
+    filter: RawComponentFilter,
+    count: usize,
+    written: usize,
+}
+
+impl RawComponentSource {
+    fn new(components: Vec<(ComponentTypeId, ComponentMeta, *const u8)>, count: usize) -> Self {
+        let types = components.iter().map(|(t, _, _)| *t).collect();
+        RawComponentSource {
+            components,
+            filter: RawComponentFilter { types },
+            count,
+            written: 0,
+        }
+    }
+}
+
+impl ComponentLayout for RawComponentSource {
+    type Filter = RawComponentFilter;
+
+    fn get_filter(&mut self) -> &mut Self::Filter { &mut self.filter }
+
+    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription) {
+        for (type_id, meta, _) in self.components.iter() {
+            archetype.register_component_raw(*type_id, *meta);
+        }
+    }
+}
+
+impl ComponentSource for RawComponentSource {
+    fn is_empty(&mut self) -> bool { self.written == self.count }
+
+    fn remaining(&mut self) -> Option<usize> { Some(self.count - self.written) }
+
+    fn write(&mut self, allocator: &mut EntityAllocator, chunk: &mut ComponentStorage) -> usize {
+        let count = (chunk.capacity() - chunk.len()).min(self.count - self.written);
+
+        let mut writer = chunk.writer();
+        let (entities, components) = writer.get();
+        for _ in 0..count {
+            entities.push(allocator.create_entity());
+        }
+
+        for (type_id, meta, ptr) in self.components.iter() {
+            unsafe {
+                let mut target = (&mut *components.get()).get_mut(*type_id).unwrap().writer();
+                let source = if meta.is_zero_sized() {
+                    meta.align() as *mut u8
+                } else {
+                    ptr.add(meta.size() * self.written) as *mut u8
+                };
+                target.push_raw(NonNull::new_unchecked(source), count);
+            }
+        }
+
+        self.written += count;
+        count
+    }
+}
+
+impl IntoComponentSource for RawComponentSource {
+    type Source = Self;
+
+    fn into(self) -> Self::Source { self }
+}
+
+/// An archetype filter which matches the layout of a `RawComponentSource`.
+struct RawComponentFilter {
+    types: Vec<ComponentTypeId>,
+}
+
+impl<'a> Filter<ArchetypeFilterData<'a>> for RawComponentFilter {
+    type Iter = ExactTypeSetIter<'a>;
+
+    fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
+        source.component_types.iter_exact(&self.types)
+    }
+
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> { Some(*item) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Vec2 {
+        x: f32,
+        y: f32,
+    }
+
+    const POS: u32 = 1;
+    const VEL: u32 = 2;
+
+    unsafe extern "C" fn integrate(
+        user_data: *mut c_void,
+        _: *const EntityHandle,
+        components: *const *mut u8,
+        count: usize,
+    ) {
+        let positions = std::slice::from_raw_parts_mut(*components as *mut Vec2, count);
+        let velocities = std::slice::from_raw_parts(*components.add(1) as *const Vec2, count);
+        for (pos, vel) in positions.iter_mut().zip(velocities.iter()) {
+            pos.x += vel.x;
+            pos.y += vel.y;
+        }
+        *(user_data as *mut usize) += count;
+    }
+
+    #[test]
+    fn insert_get_set() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+            assert!(legion_world_register_component(world, POS, 8, 4));
+
+            let positions = [Vec2 { x: 1., y: 2. }, Vec2 { x: 3., y: 4. }];
+            let types = [POS];
+            let data = [positions.as_ptr() as *const u8];
+            let mut entities = [EntityHandle {
+                index: 0,
+                version: 0,
+            }; 2];
+            assert!(legion_world_insert(
+                world,
+                types.as_ptr(),
+                data.as_ptr(),
+                1,
+                2,
+                entities.as_mut_ptr()
+            ));
+
+            let pos = legion_world_get_component(world, entities[1], POS) as *const Vec2;
+            assert_eq!(positions[1], *pos);
+            assert!(legion_world_get_component(world, entities[1], VEL).is_null());
+
+            let value = Vec2 { x: 5., y: 6. };
+            assert!(legion_world_set_component(
+                world,
+                entities[0],
+                POS,
+                &value as *const Vec2 as *const u8
+            ));
+            let pos = legion_world_get_component(world, entities[0], POS) as *const Vec2;
+            assert_eq!(value, *pos);
+
+            assert!(legion_world_delete(world, entities[0]));
+            assert!(!legion_world_is_alive(world, entities[0]));
+            assert!(legion_world_is_alive(world, entities[1]));
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+
+    #[test]
+    fn insert_unregistered() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+
+            let positions = [Vec2 { x: 1., y: 2. }];
+            let types = [POS];
+            let data = [positions.as_ptr() as *const u8];
+            assert!(!legion_world_insert(
+                world,
+                types.as_ptr(),
+                data.as_ptr(),
+                1,
+                1,
+                std::ptr::null_mut()
+            ));
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+
+    #[test]
+    fn query_for_each() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+            assert!(legion_world_register_component(world, POS, 8, 4));
+            assert!(legion_world_register_component(world, VEL, 8, 4));
+
+            let positions = vec![Vec2 { x: 0., y: 0. }; 2000];
+            let velocities = vec![Vec2 { x: 1., y: 2. }; 2000];
+            let types = [POS, VEL];
+            let data = [
+                positions.as_ptr() as *const u8,
+                velocities.as_ptr() as *const u8,
+            ];
+            let mut entities = vec![
+                EntityHandle {
+                    index: 0,
+                    version: 0
+                };
+                2000
+            ];
+            legion_world_insert(
+                world,
+                types.as_ptr(),
+                data.as_ptr(),
+                2,
+                2000,
+                entities.as_mut_ptr(),
+            );
+
+            let mut visited = 0usize;
+            assert!(legion_query_for_each(
+                world,
+                types.as_ptr(),
+                2,
+                integrate,
+                &mut visited as *mut usize as *mut c_void,
+            ));
+            assert_eq!(2000, visited);
+
+            for entity in entities {
+                let pos = legion_world_get_component(world, entity, POS) as *const Vec2;
+                assert_eq!(Vec2 { x: 1., y: 2. }, *pos);
+            }
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+
+    #[test]
+    fn register_invalid_layout() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+
+            assert!(!legion_world_register_component(world, POS, 8, 3));
+            assert!(!legion_world_register_component(world, POS, 6, 4));
+            assert!(legion_world_register_component(world, POS, 0, 1));
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+
+    #[test]
+    fn null_arrays() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+            assert!(legion_world_register_component(world, POS, 8, 4));
+
+            let mut entity = EntityHandle {
+                index: 0,
+                version: 0,
+            };
+            assert!(legion_world_insert(
+                world,
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                1,
+                &mut entity
+            ));
+            assert!(legion_world_is_alive(world, entity));
+            assert!(!legion_world_insert(
+                world,
+                std::ptr::null(),
+                std::ptr::null(),
+                1,
+                1,
+                std::ptr::null_mut()
+            ));
+
+            let types = [POS];
+            let data = [std::ptr::null()];
+            assert!(!legion_world_insert(
+                world,
+                types.as_ptr(),
+                data.as_ptr(),
+                1,
+                1,
+                std::ptr::null_mut()
+            ));
+
+            let mut visited = 0usize;
+            assert!(!legion_query_for_each(
+                world,
+                std::ptr::null(),
+                1,
+                integrate,
+                &mut visited as *mut usize as *mut c_void,
+            ));
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+
+    #[test]
+    fn query_duplicate_types() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+            assert!(legion_world_register_component(world, POS, 8, 4));
+
+            let positions = [Vec2 { x: 1., y: 2. }];
+            let types = [POS];
+            let data = [positions.as_ptr() as *const u8];
+            assert!(legion_world_insert(
+                world,
+                types.as_ptr(),
+                data.as_ptr(),
+                1,
+                1,
+                std::ptr::null_mut()
+            ));
+
+            let mut visited = 0usize;
+            let types = [POS, POS];
+            assert!(!legion_query_for_each(
+                world,
+                types.as_ptr(),
+                2,
+                integrate,
+                &mut visited as *mut usize as *mut c_void,
+            ));
+            assert_eq!(0, visited);
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+
+    #[test]
+    fn insert_duplicate_types() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        unsafe {
+            let universe = legion_universe_new();
+            let world = legion_world_new(universe);
+            assert!(legion_world_register_component(world, POS, 8, 4));
+
+            let positions = [Vec2 { x: 1., y: 2. }; 4];
+            let types = [POS, POS];
+            let data = [
+                positions.as_ptr() as *const u8,
+                positions.as_ptr() as *const u8,
+            ];
+            assert!(!legion_world_insert(
+                world,
+                types.as_ptr(),
+                data.as_ptr(),
+                2,
+                4,
+                std::ptr::null_mut()
+            ));
+            assert_eq!(0, (&*world).len());
+
+            legion_world_free(world);
+            legion_universe_free(universe);
+        }
+    }
+}
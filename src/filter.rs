@@ -1,8 +1,12 @@
+use crate::iterator::FissileIterator;
 use crate::iterator::FissileZip;
-use crate::iterator::SliceVecIter;
+use crate::storage::current_version;
 use crate::storage::ArchetypeData;
+use crate::storage::ArchetypeDescription;
 use crate::storage::ArchetypeId;
+use crate::storage::ChunkId;
 use crate::storage::Component;
+use crate::storage::ComponentIndex;
 use crate::storage::ComponentStorage;
 use crate::storage::ComponentTypeId;
 use crate::storage::ComponentTypes;
@@ -10,13 +14,18 @@ use crate::storage::Storage;
 use crate::storage::Tag;
 use crate::storage::TagTypeId;
 use crate::storage::TagTypes;
+use crate::storage::TypeSetIter;
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::iter::Enumerate;
 use std::iter::Repeat;
 use std::iter::Take;
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::slice::Iter;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 pub mod filter_fns {
     ///! Contains functions for constructing filters.
@@ -33,6 +42,66 @@ pub mod filter_fns {
         EntityFilterTuple::new(ComponentFilter::new(), Passthrough, Passthrough)
     }
 
+    /// Creates an entity data filter which includes chunks that contain entity data
+    /// components of every type in `types`.
+    ///
+    /// Unlike `component`, the component types may be chosen at runtime, such as when a
+    /// system is configured from a data file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::storage::ComponentTypeId;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Velocity(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(Position(0.0), Velocity(1.0))]);
+    /// world.insert((), vec![(Position(0.0),)]);
+    ///
+    /// let types = [ComponentTypeId::of::<Position>(), ComponentTypeId::of::<Velocity>()];
+    /// let mut moving = Read::<Position>::query().filter(contains_all(&types));
+    /// let mut exact = Read::<Position>::query().filter(exact(&types[..1]));
+    /// assert_eq!(1, moving.iter(&mut world).count());
+    /// assert_eq!(1, exact.iter(&mut world).count());
+    /// ```
+    pub fn contains_all(
+        types: &[ComponentTypeId],
+    ) -> EntityFilterTuple<ComponentSetFilter, Passthrough, Passthrough> {
+        EntityFilterTuple::new(
+            ComponentSetFilter::new(types, ComponentSetMatch::All),
+            Passthrough,
+            Passthrough,
+        )
+    }
+
+    /// Creates an entity data filter which includes chunks that contain entity data
+    /// components of none of the types in `types`.
+    pub fn contains_none(
+        types: &[ComponentTypeId],
+    ) -> EntityFilterTuple<ComponentSetFilter, Passthrough, Passthrough> {
+        EntityFilterTuple::new(
+            ComponentSetFilter::new(types, ComponentSetMatch::None),
+            Passthrough,
+            Passthrough,
+        )
+    }
+
+    /// Creates an entity data filter which includes chunks that contain entity data
+    /// components of exactly the types in `types`, and no others.
+    pub fn exact(
+        types: &[ComponentTypeId],
+    ) -> EntityFilterTuple<ComponentSetFilter, Passthrough, Passthrough> {
+        EntityFilterTuple::new(
+            ComponentSetFilter::new(types, ComponentSetMatch::Exact),
+            Passthrough,
+            Passthrough,
+        )
+    }
+
     /// Creates a shared data filter which includes chunks that contain
     /// shared data components of type `T`.
     pub fn tag<T: Tag>() -> EntityFilterTuple<TagFilter<T>, Passthrough, Passthrough> {
@@ -49,6 +118,9 @@ pub mod filter_fns {
 
     /// Creates a filter which includes chunks for which entity data components
     /// of type `T` have changed since the filter was last executed.
+    ///
+    /// Inserting entities, or moving entities into a chunk, counts as a change to the
+    /// chunk's components.
     pub fn changed<T: Component>(
     ) -> EntityFilterTuple<ComponentFilter<T>, Passthrough, ComponentChangedFilter<T>> {
         EntityFilterTuple::new(
@@ -57,6 +129,165 @@ pub mod filter_fns {
             ComponentChangedFilter::new(),
         )
     }
+
+    /// Creates a filter which includes chunks in which components of type `T` have been
+    /// attached to entities since the filter was last executed, either by inserting new
+    /// entities or by adding the component to existing entities.
+    ///
+    /// Unlike `changed`, modifying existing components does not cause a chunk to match. This
+    /// is useful for initialization systems, such as those creating GPU resources for newly
+    /// added meshes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Mesh(u32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Mesh(0),)])[0];
+    ///
+    /// let mut query = Read::<Mesh>::query().filter(added::<Mesh>());
+    /// assert_eq!(1, query.iter(&mut world).count());
+    ///
+    /// *world.get_component_mut::<Mesh>(entity).unwrap() = Mesh(1);
+    /// assert_eq!(0, query.iter(&mut world).count());
+    /// ```
+    pub fn added<T: Component>(
+    ) -> EntityFilterTuple<ComponentFilter<T>, Passthrough, ComponentAddedFilter<T>> {
+        EntityFilterTuple::new(
+            ComponentFilter::new(),
+            Passthrough,
+            ComponentAddedFilter::new(),
+        )
+    }
+
+    /// Creates a filter which includes chunks for which entity data components
+    /// of type `T` have changed since `reader` last observed them.
+    ///
+    /// Unlike `changed`, the observed changes are recorded in the reader rather than in
+    /// the filter, such that every query sharing the reader shares its baseline.
+    pub fn changed_for<T: Component>(
+        reader: &ChangeReader,
+    ) -> EntityFilterTuple<ComponentFilter<T>, Passthrough, ReaderChangedFilter<T>> {
+        EntityFilterTuple::new(
+            ComponentFilter::new(),
+            Passthrough,
+            ReaderChangedFilter::new(reader.clone()),
+        )
+    }
+
+    /// Creates a filter which includes chunks for which the version of entity data
+    /// components of type `T` satisfies `predicate`.
+    ///
+    /// Component versions are drawn from `storage::current_version`, and are updated
+    /// whenever the components in a chunk are mutably accessed. Unlike `changed`, this
+    /// filter does not record any state, so it can be used to implement custom
+    /// change tracking policies.
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::storage::current_version;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let frame_start = current_version();
+    /// world.insert((), vec![(Position(0.0),)]);
+    ///
+    /// // find chunks written to since the start of the frame
+    /// let mut query = Read::<Position>::query()
+    ///     .filter(version::<Position, _>(move |version| version > frame_start));
+    /// assert_eq!(1, query.iter(&mut world).count());
+    /// ```
+    pub fn version<T, P>(
+        predicate: P,
+    ) -> EntityFilterTuple<ComponentFilter<T>, Passthrough, ComponentVersionFilter<T, P>>
+    where
+        T: Component,
+        P: Fn(u64) -> bool + Send + Sync,
+    {
+        EntityFilterTuple::new(
+            ComponentFilter::new(),
+            Passthrough,
+            ComponentVersionFilter::new(predicate),
+        )
+    }
+
+    /// Creates an archetype filter from a closure, which includes the archetypes for which
+    /// `predicate` returns `true`.
+    ///
+    /// This is a lightweight alternative to implementing `Filter` for a custom filter type.
+    /// Every archetype is tested, so prefer `component` and `tag` where they suffice.
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::storage::ArchetypeDescription;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Velocity(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(Position(0.0), Velocity(1.0))]);
+    /// world.insert((), vec![(Position(0.0),)]);
+    ///
+    /// // only archetypes with few component types
+    /// let mut query = Read::<Position>::query()
+    ///     .filter(archetype_where(|arch: &ArchetypeDescription| arch.components().len() < 2));
+    /// assert_eq!(1, query.iter(&mut world).count());
+    /// ```
+    pub fn archetype_where<P>(
+        predicate: P,
+    ) -> EntityFilterTuple<ArchetypePredicateFilter<P>, Passthrough, Passthrough>
+    where
+        P: Fn(&ArchetypeDescription) -> bool + Send + Sync,
+    {
+        EntityFilterTuple::new(
+            ArchetypePredicateFilter::new(predicate),
+            Passthrough,
+            Passthrough,
+        )
+    }
+
+    /// Creates a chunk set filter from a closure, which includes the chunk sets whose tag
+    /// values satisfy `predicate`.
+    ///
+    /// This is a lightweight alternative to implementing `Filter` for a custom filter type,
+    /// such as to match a range of tag values rather than the single value matched by
+    /// `tag_value`.
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::filter::ChunkTagsView;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Layer(u32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((Layer(1),), vec![(Position(0.0),)]);
+    /// world.insert((Layer(5),), vec![(Position(0.0),)]);
+    /// world.insert((), vec![(Position(0.0),)]);
+    ///
+    /// let mut query = Read::<Position>::query().filter(chunkset_where(|tags: &ChunkTagsView| {
+    ///     tags.get::<Layer>().map(|layer| layer.0 > 2).unwrap_or(false)
+    /// }));
+    /// assert_eq!(1, query.iter(&mut world).count());
+    /// ```
+    pub fn chunkset_where<P>(
+        predicate: P,
+    ) -> EntityFilterTuple<Passthrough, ChunksetPredicateFilter<P>, Passthrough>
+    where
+        P: Fn(&ChunkTagsView) -> bool + Send + Sync,
+    {
+        EntityFilterTuple::new(
+            Passthrough,
+            ChunksetPredicateFilter::new(predicate),
+            Passthrough,
+        )
+    }
 }
 
 pub(crate) trait FilterResult {
@@ -96,6 +327,18 @@ pub trait Filter<T: Copy>: Send + Sync + Sized {
     /// Determines if an element of `Self::Iter` matches the filter conditions.
     fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool>;
 
+    /// Gets the indexes of the only archetypes which could pass this filter, if the filter
+    /// can determine them from the component index. Returns `None` if all archetypes must
+    /// be tested.
+    #[inline]
+    fn candidates<'i>(&self, _index: &'i ComponentIndex) -> Option<&'i [usize]> { None }
+
+    /// Called when a query begins a new pass over its results, before any elements are tested.
+    /// Filters which match changes made since their previous pass use this to advance their
+    /// baseline.
+    #[inline]
+    fn begin_pass(&mut self) {}
+
     /// Creates an iterator which yields bools for each element in the source
     /// which indicate if the element matches the filter.
     fn matches(&mut self, source: T) -> FilterIter<Self, T> {
@@ -107,6 +350,21 @@ pub trait Filter<T: Copy>: Send + Sync + Sized {
     }
 }
 
+/// Notifies each of an entity filter's inner filters that a query is beginning a new pass.
+pub(crate) fn begin_pass<A, S, C>(
+    arch_filter: &mut A,
+    chunkset_filter: &mut S,
+    chunk_filter: &mut C,
+) where
+    A: for<'a> Filter<ArchetypeFilterData<'a>>,
+    S: for<'a> Filter<ChunksetFilterData<'a>>,
+    C: for<'a> Filter<ChunkFilterData<'a>>,
+{
+    Filter::<ArchetypeFilterData<'_>>::begin_pass(arch_filter);
+    Filter::<ChunksetFilterData<'_>>::begin_pass(chunkset_filter);
+    Filter::<ChunkFilterData<'_>>::begin_pass(chunk_filter);
+}
+
 /// An iterator over the elements matching a filter.
 pub struct FilterIter<'a, F: Filter<T>, T: Copy> {
     elements: <F as Filter<T>>::Iter,
@@ -138,6 +396,8 @@ pub struct ArchetypeFilterData<'a> {
     pub component_types: &'a ComponentTypes,
     /// The tag types in each archetype.
     pub tag_types: &'a TagTypes,
+    /// The archetypes themselves.
+    pub archetypes: &'a [ArchetypeData],
 }
 
 /// Input data for chunkset filters.
@@ -265,11 +525,13 @@ where
         let data = ArchetypeFilterData {
             component_types: storage.component_types(),
             tag_types: storage.tag_types(),
+            archetypes: storage.archetypes(),
         };
 
         let iter = self.arch_filter.collect(data);
+        let candidates = self.arch_filter.candidates(storage.component_index());
         FilterArchIter {
-            archetypes: iter.enumerate(),
+            archetypes: ArchetypeCursor::new(iter, candidates),
             filter: &mut self.arch_filter,
         }
     }
@@ -293,14 +555,16 @@ where
         let data = ArchetypeFilterData {
             component_types: storage.component_types(),
             tag_types: storage.tag_types(),
+            archetypes: storage.archetypes(),
         };
 
-        let iter = self.arch_filter.collect(data).enumerate();
+        let iter = self.arch_filter.collect(data);
+        let candidates = self.arch_filter.candidates(storage.component_index());
         FilterEntityIter {
             storage,
             arch_filter: &mut self.arch_filter,
             chunk_filter: &mut self.chunkset_filter,
-            archetypes: iter,
+            archetypes: ArchetypeCursor::new(iter, candidates),
             chunks: None,
         }
     }
@@ -362,17 +626,51 @@ where
     }
 }
 
+/// Walks the archetypes of a storage, visiting only those listed in the component index
+/// candidates when the filter provides them.
+pub(crate) struct ArchetypeCursor<'a, I: Iterator> {
+    archetypes: Enumerate<I>,
+    candidates: Option<Iter<'a, usize>>,
+    next: usize,
+}
+
+impl<'a, I: Iterator> ArchetypeCursor<'a, I> {
+    pub(crate) fn new(archetypes: I, candidates: Option<&'a [usize]>) -> Self {
+        Self {
+            archetypes: archetypes.enumerate(),
+            candidates: candidates.map(|c| c.iter()),
+            next: 0,
+        }
+    }
+}
+
+impl<'a, I: Iterator> Iterator for ArchetypeCursor<'a, I> {
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.candidates {
+            Some(ref mut candidates) => {
+                let index = *candidates.next()?;
+                let skip = index - self.next;
+                self.next = index + 1;
+                self.archetypes.nth(skip)
+            }
+            None => self.archetypes.next(),
+        }
+    }
+}
+
 /// An iterator which yields the indexes of archetypes that match a filter.
 pub struct FilterArchIter<'a, 'b, F: Filter<ArchetypeFilterData<'a>>> {
     filter: &'b mut F,
-    archetypes: Enumerate<F::Iter>,
+    archetypes: ArchetypeCursor<'a, F::Iter>,
 }
 
 impl<'a, 'b, F: Filter<ArchetypeFilterData<'a>>> Iterator for FilterArchIter<'a, 'b, F> {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((i, data)) = self.archetypes.next() {
+        for (i, data) in &mut self.archetypes {
             if self.filter.is_match(&data).is_pass() {
                 return Some(i);
             }
@@ -412,7 +710,7 @@ pub struct FilterEntityIter<
     storage: &'a Storage,
     arch_filter: &'b mut Arch,
     chunk_filter: &'b mut Chunk,
-    archetypes: Enumerate<Arch::Iter>,
+    archetypes: ArchetypeCursor<'a, Arch::Iter>,
     chunks: Option<(ArchetypeId, Enumerate<Chunk::Iter>)>,
 }
 
@@ -532,6 +830,9 @@ impl<'a, T: Copy, F: Filter<T>> Filter<T> for Not<F> {
     fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
         self.filter.is_match(item).map(|x| !x)
     }
+
+    #[inline]
+    fn begin_pass(&mut self) { self.filter.begin_pass() }
 }
 
 impl<'a, F, Rhs: ActiveFilter> std::ops::BitAnd<Rhs> for Not<F> {
@@ -588,6 +889,14 @@ impl<'a, T: Copy, F: Filter<T>> Filter<T> for And<(F,)> {
     fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
         self.filters.0.is_match(item)
     }
+
+    #[inline]
+    fn candidates<'i>(&self, index: &'i ComponentIndex) -> Option<&'i [usize]> {
+        self.filters.0.candidates(index)
+    }
+
+    #[inline]
+    fn begin_pass(&mut self) { self.filters.0.begin_pass() }
 }
 
 impl<T> std::ops::Not for And<(T,)> {
@@ -670,6 +979,26 @@ macro_rules! impl_and_filter {
                 $( result = result.coalesce_and($ty.is_match($ty2)); )*
                 result
             }
+
+            fn candidates<'i>(&self, index: &'i ComponentIndex) -> Option<&'i [usize]> {
+                #![allow(non_snake_case)]
+                let ($( $ty, )*) = &self.filters;
+                let mut result: Option<&'i [usize]> = None;
+                $(
+                    if let Some(candidates) = $ty.candidates(index) {
+                        if result.map(|r| candidates.len() < r.len()).unwrap_or(true) {
+                            result = Some(candidates);
+                        }
+                    }
+                )*
+                result
+            }
+
+            fn begin_pass(&mut self) {
+                #![allow(non_snake_case)]
+                let ($( $ty, )*) = &mut self.filters;
+                $( $ty.begin_pass(); )*
+            }
         }
 
         impl<$( $ty ),*> std::ops::Not for And<($( $ty, )*)> {
@@ -765,6 +1094,12 @@ macro_rules! impl_or_filter {
                 $( result = result.coalesce_or($ty.is_match($ty2)); )*
                 result
             }
+
+            fn begin_pass(&mut self) {
+                #![allow(non_snake_case)]
+                let ($( $ty, )*) = &mut self.filters;
+                $( $ty.begin_pass(); )*
+            }
         }
 
         impl<$( $ty ),*> std::ops::Not for Or<($( $ty, )*)> {
@@ -837,7 +1172,7 @@ impl<T: Component> ComponentFilter<T> {
 impl<T> ActiveFilter for ComponentFilter<T> {}
 
 impl<'a, T: Component> Filter<ArchetypeFilterData<'a>> for ComponentFilter<T> {
-    type Iter = SliceVecIter<'a, ComponentTypeId>;
+    type Iter = TypeSetIter<'a, ComponentTypeId>;
 
     #[inline]
     fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
@@ -848,6 +1183,11 @@ impl<'a, T: Component> Filter<ArchetypeFilterData<'a>> for ComponentFilter<T> {
     fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
         Some(item.contains(&ComponentTypeId::of::<T>()))
     }
+
+    #[inline]
+    fn candidates<'i>(&self, index: &'i ComponentIndex) -> Option<&'i [usize]> {
+        Some(index.archetypes(ComponentTypeId::of::<T>()))
+    }
 }
 
 impl<T> std::ops::Not for ComponentFilter<T> {
@@ -893,6 +1233,119 @@ impl<'a, T> std::ops::BitOr<Passthrough> for ComponentFilter<T> {
     fn bitor(self, _: Passthrough) -> Self::Output { self }
 }
 
+/// Describes how a `ComponentSetFilter` compares an archetype's component types to its set.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ComponentSetMatch {
+    /// The archetype contains every component type in the set.
+    All,
+    /// The archetype contains none of the component types in the set.
+    None,
+    /// The archetype contains exactly the component types in the set.
+    Exact,
+}
+
+/// A filter which compares the component types of each archetype to a set of component types
+/// chosen at runtime.
+#[derive(Debug, Clone)]
+pub struct ComponentSetFilter {
+    types: Vec<ComponentTypeId>,
+    mode: ComponentSetMatch,
+}
+
+impl ComponentSetFilter {
+    fn new(types: &[ComponentTypeId], mode: ComponentSetMatch) -> Self {
+        let mut unique = Vec::with_capacity(types.len());
+        for type_id in types {
+            if !unique.contains(type_id) {
+                unique.push(*type_id);
+            }
+        }
+
+        ComponentSetFilter {
+            types: unique,
+            mode,
+        }
+    }
+}
+
+impl ActiveFilter for ComponentSetFilter {}
+
+impl<'a> Filter<ArchetypeFilterData<'a>> for ComponentSetFilter {
+    type Iter = TypeSetIter<'a, ComponentTypeId>;
+
+    #[inline]
+    fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
+        source.component_types.iter()
+    }
+
+    #[inline]
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        let matched = match self.mode {
+            ComponentSetMatch::All => self.types.iter().all(|t| item.contains(t)),
+            ComponentSetMatch::None => !self.types.iter().any(|t| item.contains(t)),
+            ComponentSetMatch::Exact => {
+                item.len() == self.types.len() && self.types.iter().all(|t| item.contains(t))
+            }
+        };
+        Some(matched)
+    }
+
+    #[inline]
+    fn candidates<'i>(&self, index: &'i ComponentIndex) -> Option<&'i [usize]> {
+        if self.mode == ComponentSetMatch::None {
+            return None;
+        }
+
+        self.types
+            .iter()
+            .map(|type_id| index.archetypes(*type_id))
+            .min_by_key(|candidates| candidates.len())
+    }
+}
+
+impl std::ops::Not for ComponentSetFilter {
+    type Output = Not<Self>;
+
+    #[inline]
+    fn not(self) -> Self::Output { Not { filter: self } }
+}
+
+impl<Rhs: ActiveFilter> std::ops::BitAnd<Rhs> for ComponentSetFilter {
+    type Output = And<(Self, Rhs)>;
+
+    #[inline]
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        And {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl std::ops::BitAnd<Passthrough> for ComponentSetFilter {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, _: Passthrough) -> Self::Output { self }
+}
+
+impl<Rhs: ActiveFilter> std::ops::BitOr<Rhs> for ComponentSetFilter {
+    type Output = Or<(Self, Rhs)>;
+
+    #[inline]
+    fn bitor(self, rhs: Rhs) -> Self::Output {
+        Or {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl std::ops::BitOr<Passthrough> for ComponentSetFilter {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, _: Passthrough) -> Self::Output { self }
+}
+
 /// A filter which requires that all chunks contain shared tag data of type `T`.
 #[derive(Debug, Clone)]
 pub struct TagFilter<T>(PhantomData<T>);
@@ -904,7 +1357,7 @@ impl<T: Tag> TagFilter<T> {
 impl<T> ActiveFilter for TagFilter<T> {}
 
 impl<'a, T: Tag> Filter<ArchetypeFilterData<'a>> for TagFilter<T> {
-    type Iter = SliceVecIter<'a, TagTypeId>;
+    type Iter = TypeSetIter<'a, TagTypeId>;
 
     #[inline]
     fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter { source.tag_types.iter() }
@@ -974,15 +1427,14 @@ impl<'a, 'b, T: Tag> Filter<ChunksetFilterData<'a>> for TagValueFilter<'b, T> {
     type Iter = Iter<'a, T>;
 
     fn collect(&self, source: ChunksetFilterData<'a>) -> Self::Iter {
-        unsafe {
-            source
-                .archetype_data
-                .tags()
-                .get(TagTypeId::of::<T>())
-                .unwrap()
-                .data_slice::<T>()
-                .iter()
-        }
+        source
+            .archetype_data
+            .tags()
+            .get(TagTypeId::of::<T>())
+            .and_then(|tags| tags.downcast::<T>())
+            .unwrap()
+            .as_slice()
+            .iter()
     }
 
     #[inline]
@@ -1036,16 +1488,29 @@ impl<'a, T> std::ops::BitOr<Passthrough> for TagValueFilter<'a, T> {
 
 /// A filter which requires that entity data of type `T` has changed within the
 /// chunk since the last time the filter was executed.
+///
+/// Component versions are drawn from a world-wide clock (see `storage::current_version`). Each
+/// chunk records the version at which its `T` components were last written, and the filter
+/// records the version at which each of its passes began. A chunk matches if it was written
+/// since the filter's previous pass began, such that every change is observed regardless of
+/// the order in which chunks are visited, or of how many other filters observe the same
+/// changes. The filter holds no per-chunk state, so checking a chunk takes no locks.
+///
+/// Chunks written during a pass after being checked match again in the next pass.
 #[derive(Debug)]
 pub struct ComponentChangedFilter<T: Component> {
-    last_read_version: AtomicU64,
+    // the component version at which the previous pass began
+    last_pass: u64,
+    // the component version at which the current pass began
+    this_pass: u64,
     phantom: PhantomData<T>,
 }
 
 impl<T: Component> ComponentChangedFilter<T> {
     fn new() -> ComponentChangedFilter<T> {
         ComponentChangedFilter {
-            last_read_version: AtomicU64::new(0),
+            last_pass: 0,
+            this_pass: 0,
             phantom: PhantomData,
         }
     }
@@ -1061,30 +1526,12 @@ impl<'a, T: Component> Filter<ChunkFilterData<'a>> for ComponentChangedFilter<T>
     #[inline]
     fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
         let components = item.components(ComponentTypeId::of::<T>()).unwrap();
-        let version = components.version();
-        let mut last_read = self.last_read_version.load(Ordering::Relaxed);
-        if last_read < version {
-            loop {
-                match self.last_read_version.compare_exchange_weak(
-                    last_read,
-                    version,
-                    Ordering::Relaxed,
-                    Ordering::Relaxed,
-                ) {
-                    Ok(_) => break,
-                    Err(stored_last_read) => {
-                        last_read = stored_last_read;
-                        if last_read < version {
-                            // matched version is already considered visited, update no longer needed
-                            break;
-                        }
-                    }
-                }
-            }
-            Some(true)
-        } else {
-            Some(false)
-        }
+        Some(components.version() > self.last_pass)
+    }
+
+    fn begin_pass(&mut self) {
+        self.last_pass = self.this_pass;
+        self.this_pass = current_version();
     }
 }
 
@@ -1131,6 +1578,531 @@ impl<'a, T: Component> std::ops::BitOr<Passthrough> for ComponentChangedFilter<T
     fn bitor(self, _: Passthrough) -> Self::Output { self }
 }
 
+/// A filter which requires that components of type `T` have been attached to entities within
+/// the chunk since the last time the filter was executed.
+///
+/// Each chunk records the version at which `T` components were last attached to its entities,
+/// which is compared against the version at which the filter's previous pass began, as with
+/// `ComponentChangedFilter`. Modifications of existing components are not observed.
+#[derive(Debug)]
+pub struct ComponentAddedFilter<T: Component> {
+    // the component version at which the previous pass began
+    last_pass: u64,
+    // the component version at which the current pass began
+    this_pass: u64,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Component> ComponentAddedFilter<T> {
+    fn new() -> ComponentAddedFilter<T> {
+        ComponentAddedFilter {
+            last_pass: 0,
+            this_pass: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> ActiveFilter for ComponentAddedFilter<T> {}
+
+impl<'a, T: Component> Filter<ChunkFilterData<'a>> for ComponentAddedFilter<T> {
+    type Iter = Iter<'a, ComponentStorage>;
+
+    fn collect(&self, source: ChunkFilterData<'a>) -> Self::Iter { source.chunks.iter() }
+
+    #[inline]
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        let components = item.components(ComponentTypeId::of::<T>()).unwrap();
+        Some(components.added_version() > self.last_pass)
+    }
+
+    fn begin_pass(&mut self) {
+        self.last_pass = self.this_pass;
+        self.this_pass = current_version();
+    }
+}
+
+impl<T: Component> std::ops::Not for ComponentAddedFilter<T> {
+    type Output = Not<Self>;
+
+    #[inline]
+    fn not(self) -> Self::Output { Not { filter: self } }
+}
+
+impl<T: Component, Rhs: ActiveFilter> std::ops::BitAnd<Rhs> for ComponentAddedFilter<T> {
+    type Output = And<(Self, Rhs)>;
+
+    #[inline]
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        And {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<T: Component> std::ops::BitAnd<Passthrough> for ComponentAddedFilter<T> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, _: Passthrough) -> Self::Output { self }
+}
+
+impl<T: Component, Rhs: ActiveFilter> std::ops::BitOr<Rhs> for ComponentAddedFilter<T> {
+    type Output = Or<(Self, Rhs)>;
+
+    #[inline]
+    fn bitor(self, rhs: Rhs) -> Self::Output {
+        Or {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<T: Component> std::ops::BitOr<Passthrough> for ComponentAddedFilter<T> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, _: Passthrough) -> Self::Output { self }
+}
+
+/// A named consumer of component changes, which tracks the changes it has observed
+/// independently of all other readers. Created by `World::change_reader`.
+///
+/// Readers are cheap to clone, and all clones share the same observed state.
+#[derive(Debug, Clone)]
+pub struct ChangeReader {
+    name: Arc<str>,
+    state: Arc<ChangeReaderState>,
+}
+
+#[derive(Debug, Default)]
+struct ChangeReaderState {
+    // all versions up to and including the baseline have been observed
+    baseline: AtomicU64,
+    // the last observed version of each chunk's components, where newer than the baseline.
+    // Readers are used by filters, which must be `Send` and `Sync` in every build.
+    observed: Mutex<HashMap<(ChunkId, ComponentTypeId), u64>>,
+}
+
+impl ChangeReader {
+    pub(crate) fn new(name: &str) -> Self {
+        ChangeReader {
+            name: Arc::from(name),
+            state: Arc::new(ChangeReaderState::default()),
+        }
+    }
+
+    /// Gets the name the reader was registered with.
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Determines if the chunk's components of the given type have changed since the reader
+    /// last observed them, without marking the changes as observed.
+    pub fn is_changed(&self, chunk: &ComponentStorage, type_id: ComponentTypeId) -> bool {
+        match chunk.components(type_id) {
+            Some(components) => components.version() > self.last_observed(chunk.id(), type_id),
+            None => false,
+        }
+    }
+
+    /// Marks all changes made so far as observed by this reader.
+    pub fn mark_unchanged(&self) {
+        let mut observed = self.state.observed.lock();
+        self.state
+            .baseline
+            .store(current_version(), Ordering::Relaxed);
+        observed.clear();
+    }
+
+    fn last_observed(&self, chunk: ChunkId, type_id: ComponentTypeId) -> u64 {
+        let observed = self.state.observed.lock();
+        let baseline = self.state.baseline.load(Ordering::Relaxed);
+        observed
+            .get(&(chunk, type_id))
+            .map_or(baseline, |version| baseline.max(*version))
+    }
+
+    // Determines if the chunk's components have changed, and marks the changes as observed.
+    fn observe(&self, chunk: &ComponentStorage, type_id: ComponentTypeId) -> bool {
+        let version = chunk.components(type_id).unwrap().version();
+        let mut observed = self.state.observed.lock();
+        let baseline = self.state.baseline.load(Ordering::Relaxed);
+        let last = observed.entry((chunk.id(), type_id)).or_insert(baseline);
+        if (*last).max(baseline) < version {
+            *last = version;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A filter which requires that entity data of type `T` has changed within the
+/// chunk since a `ChangeReader` last observed it.
+#[derive(Debug)]
+pub struct ReaderChangedFilter<T: Component> {
+    reader: ChangeReader,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Component> ReaderChangedFilter<T> {
+    fn new(reader: ChangeReader) -> ReaderChangedFilter<T> {
+        ReaderChangedFilter {
+            reader,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> ActiveFilter for ReaderChangedFilter<T> {}
+
+impl<'a, T: Component> Filter<ChunkFilterData<'a>> for ReaderChangedFilter<T> {
+    type Iter = Iter<'a, ComponentStorage>;
+
+    fn collect(&self, source: ChunkFilterData<'a>) -> Self::Iter { source.chunks.iter() }
+
+    #[inline]
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        Some(self.reader.observe(item, ComponentTypeId::of::<T>()))
+    }
+}
+
+impl<T: Component> std::ops::Not for ReaderChangedFilter<T> {
+    type Output = Not<Self>;
+
+    #[inline]
+    fn not(self) -> Self::Output { Not { filter: self } }
+}
+
+impl<T: Component, Rhs: ActiveFilter> std::ops::BitAnd<Rhs> for ReaderChangedFilter<T> {
+    type Output = And<(Self, Rhs)>;
+
+    #[inline]
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        And {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<T: Component> std::ops::BitAnd<Passthrough> for ReaderChangedFilter<T> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, _: Passthrough) -> Self::Output { self }
+}
+
+impl<T: Component, Rhs: ActiveFilter> std::ops::BitOr<Rhs> for ReaderChangedFilter<T> {
+    type Output = Or<(Self, Rhs)>;
+
+    #[inline]
+    fn bitor(self, rhs: Rhs) -> Self::Output {
+        Or {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<T: Component> std::ops::BitOr<Passthrough> for ReaderChangedFilter<T> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, _: Passthrough) -> Self::Output { self }
+}
+
+/// A filter which requires that the version of entity data of type `T` within the
+/// chunk satisfies a predicate.
+pub struct ComponentVersionFilter<T: Component, P> {
+    predicate: P,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Component, P: Fn(u64) -> bool + Send + Sync> ComponentVersionFilter<T, P> {
+    fn new(predicate: P) -> ComponentVersionFilter<T, P> {
+        ComponentVersionFilter {
+            predicate,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Component, P> std::fmt::Debug for ComponentVersionFilter<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ComponentVersionFilter<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T: Component, P> ActiveFilter for ComponentVersionFilter<T, P> {}
+
+impl<'a, T: Component, P: Fn(u64) -> bool + Send + Sync> Filter<ChunkFilterData<'a>>
+    for ComponentVersionFilter<T, P>
+{
+    type Iter = Iter<'a, ComponentStorage>;
+
+    fn collect(&self, source: ChunkFilterData<'a>) -> Self::Iter { source.chunks.iter() }
+
+    #[inline]
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        let components = item.components(ComponentTypeId::of::<T>()).unwrap();
+        Some((self.predicate)(components.version()))
+    }
+}
+
+impl<T: Component, P> std::ops::Not for ComponentVersionFilter<T, P> {
+    type Output = Not<Self>;
+
+    #[inline]
+    fn not(self) -> Self::Output { Not { filter: self } }
+}
+
+impl<T: Component, P, Rhs: ActiveFilter> std::ops::BitAnd<Rhs>
+    for ComponentVersionFilter<T, P>
+{
+    type Output = And<(Self, Rhs)>;
+
+    #[inline]
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        And {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<T: Component, P> std::ops::BitAnd<Passthrough> for ComponentVersionFilter<T, P> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, _: Passthrough) -> Self::Output { self }
+}
+
+impl<T: Component, P, Rhs: ActiveFilter> std::ops::BitOr<Rhs>
+    for ComponentVersionFilter<T, P>
+{
+    type Output = Or<(Self, Rhs)>;
+
+    #[inline]
+    fn bitor(self, rhs: Rhs) -> Self::Output {
+        Or {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<T: Component, P> std::ops::BitOr<Passthrough> for ComponentVersionFilter<T, P> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, _: Passthrough) -> Self::Output { self }
+}
+
+/// A filter which includes the archetypes for which a predicate returns `true`.
+pub struct ArchetypePredicateFilter<P> {
+    predicate: P,
+}
+
+impl<P: Fn(&ArchetypeDescription) -> bool + Send + Sync> ArchetypePredicateFilter<P> {
+    fn new(predicate: P) -> Self { ArchetypePredicateFilter { predicate } }
+}
+
+impl<P> std::fmt::Debug for ArchetypePredicateFilter<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ArchetypePredicateFilter")
+    }
+}
+
+impl<P> ActiveFilter for ArchetypePredicateFilter<P> {}
+
+impl<'a, P: Fn(&ArchetypeDescription) -> bool + Send + Sync> Filter<ArchetypeFilterData<'a>>
+    for ArchetypePredicateFilter<P>
+{
+    type Iter = Iter<'a, ArchetypeData>;
+
+    fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter { source.archetypes.iter() }
+
+    #[inline]
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        Some((self.predicate)(item.description()))
+    }
+}
+
+impl<P> std::ops::Not for ArchetypePredicateFilter<P> {
+    type Output = Not<Self>;
+
+    #[inline]
+    fn not(self) -> Self::Output { Not { filter: self } }
+}
+
+impl<P, Rhs: ActiveFilter> std::ops::BitAnd<Rhs> for ArchetypePredicateFilter<P> {
+    type Output = And<(Self, Rhs)>;
+
+    #[inline]
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        And {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<P> std::ops::BitAnd<Passthrough> for ArchetypePredicateFilter<P> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, _: Passthrough) -> Self::Output { self }
+}
+
+impl<P, Rhs: ActiveFilter> std::ops::BitOr<Rhs> for ArchetypePredicateFilter<P> {
+    type Output = Or<(Self, Rhs)>;
+
+    #[inline]
+    fn bitor(self, rhs: Rhs) -> Self::Output {
+        Or {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<P> std::ops::BitOr<Passthrough> for ArchetypePredicateFilter<P> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, _: Passthrough) -> Self::Output { self }
+}
+
+/// The tag values attached to a chunk set, as passed to `chunkset_where` predicates.
+#[derive(Copy, Clone)]
+pub struct ChunkTagsView<'a> {
+    archetype: &'a ArchetypeData,
+    set: usize,
+}
+
+impl<'a> ChunkTagsView<'a> {
+    /// Gets the index of the chunk set within its archetype.
+    pub fn index(&self) -> usize { self.set }
+
+    /// Gets the description of the chunk set's archetype.
+    pub fn archetype(&self) -> &'a ArchetypeDescription { self.archetype.description() }
+
+    /// Gets the chunk set's value of tag type `T`, if its archetype has such a tag.
+    pub fn get<T: Tag>(&self) -> Option<&'a T> {
+        self.archetype
+            .tags()
+            .get(TagTypeId::of::<T>())
+            .and_then(|tags| tags.downcast::<T>())
+            .map(|tags| &tags.as_slice()[self.set])
+    }
+}
+
+/// An iterator over the tag values of each chunk set in an archetype.
+pub struct ChunkTagsIter<'a> {
+    archetype: &'a ArchetypeData,
+    sets: Range<usize>,
+}
+
+impl<'a> Iterator for ChunkTagsIter<'a> {
+    type Item = ChunkTagsView<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let archetype = self.archetype;
+        self.sets.next().map(|set| ChunkTagsView { archetype, set })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.sets.size_hint() }
+}
+
+impl<'a> FissileIterator for ChunkTagsIter<'a> {
+    fn split(self) -> (Self, Self, usize) {
+        let mid = self.sets.start + self.sets.len() / 2;
+        (
+            ChunkTagsIter {
+                archetype: self.archetype,
+                sets: self.sets.start..mid,
+            },
+            ChunkTagsIter {
+                archetype: self.archetype,
+                sets: mid..self.sets.end,
+            },
+            mid - self.sets.start,
+        )
+    }
+}
+
+/// A filter which includes the chunk sets whose tag values satisfy a predicate.
+pub struct ChunksetPredicateFilter<P> {
+    predicate: P,
+}
+
+impl<P: Fn(&ChunkTagsView) -> bool + Send + Sync> ChunksetPredicateFilter<P> {
+    fn new(predicate: P) -> Self { ChunksetPredicateFilter { predicate } }
+}
+
+impl<P> std::fmt::Debug for ChunksetPredicateFilter<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ChunksetPredicateFilter")
+    }
+}
+
+impl<P> ActiveFilter for ChunksetPredicateFilter<P> {}
+
+impl<'a, P: Fn(&ChunkTagsView) -> bool + Send + Sync> Filter<ChunksetFilterData<'a>>
+    for ChunksetPredicateFilter<P>
+{
+    type Iter = ChunkTagsIter<'a>;
+
+    fn collect(&self, source: ChunksetFilterData<'a>) -> Self::Iter {
+        ChunkTagsIter {
+            archetype: source.archetype_data,
+            sets: 0..source.archetype_data.len(),
+        }
+    }
+
+    #[inline]
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
+        Some((self.predicate)(item))
+    }
+}
+
+impl<P> std::ops::Not for ChunksetPredicateFilter<P> {
+    type Output = Not<Self>;
+
+    #[inline]
+    fn not(self) -> Self::Output { Not { filter: self } }
+}
+
+impl<P, Rhs: ActiveFilter> std::ops::BitAnd<Rhs> for ChunksetPredicateFilter<P> {
+    type Output = And<(Self, Rhs)>;
+
+    #[inline]
+    fn bitand(self, rhs: Rhs) -> Self::Output {
+        And {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<P> std::ops::BitAnd<Passthrough> for ChunksetPredicateFilter<P> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, _: Passthrough) -> Self::Output { self }
+}
+
+impl<P, Rhs: ActiveFilter> std::ops::BitOr<Rhs> for ChunksetPredicateFilter<P> {
+    type Output = Or<(Self, Rhs)>;
+
+    #[inline]
+    fn bitor(self, rhs: Rhs) -> Self::Output {
+        Or {
+            filters: (self, rhs),
+        }
+    }
+}
+
+impl<P> std::ops::BitOr<Passthrough> for ChunksetPredicateFilter<P> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, _: Passthrough) -> Self::Output { self }
+}
+
 #[cfg(test)]
 mod test {
     use super::filter_fns::*;
@@ -0,0 +1,205 @@
+//! Snapshots of component values for interpolation between simulation ticks.
+//!
+//! When the simulation runs at a fixed timestep, rendering may occur part way between two
+//! ticks. Implement `Interpolate` for the components to be rendered (e.g. transforms) and enable
+//! their history with `World::track_snapshots`. Call `World::capture_snapshots` at the end of
+//! each tick, and `World::sample_interpolated` to blend the two most recent ticks when rendering.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! use legion::interpolate::Interpolate;
+//!
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! struct Position(f32);
+//!
+//! impl Interpolate for Position {
+//!     fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+//!         Position(self.0 + (next.0 - self.0) * alpha)
+//!     }
+//! }
+//!
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! world.track_snapshots::<Position>(2);
+//! let entity = world.insert((), vec![(Position(0.0),)])[0];
+//! world.capture_snapshots();
+//!
+//! *world.get_component_mut::<Position>(entity).unwrap() = Position(10.0);
+//! world.capture_snapshots();
+//!
+//! assert_eq!(vec![(entity, Position(2.5))], world.sample_interpolated::<Position>(0.25));
+//! ```
+use crate::entity::Entity;
+use crate::storage::Component;
+use crate::storage::ComponentTypeId;
+use crate::storage::Storage;
+use std::any::Any;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+/// A component whose values can be blended between two simulation ticks.
+pub trait Interpolate: Component + Clone {
+    /// Blends between `self` and `next`, where an `alpha` of `0.0` produces `self` and an
+    /// `alpha` of `1.0` produces `next`.
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self;
+}
+
+/// The captured history of a single component type.
+pub(crate) trait SnapshotHistory: Send + Sync {
+    /// Records the current value of every component of the tracked type.
+    fn capture(&mut self, storage: &Storage);
+
+    /// Discards all recorded snapshots.
+    fn clear(&mut self);
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// A ring buffer of the most recent snapshots of component type `T`.
+pub(crate) struct Snapshots<T: Interpolate> {
+    capacity: usize,
+    snapshots: VecDeque<Vec<(Entity, T)>>,
+}
+
+impl<T: Interpolate> Snapshots<T> {
+    /// Creates a history which retains at most `capacity` snapshots.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity >= 2,
+            "at least two snapshots are required to interpolate"
+        );
+        Snapshots {
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Blends the values of the two most recent snapshots.
+    ///
+    /// Entities which did not exist in the previous snapshot take their latest value.
+    pub fn sample(&self, alpha: f32) -> Vec<(Entity, T)> {
+        let mut recent = self.snapshots.iter().rev();
+        let (current, previous) = match (recent.next(), recent.next()) {
+            (Some(current), Some(previous)) => (current, previous),
+            (Some(current), None) => return current.clone(),
+            _ => return Vec::new(),
+        };
+
+        let previous = previous
+            .iter()
+            .map(|(entity, value)| (*entity, value))
+            .collect::<HashMap<_, _>>();
+        current
+            .iter()
+            .map(|(entity, value)| match previous.get(entity) {
+                Some(previous) => (*entity, previous.interpolate(value, alpha)),
+                None => (*entity, value.clone()),
+            })
+            .collect()
+    }
+}
+
+impl<T: Interpolate> SnapshotHistory for Snapshots<T> {
+    fn capture(&mut self, storage: &Storage) {
+        let type_id = ComponentTypeId::of::<T>();
+
+        // reuse the allocation of the oldest snapshot once the buffer is full
+        let mut snapshot = if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front().unwrap()
+        } else {
+            Vec::new()
+        };
+        snapshot.clear();
+
+        for archetype in storage.archetypes() {
+            if !archetype.description().components().iter().any(|(t, _)| *t == type_id) {
+                continue;
+            }
+
+            for chunk in archetype.chunksets().iter().flat_map(|set| set.iter()) {
+                let values = unsafe { chunk.components(type_id).unwrap().data_slice::<T>() };
+                snapshot.extend(chunk.entities().iter().copied().zip(values.iter().cloned()));
+            }
+        }
+
+        self.snapshots.push_back(snapshot);
+    }
+
+    fn clear(&mut self) { self.snapshots.clear(); }
+
+    fn as_any(&self) -> &dyn Any { self }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpolate::Interpolate;
+    use crate::prelude::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32, f32);
+
+    impl Interpolate for Pos {
+        fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+            Pos(
+                self.0 + (next.0 - self.0) * alpha,
+                self.1 + (next.1 - self.1) * alpha,
+            )
+        }
+    }
+
+    fn sorted(mut values: Vec<(Entity, Pos)>) -> Vec<(Entity, Pos)> {
+        values.sort_by_key(|(e, _)| e.index());
+        values
+    }
+
+    #[test]
+    fn sample_interpolated() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.track_snapshots::<Pos>(3);
+
+        let entities = world
+            .insert((), vec![(Pos(0., 0.),), (Pos(10., 10.),)])
+            .to_vec();
+        assert!(world.sample_interpolated::<Pos>(0.5).is_empty());
+
+        // a single snapshot has nothing to blend with
+        world.capture_snapshots();
+        assert_eq!(
+            vec![(entities[0], Pos(0., 0.)), (entities[1], Pos(10., 10.))],
+            sorted(world.sample_interpolated::<Pos>(0.5))
+        );
+
+        for mut pos in Write::<Pos>::query().iter(&mut world) {
+            pos.0 += 4.;
+        }
+        let spawned = world.insert((), vec![(Pos(100., 0.),)])[0];
+        world.capture_snapshots();
+
+        assert_eq!(
+            vec![
+                (entities[0], Pos(1., 0.)),
+                (entities[1], Pos(11., 10.)),
+                (spawned, Pos(100., 0.))
+            ],
+            sorted(world.sample_interpolated::<Pos>(0.25))
+        );
+
+        // deleted entities are no longer sampled
+        world.delete(entities[0]);
+        world.capture_snapshots();
+        world.capture_snapshots();
+        assert_eq!(
+            vec![(entities[1], Pos(14., 10.)), (spawned, Pos(100., 0.))],
+            sorted(world.sample_interpolated::<Pos>(0.5))
+        );
+    }
+
+    #[test]
+    fn untracked() {
+        let world = World::new();
+        assert!(world.sample_interpolated::<Pos>(0.5).is_empty());
+    }
+}
@@ -27,6 +27,20 @@ impl<'a, T> Iterator for SliceVecIter<'a, T> {
         }
     }
 
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.counts.len() {
+            self.data = &[];
+            self.counts = &[];
+            return None;
+        }
+
+        let skipped = self.counts[..n].iter().sum();
+        self.data = &self.data[skipped..];
+        self.counts = &self.counts[n..];
+        self.next()
+    }
+
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) { (self.counts.len(), Some(self.counts.len())) }
 
@@ -160,6 +174,8 @@ impl<T: Clone> FissileIterator for Take<Repeat<T>> {
             let (len, len_max) = self.size_hint();
             assert_eq!(Some(len), len_max);
 
+            // include the value taken above
+            let len = len + 1;
             let first_part = len / 2;
             let second_part = len - first_part;
             (
@@ -189,6 +205,9 @@ impl<A: Iterator, B: Iterator> Iterator for FissileZip<A, B> {
     fn next(&mut self) -> Option<(A::Item, B::Item)> {
         self.a.next().and_then(|x| self.b.next().map(|y| (x, y)))
     }
+    fn nth(&mut self, n: usize) -> Option<(A::Item, B::Item)> {
+        self.a.nth(n).and_then(|x| self.b.nth(n).map(|y| (x, y)))
+    }
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (a_lower, a_upper) = self.a.size_hint();
         let (b_lower, b_upper) = self.b.size_hint();
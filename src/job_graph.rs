@@ -0,0 +1,278 @@
+//! The dependency graph between the systems of a stage.
+//!
+//! The parallel scheduler orders the systems within a stage by their declared access. A
+//! `JobGraph` exposes the dependencies it computes, along with the conflicting accesses which
+//! caused each dependency, so that the reasons systems are serialized can be inspected. Graphs
+//! can be exported to Graphviz DOT or JSON for visualization.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! # #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+//! # struct Update;
+//! # impl std::fmt::Display for Update {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "update") }
+//! # }
+//! # impl legion::schedule::Stage for Update {}
+//! # struct Time(f32);
+//! let mut scheduler = legion::schedule::SystemScheduler::new();
+//! scheduler.add_system(
+//!     Update,
+//!     SystemBuilder::new("tick").write_resource::<Time>().build(|_, _, _, _| {}),
+//! );
+//! scheduler.add_system(
+//!     Update,
+//!     SystemBuilder::new("clock").read_resource::<Time>().build(|_, _, _, _| {}),
+//! );
+//!
+//! for (stage, graph) in scheduler.job_graphs() {
+//!     assert_eq!(graph.edges().len(), 1);
+//!     println!("{}", graph.to_dot(&stage.to_string()));
+//! }
+//! ```
+
+use crate::resource::ResourceTypeId;
+use crate::schedule::Schedulable;
+use crate::storage::ComponentTypeId;
+use crate::system::SystemId;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The access conflict which causes one system to depend upon another.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The dependant reads a resource which the dependency writes.
+    ResourceRead(ResourceTypeId),
+    /// The dependant writes a resource which the dependency reads or writes.
+    ResourceWrite(ResourceTypeId),
+    /// The dependant accesses a component which the dependency writes. The systems are only
+    /// serialized if they access a common archetype when the stage executes.
+    Component(ComponentTypeId),
+}
+
+impl Conflict {
+    /// Determines if the conflict always orders the two systems, regardless of the
+    /// archetypes they access.
+    pub fn is_static(&self) -> bool {
+        match self {
+            Conflict::ResourceRead(_) | Conflict::ResourceWrite(_) => true,
+            Conflict::Component(_) => false,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Conflict::ResourceRead(_) => "resource_read",
+            Conflict::ResourceWrite(_) => "resource_write",
+            Conflict::Component(_) => "component",
+        }
+    }
+
+    fn type_name(&self) -> String {
+        match self {
+            Conflict::ResourceRead(ty) | Conflict::ResourceWrite(ty) => format!("{:?}", ty),
+            Conflict::Component(ty) => format!("{:?}", ty),
+        }
+    }
+}
+
+/// A dependency between two systems of a stage.
+#[derive(Debug, Clone)]
+pub struct JobEdge {
+    dependency: usize,
+    dependant: usize,
+    conflicts: Vec<Conflict>,
+}
+
+impl JobEdge {
+    /// Gets the index of the system which must run first.
+    pub fn dependency(&self) -> usize { self.dependency }
+
+    /// Gets the index of the system which must wait for the dependency.
+    pub fn dependant(&self) -> usize { self.dependant }
+
+    /// Gets the access conflicts which caused the dependency.
+    pub fn conflicts(&self) -> &[Conflict] { &self.conflicts }
+
+    /// Determines if the dependency always orders the two systems.
+    pub fn is_static(&self) -> bool { self.conflicts.iter().any(Conflict::is_static) }
+
+    /// Determines if the dependency orders the two systems only when they access a common
+    /// archetype.
+    pub fn is_dynamic(&self) -> bool { self.conflicts.iter().any(|c| !c.is_static()) }
+}
+
+/// The dependency graph between the systems of a stage.
+#[derive(Debug, Clone)]
+pub struct JobGraph {
+    nodes: Vec<SystemId>,
+    edges: Vec<JobEdge>,
+}
+
+impl JobGraph {
+    /// Computes the dependency graph for systems which are to be run in the given order.
+    pub fn new(systems: &[Box<dyn Schedulable>]) -> Self {
+        let mut conflicts = HashMap::<(usize, usize), Vec<Conflict>>::new();
+        let mut add = |dependency: usize, dependant: usize, conflict: Conflict| {
+            // dont be dependent on ourselves
+            if dependency != dependant {
+                let entry = conflicts.entry((dependant, dependency)).or_default();
+                if !entry.contains(&conflict) {
+                    entry.push(conflict);
+                }
+            }
+        };
+
+        let mut resource_last_mutated = HashMap::<ResourceTypeId, usize>::new();
+        let mut resource_last_read = HashMap::<ResourceTypeId, usize>::new();
+        let mut component_mutated = HashMap::<ComponentTypeId, Vec<usize>>::new();
+
+        for (i, system) in systems.iter().enumerate() {
+            let (read_res, read_comp) = system.reads();
+            let (write_res, write_comp) = system.writes();
+
+            for res in read_res {
+                if let Some(n) = resource_last_mutated.get(res) {
+                    add(*n, i, Conflict::ResourceRead(*res));
+                }
+                resource_last_read.insert(*res, i);
+            }
+            for res in write_res {
+                // Writes have to be exclusive, so we are dependent on reads too
+                if let Some(n) = resource_last_read.get(res) {
+                    add(*n, i, Conflict::ResourceWrite(*res));
+                }
+                if let Some(n) = resource_last_mutated.get(res) {
+                    add(*n, i, Conflict::ResourceWrite(*res));
+                }
+                resource_last_mutated.insert(*res, i);
+            }
+
+            for comp in read_comp {
+                for n in component_mutated.get(comp).into_iter().flatten() {
+                    add(*n, i, Conflict::Component(*comp));
+                }
+            }
+            for comp in write_comp {
+                for n in component_mutated.get(comp).into_iter().flatten() {
+                    add(*n, i, Conflict::Component(*comp));
+                }
+                component_mutated.entry(*comp).or_default().push(i);
+            }
+        }
+
+        let mut edges: Vec<_> = conflicts
+            .into_iter()
+            .map(|((dependant, dependency), conflicts)| JobEdge {
+                dependency,
+                dependant,
+                conflicts,
+            })
+            .collect();
+        edges.sort_by_key(|edge| (edge.dependant, edge.dependency));
+
+        Self {
+            nodes: systems.iter().map(|system| system.name().clone()).collect(),
+            edges,
+        }
+    }
+
+    /// Gets the systems in the graph. Edges refer to systems by their index in this slice.
+    pub fn nodes(&self) -> &[SystemId] { &self.nodes }
+
+    /// Gets the dependencies between systems, ordered by dependant.
+    pub fn edges(&self) -> &[JobEdge] { &self.edges }
+
+    /// Gets the dependencies of the system at the given index.
+    pub fn dependencies(&self, system: usize) -> impl Iterator<Item = &JobEdge> + '_ {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.dependant == system)
+    }
+
+    /// Formats the graph in the Graphviz DOT language.
+    ///
+    /// Static dependencies are drawn as solid edges, and dependencies which only apply when the
+    /// systems share an archetype as dashed edges. Edges are labelled with their conflicts.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph \"{}\" {{", escape(name)).unwrap();
+        for (i, node) in self.nodes.iter().enumerate() {
+            writeln!(dot, "    {} [label=\"{}\"];", i, escape(node.name())).unwrap();
+        }
+        for edge in &self.edges {
+            let label = edge
+                .conflicts
+                .iter()
+                .map(|c| escape(&format!("{} {}", c.kind(), c.type_name())))
+                .collect::<Vec<_>>()
+                .join("\\n");
+            writeln!(
+                dot,
+                "    {} -> {} [label=\"{}\", style={}];",
+                edge.dependency,
+                edge.dependant,
+                label,
+                if edge.is_static() { "solid" } else { "dashed" }
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Formats the graph as JSON.
+    ///
+    /// The document contains a `nodes` array of system names, and an `edges` array of objects
+    /// with `dependency` and `dependant` node indices, a `static` flag and a list of
+    /// `conflicts`, each with a `kind` and a `type`.
+    pub fn to_json(&self) -> String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| format!("\"{}\"", escape(node.name())))
+            .collect::<Vec<_>>()
+            .join(",");
+        let edges = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let conflicts = edge
+                    .conflicts
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "{{\"kind\":\"{}\",\"type\":\"{}\"}}",
+                            c.kind(),
+                            escape(&c.type_name())
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    "{{\"dependency\":{},\"dependant\":{},\"static\":{},\"conflicts\":[{}]}}",
+                    edge.dependency,
+                    edge.dependant,
+                    edge.is_static(),
+                    conflicts
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{{\"nodes\":[{}],\"edges\":[{}]}}", nodes, edges)
+    }
+}
+
+// Escapes a string for use within a quoted DOT or JSON string.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
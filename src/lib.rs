@@ -226,34 +226,110 @@
 //!  * `log`: Configures `tracing` to redirect events to the `log` crate. This is a convenience feature for applications
 //!  that use `log` and do not wish to interact with `tracing`.
 //!  * `events`: Enables eventing APIs on worlds (enabled by default).
+//!  * `ffi`: Supports component types defined outside of Rust, identified by `ComponentTypeId::external` (enabled
+//!    by default).
+//!  * `c-api`: Exports a C ABI for driving worlds from foreign hosts. See the `ffi` module. Implies `ffi`.
+//!  * `access-audit`: Records the component types accessed by each system. See the `audit` module.
+//!  * `profiling`: Notifies a pluggable `Profiler` of ECS work. See the `profile` module.
+//!  * `derive`: Enables `#[derive(LegionComponent)]`, which generates `Registry` registration for component types.
+//!  * `serialize`: Enables registering serde hooks for component types via `Registry::register_serde`, and patching
+//!    reflected components from JSON via `World::apply_json_patch`.
+//!  * `async`: Enables systems whose bodies are futures and async execution of schedules. See the `async_system`
+//!    module.
+//!  * `spatial`: Enables a spatial index over a position component. See the `spatial` module.
+//!  * `transform`: Enables hierarchical transform components and their propagation. See the `transform` module.
+//!  * `validation`: Enables `World::validate`, which cross-checks the world's internal bookkeeping for consistency.
+//!  * `alloc-tracking`: Counts the bytes allocated and freed for chunk and tag storage, for tracking down leaks.
+//!    See `World::allocation_report`.
+//!  * `testing`: Enables a world fuzzer for stress testing component types. See the `testing` module.
+//!  * `type-names`: Records type names in `ComponentTypeId` and `TagTypeId`, which are shown in their `Debug` output,
+//!    archetype descriptions and borrow conflict panics.
+//!  * `stable-type-ids`: Identifies component and tag types by a hash of their names and layouts rather than their
+//!    `TypeId`, so that component data survives hot reloading of the library defining the types. See
+//!    `Registry::register_stable`.
+//!  * `single-thread`: Compiles out all threading for single-threaded targets such as `wasm32-unknown-unknown`.
+//!    Rayon is never used, and internal locks are replaced with `RefCell`s, so `Universe` and `World`
+//!    are neither `Send` nor `Sync`. Incompatible with `par-iter` and `par-schedule`, so disable
+//!    default features:
+//!    `legion = { version = "*", default-features = false, features = ["single-thread", "events"] }`.
 #![allow(dead_code)]
 
+#[cfg(all(feature = "single-thread", any(feature = "par-iter", feature = "par-schedule")))]
+compile_error!("the `single-thread` feature cannot be combined with `par-iter` or `par-schedule`");
+
+/// Enters a profiling scope which lasts until the end of the enclosing block.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "profiling")]
+        let _profile_scope = crate::profile::ProfileScope::new($name);
+    };
+}
+
 pub mod borrow;
 pub mod command;
+pub mod diagnostics;
 pub mod entity;
+pub mod error;
 pub mod filter;
+pub mod interpolate;
 pub mod iterator;
+pub mod job_graph;
+pub mod name;
 pub mod query;
+pub mod reflect;
+pub mod registry;
+pub mod replay;
 pub mod resource;
 pub mod schedule;
 pub mod storage;
 pub mod system;
+pub mod upload;
 pub mod world;
 
 mod cons;
+mod sync;
+mod tracker;
 mod tuple;
 mod zip;
 
 pub use bit_set;
 
+#[cfg(feature = "derive")]
+pub use legion_derive::LegionComponent;
+
 #[cfg(feature = "events")]
 pub mod event;
 
+#[cfg(feature = "c-api")]
+pub mod ffi;
+
+#[cfg(feature = "access-audit")]
+pub mod audit;
+
+#[cfg(feature = "async")]
+pub mod async_system;
+
+#[cfg(feature = "profiling")]
+pub mod profile;
+
+#[cfg(feature = "spatial")]
+pub mod spatial;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "transform")]
+pub mod transform;
+
 pub mod prelude {
     pub use crate::command::CommandBuffer;
+    pub use crate::command::CommandQueue;
     pub use crate::entity::Entity;
     pub use crate::filter::filter_fns::*;
-    pub use crate::query::{IntoQuery, Query, Read, Tagged, TryRead, TryWrite, Write};
+    pub use crate::name::Name;
+    pub use crate::query::{
+        IntoQuery, Query, Read, ReadAs, Tagged, TryRead, TryWrite, Write, WriteAs, WriteOnly,
+    };
     pub use crate::resource::{ResourceSet, Resources};
     pub use crate::schedule::{Runnable, Schedulable, Stage, StageExecutor, SystemScheduler};
     pub use crate::system::{System, SystemBuilder};
@@ -0,0 +1,124 @@
+//! Human readable entity names.
+//!
+//! Attach a `Name` component to an entity to allow it to be found with `World::find_by_name`.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! let player = world.insert((), vec![(Name::new("player"),)])[0];
+//!
+//! assert_eq!(vec![player], world.find_by_name("player").collect::<Vec<_>>());
+//! ```
+use crate::entity::Entity;
+use crate::storage::ComponentTypeId;
+use crate::storage::Storage;
+use crate::tracker::ChunkTracker;
+use std::borrow::Borrow;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ops::Deref;
+
+/// A component which gives an entity a human readable name.
+///
+/// Names need not be unique.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Name(Cow<'static, str>);
+
+impl Name {
+    /// Creates a new name.
+    pub fn new<S: Into<Cow<'static, str>>>(name: S) -> Self { Name(name.into()) }
+
+    /// Gets the name as a string slice.
+    pub fn as_str(&self) -> &str { &self.0 }
+}
+
+impl Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl Borrow<str> for Name {
+    fn borrow(&self) -> &str { &self.0 }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result { f.write_str(&self.0) }
+}
+
+impl From<&'static str> for Name {
+    fn from(name: &'static str) -> Self { Name::new(name) }
+}
+
+impl From<String> for Name {
+    fn from(name: String) -> Self { Name::new(name) }
+}
+
+/// Maps names to the entities which carry them.
+///
+/// The index is refreshed lazily from the change versions of each chunk's `Name` column, so only
+/// chunks whose names may have changed are re-read.
+#[derive(Debug)]
+pub(crate) struct NameIndex {
+    chunks: ChunkTracker<Vec<(Name, Entity)>>,
+    names: HashMap<Name, Vec<Entity>>,
+}
+
+impl Default for NameIndex {
+    fn default() -> Self {
+        NameIndex {
+            chunks: ChunkTracker::new(ComponentTypeId::of::<Name>()),
+            names: HashMap::new(),
+        }
+    }
+}
+
+impl NameIndex {
+    /// Re-indexes all chunks in `storage` whose names may have changed.
+    pub fn refresh(&mut self, storage: &Storage) {
+        let changes = self.chunks.refresh(storage);
+        for entries in changes.removed {
+            self.remove_entries(entries);
+        }
+
+        for chunk in changes.changed {
+            let names = unsafe {
+                chunk
+                    .components(self.chunks.type_id())
+                    .unwrap()
+                    .data_slice::<Name>()
+            };
+            let entries = names
+                .iter()
+                .cloned()
+                .zip(chunk.entities().iter().copied())
+                .collect::<Vec<_>>();
+            for (name, entity) in entries.iter() {
+                self.names.entry(name.clone()).or_default().push(*entity);
+            }
+            self.chunks.insert(chunk, entries);
+        }
+    }
+
+    /// Gets the entities with the given name.
+    pub fn find(&self, name: &str) -> &[Entity] {
+        self.names
+            .get(name)
+            .map(|entities| entities.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn remove_entries(&mut self, entries: Vec<(Name, Entity)>) {
+        for (name, entity) in entries {
+            if let Some(entities) = self.names.get_mut(&name) {
+                entities.retain(|e| *e != entity);
+                if entities.is_empty() {
+                    self.names.remove(&name);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Pluggable profiling hooks for ECS operations.
+//!
+//! This module is only available with the `profiling` feature enabled. Once a `Profiler` has been
+//! installed via `set_profiler`, it is notified when legion enters and exits scopes around system
+//! execution, query iteration, world defragmentation and chunk allocation. This allows frame
+//! profilers such as Tracy to show where ECS time is spent.
+
+use std::sync::Arc;
+use std::sync::RwLock;
+
+/// Receives notifications when profiling scopes are entered and exited.
+///
+/// Scopes are strictly nested on each thread; `exit` is always called on the same thread as,
+/// and in reverse order of, the corresponding `enter`.
+pub trait Profiler: Send + Sync {
+    /// Called when a scope is entered on the current thread.
+    fn enter(&self, name: &str);
+
+    /// Called when a scope is exited on the current thread.
+    fn exit(&self, name: &str);
+}
+
+static PROFILER: RwLock<Option<Arc<dyn Profiler>>> = RwLock::new(None);
+
+/// Installs a profiler, replacing any previously installed profiler.
+pub fn set_profiler<P: Profiler + 'static>(profiler: P) {
+    *PROFILER.write().unwrap() = Some(Arc::new(profiler));
+}
+
+/// Removes the installed profiler, if any.
+pub fn clear_profiler() { *PROFILER.write().unwrap() = None; }
+
+/// A profiling scope, which is exited when dropped.
+pub struct ProfileScope<'a> {
+    name: &'a str,
+    profiler: Option<Arc<dyn Profiler>>,
+}
+
+impl<'a> ProfileScope<'a> {
+    /// Enters a new profiling scope.
+    pub fn new(name: &'a str) -> Self {
+        let profiler = PROFILER.read().unwrap().clone();
+        if let Some(profiler) = &profiler {
+            profiler.enter(name);
+        }
+
+        Self { name, profiler }
+    }
+}
+
+impl<'a> Drop for ProfileScope<'a> {
+    fn drop(&mut self) {
+        if let Some(profiler) = &self.profiler {
+            profiler.exit(self.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default, Clone)]
+    struct RecordingProfiler {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Profiler for RecordingProfiler {
+        fn enter(&self, name: &str) { self.events.lock().push(format!("enter {}", name)); }
+
+        fn exit(&self, name: &str) { self.events.lock().push(format!("exit {}", name)); }
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32);
+
+    #[test]
+    fn profile_scopes() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let profiler = RecordingProfiler::default();
+        set_profiler(profiler.clone());
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.insert((), vec![(Pos(0.),)]);
+
+        let mut query = Read::<Pos>::query();
+        query.for_each(&mut world, |_| {});
+        world.defrag(None);
+
+        clear_profiler();
+
+        let events = profiler.events.lock();
+        for name in &["Chunk allocation", "Query::for_each", "World::defrag"] {
+            assert!(events.contains(&format!("enter {}", name)));
+            assert!(events.contains(&format!("exit {}", name)));
+        }
+    }
+}
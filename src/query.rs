@@ -6,8 +6,11 @@ use crate::borrow::RefMapMut;
 use crate::borrow::Shared;
 use crate::borrow::TryRefIter;
 use crate::borrow::TryRefIterMut;
+use crate::borrow::UnsafeClone;
 use crate::entity::Entity;
+use crate::filter::begin_pass;
 use crate::filter::And;
+use crate::filter::ArchetypeCursor;
 use crate::filter::ArchetypeFilterData;
 use crate::filter::ChunkFilterData;
 use crate::filter::ChunksetFilterData;
@@ -21,19 +24,27 @@ use crate::filter::TagFilter;
 #[cfg(feature = "par-iter")]
 use crate::iterator::{FissileEnumerate, FissileIterator};
 use crate::storage::ArchetypeData;
+use crate::storage::ChunkId;
+use crate::storage::Chunkset;
 use crate::storage::Component;
+use crate::storage::ComponentAlias;
+use crate::storage::ComponentResourceSet;
 use crate::storage::ComponentStorage;
 use crate::storage::ComponentTypeId;
 use crate::storage::Storage;
+use crate::storage::Streamable;
 use crate::storage::Tag;
 use crate::storage::TagTypeId;
 use crate::world::World;
 use derivative::Derivative;
 use std::any::TypeId;
+use std::collections::HashMap;
 use std::iter::Enumerate;
 use std::iter::Repeat;
 use std::iter::Take;
 use std::marker::PhantomData;
+use std::ops::Range;
+use std::rc::Rc;
 use std::slice::Iter;
 use std::slice::IterMut;
 
@@ -53,7 +64,23 @@ pub trait View<'a>: Sized + Send + Sync + 'static {
     fn fetch(
         archetype: &'a ArchetypeData,
         chunk: &'a ComponentStorage,
-        chunk_index: usize,
+        set_index: usize,
+    ) -> Self::Iter;
+
+    /// Pulls data for a range of the entities in a chunk out of the chunk.
+    ///
+    /// Components written by the view are borrowed such that the data of disjoint ranges of
+    /// the same chunk can be fetched at the same time. The changes to the written components are
+    /// not tracked, and so `fetch` must have been called for the chunk beforehand.
+    ///
+    /// # Safety
+    ///
+    /// The ranges of data fetched at the same time for a chunk must not overlap.
+    unsafe fn fetch_range(
+        archetype: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        set_index: usize,
+        range: Range<usize>,
     ) -> Self::Iter;
 
     /// Validates that the view does not break any component borrowing rules.
@@ -125,6 +152,9 @@ impl<'a, T: Component> View<'a> for Read<T> {
     type Iter = RefIter<'a, Shared<'a>, T, Iter<'a, T>>;
 
     fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
         let (slice_borrow, slice) = unsafe {
             chunk
                 .components(ComponentTypeId::of::<T>())
@@ -140,6 +170,21 @@ impl<'a, T: Component> View<'a> for Read<T> {
         RefIter::new(slice_borrow, slice.iter())
     }
 
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
+        let (slice_borrow, slice) = view_components::<T, Self>(chunk)
+            .data_slice_range::<T>(range)
+            .deconstruct();
+        RefIter::new(slice_borrow, slice.iter())
+    }
+
     fn validate() -> bool { true }
 
     fn reads<D: Component>() -> bool { TypeId::of::<T>() == TypeId::of::<D>() }
@@ -156,10 +201,34 @@ impl<T: Component> ViewElement for Read<T> {
 }
 
 /// Reads a single entity data component type from a chunk, if it's present.
+///
+/// Unlike `Read`, this view matches archetypes regardless of whether they contain `T`,
+/// yielding `None` for entities which do not have the component.
+///
+/// ```
+/// # use legion::prelude::*;
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Position(f32);
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Velocity(f32);
+/// # let universe = Universe::new();
+/// # let mut world = universe.create_world();
+/// world.insert((), vec![(Position(0.0),)]);
+/// world.insert((), vec![(Position(0.0), Velocity(1.0))]);
+///
+/// let mut query = <(Write<Position>, TryRead<Velocity>)>::query();
+/// for (mut pos, vel) in query.iter(&mut world) {
+///     if let Some(vel) = vel {
+///         pos.0 += vel.0;
+///     }
+/// }
+/// ```
 #[derive(Derivative, Debug)]
 #[derivative(Default(bound = ""))]
 pub struct TryRead<T: Component>(PhantomData<T>);
 
+impl<T: Component> ReadOnly for TryRead<T> {}
+
 impl<'a, T: Component> DefaultFilter for TryRead<T> {
     type Filter = EntityFilterTuple<Passthrough, Passthrough, Passthrough>;
 
@@ -170,6 +239,9 @@ impl<'a, T: Component> View<'a> for TryRead<T> {
     type Iter = TryRefIter<'a, Shared<'a>, T, Iter<'a, T>>;
 
     fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
         unsafe {
             chunk
                 .components(ComponentTypeId::of::<T>())
@@ -181,6 +253,25 @@ impl<'a, T: Component> View<'a> for TryRead<T> {
         }
     }
 
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
+        let len = range.len();
+        chunk
+            .components(ComponentTypeId::of::<T>())
+            .map(|x| {
+                let (borrow, slice) = x.data_slice_range::<T>(range).deconstruct();
+                TryRefIter::found(borrow, slice.iter())
+            })
+            .unwrap_or_else(|| TryRefIter::missing(len))
+    }
+
     fn validate() -> bool { true }
 
     fn reads<D: Component>() -> bool { TypeId::of::<T>() == TypeId::of::<D>() }
@@ -212,6 +303,9 @@ impl<'a, T: Component> View<'a> for Write<T> {
 
     #[inline]
     fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
         let (slice_borrow, slice) = unsafe {
             chunk
                 .components(ComponentTypeId::of::<T>())
@@ -227,6 +321,22 @@ impl<'a, T: Component> View<'a> for Write<T> {
         RefIterMut::new(slice_borrow, slice.iter_mut())
     }
 
+    #[inline]
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
+        let (slice_borrow, slice) = view_components::<T, Self>(chunk)
+            .data_slice_range_mut::<T>(range)
+            .deconstruct();
+        RefIterMut::new(slice_borrow, slice.iter_mut())
+    }
+
     #[inline]
     fn validate() -> bool { true }
 
@@ -248,6 +358,9 @@ impl<T: Component> ViewElement for Write<T> {
 }
 
 /// Writes a single entity data component type from a chunk, if it's present.
+///
+/// Unlike `Write`, this view matches archetypes regardless of whether they contain `T`,
+/// yielding `None` for entities which do not have the component.
 #[derive(Derivative, Debug)]
 #[derivative(Default(bound = ""))]
 pub struct TryWrite<T: Component>(PhantomData<T>);
@@ -262,6 +375,9 @@ impl<'a, T: Component> View<'a> for TryWrite<T> {
     type Iter = TryRefIterMut<'a, Exclusive<'a>, T, IterMut<'a, T>>;
 
     fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
         unsafe {
             chunk
                 .components(ComponentTypeId::of::<T>())
@@ -273,6 +389,25 @@ impl<'a, T: Component> View<'a> for TryWrite<T> {
         }
     }
 
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
+        let len = range.len();
+        chunk
+            .components(ComponentTypeId::of::<T>())
+            .map(|x| {
+                let (borrow, slice) = x.data_slice_range_mut::<T>(range).deconstruct();
+                TryRefIterMut::found(borrow, slice.iter_mut())
+            })
+            .unwrap_or_else(|| TryRefIterMut::missing(len))
+    }
+
     fn validate() -> bool { true }
 
     #[inline]
@@ -292,41 +427,91 @@ impl<T: Component> ViewElement for TryWrite<T> {
     type Component = T;
 }
 
-/// Reads a single shared data component type in a chunk.
-#[derive(Debug)]
-pub struct Tagged<T: Tag>(PhantomData<T>);
-
-impl<T: Tag> ReadOnly for Tagged<T> {}
+/// Writes to a single entity data component type from a chunk, without reading it.
+///
+/// Components are written through `StreamSlot::write`, or through `StreamSlot::stream` for
+/// `Streamable` types, which uses non-temporal stores where the target supports them. These
+/// bypass the CPU caches, avoiding evicting data which is still in use when streaming through
+/// large numbers of components which will not be read again soon, such as when filling render
+/// buffers.
+///
+/// # Examples
+///
+/// ```
+/// # use legion::prelude::*;
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Position(f32);
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Render(f32);
+/// # let universe = Universe::new();
+/// # let mut world = universe.create_world();
+/// # world.insert((), vec![(Position(1.0), Render(0.0))]);
+/// let mut query = <(Read<Position>, WriteOnly<Render>)>::query();
+/// for (pos, render) in query.iter(&mut world) {
+///     render.write(Render(pos.0 * 2.0));
+/// }
+/// ```
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct WriteOnly<T: Component>(PhantomData<T>);
 
-impl<'a, T: Tag> DefaultFilter for Tagged<T> {
-    type Filter = EntityFilterTuple<TagFilter<T>, Passthrough, Passthrough>;
+impl<T: Component> DefaultFilter for WriteOnly<T> {
+    type Filter = EntityFilterTuple<ComponentFilter<T>, Passthrough, Passthrough>;
 
-    fn filter() -> Self::Filter { super::filter::filter_fns::tag() }
+    fn filter() -> Self::Filter { super::filter::filter_fns::component() }
 }
 
-impl<'a, T: Tag> View<'a> for Tagged<T> {
-    type Iter = Take<Repeat<&'a T>>;
+impl<'a, T: Component> View<'a> for WriteOnly<T> {
+    type Iter = StreamIter<'a, T>;
 
     #[inline]
-    fn fetch(
-        archetype: &'a ArchetypeData,
-        chunk: &'a ComponentStorage,
-        chunk_index: usize,
-    ) -> Self::Iter {
-        let data = unsafe {
-            archetype
-                .tags()
-                .get(TagTypeId::of::<T>())
+    fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
+        let (slice_borrow, slice) = unsafe {
+            chunk
+                .components(ComponentTypeId::of::<T>())
                 .unwrap_or_else(|| {
                     panic!(
-                        "Component of type {:?} not found in archetype when fetching Tagged view",
+                        "Component of type {:?} not found in chunk when fetching WriteOnly view",
                         std::any::type_name::<T>()
                     )
                 })
-                .data_slice::<T>()
-                .get_unchecked(chunk_index)
+                .data_slice_mut::<T>()
+                .deconstruct()
         };
-        std::iter::repeat(data).take(chunk.len())
+        StreamIter {
+            borrow: slice_borrow,
+            fence: StreamFence::default(),
+            ptr: slice.as_mut_ptr(),
+            index: 0,
+            len: slice.len(),
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
+        let (slice_borrow, slice) = view_components::<T, Self>(chunk)
+            .data_slice_range_mut::<T>(range)
+            .deconstruct();
+        StreamIter {
+            borrow: slice_borrow,
+            fence: StreamFence::default(),
+            ptr: slice.as_mut_ptr(),
+            index: 0,
+            len: slice.len(),
+            _phantom: PhantomData,
+        }
     }
 
     #[inline]
@@ -336,141 +521,632 @@ impl<'a, T: Tag> View<'a> for Tagged<T> {
     fn reads<D: Component>() -> bool { false }
 
     #[inline]
-    fn writes<D: Component>() -> bool { false }
+    fn writes<D: Component>() -> bool { TypeId::of::<T>() == TypeId::of::<D>() }
 
     #[inline]
     fn read_types() -> Vec<ComponentTypeId> { Vec::with_capacity(0) }
 
     #[inline]
-    fn write_types() -> Vec<ComponentTypeId> { Vec::with_capacity(0) }
+    fn write_types() -> Vec<ComponentTypeId> { vec![ComponentTypeId::of::<T>()] }
 }
 
-impl<T: Tag> ViewElement for Tagged<T> {
-    type Component = Tagged<T>;
+impl<T: Component> ViewElement for WriteOnly<T> {
+    type Component = T;
 }
 
-macro_rules! impl_view_tuple {
-    ( $( $ty: ident ),* ) => {
-        impl<$( $ty: ViewElement + DefaultFilter ),*> DefaultFilter for ($( $ty, )*) {
-            type Filter = EntityFilterTuple<
-                And<($( <$ty::Filter as EntityFilter>::ArchetypeFilter, )*)>,
-                And<($( <$ty::Filter as EntityFilter>::ChunksetFilter, )*)>,
-                And<($( <$ty::Filter as EntityFilter>::ChunkFilter, )*)>,
-            >;
+/// Reads a component type through its `ComponentAlias`, from the columns in which the alias
+/// target is stored.
+///
+/// # Examples
+///
+/// ```
+/// # use legion::prelude::*;
+/// use legion::storage::ComponentAlias;
+///
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// struct Vec2(f32, f32);
+///
+/// #[repr(transparent)]
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// struct Position2D(Vec2);
+///
+/// unsafe impl ComponentAlias for Position2D {
+///     type Target = Vec2;
+/// }
+///
+/// # let universe = Universe::new();
+/// # let mut world = universe.create_world();
+/// world.insert((), vec![(Vec2(1.0, 2.0),)]);
+///
+/// let mut query = WriteAs::<Position2D>::query();
+/// for mut pos in query.iter(&mut world) {
+///     (pos.0).0 += 1.0;
+/// }
+///
+/// let mut query = ReadAs::<Position2D>::query();
+/// let positions = query.iter(&mut world).map(|p| *p).collect::<Vec<_>>();
+/// assert_eq!(vec![Position2D(Vec2(2.0, 2.0))], positions);
+/// ```
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct ReadAs<T: ComponentAlias>(PhantomData<T>);
 
-            fn filter() -> Self::Filter {
-                #![allow(non_snake_case)]
-                $( let $ty = $ty::filter().into_filters(); )*
-                EntityFilterTuple::new(
-                    And { filters: ($( $ty.0, )*) },
-                    And { filters: ($( $ty.1, )*) },
-                    And { filters: ($( $ty.2, )*) },
-                )
-            }
-        }
+impl<T: ComponentAlias> ReadOnly for ReadAs<T> {}
 
-        impl<$( $ty: ReadOnly ),* > ReadOnly for ($( $ty, )*) {}
+impl<T: ComponentAlias> DefaultFilter for ReadAs<T> {
+    type Filter = EntityFilterTuple<ComponentFilter<T::Target>, Passthrough, Passthrough>;
 
-        impl<'a, $( $ty: ViewElement + View<'a> ),* > View<'a> for ($( $ty, )*) {
-            type Iter = crate::zip::Zip<($( $ty::Iter, )*)>;
+    fn filter() -> Self::Filter { super::filter::filter_fns::component() }
+}
 
-            #[inline]
-            fn fetch(
-                archetype: &'a ArchetypeData,
-                chunk: &'a ComponentStorage,
-                chunk_index: usize,
-            ) -> Self::Iter {
-                crate::zip::multizip(($( $ty::fetch(archetype.clone(), chunk.clone(), chunk_index), )*))
-            }
+impl<'a, T: ComponentAlias> View<'a> for ReadAs<T> {
+    type Iter = RefIter<'a, Shared<'a>, T, Iter<'a, T>>;
 
-            fn validate() -> bool {
-                let types = &[$( TypeId::of::<$ty::Component>() ),*];
-                for i in 0..types.len() {
-                    for j in (i + 1)..types.len() {
-                        if unsafe { types.get_unchecked(i) == types.get_unchecked(j) } {
-                            return false;
-                        }
-                    }
-                }
+    fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T::Target>(false);
 
-                true
-            }
+        debug_assert_eq!(
+            std::alloc::Layout::new::<T>(),
+            std::alloc::Layout::new::<T::Target>()
+        );
+        let (slice_borrow, slice) = unsafe {
+            let (borrow, slice) = chunk
+                .components(ComponentTypeId::of::<T::Target>())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Component of type {:?} not found in chunk when fetching ReadAs view",
+                        std::any::type_name::<T::Target>()
+                    )
+                })
+                .data_slice::<T::Target>()
+                .deconstruct();
+            let slice = std::slice::from_raw_parts(slice.as_ptr() as *const T, slice.len());
+            (borrow, slice)
+        };
+        RefIter::new(slice_borrow, slice.iter())
+    }
 
-            fn reads<Data: Component>() -> bool {
-                $( $ty::reads::<Data>() )||*
-            }
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T::Target>(false);
 
-            fn writes<Data: Component>() -> bool {
-                $( $ty::writes::<Data>() )||*
-            }
+        let (slice_borrow, slice) = view_components::<T::Target, Self>(chunk)
+            .data_slice_range::<T::Target>(range)
+            .deconstruct();
+        let slice = std::slice::from_raw_parts(slice.as_ptr() as *const T, slice.len());
+        RefIter::new(slice_borrow, slice.iter())
+    }
 
-            fn read_types() -> Vec<ComponentTypeId> { vec![$( ComponentTypeId::of::<$ty>()),*] }
+    fn validate() -> bool { true }
 
-            fn write_types() -> Vec<ComponentTypeId> { vec![$( ComponentTypeId::of::<$ty>()),*] }
-        }
-    };
+    fn reads<D: Component>() -> bool { TypeId::of::<T::Target>() == TypeId::of::<D>() }
+
+    fn writes<D: Component>() -> bool { false }
+
+    fn read_types() -> Vec<ComponentTypeId> { vec![ComponentTypeId::of::<T::Target>()] }
+
+    fn write_types() -> Vec<ComponentTypeId> { Vec::with_capacity(0) }
 }
 
-impl_view_tuple!(A);
-impl_view_tuple!(A, B);
-impl_view_tuple!(A, B, C);
-impl_view_tuple!(A, B, C, D);
-impl_view_tuple!(A, B, C, D, E);
-impl_view_tuple!(A, B, C, D, E, F);
+impl<T: ComponentAlias> ViewElement for ReadAs<T> {
+    type Component = T::Target;
+}
 
-/// A type-safe view of a chunk of entities all of the same data layout.
-pub struct Chunk<'a, V: for<'b> View<'b>> {
-    archetype: &'a ArchetypeData,
-    components: &'a ComponentStorage,
-    index: usize,
-    view: PhantomData<V>,
+/// Writes a component type through its `ComponentAlias`, to the columns in which the alias
+/// target is stored.
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct WriteAs<T: ComponentAlias>(PhantomData<T>);
+
+impl<T: ComponentAlias> DefaultFilter for WriteAs<T> {
+    type Filter = EntityFilterTuple<ComponentFilter<T::Target>, Passthrough, Passthrough>;
+
+    fn filter() -> Self::Filter { super::filter::filter_fns::component() }
 }
 
-impl<'a, V: for<'b> View<'b>> Chunk<'a, V> {
-    pub fn new(archetype: &'a ArchetypeData, set: usize, index: usize) -> Self {
-        Self {
-            components: unsafe {
-                archetype
-                    .chunksets()
-                    .get_unchecked(set)
-                    .get_unchecked(index)
-            },
-            archetype,
-            index,
-            view: PhantomData,
-        }
-    }
+impl<'a, T: ComponentAlias> View<'a> for WriteAs<T> {
+    type Iter = RefIterMut<'a, Exclusive<'a>, T, IterMut<'a, T>>;
 
-    /// Get a slice of all entities contained within the chunk.
     #[inline]
-    pub fn entities(&self) -> &'a [Entity] { self.components.entities() }
+    fn fetch(_: &'a ArchetypeData, chunk: &'a ComponentStorage, _: usize) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T::Target>(true);
 
-    /// Get an iterator of all data contained within the chunk.
-    #[inline]
-    pub fn iter(&mut self) -> <V as View<'a>>::Iter {
-        V::fetch(self.archetype, self.components, self.index)
+        debug_assert_eq!(
+            std::alloc::Layout::new::<T>(),
+            std::alloc::Layout::new::<T::Target>()
+        );
+        let (slice_borrow, slice) = unsafe {
+            let (borrow, slice) = chunk
+                .components(ComponentTypeId::of::<T::Target>())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Component of type {:?} not found in chunk when fetching WriteAs view",
+                        std::any::type_name::<T::Target>()
+                    )
+                })
+                .data_slice_mut::<T::Target>()
+                .deconstruct();
+            let slice = std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut T, slice.len());
+            (borrow, slice)
+        };
+        RefIterMut::new(slice_borrow, slice.iter_mut())
     }
 
-    /// Get an iterator of all data and entity IDs contained within the chunk.
     #[inline]
-    pub fn iter_entities(&mut self) -> ZipEntities<'a, V> {
-        ZipEntities {
-            entities: self.entities(),
-            data: V::fetch(self.archetype, self.components, self.index),
-            index: 0,
-            view: PhantomData,
-        }
-    }
+    unsafe fn fetch_range(
+        _: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        _: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T::Target>(true);
 
-    /// Get a tag value.
-    pub fn tag<T: Tag>(&self) -> Option<&T> {
-        self.archetype
-            .tags()
-            .get(TagTypeId::of::<T>())
-            .map(|tags| unsafe { tags.data_slice::<T>() })
-            .map(|slice| unsafe { slice.get_unchecked(self.index) })
+        let (slice_borrow, slice) = view_components::<T::Target, Self>(chunk)
+            .data_slice_range_mut::<T::Target>(range)
+            .deconstruct();
+        let slice = std::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut T, slice.len());
+        RefIterMut::new(slice_borrow, slice.iter_mut())
     }
 
+    #[inline]
+    fn validate() -> bool { true }
+
+    #[inline]
+    fn reads<D: Component>() -> bool { TypeId::of::<T::Target>() == TypeId::of::<D>() }
+
+    #[inline]
+    fn writes<D: Component>() -> bool { TypeId::of::<T::Target>() == TypeId::of::<D>() }
+
+    #[inline]
+    fn read_types() -> Vec<ComponentTypeId> { vec![ComponentTypeId::of::<T::Target>()] }
+
+    #[inline]
+    fn write_types() -> Vec<ComponentTypeId> { vec![ComponentTypeId::of::<T::Target>()] }
+}
+
+impl<T: ComponentAlias> ViewElement for WriteAs<T> {
+    type Component = T::Target;
+}
+
+/// Gets the components of type `T` in a chunk from which view `V` is fetching data.
+fn view_components<T: Component, V>(chunk: &ComponentStorage) -> &ComponentResourceSet {
+    chunk
+        .components(ComponentTypeId::of::<T>())
+        .unwrap_or_else(|| {
+            panic!(
+                "Component of type {:?} not found in chunk when fetching {} view",
+                std::any::type_name::<T>(),
+                std::any::type_name::<V>()
+            )
+        })
+}
+
+/// An iterator over the components of a chunk fetched by a `WriteOnly` view.
+///
+/// Streamed writes are fenced once the iterator and all of the slots it returned are dropped.
+pub struct StreamIter<'a, T: Component> {
+    borrow: Exclusive<'a>,
+    fence: StreamFence,
+    ptr: *mut T,
+    index: usize,
+    len: usize,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: Component> Iterator for StreamIter<'a, T> {
+    type Item = StreamSlot<'a, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            let slot = StreamSlot {
+                _borrow: unsafe { self.borrow.clone() },
+                _fence: self.fence.clone(),
+                ptr: unsafe { self.ptr.add(self.index) },
+                _phantom: PhantomData,
+            };
+            self.index += 1;
+            Some(slot)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Component> ExactSizeIterator for StreamIter<'a, T> {}
+
+/// A write-only reference to a single component.
+pub struct StreamSlot<'a, T: Component> {
+    _borrow: Exclusive<'a>,
+    _fence: StreamFence,
+    ptr: *mut T,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: Component> StreamSlot<'a, T> {
+    /// Replaces the component, dropping the previous value.
+    #[inline]
+    pub fn write(self, value: T) {
+        unsafe {
+            std::ptr::drop_in_place(self.ptr);
+            std::ptr::write(self.ptr, value);
+        }
+    }
+
+    /// Replaces the component, dropping the previous value, using non-temporal stores.
+    ///
+    /// Components whose size and alignment are multiples of 4 bytes are written with
+    /// non-temporal stores on x86 targets. Other components are written normally. The stores
+    /// are fenced once the slot, its iterator and all other slots from that iterator are dropped.
+    #[inline]
+    pub fn stream(self, value: T)
+    where
+        T: Streamable,
+    {
+        unsafe {
+            std::ptr::drop_in_place(self.ptr);
+            stream_store(self.ptr, value);
+        }
+    }
+}
+
+/// Fences the non-temporal stores made through a `StreamIter` and its slots when the last of
+/// them is dropped, such that each chunk is fenced once rather than once per component.
+#[derive(Clone, Default)]
+struct StreamFence(Rc<()>);
+
+impl Drop for StreamFence {
+    fn drop(&mut self) {
+        if Rc::strong_count(&self.0) == 1 {
+            store_fence();
+        }
+    }
+}
+
+/// Writes `value` to `dst` with non-temporal stores, if supported for `T`.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes and properly aligned.
+#[inline(always)]
+unsafe fn stream_store<T: Streamable>(dst: *mut T, value: T) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    {
+        use std::arch::x86_64::{_mm_stream_si32, _mm_stream_si64};
+
+        let size = std::mem::size_of::<T>();
+        let align = std::mem::align_of::<T>();
+        let src = &value as *const T;
+        if size.is_multiple_of(8) && align >= 8 {
+            for i in 0..size / 8 {
+                let word = std::ptr::read((src as *const i64).add(i));
+                _mm_stream_si64((dst as *mut i64).add(i), word);
+            }
+            std::mem::forget(value);
+            return;
+        }
+        if size.is_multiple_of(4) && align >= 4 {
+            for i in 0..size / 4 {
+                let word = std::ptr::read((src as *const i32).add(i));
+                _mm_stream_si32((dst as *mut i32).add(i), word);
+            }
+            std::mem::forget(value);
+            return;
+        }
+    }
+
+    std::ptr::write(dst, value);
+}
+
+/// Orders all previous non-temporal stores before any later stores.
+#[inline(always)]
+fn store_fence() {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse"))]
+    unsafe {
+        std::arch::x86_64::_mm_sfence()
+    };
+}
+
+/// Reads a single shared data component type in a chunk.
+#[derive(Debug)]
+pub struct Tagged<T: Tag>(PhantomData<T>);
+
+impl<T: Tag> ReadOnly for Tagged<T> {}
+
+impl<'a, T: Tag> DefaultFilter for Tagged<T> {
+    type Filter = EntityFilterTuple<TagFilter<T>, Passthrough, Passthrough>;
+
+    fn filter() -> Self::Filter { super::filter::filter_fns::tag() }
+}
+
+impl<T: Tag> Tagged<T> {
+    fn tag(archetype: &ArchetypeData, set_index: usize) -> &T {
+        let tags = archetype
+            .tags()
+            .get(TagTypeId::of::<T>())
+            .and_then(|tags| tags.downcast::<T>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Component of type {:?} not found in archetype when fetching Tagged view",
+                    std::any::type_name::<T>()
+                )
+            });
+        unsafe { tags.as_slice().get_unchecked(set_index) }
+    }
+}
+
+impl<'a, T: Tag> View<'a> for Tagged<T> {
+    type Iter = Take<Repeat<&'a T>>;
+
+    #[inline]
+    fn fetch(
+        archetype: &'a ArchetypeData,
+        chunk: &'a ComponentStorage,
+        set_index: usize,
+    ) -> Self::Iter {
+        std::iter::repeat(Self::tag(archetype, set_index)).take(chunk.len())
+    }
+
+    #[inline]
+    #[allow(clippy::manual_repeat_n)]
+    unsafe fn fetch_range(
+        archetype: &'a ArchetypeData,
+        _: &'a ComponentStorage,
+        set_index: usize,
+        range: Range<usize>,
+    ) -> Self::Iter {
+        std::iter::repeat(Self::tag(archetype, set_index)).take(range.len())
+    }
+
+    #[inline]
+    fn validate() -> bool { true }
+
+    #[inline]
+    fn reads<D: Component>() -> bool { false }
+
+    #[inline]
+    fn writes<D: Component>() -> bool { false }
+
+    #[inline]
+    fn read_types() -> Vec<ComponentTypeId> { Vec::with_capacity(0) }
+
+    #[inline]
+    fn write_types() -> Vec<ComponentTypeId> { Vec::with_capacity(0) }
+}
+
+impl<T: Tag> ViewElement for Tagged<T> {
+    type Component = Tagged<T>;
+}
+
+macro_rules! impl_view_tuple {
+    ( $( $ty: ident ),* ) => {
+        impl<$( $ty: ViewElement + DefaultFilter ),*> DefaultFilter for ($( $ty, )*) {
+            type Filter = EntityFilterTuple<
+                And<($( <$ty::Filter as EntityFilter>::ArchetypeFilter, )*)>,
+                And<($( <$ty::Filter as EntityFilter>::ChunksetFilter, )*)>,
+                And<($( <$ty::Filter as EntityFilter>::ChunkFilter, )*)>,
+            >;
+
+            fn filter() -> Self::Filter {
+                #![allow(non_snake_case)]
+                $( let $ty = $ty::filter().into_filters(); )*
+                EntityFilterTuple::new(
+                    And { filters: ($( $ty.0, )*) },
+                    And { filters: ($( $ty.1, )*) },
+                    And { filters: ($( $ty.2, )*) },
+                )
+            }
+        }
+
+        impl<$( $ty: ReadOnly ),* > ReadOnly for ($( $ty, )*) {}
+
+        impl<'a, $( $ty: ViewElement + View<'a> ),* > View<'a> for ($( $ty, )*) {
+            type Iter = crate::zip::Zip<($( $ty::Iter, )*)>;
+
+            #[inline]
+            fn fetch(
+                archetype: &'a ArchetypeData,
+                chunk: &'a ComponentStorage,
+                set_index: usize,
+            ) -> Self::Iter {
+                crate::zip::multizip(($( $ty::fetch(archetype.clone(), chunk.clone(), set_index), )*))
+            }
+
+            unsafe fn fetch_range(
+                archetype: &'a ArchetypeData,
+                chunk: &'a ComponentStorage,
+                set_index: usize,
+                range: Range<usize>,
+            ) -> Self::Iter {
+                crate::zip::multizip(($( $ty::fetch_range(archetype, chunk, set_index, range.clone()), )*))
+            }
+
+            fn validate() -> bool {
+                let types = &[$( TypeId::of::<$ty::Component>() ),*];
+                for i in 0..types.len() {
+                    for j in (i + 1)..types.len() {
+                        if unsafe { types.get_unchecked(i) == types.get_unchecked(j) } {
+                            return false;
+                        }
+                    }
+                }
+
+                true
+            }
+
+            fn reads<Data: Component>() -> bool {
+                $( $ty::reads::<Data>() )||*
+            }
+
+            fn writes<Data: Component>() -> bool {
+                $( $ty::writes::<Data>() )||*
+            }
+
+            fn read_types() -> Vec<ComponentTypeId> { vec![$( ComponentTypeId::of::<$ty>()),*] }
+
+            fn write_types() -> Vec<ComponentTypeId> { vec![$( ComponentTypeId::of::<$ty>()),*] }
+        }
+    };
+}
+
+impl_view_tuple!(A);
+impl_view_tuple!(A, B);
+impl_view_tuple!(A, B, C);
+impl_view_tuple!(A, B, C, D);
+impl_view_tuple!(A, B, C, D, E);
+impl_view_tuple!(A, B, C, D, E, F);
+
+/// A tuple of tag types which can be fetched together for a chunk with `Chunk::tags`.
+pub trait TagTuple<'a> {
+    /// A tuple of references to the tag values.
+    type Refs;
+
+    /// Gets references to the tag values of the given chunk set, or `None` if the archetype
+    /// does not contain all of the tag types.
+    fn fetch(archetype: &'a ArchetypeData, set_index: usize) -> Option<Self::Refs>;
+}
+
+macro_rules! impl_tag_tuple {
+    ( $( $ty: ident ),* ) => {
+        impl<'a, $( $ty: Tag ),* > TagTuple<'a> for ($( $ty, )*) {
+            type Refs = ($( &'a $ty, )*);
+
+            #[inline]
+            fn fetch(archetype: &'a ArchetypeData, set_index: usize) -> Option<Self::Refs> {
+                let tags = archetype.tags();
+                Some(($(
+                    tags.get(TagTypeId::of::<$ty>())
+                        .and_then(|tags| tags.downcast::<$ty>())
+                        .and_then(|tags| tags.as_slice().get(set_index))?,
+                )*))
+            }
+        }
+    };
+}
+
+impl_tag_tuple!(A);
+impl_tag_tuple!(A, B);
+impl_tag_tuple!(A, B, C);
+impl_tag_tuple!(A, B, C, D);
+impl_tag_tuple!(A, B, C, D, E);
+impl_tag_tuple!(A, B, C, D, E, F);
+
+/// A type-safe view of a chunk of entities all of the same data layout.
+pub struct Chunk<'a, V: for<'b> View<'b>> {
+    archetype: &'a ArchetypeData,
+    components: &'a ComponentStorage,
+    set: usize,
+    view: PhantomData<V>,
+}
+
+impl<'a, V: for<'b> View<'b>> Chunk<'a, V> {
+    pub fn new(archetype: &'a ArchetypeData, set: usize, index: usize) -> Self {
+        Self {
+            components: unsafe {
+                archetype
+                    .chunksets()
+                    .get_unchecked(set)
+                    .get_unchecked(index)
+            },
+            archetype,
+            set,
+            view: PhantomData,
+        }
+    }
+
+    /// Gets the ID of the chunk.
+    #[inline]
+    pub fn id(&self) -> ChunkId { self.components.id() }
+
+    /// Gets the archetype which contains the chunk.
+    #[inline]
+    pub fn archetype(&self) -> &'a ArchetypeData { self.archetype }
+
+    /// Gets the chunk set which contains the chunk.
+    #[inline]
+    pub fn chunkset(&self) -> &'a Chunkset { &self.archetype.chunksets()[self.set] }
+
+    /// Get a slice of all entities contained within the chunk.
+    #[inline]
+    pub fn entities(&self) -> &'a [Entity] { self.components.entities() }
+
+    /// Get an iterator of all data contained within the chunk.
+    #[inline]
+    pub fn iter(&mut self) -> <V as View<'a>>::Iter {
+        V::fetch(self.archetype, self.components, self.set)
+    }
+
+    /// Get an iterator of all data and entity IDs contained within the chunk.
+    #[inline]
+    pub fn iter_entities(&mut self) -> ZipEntities<'a, V> {
+        ZipEntities {
+            entities: self.entities(),
+            data: V::fetch(self.archetype, self.components, self.set),
+            index: 0,
+            view: PhantomData,
+        }
+    }
+
+    /// Hints to the CPU that the component data accessed by the view will soon be read.
+    ///
+    /// See `ChunkViewIter::prefetch`.
+    #[inline]
+    pub fn prefetch(&self) {
+        for type_id in V::read_types().into_iter().chain(V::write_types()) {
+            if let Some(components) = self.components.components(type_id) {
+                components.prefetch();
+            }
+        }
+    }
+
+    /// Get a tag value.
+    pub fn tag<T: Tag>(&self) -> Option<&'a T> {
+        self.archetype
+            .tags()
+            .get(TagTypeId::of::<T>())
+            .and_then(|tags| tags.downcast::<T>())
+            .and_then(|tags| tags.as_slice().get(self.set))
+    }
+
+    /// Get the values of several tags at once.
+    ///
+    /// Returns `None` if the chunk does not have all of the requested tag types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Model(u32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Material(u32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((Model(1), Material(2)), vec![(Position(0.0),)]);
+    ///
+    /// let mut query = Read::<Position>::query();
+    /// for chunk in query.iter_chunks(&mut world) {
+    ///     let (model, material) = chunk.tags::<(Model, Material)>().unwrap();
+    ///     assert_eq!((&Model(1), &Material(2)), (model, material));
+    /// }
+    /// ```
+    pub fn tags<T: TagTuple<'a>>(&self) -> Option<T::Refs> { T::fetch(self.archetype, self.set) }
+
     /// Get a slice of component data.
     ///
     /// # Panics
@@ -481,11 +1157,43 @@ impl<'a, V: for<'b> View<'b>> Chunk<'a, V> {
         if !V::reads::<T>() {
             panic!("data type not readable via this query");
         }
+
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
         self.components
             .components(ComponentTypeId::of::<T>())
             .map(|c| unsafe { c.data_slice::<T>() })
     }
 
+    /// Get a slice of component data, extended with zeroed elements up to the next multiple
+    /// of the world's component padding.
+    ///
+    /// With `World::set_component_padding`, kernels can process the slice in full-width SIMD
+    /// lanes without a scalar remainder loop. Results computed for the padding elements should
+    /// be discarded.
+    ///
+    /// # Safety
+    ///
+    /// The all-zero bit pattern must be a valid value of `T`.
+    ///
+    /// # Panics
+    ///
+    /// This method performs runtime borrow checking. It will panic if
+    /// any other code is concurrently writing to the data slice.
+    pub unsafe fn components_padded<T: Component>(&self) -> Option<RefMap<'a, Shared<'a>, &[T]>> {
+        if !V::reads::<T>() {
+            panic!("data type not readable via this query");
+        }
+
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
+        self.components
+            .components(ComponentTypeId::of::<T>())
+            .map(|c| c.data_slice_padded::<T>())
+    }
+
     /// Get a mutable slice of component data.
     ///
     /// # Panics
@@ -496,10 +1204,134 @@ impl<'a, V: for<'b> View<'b>> Chunk<'a, V> {
         if !V::writes::<T>() {
             panic!("data type not writable via this query");
         }
+
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
         self.components
             .components(ComponentTypeId::of::<T>())
             .map(|c| unsafe { c.data_slice_mut::<T>() })
     }
+
+    /// Converts the chunk into a `ChunkView` of all of its entities.
+    pub fn into_view(self) -> ChunkView<'a, V> {
+        // fetching the whole chunk performs change tracking for the components written by the view
+        drop(V::fetch(self.archetype, self.components, self.set));
+
+        ChunkView {
+            archetype: self.archetype,
+            components: self.components,
+            set: self.set,
+            range: 0..self.components.len(),
+            view: PhantomData,
+        }
+    }
+
+    /// Divides the chunk into views of the entities before and after `mid`.
+    ///
+    /// See `ChunkView::split_at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the number of entities in the chunk.
+    pub fn split_at(self, mid: usize) -> (ChunkView<'a, V>, ChunkView<'a, V>) {
+        self.into_view().split_at(mid)
+    }
+}
+
+/// A type-safe view of a range of the entities in a chunk.
+///
+/// Views of disjoint ranges of the same chunk can access their components at the same time,
+/// allowing the entities of a single chunk to be processed in parallel.
+///
+/// # Examples
+///
+/// ```
+/// # use legion::prelude::*;
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Position(f32);
+/// # let universe = Universe::new();
+/// # let mut world = universe.create_world();
+/// world.insert((), (0..100).map(|_| (Position(0.0),)));
+///
+/// let mut query = Write::<Position>::query();
+/// for chunk in query.iter_chunks(&mut world) {
+///     let mid = chunk.entities().len() / 2;
+///     let (mut left, mut right) = chunk.split_at(mid);
+///     std::thread::scope(|scope| {
+///         scope.spawn(move || left.iter().for_each(|mut pos| pos.0 += 1.0));
+///         right.iter().for_each(|mut pos| pos.0 += 1.0);
+///     });
+/// }
+///
+/// let mut query = Read::<Position>::query();
+/// assert!(query.iter(&mut world).all(|pos| *pos == Position(1.0)));
+/// ```
+pub struct ChunkView<'a, V: for<'b> View<'b>> {
+    archetype: &'a ArchetypeData,
+    components: &'a ComponentStorage,
+    set: usize,
+    range: Range<usize>,
+    view: PhantomData<V>,
+}
+
+impl<'a, V: for<'b> View<'b>> ChunkView<'a, V> {
+    /// Gets the ID of the chunk.
+    #[inline]
+    pub fn id(&self) -> ChunkId { self.components.id() }
+
+    /// Gets the number of entities in the view.
+    #[inline]
+    pub fn len(&self) -> usize { self.range.len() }
+
+    /// Determines if the view contains no entities.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.range.is_empty() }
+
+    /// Get a slice of the entities in the view.
+    #[inline]
+    pub fn entities(&self) -> &'a [Entity] { &self.components.entities()[self.range.clone()] }
+
+    /// Divides the view into views of the entities before and after `mid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the number of entities in the view.
+    pub fn split_at(self, mid: usize) -> (ChunkView<'a, V>, ChunkView<'a, V>) {
+        assert!(mid <= self.len(), "split index out of bounds");
+        let mid = self.range.start + mid;
+        let left = ChunkView {
+            archetype: self.archetype,
+            components: self.components,
+            set: self.set,
+            range: self.range.start..mid,
+            view: PhantomData,
+        };
+        let right = ChunkView {
+            range: mid..self.range.end,
+            ..self
+        };
+        (left, right)
+    }
+
+    /// Get an iterator of the data of the entities in the view.
+    #[inline]
+    pub fn iter<'b>(&'b mut self) -> <V as View<'b>>::Iter {
+        // safe because views are only created by dividing a chunk into disjoint ranges
+        let range = self.range.clone();
+        unsafe { V::fetch_range(self.archetype, self.components, self.set, range) }
+    }
+
+    /// Get an iterator of the data and entity IDs of the entities in the view.
+    #[inline]
+    pub fn iter_entities<'b>(&'b mut self) -> ZipEntities<'b, V> {
+        ZipEntities {
+            entities: self.entities(),
+            data: self.iter(),
+            index: 0,
+            view: PhantomData,
+        }
+    }
 }
 
 /// An iterator which yields view data tuples and entity IDs from a `Chunk`.
@@ -544,7 +1376,7 @@ where
     arch_filter: &'filter FArch,
     chunkset_filter: &'filter FChunkset,
     chunk_filter: &'filter FChunk,
-    archetypes: Enumerate<FArch::Iter>,
+    archetypes: ArchetypeCursor<'data, FArch::Iter>,
     set_frontier: Option<(&'data ArchetypeData, Take<Enumerate<FChunkset::Iter>>)>,
     chunk_frontier: Option<(&'data ArchetypeData, usize, Take<Enumerate<FChunk::Iter>>)>,
 }
@@ -614,28 +1446,106 @@ where
         loop {
             // if we are looping through a set, then yield the next chunk
             if let Some((ref arch, set_index, ref mut set)) = self.chunk_frontier {
+                let chunkset = unsafe { arch.chunksets().get_unchecked(set_index) };
                 for (chunk_index, filter_data) in set {
-                    if self.chunk_filter.is_match(&filter_data).is_pass() {
+                    if chunkset.is_occupied(chunk_index)
+                        && self.chunk_filter.is_match(&filter_data).is_pass()
+                    {
                         return Some(Chunk::new(arch, set_index, chunk_index));
                     }
                 }
             }
 
-            // we have completed the set, find the next
-            if let Some((ref arch, set_index)) = self.next_set() {
-                let chunks = unsafe { arch.chunksets().get_unchecked(set_index) }.occupied();
-                self.chunk_frontier = Some((
-                    arch,
-                    set_index,
-                    self.chunk_filter
-                        .collect(ChunkFilterData { chunks })
-                        .enumerate()
-                        .take(chunks.len()),
-                ))
-            } else {
-                return None;
+            // we have completed the set, find the next
+            if let Some((ref arch, set_index)) = self.next_set() {
+                let chunks = unsafe { arch.chunksets().get_unchecked(set_index) }.occupied();
+                self.chunk_frontier = Some((
+                    arch,
+                    set_index,
+                    self.chunk_filter
+                        .collect(ChunkFilterData { chunks })
+                        .enumerate()
+                        .take(chunks.len()),
+                ))
+            } else {
+                return None;
+            }
+        }
+    }
+}
+
+impl<'data, 'filter, V, FArch, FChunkset, FChunk>
+    ChunkViewIter<'data, 'filter, V, FArch, FChunkset, FChunk>
+where
+    V: for<'a> View<'a>,
+    FArch: Filter<ArchetypeFilterData<'data>>,
+    FChunkset: Filter<ChunksetFilterData<'data>>,
+    FChunk: Filter<ChunkFilterData<'data>>,
+{
+    /// Creates an iterator which prefetches the component data of the next chunk while the
+    /// current chunk is being processed. This helps to hide memory latency when iterating
+    /// over worlds which are too large to remain in the CPU caches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// # world.insert((), vec![(Position(1.0),)]);
+    /// let mut query = Write::<Position>::query();
+    /// for mut chunk in query.iter_chunks(&mut world).prefetch() {
+    ///     for mut pos in chunk.iter() {
+    ///         pos.0 += 1.0;
+    ///     }
+    /// }
+    /// ```
+    pub fn prefetch(self) -> PrefetchChunks<'data, V, Self> {
+        PrefetchChunks {
+            iter: self,
+            next: None,
+            started: false,
+        }
+    }
+}
+
+/// An iterator over chunks which prefetches the component data of each chunk one chunk
+/// ahead of it being yielded. See `ChunkViewIter::prefetch`.
+pub struct PrefetchChunks<'data, V, I>
+where
+    V: for<'a> View<'a>,
+    I: Iterator<Item = Chunk<'data, V>>,
+{
+    iter: I,
+    next: Option<Chunk<'data, V>>,
+    started: bool,
+}
+
+impl<'data, V, I> Iterator for PrefetchChunks<'data, V, I>
+where
+    V: for<'a> View<'a>,
+    I: Iterator<Item = Chunk<'data, V>>,
+{
+    type Item = Chunk<'data, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = if self.started {
+            self.next.take()
+        } else {
+            self.started = true;
+            self.iter.next()
+        };
+
+        if current.is_some() {
+            self.next = self.iter.next();
+            if let Some(next) = &self.next {
+                next.prefetch();
             }
         }
+
+        current
     }
 }
 
@@ -673,6 +1583,99 @@ where
     }
 }
 
+/// An iterator which iterates through the entity data of a group of chunks which share the
+/// same tag value. Yielded by `Query::iter_by_tag`.
+pub type TagGroupIter<'data, V> = ChunkDataIter<'data, V, std::vec::IntoIter<Chunk<'data, V>>>;
+
+/// An iterator which iterates through all entities which match two queries, yielding the
+/// entity ID along with the data of both queries' views.
+pub struct JoinIter<'data, V1, V2, I>
+where
+    V1: for<'a> View<'a>,
+    V2: for<'a> View<'a>,
+    I: Iterator<Item = Chunk<'data, V1>>,
+{
+    iter: I,
+    others: HashMap<ChunkId, Chunk<'data, V2>>,
+    frontier: Option<(
+        Iter<'data, Entity>,
+        <V1 as View<'data>>::Iter,
+        <V2 as View<'data>>::Iter,
+    )>,
+}
+
+impl<'data, V1, V2, I> Iterator for JoinIter<'data, V1, V2, I>
+where
+    V1: for<'a> View<'a>,
+    V2: for<'a> View<'a>,
+    I: Iterator<Item = Chunk<'data, V1>>,
+{
+    type Item = (
+        Entity,
+        <<V1 as View<'data>>::Iter as Iterator>::Item,
+        <<V2 as View<'data>>::Iter as Iterator>::Item,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((ref mut entities, ref mut a, ref mut b)) = self.frontier {
+                if let (Some(entity), Some(a), Some(b)) = (entities.next(), a.next(), b.next()) {
+                    return Some((*entity, a, b));
+                }
+            }
+
+            let mut chunk = self.iter.next()?;
+            if let Some(mut other) = self.others.remove(&chunk.id()) {
+                self.frontier = Some((chunk.entities().iter(), chunk.iter(), other.iter()));
+            }
+        }
+    }
+}
+
+/// An iterator which iterates through the entities found by a query in one world which are also
+/// found by a second query in another world, yielding the entity ID along with the data of both
+/// views.
+pub struct WorldJoinIter<'data, 'a, V1, V2, F2, I>
+where
+    V1: for<'b> View<'b>,
+    V2: for<'b> View<'b>,
+    F2: EntityFilter,
+    I: Iterator<Item = (Entity, <<V1 as View<'data>>::Iter as Iterator>::Item)>,
+{
+    iter: I,
+    other: &'a mut Query<V2, F2>,
+    other_world: &'data World,
+    _view: PhantomData<V1>,
+}
+
+impl<'data, 'a, V1, V2, F2, I> Iterator for WorldJoinIter<'data, 'a, V1, V2, F2, I>
+where
+    V1: for<'b> View<'b>,
+    V2: for<'b> View<'b>,
+    F2: EntityFilter,
+    I: Iterator<Item = (Entity, <<V1 as View<'data>>::Iter as Iterator>::Item)>,
+{
+    type Item = (
+        Entity,
+        <<V1 as View<'data>>::Iter as Iterator>::Item,
+        <<V2 as View<'data>>::Iter as Iterator>::Item,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (entity, a) = self.iter.next()?;
+
+            // the worlds may belong to different universes, so look the entity up by ID alone
+            let id = Entity::new(entity.index(), entity.version());
+
+            // safe because access to the other world was validated when the iterator was created
+            if let Some(b) = unsafe { self.other.find_unchecked(self.other_world, id) } {
+                return Some((entity, a, b));
+            }
+        }
+    }
+}
+
 /// An iterator which iterates through all entity data in all chunks, zipped with entity ID.
 pub struct ChunkEntityIter<'data, V, I>
 where
@@ -829,6 +1832,26 @@ where
 /// The `ChunkView` yielded from `iter_chunks` allows access to all shared data in the chunk (queried for or not),
 /// but entity data slices can only be accessed if they were requested in the query's view. Attempting to access
 /// other data types, or attempting to write to components that were only requested via a `Read` will panic.
+/// The reason that `Query::single` could not return exactly one entity's data.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SingleError {
+    /// No entities matched the query.
+    NoMatches,
+    /// More than one entity matched the query.
+    MultipleMatches,
+}
+
+impl std::fmt::Display for SingleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SingleError::NoMatches => write!(f, "no entities matched the query"),
+            SingleError::MultipleMatches => write!(f, "more than one entity matched the query"),
+        }
+    }
+}
+
+impl std::error::Error for SingleError {}
+
 #[derive(Derivative)]
 #[derivative(Clone(bound = "F: Clone"))]
 pub struct Query<V: for<'a> View<'a>, F: EntityFilter> {
@@ -853,9 +1876,257 @@ where
         }
     }
 
-    /// Gets an iterator which iterates through all chunks that match the query.
+    /// Converts the query into a `PagedQuery`, which processes a bounded number of entities
+    /// per call and resumes where it left off on the next call.
+    pub fn paged(self) -> PagedQuery<V, F> {
+        PagedQuery {
+            query: self,
+            cursor: None,
+        }
+    }
+
+    /// Gets an iterator which iterates through all chunks that match the query.
+    /// Does not perform static borrow checking.
+    ///
+    /// # Safety
+    ///
+    /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if other code is concurrently accessing the same components.
+    pub unsafe fn iter_chunks_unchecked<'a, 'data>(
+        &'a mut self,
+        world: &'data World,
+    ) -> ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter> {
+        let (arch_filter, chunkset_filter, chunk_filter) = self.filter.filters();
+        begin_pass(arch_filter, chunkset_filter, chunk_filter);
+        let storage = world.storage();
+        let archetypes = ArchetypeCursor::new(
+            arch_filter.collect(ArchetypeFilterData {
+                component_types: storage.component_types(),
+                tag_types: storage.tag_types(),
+                archetypes: storage.archetypes(),
+            }),
+            arch_filter.candidates(storage.component_index()),
+        );
+        ChunkViewIter {
+            storage,
+            arch_filter,
+            chunkset_filter,
+            chunk_filter,
+            archetypes,
+            set_frontier: None,
+            chunk_frontier: None,
+            _view: PhantomData,
+        }
+    }
+
+    /// Gets an iterator which iterates through all chunks that match the query.
+    pub fn iter_chunks_immutable<'a, 'data>(
+        &'a mut self,
+        world: &'data World,
+    ) -> ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>
+    where
+        V: ReadOnly,
+    {
+        // safe because the view can only read data immutably
+        unsafe { self.iter_chunks_unchecked(world) }
+    }
+
+    /// Gets an iterator which iterates through all chunks that match the query.
+    pub fn iter_chunks<'a, 'data>(
+        &'a mut self,
+        world: &'data mut World,
+    ) -> ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter> {
+        // safe because the &mut World ensures exclusivity
+        unsafe { self.iter_chunks_unchecked(world) }
+    }
+
+    /// Gets an iterator which iterates through all entity data that matches the query, and also yields the the `Entity` IDs.
+    /// Does not perform static borrow checking.
+    ///
+    /// # Safety
+    ///
+    /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if other code is concurrently accessing the same components.
+    pub unsafe fn iter_entities_unchecked<'a, 'data>(
+        &'a mut self,
+        world: &'data World,
+    ) -> ChunkEntityIter<
+        'data,
+        V,
+        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    > {
+        ChunkEntityIter {
+            iter: self.iter_chunks_unchecked(world),
+            frontier: None,
+            _view: PhantomData,
+        }
+    }
+
+    /// Gets an iterator which iterates through all entity data that matches the query, and also yields the the `Entity` IDs.
+    pub fn iter_entities_immutable<'a, 'data>(
+        &'a mut self,
+        world: &'data World,
+    ) -> ChunkEntityIter<
+        'data,
+        V,
+        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    >
+    where
+        V: ReadOnly,
+    {
+        // safe because the view can only read data immutably
+        unsafe { self.iter_entities_unchecked(world) }
+    }
+
+    /// Gets an iterator which iterates through all entity data that matches the query, and also yields the the `Entity` IDs.
+    pub fn iter_entities<'a, 'data>(
+        &'a mut self,
+        world: &'data mut World,
+    ) -> ChunkEntityIter<
+        'data,
+        V,
+        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    > {
+        // safe because the &mut World ensures exclusivity
+        unsafe { self.iter_entities_unchecked(world) }
+    }
+
+    /// Gets an iterator which iterates through all entity data that matches the query.
+    /// Does not perform static borrow checking.
+    ///
+    /// # Safety
+    ///
+    /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if other code is concurrently accessing the same components.
+    pub unsafe fn iter_unchecked<'a, 'data>(
+        &'a mut self,
+        world: &'data World,
+    ) -> ChunkDataIter<
+        'data,
+        V,
+        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    > {
+        ChunkDataIter {
+            iter: self.iter_chunks_unchecked(world),
+            frontier: None,
+            _view: PhantomData,
+        }
+    }
+
+    /// Gets an iterator which iterates through all entity data that matches the query.
+    pub fn iter_immutable<'a, 'data>(
+        &'a mut self,
+        world: &'data World,
+    ) -> ChunkDataIter<
+        'data,
+        V,
+        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    >
+    where
+        V: ReadOnly,
+    {
+        // safe because the view can only read data immutably
+        unsafe { self.iter_unchecked(world) }
+    }
+
+    /// Gets an iterator which iterates through all entity data that matches the query.
+    pub fn iter<'a, 'data>(
+        &'a mut self,
+        world: &'data mut World,
+    ) -> ChunkDataIter<
+        'data,
+        V,
+        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    > {
+        // safe because the &mut World ensures exclusivity
+        unsafe { self.iter_unchecked(world) }
+    }
+
+    /// Gets the data of the only entity which matches the query.
+    /// Does not perform static borrow checking.
+    ///
+    /// # Safety
+    ///
+    /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if other code is concurrently accessing the same components.
+    pub unsafe fn single_unchecked<'data>(
+        &mut self,
+        world: &'data World,
+    ) -> Result<<<V as View<'data>>::Iter as Iterator>::Item, SingleError> {
+        let mut iter = self.iter_unchecked(world);
+        match (iter.next(), iter.next()) {
+            (Some(item), None) => Ok(item),
+            (None, _) => Err(SingleError::NoMatches),
+            (Some(_), Some(_)) => Err(SingleError::MultipleMatches),
+        }
+    }
+
+    /// Gets the data of the only entity which matches the query, such as a singleton player or
+    /// camera entity.
+    ///
+    /// Returns an error if either no entities or more than one entity matched the query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// use legion::query::SingleError;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Camera;
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// world.insert((), vec![(Position(1.0), Camera)]);
+    /// world.insert((), vec![(Position(2.0),), (Position(3.0),)]);
+    ///
+    /// let mut query = <(Read<Position>, Read<Camera>)>::query();
+    /// let (position, _) = query.single(&world).unwrap();
+    /// assert_eq!(Position(1.0), *position);
+    ///
+    /// let mut query = Read::<Position>::query();
+    /// assert_eq!(Some(SingleError::MultipleMatches), query.single(&world).err());
+    /// ```
+    pub fn single<'data>(
+        &mut self,
+        world: &'data World,
+    ) -> Result<<<V as View<'data>>::Iter as Iterator>::Item, SingleError>
+    where
+        V: ReadOnly,
+    {
+        // safe because the view can only read data immutably
+        unsafe { self.single_unchecked(world) }
+    }
+
+    /// Gets the data of the only entity which matches the query, such as a singleton player or
+    /// camera entity.
+    ///
+    /// Returns an error if either no entities or more than one entity matched the query.
+    pub fn single_mut<'data>(
+        &mut self,
+        world: &'data mut World,
+    ) -> Result<<<V as View<'data>>::Iter as Iterator>::Item, SingleError> {
+        // safe because the &mut World ensures exclusivity
+        unsafe { self.single_unchecked(world) }
+    }
+
+    /// Gets the data of the given entity, as it would be yielded by iterating the query.
     /// Does not perform static borrow checking.
     ///
+    /// Returns `None` if the entity is not alive or does not match the query's filter.
+    ///
     /// # Safety
     ///
     /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
@@ -863,157 +2134,487 @@ where
     /// # Panics
     ///
     /// This function may panic if other code is concurrently accessing the same components.
-    pub unsafe fn iter_chunks_unchecked<'a, 'data>(
-        &'a mut self,
+    pub unsafe fn find_unchecked<'data>(
+        &mut self,
         world: &'data World,
-    ) -> ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter> {
-        let (arch_filter, chunkset_filter, chunk_filter) = self.filter.filters();
+        entity: Entity,
+    ) -> Option<<<V as View<'data>>::Iter as Iterator>::Item> {
+        if !world.is_alive(entity) {
+            return None;
+        }
+
+        let location = world.entity_allocator.get_location(entity.index())?;
         let storage = world.storage();
-        let archetypes = arch_filter
-            .collect(ArchetypeFilterData {
-                component_types: storage.component_types(),
-                tag_types: storage.tag_types(),
-            })
-            .enumerate();
-        ChunkViewIter {
-            storage,
-            arch_filter,
-            chunkset_filter,
-            chunk_filter,
-            archetypes,
-            set_frontier: None,
-            chunk_frontier: None,
-            _view: PhantomData,
+        let (arch_filter, chunkset_filter, chunk_filter) = self.filter.filters();
+        begin_pass(arch_filter, chunkset_filter, chunk_filter);
+
+        let archetypes = ArchetypeFilterData {
+            component_types: storage.component_types(),
+            tag_types: storage.tag_types(),
+            archetypes: storage.archetypes(),
+        };
+        let filter_data = arch_filter.collect(archetypes).nth(location.archetype())?;
+        if !arch_filter.is_match(&filter_data).is_pass() {
+            return None;
+        }
+
+        let archetype = storage.archetypes().get(location.archetype())?;
+        let sets = ChunksetFilterData {
+            archetype_data: archetype,
+        };
+        let filter_data = chunkset_filter.collect(sets).nth(location.set())?;
+        if !chunkset_filter.is_match(&filter_data).is_pass() {
+            return None;
+        }
+
+        let chunks = archetype.chunksets().get(location.set())?.occupied();
+        let filter_data = chunk_filter
+            .collect(ChunkFilterData { chunks })
+            .nth(location.chunk())?;
+        if !chunk_filter.is_match(&filter_data).is_pass() {
+            return None;
         }
+
+        V::fetch(archetype, chunks.get(location.chunk())?, location.set()).nth(location.component())
     }
 
-    /// Gets an iterator which iterates through all chunks that match the query.
-    pub fn iter_chunks_immutable<'a, 'data>(
-        &'a mut self,
+    /// Gets the data of the given entity, as it would be yielded by iterating the query, such
+    /// that code acting upon a single target can reuse the query's view.
+    ///
+    /// Returns `None` if the entity is not alive or does not match the query's filter. Filters
+    /// are evaluated as they are during iteration, so change detection filters will record the
+    /// entity's chunk as having been read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Health(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Armor(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Invulnerable;
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// let target = world.insert((), vec![(Health(10.0), Armor(0.5))])[0];
+    /// let immune = world.insert((Invulnerable,), vec![(Health(10.0), Armor(0.5))])[0];
+    ///
+    /// let mut query = <(Read<Armor>, Read<Health>)>::query().filter(!tag::<Invulnerable>());
+    /// let (armor, health) = query.find(&world, target).unwrap();
+    /// assert_eq!(Health(10.0), *health);
+    /// assert_eq!(Armor(0.5), *armor);
+    /// assert!(query.find(&world, immune).is_none());
+    /// ```
+    pub fn find<'data>(
+        &mut self,
         world: &'data World,
-    ) -> ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>
+        entity: Entity,
+    ) -> Option<<<V as View<'data>>::Iter as Iterator>::Item>
     where
         V: ReadOnly,
     {
         // safe because the view can only read data immutably
-        unsafe { self.iter_chunks_unchecked(world) }
+        unsafe { self.find_unchecked(world, entity) }
     }
 
-    /// Gets an iterator which iterates through all chunks that match the query.
-    pub fn iter_chunks<'a, 'data>(
-        &'a mut self,
+    /// Gets the data of the given entity, as it would be yielded by iterating the query, such
+    /// that code acting upon a single target can reuse the query's view.
+    ///
+    /// Returns `None` if the entity is not alive or does not match the query's filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Health(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Armor(f32);
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// let target = world.insert((), vec![(Health(10.0), Armor(0.5))])[0];
+    ///
+    /// let mut damage = <(Read<Armor>, Write<Health>)>::query();
+    /// if let Some((armor, mut health)) = damage.find_mut(&mut world, target) {
+    ///     health.0 -= 4.0 * armor.0;
+    /// }
+    /// assert_eq!(Health(8.0), *world.get_component::<Health>(target).unwrap());
+    /// ```
+    pub fn find_mut<'data>(
+        &mut self,
         world: &'data mut World,
-    ) -> ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter> {
+        entity: Entity,
+    ) -> Option<<<V as View<'data>>::Iter as Iterator>::Item> {
         // safe because the &mut World ensures exclusivity
-        unsafe { self.iter_chunks_unchecked(world) }
+        unsafe { self.find_unchecked(world, entity) }
     }
 
-    /// Gets an iterator which iterates through all entity data that matches the query, and also yields the the `Entity` IDs.
+    /// Gets an iterator which iterates through all entity data that matches the query, ordered
+    /// by the key returned from `key` for each entity.
+    ///
+    /// All matching entity data is gathered before the first item is yielded, and `key` is called
+    /// once per entity. The sort is stable, so entities with equal keys are yielded in storage
+    /// order. Floating point keys can be sorted by wrapping them in a type which implements `Ord`,
+    /// such as by comparing them with `f32::total_cmp`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Layer(u8);
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// world.insert((), vec![(Layer(2),), (Layer(0),), (Layer(1),)]);
+    ///
+    /// let mut query = Read::<Layer>::query();
+    /// let layers = query
+    ///     .iter_sorted_by_key(&world, |layer| layer.0)
+    ///     .map(|layer| layer.0)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(vec![0, 1, 2], layers);
+    /// ```
+    pub fn iter_sorted_by_key<'a, 'data, K, T>(
+        &'a mut self,
+        world: &'data World,
+        mut key: T,
+    ) -> std::vec::IntoIter<<<V as View<'data>>::Iter as Iterator>::Item>
+    where
+        V: ReadOnly,
+        K: Ord,
+        T: FnMut(&<<V as View<'data>>::Iter as Iterator>::Item) -> K,
+    {
+        let mut items = self.iter_immutable(world).collect::<Vec<_>>();
+        items.sort_by_cached_key(|item| key(item));
+        items.into_iter()
+    }
+
+    /// Gets an iterator which iterates through all entities which match both this query and
+    /// `other`, yielding the entity ID along with the data of both views.
     /// Does not perform static borrow checking.
     ///
+    /// Entities are matched per chunk, so the cost of the join is linear in the number of chunks
+    /// matched by either query, rather than in the product of their entity counts.
+    ///
     /// # Safety
     ///
     /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
     ///
     /// # Panics
     ///
-    /// This function may panic if other code is concurrently accessing the same components.
-    pub unsafe fn iter_entities_unchecked<'a, 'data>(
+    /// This function may panic if other code is concurrently accessing the same components, or if
+    /// one of the views writes a component type which is also accessed by the other view.
+    pub unsafe fn join_unchecked<'a, 'data, V2, F2>(
         &'a mut self,
+        other: &'a mut Query<V2, F2>,
         world: &'data World,
-    ) -> ChunkEntityIter<
+    ) -> JoinIter<
         'data,
         V,
+        V2,
         ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
-    > {
-        ChunkEntityIter {
+    >
+    where
+        V2: for<'b> View<'b>,
+        F2: EntityFilter,
+    {
+        JoinIter {
+            others: other
+                .iter_chunks_unchecked(world)
+                .map(|chunk| (chunk.id(), chunk))
+                .collect(),
             iter: self.iter_chunks_unchecked(world),
             frontier: None,
-            _view: PhantomData,
         }
     }
 
-    /// Gets an iterator which iterates through all entity data that matches the query, and also yields the the `Entity` IDs.
-    pub fn iter_entities_immutable<'a, 'data>(
+    /// Gets an iterator which iterates through all entities which match both this query and
+    /// `other`, yielding the entity ID along with the data of both views.
+    pub fn join_immutable<'a, 'data, V2, F2>(
         &'a mut self,
+        other: &'a mut Query<V2, F2>,
         world: &'data World,
-    ) -> ChunkEntityIter<
+    ) -> JoinIter<
         'data,
         V,
+        V2,
         ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
     >
     where
         V: ReadOnly,
+        V2: for<'b> View<'b> + ReadOnly,
+        F2: EntityFilter,
     {
-        // safe because the view can only read data immutably
-        unsafe { self.iter_entities_unchecked(world) }
+        // safe because the views can only read data immutably
+        unsafe { self.join_unchecked(other, world) }
     }
 
-    /// Gets an iterator which iterates through all entity data that matches the query, and also yields the the `Entity` IDs.
-    pub fn iter_entities<'a, 'data>(
+    /// Gets an iterator which iterates through all entities which match both this query and
+    /// `other`, yielding the entity ID along with the data of both views.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if one of the views writes a component type which is also
+    /// accessed by the other view.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Health(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Armor(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Burning;
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// world.insert((), vec![(Health(10.0), Armor(0.5))]);
+    /// world.insert((Burning,), vec![(Health(10.0),), (Health(5.0),)]);
+    ///
+    /// let mut damageable = <(Write<Health>, TryRead<Armor>)>::query();
+    /// let mut burning = Tagged::<Burning>::query();
+    /// for (_, (mut health, armor), _) in damageable.join(&mut burning, &mut world) {
+    ///     health.0 -= 1.0 - armor.map(|a| a.0).unwrap_or(0.0);
+    /// }
+    /// ```
+    pub fn join<'a, 'data, V2, F2>(
         &'a mut self,
+        other: &'a mut Query<V2, F2>,
         world: &'data mut World,
-    ) -> ChunkEntityIter<
+    ) -> JoinIter<
         'data,
         V,
+        V2,
         ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
-    > {
+    >
+    where
+        V2: for<'b> View<'b>,
+        F2: EntityFilter,
+    {
         // safe because the &mut World ensures exclusivity
-        unsafe { self.iter_entities_unchecked(world) }
+        unsafe { self.join_unchecked(other, world) }
     }
 
-    /// Gets an iterator which iterates through all entity data that matches the query.
+    /// Gets an iterator which iterates through all entities which match this query in `world`
+    /// and which also match `other` in `other_world`, yielding the entity ID along with the data
+    /// of both views. Entities are matched by ID.
     /// Does not perform static borrow checking.
     ///
     /// # Safety
     ///
     /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    /// If both worlds are the same world, this includes the components accessed by both views.
     ///
     /// # Panics
     ///
     /// This function may panic if other code is concurrently accessing the same components.
-    pub unsafe fn iter_unchecked<'a, 'data>(
+    pub unsafe fn join_worlds_unchecked<'a, 'data, V2, F2>(
         &'a mut self,
         world: &'data World,
-    ) -> ChunkDataIter<
+        other: &'a mut Query<V2, F2>,
+        other_world: &'data World,
+    ) -> WorldJoinIter<
         'data,
+        'a,
         V,
-        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
-    > {
-        ChunkDataIter {
-            iter: self.iter_chunks_unchecked(world),
-            frontier: None,
+        V2,
+        F2,
+        ChunkEntityIter<
+            'data,
+            V,
+            ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+        >,
+    >
+    where
+        V2: for<'b> View<'b>,
+        F2: EntityFilter,
+    {
+        WorldJoinIter {
+            iter: self.iter_entities_unchecked(world),
+            other,
+            other_world,
             _view: PhantomData,
         }
     }
 
-    /// Gets an iterator which iterates through all entity data that matches the query.
-    pub fn iter_immutable<'a, 'data>(
+    /// Gets an iterator which iterates through all entities which match this query in `world`
+    /// and which also match `other` in `other_world`, yielding the entity ID along with the data
+    /// of both views. Entities are matched by ID, even if the worlds belong to different universes.
+    ///
+    /// This is intended for reconciling two copies of the same simulation, such as a client's
+    /// predicted world against an authoritative snapshot received from a server.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// let mut predicted = World::new();
+    /// let mut authoritative = World::new();
+    /// let entity = predicted.insert((), vec![(Position(1.5),)])[0];
+    /// authoritative.insert((), vec![(Position(1.0),)]);
+    ///
+    /// let mut local = Write::<Position>::query();
+    /// let mut remote = Read::<Position>::query();
+    /// for (_, mut predicted, authoritative) in local.join_worlds(&mut predicted, &mut remote, &authoritative) {
+    ///     if (predicted.0 - authoritative.0).abs() > 0.1 {
+    ///         *predicted = *authoritative;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Position(1.0), *predicted.get_component::<Position>(entity).unwrap());
+    /// ```
+    pub fn join_worlds<'a, 'data, V2, F2>(
+        &'a mut self,
+        world: &'data mut World,
+        other: &'a mut Query<V2, F2>,
+        other_world: &'data World,
+    ) -> WorldJoinIter<
+        'data,
+        'a,
+        V,
+        V2,
+        F2,
+        ChunkEntityIter<
+            'data,
+            V,
+            ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+        >,
+    >
+    where
+        V2: for<'b> View<'b> + ReadOnly,
+        F2: EntityFilter,
+    {
+        // safe because the &mut World ensures exclusivity, and the other view can only read
+        unsafe { self.join_worlds_unchecked(world, other, other_world) }
+    }
+
+    /// Gets an iterator which iterates through all entities which match this query in `world`
+    /// and which also match `other` in `other_world`, yielding the entity ID along with the data
+    /// of both views. Entities are matched by ID.
+    pub fn join_worlds_immutable<'a, 'data, V2, F2>(
         &'a mut self,
         world: &'data World,
-    ) -> ChunkDataIter<
+        other: &'a mut Query<V2, F2>,
+        other_world: &'data World,
+    ) -> WorldJoinIter<
         'data,
+        'a,
         V,
-        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+        V2,
+        F2,
+        ChunkEntityIter<
+            'data,
+            V,
+            ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+        >,
     >
+    where
+        V: ReadOnly,
+        V2: for<'b> View<'b> + ReadOnly,
+        F2: EntityFilter,
+    {
+        // safe because the views can only read data immutably
+        unsafe { self.join_worlds_unchecked(world, other, other_world) }
+    }
+
+    /// Gets an iterator which groups all entity data that matches the query by the value of tag
+    /// `T`, yielding each distinct tag value along with an iterator over the entity data of all
+    /// chunks which carry it.
+    /// Does not perform static borrow checking.
+    ///
+    /// Groups are yielded in the order in which their tag value is first found. Chunks which do
+    /// not have tag `T` are skipped. All matching chunks are gathered before the first group is
+    /// yielded.
+    ///
+    /// # Safety
+    ///
+    /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if other code is concurrently accessing the same components.
+    pub unsafe fn iter_by_tag_unchecked<'data, T: Tag>(
+        &mut self,
+        world: &'data World,
+    ) -> std::vec::IntoIter<(&'data T, TagGroupIter<'data, V>)> {
+        let mut groups: Vec<(&'data T, Vec<Chunk<'data, V>>)> = Vec::new();
+        for chunk in self.iter_chunks_unchecked(world) {
+            if let Some(tag) = chunk.tag::<T>() {
+                match groups.iter_mut().find(|(value, _)| *value == tag) {
+                    Some((_, chunks)) => chunks.push(chunk),
+                    None => groups.push((tag, vec![chunk])),
+                }
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(tag, chunks)| {
+                let iter = ChunkDataIter {
+                    iter: chunks.into_iter(),
+                    frontier: None,
+                    _view: PhantomData,
+                };
+                (tag, iter)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Gets an iterator which groups all entity data that matches the query by the value of tag
+    /// `T`, yielding each distinct tag value along with an iterator over the entity data of all
+    /// chunks which carry it.
+    pub fn iter_by_tag_immutable<'data, T: Tag>(
+        &mut self,
+        world: &'data World,
+    ) -> std::vec::IntoIter<(&'data T, TagGroupIter<'data, V>)>
     where
         V: ReadOnly,
     {
         // safe because the view can only read data immutably
-        unsafe { self.iter_unchecked(world) }
+        unsafe { self.iter_by_tag_unchecked(world) }
     }
 
-    /// Gets an iterator which iterates through all entity data that matches the query.
-    pub fn iter<'a, 'data>(
-        &'a mut self,
+    /// Gets an iterator which groups all entity data that matches the query by the value of tag
+    /// `T`, yielding each distinct tag value along with an iterator over the entity data of all
+    /// chunks which carry it.
+    ///
+    /// Groups are yielded in the order in which their tag value is first found. Chunks which do
+    /// not have tag `T` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Transform(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Material(u32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Static;
+    /// # fn draw_instanced(material: &Material, transforms: &[Transform]) {}
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// world.insert((Material(1),), vec![(Transform(0.0),), (Transform(1.0),)]);
+    /// world.insert((Material(1), Static), vec![(Transform(2.0),)]);
+    /// world.insert((Material(2),), vec![(Transform(3.0),)]);
+    ///
+    /// let mut query = Read::<Transform>::query();
+    /// for (material, transforms) in query.iter_by_tag::<Material>(&mut world) {
+    ///     let transforms = transforms.map(|t| *t).collect::<Vec<_>>();
+    ///     draw_instanced(material, &transforms);
+    /// }
+    /// ```
+    pub fn iter_by_tag<'data, T: Tag>(
+        &mut self,
         world: &'data mut World,
-    ) -> ChunkDataIter<
-        'data,
-        V,
-        ChunkViewIter<'data, 'a, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
-    > {
+    ) -> std::vec::IntoIter<(&'data T, TagGroupIter<'data, V>)> {
         // safe because the &mut World ensures exclusivity
-        unsafe { self.iter_unchecked(world) }
+        unsafe { self.iter_by_tag_unchecked(world) }
     }
 
     /// Iterates through all entity data that matches the query.
@@ -1033,6 +2634,7 @@ where
     ) where
         T: Fn((Entity, <<V as View<'data>>::Iter as Iterator>::Item)),
     {
+        profile_scope!("Query::for_each");
         self.iter_entities_unchecked(world).for_each(&mut f);
     }
 
@@ -1069,6 +2671,7 @@ where
     where
         T: Fn(<<V as View<'data>>::Iter as Iterator>::Item),
     {
+        profile_scope!("Query::for_each");
         self.iter_unchecked(world).for_each(&mut f);
     }
 
@@ -1112,10 +2715,12 @@ where
         <F::ChunkFilter as Filter<ChunkFilterData<'data>>>::Iter: FissileIterator,
     {
         let (arch_filter, chunkset_filter, chunk_filter) = self.filter.filters();
+        begin_pass(arch_filter, chunkset_filter, chunk_filter);
         let storage = world.storage();
         let archetypes = FissileEnumerate::new(arch_filter.collect(ArchetypeFilterData {
             component_types: storage.component_types(),
             tag_types: storage.tag_types(),
+            archetypes: storage.archetypes(),
         }));
         ChunkViewParIter {
             storage,
@@ -1125,6 +2730,7 @@ where
             archetypes,
             set_frontier: None,
             chunk_frontier: None,
+            batch_size: 1,
             _view: PhantomData,
         }
     }
@@ -1282,6 +2888,7 @@ where
         <F::ChunksetFilter as Filter<ChunksetFilterData<'a>>>::Iter: FissileIterator,
         <F::ChunkFilter as Filter<ChunkFilterData<'a>>>::Iter: FissileIterator,
     {
+        profile_scope!("Query::par_for_each");
         let par_iter = self.par_iter_chunks_unchecked(world);
         ParallelIterator::for_each(par_iter, |chunk| {
             f(chunk);
@@ -1316,6 +2923,168 @@ where
     }
 }
 
+/// The position within the query results at which a `PagedQuery` resumes.
+#[derive(Copy, Clone, Debug)]
+struct PageCursor {
+    /// The archetype, chunkset and chunk index of the chunk to resume in.
+    chunk: (usize, usize, usize),
+    /// The index of the next entity to process within the chunk.
+    offset: usize,
+    /// The structural version of the chunk when the cursor was recorded.
+    version: u64,
+}
+
+/// A query which processes at most a given budget of entities per call, resuming from where
+/// the previous call stopped. This allows expensive systems (such as AI) to time-slice their
+/// work across multiple frames.
+///
+/// Once all matching entities have been visited, the next call begins again from the start of
+/// the query results.
+///
+/// If the chunk the query stopped in has since had entities added, removed or reordered,
+/// processing resumes from the start of that chunk. Entities may therefore be visited more than
+/// once in a single pass, but entities which remain in place are never skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use legion::prelude::*;
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Brain(u32);
+/// let universe = Universe::new();
+/// let mut world = universe.create_world();
+/// world.insert((), (0..10).map(|i| (Brain(i),)));
+///
+/// let mut query = Write::<Brain>::query().paged();
+///
+/// // think for at most 4 entities each frame
+/// assert_eq!(4, query.for_each_page(&mut world, 4, |mut brain| brain.0 += 100));
+/// assert_eq!(4, query.for_each_page(&mut world, 4, |mut brain| brain.0 += 100));
+/// assert_eq!(2, query.for_each_page(&mut world, 4, |mut brain| brain.0 += 100));
+/// assert!(query.is_at_start());
+/// ```
+#[derive(Derivative)]
+#[derivative(Clone(bound = "F: Clone"))]
+pub struct PagedQuery<V: for<'a> View<'a>, F: EntityFilter> {
+    query: Query<V, F>,
+    cursor: Option<PageCursor>,
+}
+
+impl<V, F> PagedQuery<V, F>
+where
+    V: for<'a> View<'a>,
+    F: EntityFilter,
+{
+    /// Determines if the next page will begin from the start of the query results.
+    pub fn is_at_start(&self) -> bool { self.cursor.is_none() }
+
+    /// Discards the cursor, such that the next page begins from the start of the query results.
+    pub fn reset(&mut self) { self.cursor = None; }
+
+    /// Gets the underlying query.
+    pub fn query(&mut self) -> &mut Query<V, F> { &mut self.query }
+
+    /// Processes up to `budget` entities which match the query, continuing from where the
+    /// previous page stopped.
+    /// Does not perform static borrow checking.
+    ///
+    /// Returns the number of entities processed. This is less than `budget` only when the end
+    /// of the query results was reached.
+    ///
+    /// # Safety
+    ///
+    /// Incorrectly accessing components that are already borrowed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if other code is concurrently accessing the same components.
+    pub unsafe fn for_each_page_unchecked<'a, 'data, T>(
+        &'a mut self,
+        world: &'data World,
+        budget: usize,
+        mut f: T,
+    ) -> usize
+    where
+        T: FnMut(<<V as View<'data>>::Iter as Iterator>::Item),
+    {
+        profile_scope!("PagedQuery::for_each_page");
+
+        if budget == 0 {
+            return 0;
+        }
+
+        let resume = self.cursor.take();
+        let mut processed = 0;
+        for mut chunk in self.query.iter_chunks_unchecked(world) {
+            let id = chunk.id();
+            let key = (id.archetype_id().index(), id.set(), id.index());
+            let version = chunk.components.structural_version();
+            let start = match resume {
+                Some(ref cursor) if key < cursor.chunk => continue,
+                Some(ref cursor) if key == cursor.chunk && version == cursor.version => {
+                    cursor.offset
+                }
+                _ => 0,
+            };
+
+            let count = std::cmp::min(
+                budget - processed,
+                chunk.components.len().saturating_sub(start),
+            );
+            chunk.iter().skip(start).take(count).for_each(&mut f);
+            processed += count;
+
+            if processed == budget {
+                self.cursor = Some(PageCursor {
+                    chunk: key,
+                    offset: start + count,
+                    version,
+                });
+                break;
+            }
+        }
+
+        processed
+    }
+
+    /// Processes up to `budget` entities which match the query, continuing from where the
+    /// previous page stopped.
+    ///
+    /// Returns the number of entities processed. This is less than `budget` only when the end
+    /// of the query results was reached.
+    pub fn for_each_page_immutable<'a, 'data, T>(
+        &'a mut self,
+        world: &'data World,
+        budget: usize,
+        f: T,
+    ) -> usize
+    where
+        T: FnMut(<<V as View<'data>>::Iter as Iterator>::Item),
+        V: ReadOnly,
+    {
+        // safe because the view can only read data immutably
+        unsafe { self.for_each_page_unchecked(world, budget, f) }
+    }
+
+    /// Processes up to `budget` entities which match the query, continuing from where the
+    /// previous page stopped.
+    ///
+    /// Returns the number of entities processed. This is less than `budget` only when the end
+    /// of the query results was reached.
+    pub fn for_each_page<'a, 'data, T>(
+        &'a mut self,
+        world: &'data mut World,
+        budget: usize,
+        f: T,
+    ) -> usize
+    where
+        T: FnMut(<<V as View<'data>>::Iter as Iterator>::Item),
+    {
+        // safe because the &mut World ensures exclusivity
+        unsafe { self.for_each_page_unchecked(world, budget, f) }
+    }
+}
+
 /// An iterator over all chunks that match a given query.
 #[cfg(feature = "par-iter")]
 pub struct ChunkViewParIter<'data, 'filter, V, FArch, FChunkset, FChunk>
@@ -1345,6 +3114,7 @@ where
         FissileEnumerate<FChunk::Iter>,
         usize,
     )>,
+    batch_size: usize,
 }
 
 #[cfg(feature = "par-iter")]
@@ -1359,6 +3129,45 @@ where
     FChunkset::Iter: FissileIterator,
     FChunk::Iter: FissileIterator,
 {
+    /// Sets the minimum number of chunks from a chunkset that are processed together by each
+    /// parallel task. Defaults to `1`.
+    ///
+    /// Larger batches reduce scheduling overhead for queries over many small chunks.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn enter_set(&mut self, arch: &'data ArchetypeData, set_index: usize) {
+        let chunks = unsafe { arch.chunksets().get_unchecked(set_index) }.occupied();
+        self.chunk_frontier = Some((
+            arch,
+            set_index,
+            FissileEnumerate::new(self.chunk_filter.collect(ChunkFilterData { chunks })),
+            chunks.len(),
+        ))
+    }
+
+    /// Advances into the next chunkset if no more than one archetype or chunkset remains to be
+    /// split, so that the chunks within it can be divided between tasks.
+    fn descend(&mut self) {
+        let remaining = |hint: (usize, Option<usize>)| hint.1.unwrap_or(usize::MAX);
+        let chunks = self
+            .chunk_frontier
+            .as_ref()
+            .map_or(0, |(_, _, iter, _)| remaining(iter.size_hint()));
+        let sets = self
+            .set_frontier
+            .as_ref()
+            .map_or(0, |(_, iter, _)| remaining(iter.size_hint()));
+        let archetypes = remaining(self.archetypes.size_hint());
+        if chunks == 0 && sets.saturating_add(archetypes) <= 1 {
+            if let Some((arch, set_index)) = self.next_set() {
+                self.enter_set(arch, set_index);
+            }
+        }
+    }
+
     fn next_set(&mut self) -> Option<(&'data ArchetypeData, usize)> {
         loop {
             // if we are looping through an archetype, find the next set
@@ -1420,8 +3229,10 @@ where
         loop {
             // if we are looping through a set, then yield the next chunk
             if let Some((ref arch, set_index, ref mut set, index_bound)) = self.chunk_frontier {
+                let chunkset = unsafe { arch.chunksets().get_unchecked(set_index) };
                 for (chunk_index, filter_data) in set {
                     if chunk_index < index_bound
+                        && chunkset.is_occupied(chunk_index)
                         && self.chunk_filter.is_match(&filter_data).is_pass()
                     {
                         return Some(Chunk::new(arch, set_index, chunk_index));
@@ -1430,14 +3241,8 @@ where
             }
 
             // we have completed the set, find the next
-            if let Some((ref arch, set_index)) = self.next_set() {
-                let chunks = unsafe { arch.chunksets().get_unchecked(set_index) }.occupied();
-                self.chunk_frontier = Some((
-                    arch,
-                    set_index,
-                    FissileEnumerate::new(self.chunk_filter.collect(ChunkFilterData { chunks })),
-                    chunks.len(),
-                ))
+            if let Some((arch, set_index)) = self.next_set() {
+                self.enter_set(arch, set_index);
             } else {
                 return None;
             }
@@ -1481,7 +3286,9 @@ where
 {
     type Item = Chunk<'data, V>;
 
-    fn split(self) -> (Self, Option<Self>) {
+    fn split(mut self) -> (Self, Option<Self>) {
+        self.descend();
+
         let Self {
             _view,
             storage,
@@ -1491,6 +3298,7 @@ where
             archetypes,
             set_frontier,
             chunk_frontier,
+            batch_size,
         } = self;
 
         let (left_archetypes, right_archetypes, arch_size) = archetypes.split();
@@ -1506,17 +3314,23 @@ where
             (None, None, 0)
         };
 
-        let (left_chunk, right_chunk, chunk_size) =
-            if let Some((data, idx, iter, bound)) = chunk_frontier {
+        // only divide the current chunkset if both halves can fill a batch
+        let (left_chunk, right_chunk, chunk_size) = match chunk_frontier {
+            Some((data, idx, iter, bound))
+                if iter
+                    .size_hint()
+                    .1
+                    .is_none_or(|len| len >= batch_size.saturating_mul(2)) =>
+            {
                 let (left_iter, right_iter, iter_size) = iter.split();
                 (
                     Some((data, idx, left_iter, bound)),
                     Some((data, idx, right_iter, bound)),
                     iter_size,
                 )
-            } else {
-                (None, None, 0)
-            };
+            }
+            frontier => (None, frontier, 0),
+        };
 
         let right_split = Self {
             _view,
@@ -1527,6 +3341,7 @@ where
             archetypes: right_archetypes,
             set_frontier: right_set,
             chunk_frontier: right_chunk,
+            batch_size,
         };
 
         if arch_size + set_size + chunk_size == 0 {
@@ -1542,6 +3357,7 @@ where
                     archetypes: left_archetypes,
                     set_frontier: left_set,
                     chunk_frontier: left_chunk,
+                    batch_size,
                 },
                 Some(right_split),
             )
@@ -1554,3 +3370,36 @@ where
         folder.consume_iter(self)
     }
 }
+
+#[cfg(all(test, feature = "par-iter"))]
+mod tests {
+    use crate::prelude::*;
+    use rayon::iter::ParallelIterator;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Large([u64; 128]);
+
+    #[test]
+    fn par_iter_chunks_batch_size() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.insert((), (0..512).map(|_| (Large([0; 128]),)));
+
+        let mut query = Read::<Large>::query();
+        let chunks = query.iter_chunks_immutable(&world).count();
+        assert!(chunks > 8);
+
+        // each task folds its chunks into a single count
+        let par_iter = query.par_iter_chunks(&mut world).batch_size(4);
+        let batches =
+            ParallelIterator::fold(par_iter, || 0, |count, _| count + 1).collect::<Vec<usize>>();
+        assert_eq!(chunks, batches.iter().sum::<usize>());
+        assert!(batches.iter().all(|count| *count == 0 || *count >= 4));
+
+        if rayon::current_num_threads() > 1 {
+            assert!(batches.iter().filter(|count| **count > 0).count() > 1);
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Runtime reflection of component data, for building tools such as editor inspectors.
+//!
+//! Component types implement `Reflect` (either by hand, or via the `impl_reflect!` macro) to
+//! expose their fields by name. Once registered with `Registry::register_reflect`, the
+//! components attached to an entity can be inspected via `World::inspect` and modified via
+//! `World::reflect_component_mut`. With the `serialize` feature enabled, fields can also be set
+//! from JSON via `World::apply_json_patch`.
+
+#[cfg(feature = "serialize")]
+use crate::error::EntityError;
+use std::any::Any;
+use std::fmt::Debug;
+#[cfg(feature = "serialize")]
+use std::fmt::Display;
+#[cfg(feature = "serialize")]
+use std::fmt::Formatter;
+
+/// A type whose fields can be enumerated and accessed by name at runtime.
+pub trait Reflect: Any {
+    /// Gets the names of the value's fields.
+    ///
+    /// Returns an empty `Vec` for values which have no fields, such as primitives.
+    fn field_names(&self) -> Vec<&'static str> { Vec::new() }
+
+    /// Gets a field by name.
+    fn field(&self, _name: &str) -> Option<&dyn Reflect> { None }
+
+    /// Mutably gets a field by name.
+    fn field_mut(&mut self, _name: &str) -> Option<&mut dyn Reflect> { None }
+
+    /// Formats the value for display, if it is a leaf value.
+    fn display_value(&self) -> Option<String> { None }
+
+    /// Gets the value as `Any`.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Gets the value as a mutable `Any`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl dyn Reflect {
+    /// Gets the value at the given `.` separated field path.
+    pub fn path(&self, path: &str) -> Option<&dyn Reflect> {
+        path.split('.')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |value, name| value.field(name))
+    }
+
+    /// Mutably gets the value at the given `.` separated field path.
+    pub fn path_mut(&mut self, path: &str) -> Option<&mut dyn Reflect> {
+        path.split('.')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |value, name| value.field_mut(name))
+    }
+
+    /// Gets the value at the given field path, if it is of type `T`.
+    pub fn get<T: Reflect>(&self, path: &str) -> Option<&T> {
+        self.path(path).and_then(|v| v.as_any().downcast_ref::<T>())
+    }
+
+    /// Sets the value at the given field path.
+    ///
+    /// Returns `false` if the field could not be found, or is not of type `T`.
+    pub fn set<T: Reflect>(&mut self, path: &str, value: T) -> bool {
+        match self
+            .path_mut(path)
+            .and_then(|v| v.as_any_mut().downcast_mut::<T>())
+        {
+            Some(field) => {
+                *field = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Builds a tree describing the value and all of its fields.
+    pub fn to_node(&self, name: &str) -> FieldNode {
+        FieldNode {
+            name: name.to_owned(),
+            value: self.display_value(),
+            children: self
+                .field_names()
+                .into_iter()
+                .filter_map(|field| self.field(field).map(|v| v.to_node(field)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl dyn Reflect {
+    /// Sets the value from JSON.
+    ///
+    /// JSON objects set each of their members on the field of the same name, recursively.
+    /// Any other JSON value replaces the whole value, which must be one of the primitive types
+    /// which implement `Reflect`. Fields not named in an object are left unchanged.
+    ///
+    /// The patch is not applied atomically; fields visited before an error is encountered
+    /// keep their new values.
+    pub fn apply_json(&mut self, value: &serde_json::Value) -> Result<(), PatchError> {
+        self.apply_json_at(value, "")
+    }
+
+    // Applies `value`, naming fields in errors relative to `path`.
+    pub(crate) fn apply_json_at(
+        &mut self,
+        value: &serde_json::Value,
+        path: &str,
+    ) -> Result<(), PatchError> {
+        match value {
+            serde_json::Value::Object(fields) if !self.field_names().is_empty() => {
+                for (name, value) in fields {
+                    let path = if path.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{}.{}", path, name)
+                    };
+                    match self.field_mut(name) {
+                        Some(field) => field.apply_json_at(value, &path)?,
+                        None => return Err(PatchError::UnknownField(path)),
+                    }
+                }
+                Ok(())
+            }
+            _ if set_json_value(self.as_any_mut(), value) => Ok(()),
+            _ => Err(PatchError::InvalidValue(path.to_owned())),
+        }
+    }
+}
+
+// Deserializes `value` into `target`, if it is one of the primitive reflected types.
+#[cfg(feature = "serialize")]
+fn set_json_value(target: &mut dyn Any, value: &serde_json::Value) -> bool {
+    macro_rules! set_primitive {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(target) = target.downcast_mut::<$ty>() {
+                    return match serde_json::from_value::<$ty>(value.clone()) {
+                        Ok(value) => {
+                            *target = value;
+                            true
+                        }
+                        Err(_) => false,
+                    };
+                }
+            )*
+        };
+    }
+
+    set_primitive!(
+        bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64,
+        String
+    );
+    false
+}
+
+/// The error returned when a JSON patch could not be applied to an entity.
+#[cfg(feature = "serialize")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PatchError {
+    /// The entity could not be patched.
+    Entity(EntityError),
+    /// The entity has no reflected component with the given name.
+    UnknownComponent(String),
+    /// There is no field at the given path.
+    UnknownField(String),
+    /// The value at the given path was not an object, or could not be converted to the type of
+    /// the field.
+    InvalidValue(String),
+}
+
+#[cfg(feature = "serialize")]
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PatchError::Entity(err) => Display::fmt(err, f),
+            PatchError::UnknownComponent(name) => {
+                write!(f, "entity has no reflected component named `{}`", name)
+            }
+            PatchError::UnknownField(path) => write!(f, "no field at `{}`", path),
+            PatchError::InvalidValue(path) => write!(f, "invalid value for `{}`", path),
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl std::error::Error for PatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PatchError::Entity(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl From<EntityError> for PatchError {
+    fn from(err: EntityError) -> Self { PatchError::Entity(err) }
+}
+
+/// A node in a tree of reflected fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldNode {
+    /// The name of the field, or of the component type for root nodes.
+    pub name: String,
+    /// The formatted value of the field, for leaf values.
+    pub value: Option<String>,
+    /// The child fields.
+    pub children: Vec<FieldNode>,
+}
+
+macro_rules! impl_reflect_value {
+    ($($ty:ty),*) => {
+        $(
+            impl Reflect for $ty {
+                fn display_value(&self) -> Option<String> { Some(format!("{:?}", self)) }
+
+                fn as_any(&self) -> &dyn Any { self }
+
+                fn as_any_mut(&mut self) -> &mut dyn Any { self }
+            }
+        )*
+    };
+}
+
+impl_reflect_value!(
+    bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, String
+);
+
+impl<T: Debug + 'static> Reflect for Option<T> {
+    fn display_value(&self) -> Option<String> { Some(format!("{:?}", self)) }
+
+    fn as_any(&self) -> &dyn Any { self }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+}
+
+/// Implements `Reflect` for a struct with named fields, all of which implement `Reflect`.
+///
+/// ```
+/// # use legion::impl_reflect;
+/// # use legion::reflect::Reflect;
+/// struct Health {
+///     current: f32,
+///     max: f32,
+/// }
+///
+/// impl_reflect!(Health { current, max });
+///
+/// let mut health = Health { current: 5.0, max: 10.0 };
+/// let reflect: &mut dyn Reflect = &mut health;
+/// assert!(reflect.set("current", 7.5f32));
+/// assert_eq!(Some(&7.5f32), reflect.get::<f32>("current"));
+/// ```
+#[macro_export]
+macro_rules! impl_reflect {
+    ($ty:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::reflect::Reflect for $ty {
+            fn field_names(&self) -> Vec<&'static str> { vec![$(stringify!($field)),*] }
+
+            fn field(&self, name: &str) -> Option<&dyn $crate::reflect::Reflect> {
+                match name {
+                    $(stringify!($field) => Some(&self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn $crate::reflect::Reflect> {
+                match name {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any { self }
+
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Transform {
+        position: Vec3,
+        scale: f32,
+    }
+
+    struct Vec3 {
+        x: f32,
+        y: f32,
+        z: f32,
+    }
+
+    impl_reflect!(Transform { position, scale });
+    impl_reflect!(Vec3 { x, y, z });
+
+    #[test]
+    fn nested_fields() {
+        let mut transform = Transform {
+            position: Vec3 {
+                x: 1.,
+                y: 2.,
+                z: 3.,
+            },
+            scale: 1.,
+        };
+        let reflect: &mut dyn Reflect = &mut transform;
+
+        assert_eq!(Some(&2.), reflect.get::<f32>("position.y"));
+        assert!(reflect.set("position.z", 5f32));
+        assert!(!reflect.set("position.z", 5u32));
+        assert!(!reflect.set("position.w", 5f32));
+        assert_eq!(5., transform.position.z);
+
+        let reflect: &dyn Reflect = &transform;
+        let node = reflect.to_node("Transform");
+        assert_eq!(vec!["position", "scale"], node.children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>());
+        assert_eq!(Some("5.0".to_owned()), node.children[0].children[2].value);
+    }
+}
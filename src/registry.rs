@@ -0,0 +1,467 @@
+use crate::reflect::Reflect;
+use crate::storage::Component;
+use crate::storage::ComponentMeta;
+use crate::storage::ComponentTypeId;
+use crate::storage::NonSyncComponent;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// Describes how a component type can be constructed at runtime.
+#[derive(Copy, Clone)]
+pub struct ComponentRegistration {
+    meta: ComponentMeta,
+    name: &'static str,
+    default_fn: Option<fn(*mut u8)>,
+    clone_fn: Option<fn(*const u8, *mut u8)>,
+    pod: bool,
+    hash_fn: Option<fn(*const u8, &mut dyn Hasher)>,
+    reflect_fn: Option<fn(*mut u8) -> *mut dyn Reflect>,
+    #[cfg(feature = "serialize")]
+    serialize_fn: Option<fn(*const u8) -> serde_json::Result<serde_json::Value>>,
+    #[cfg(feature = "serialize")]
+    deserialize_fn: Option<fn(&serde_json::Value, *mut u8) -> serde_json::Result<()>>,
+}
+
+impl ComponentRegistration {
+    /// Creates a registration for component type `T`.
+    pub fn of<T: Component>() -> Self {
+        Self::raw(ComponentMeta::of::<T>(), std::any::type_name::<T>())
+    }
+
+    /// Creates a registration for a component type described only by its meta, such as a
+    /// component type defined outside of Rust.
+    pub fn raw(meta: ComponentMeta, name: &'static str) -> Self {
+        ComponentRegistration {
+            meta,
+            name,
+            default_fn: None,
+            clone_fn: None,
+            pod: false,
+            hash_fn: None,
+            reflect_fn: None,
+            #[cfg(feature = "serialize")]
+            serialize_fn: None,
+            #[cfg(feature = "serialize")]
+            deserialize_fn: None,
+        }
+    }
+
+    /// Creates a registration for component type `T` which can construct default values.
+    pub fn of_default<T: Component + Default>() -> Self {
+        ComponentRegistration {
+            default_fn: Some(|ptr| unsafe { std::ptr::write(ptr as *mut T, T::default()) }),
+            ..Self::of::<T>()
+        }
+    }
+
+    /// Gets the component meta of the registered type.
+    pub fn meta(&self) -> &ComponentMeta { &self.meta }
+
+    /// Gets the name of the registered type.
+    pub fn name(&self) -> &'static str { self.name }
+
+    /// Determines if the registered type can construct default values.
+    pub fn has_default(&self) -> bool { self.default_fn.is_some() }
+
+    /// Determines if the registered type can be cloned.
+    pub fn has_clone(&self) -> bool { self.clone_fn.is_some() }
+
+    /// Determines if the registered type is plain old data, which can be cloned by copying
+    /// its bytes.
+    pub fn is_pod(&self) -> bool { self.pod }
+
+    /// Writes a clone of the value at `src` to `dst`.
+    ///
+    /// Returns `false` if the type was not registered via `Registry::register_clone`.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to a valid value of the registered type. `dst` must be valid for
+    /// writes and aligned for the registered type. Any value already at `dst` will be
+    /// overwritten without being dropped.
+    pub unsafe fn clone_into(&self, src: *const u8, dst: *mut u8) -> bool {
+        if let Some(clone_fn) = self.clone_fn {
+            clone_fn(src, dst);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Determines if the registered type can be hashed.
+    pub fn has_hash(&self) -> bool { self.hash_fn.is_some() }
+
+    /// Feeds the value at `ptr` into `hasher`.
+    ///
+    /// Returns `false` if the type was not registered via `Registry::register_hash`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid value of the registered type.
+    pub unsafe fn hash(&self, ptr: *const u8, hasher: &mut dyn Hasher) -> bool {
+        if let Some(hash_fn) = self.hash_fn {
+            hash_fn(ptr, hasher);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Determines if the registered type can be serialized.
+    #[cfg(feature = "serialize")]
+    pub fn has_serde(&self) -> bool { self.serialize_fn.is_some() }
+
+    /// Serializes the value at `ptr`.
+    ///
+    /// Returns `None` if the type was not registered via `Registry::register_serde`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid value of the registered type.
+    #[cfg(feature = "serialize")]
+    pub unsafe fn serialize(
+        &self,
+        ptr: *const u8,
+    ) -> Option<serde_json::Result<serde_json::Value>> {
+        self.serialize_fn.map(|serialize_fn| serialize_fn(ptr))
+    }
+
+    /// Deserializes `value` and writes the result to `ptr`.
+    ///
+    /// Returns `None` if the type was not registered via `Registry::register_serde`.
+    /// Nothing is written to `ptr` if deserialization fails.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes and aligned for the registered type. Any value
+    /// already at `ptr` will be overwritten without being dropped.
+    #[cfg(feature = "serialize")]
+    pub unsafe fn deserialize(
+        &self,
+        value: &serde_json::Value,
+        ptr: *mut u8,
+    ) -> Option<serde_json::Result<()>> {
+        self.deserialize_fn
+            .map(|deserialize_fn| deserialize_fn(value, ptr))
+    }
+
+    /// Determines if the registered type implements `Reflect`.
+    pub fn has_reflect(&self) -> bool { self.reflect_fn.is_some() }
+
+    /// Casts a pointer to a value of the registered type into a `Reflect` trait object.
+    ///
+    /// Returns `None` if the type was not registered via `Registry::register_reflect`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid value of the registered type. The returned pointer
+    /// is subject to the same aliasing rules as `ptr`.
+    pub unsafe fn reflect(&self, ptr: *mut u8) -> Option<*mut dyn Reflect> {
+        self.reflect_fn.map(|reflect_fn| reflect_fn(ptr))
+    }
+
+    /// Writes a default value of the registered type to `ptr`.
+    ///
+    /// Returns `false` if the type was not registered with a default.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes and aligned for the registered type. Any value
+    /// already at `ptr` will be overwritten without being dropped.
+    pub unsafe fn write_default(&self, ptr: *mut u8) -> bool {
+        if let Some(default_fn) = self.default_fn {
+            default_fn(ptr);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A component type which knows how to register itself, and its runtime hooks, into a `Registry`.
+///
+/// This is usually implemented via `#[derive(LegionComponent)]` when the `derive` feature is enabled.
+pub trait RegisterComponent: Component {
+    /// Registers the component type into `registry`.
+    fn register(registry: &mut Registry);
+}
+
+/// Stores runtime type information about component types.
+#[derive(Default, Clone)]
+pub struct Registry {
+    components: HashMap<ComponentTypeId, ComponentRegistration>,
+    non_sync: HashMap<TypeId, &'static str>,
+}
+
+impl Registry {
+    /// Creates a new empty `Registry`.
+    pub fn new() -> Self { Self::default() }
+
+    /// Registers component type `T`.
+    pub fn register<T: Component>(&mut self) {
+        self.components
+            .entry(ComponentTypeId::of::<T>())
+            .or_insert_with(ComponentRegistration::of::<T>);
+    }
+
+    /// Registers component type `T`, along with its `Default` constructor.
+    ///
+    /// Defaulted components can be added to entities without providing a value, such as
+    /// via `World::insert_with_defaults`.
+    pub fn register_with_default<T: Component + Default>(&mut self) {
+        self.components
+            .entry(ComponentTypeId::of::<T>())
+            .or_insert_with(ComponentRegistration::of::<T>)
+            .default_fn = ComponentRegistration::of_default::<T>().default_fn;
+    }
+
+    /// Registers a component type from a runtime registration.
+    ///
+    /// Any existing registration for the type is replaced.
+    pub fn register_raw(&mut self, type_id: ComponentTypeId, registration: ComponentRegistration) {
+        self.components.insert(type_id, registration);
+    }
+
+    /// Registers component type `T`, identifying it by its name and the given salt rather than
+    /// by its `TypeId`.
+    ///
+    /// When game code is hot reloaded from a dynamic library, the `TypeId` of each of its types
+    /// changes, orphaning any existing component data. Stably identified types instead refer to
+    /// the same component columns before and after the reload. The salt distinguishes types
+    /// whose names would otherwise collide, and may be changed to deliberately treat a type as
+    /// new.
+    ///
+    /// The salt applies to every world, and must be registered before any world stores the
+    /// type. A type whose layout changes across a reload is treated as a new type. The library
+    /// which defined the type must remain loaded while existing components may still be dropped.
+    #[cfg(feature = "stable-type-ids")]
+    pub fn register_stable<T: Component>(&mut self, salt: u64) {
+        crate::storage::set_type_salt(std::any::type_name::<T>(), salt);
+        self.register::<T>();
+    }
+
+    /// Registers component type `T` via its `RegisterComponent` implementation.
+    pub fn register_component<T: RegisterComponent>(&mut self) { T::register(self); }
+
+    /// Registers component type `T`, along with its `Clone` implementation.
+    pub fn register_clone<T: Component + Clone>(&mut self) {
+        self.components
+            .entry(ComponentTypeId::of::<T>())
+            .or_insert_with(ComponentRegistration::of::<T>)
+            .clone_fn = Some(|src, dst| unsafe {
+            std::ptr::write(dst as *mut T, (*(src as *const T)).clone())
+        });
+    }
+
+    /// Registers component type `T` as plain old data, which can be cloned by copying its bytes.
+    ///
+    /// The chunks of plain old data components are shared with world snapshots, and only
+    /// copied when they are first written to. See `World::snapshot`.
+    pub fn register_pod<T: Component + Copy>(&mut self) {
+        self.register_clone::<T>();
+        self.components
+            .get_mut(&ComponentTypeId::of::<T>())
+            .unwrap()
+            .pod = true;
+    }
+
+    /// Registers component type `T`, along with its `Hash` implementation.
+    ///
+    /// Hashed components are hashed by value in `World::checksum`, rather than by their bytes.
+    pub fn register_hash<T: Component + Hash>(&mut self) {
+        self.components
+            .entry(ComponentTypeId::of::<T>())
+            .or_insert_with(ComponentRegistration::of::<T>)
+            .hash_fn = Some(|ptr, mut hasher| unsafe { (*(ptr as *const T)).hash(&mut hasher) });
+    }
+
+    /// Registers component type `T`, along with its serde implementations.
+    #[cfg(feature = "serialize")]
+    pub fn register_serde<T>(&mut self)
+    where
+        T: Component + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let registration = self
+            .components
+            .entry(ComponentTypeId::of::<T>())
+            .or_insert_with(ComponentRegistration::of::<T>);
+        registration.serialize_fn =
+            Some(|ptr| serde_json::to_value(unsafe { &*(ptr as *const T) }));
+        registration.deserialize_fn = Some(|value, ptr| {
+            let component = T::deserialize(value)?;
+            unsafe { std::ptr::write(ptr as *mut T, component) };
+            Ok(())
+        });
+    }
+
+    /// Registers component type `T`, along with its `Reflect` implementation.
+    ///
+    /// Reflected components are included in `World::inspect`, and can be modified by
+    /// field name via `World::reflect_component_mut`.
+    pub fn register_reflect<T: Component + Reflect>(&mut self) {
+        self.components
+            .entry(ComponentTypeId::of::<T>())
+            .or_insert_with(ComponentRegistration::of::<T>)
+            .reflect_fn = Some(|ptr| ptr as *mut T as *mut dyn Reflect);
+    }
+
+    /// Registers non-sync component type `T`.
+    ///
+    /// Non-sync components must be registered before they can be attached to entities via
+    /// `World::add_non_sync_component`.
+    pub fn register_non_sync<T: NonSyncComponent>(&mut self) {
+        self.non_sync
+            .insert(TypeId::of::<T>(), std::any::type_name::<T>());
+    }
+
+    /// Determines if `T` has been registered as a non-sync component type.
+    pub fn is_non_sync<T: NonSyncComponent>(&self) -> bool {
+        self.non_sync.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Gets the registration of the given component type.
+    pub fn get(&self, type_id: ComponentTypeId) -> Option<&ComponentRegistration> {
+        self.components.get(&type_id)
+    }
+
+    /// Determines if the given component type has been registered.
+    pub fn is_registered(&self, type_id: ComponentTypeId) -> bool {
+        self.components.contains_key(&type_id)
+    }
+
+    /// Gets an iterator over all registered component types.
+    pub fn iter(&self) -> impl Iterator<Item = (&ComponentTypeId, &ComponentRegistration)> {
+        self.components.iter()
+    }
+
+    /// Copies all registrations from `other` which are not already present in this registry.
+    pub fn merge(&mut self, other: &Registry) {
+        for (type_id, registration) in other.components.iter() {
+            self.components.entry(*type_id).or_insert(*registration);
+        }
+        for (type_id, name) in other.non_sync.iter() {
+            self.non_sync.entry(*type_id).or_insert(*name);
+        }
+    }
+}
+
+impl std::fmt::Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.components.values().map(|r| r.name))
+            .entries(self.non_sync.values())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[test]
+    fn register_with_default() {
+        let mut registry = Registry::new();
+        registry.register::<f32>();
+        registry.register_with_default::<Health>();
+
+        assert!(!registry.get(ComponentTypeId::of::<f32>()).unwrap().has_default());
+
+        let registration = registry.get(ComponentTypeId::of::<Health>()).unwrap();
+        let mut value = std::mem::MaybeUninit::<Health>::uninit();
+        unsafe {
+            assert!(registration.write_default(value.as_mut_ptr() as *mut u8));
+            assert_eq!(Health(0), value.assume_init());
+        }
+    }
+
+    #[test]
+    fn register_clone() {
+        let mut registry = Registry::new();
+        registry.register_with_default::<Health>();
+        registry.register_clone::<String>();
+
+        let registration = registry.get(ComponentTypeId::of::<String>()).unwrap();
+        let source = "hello".to_owned();
+        let mut value = std::mem::MaybeUninit::<String>::uninit();
+        unsafe {
+            assert!(registration.clone_into(
+                &source as *const String as *const u8,
+                value.as_mut_ptr() as *mut u8
+            ));
+            assert_eq!(source, value.assume_init());
+        }
+
+        let registration = registry.get(ComponentTypeId::of::<Health>()).unwrap();
+        assert!(registration.has_default());
+        assert!(!registration.has_clone());
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn register_serde() {
+        let mut registry = Registry::new();
+        registry.register_serde::<String>();
+
+        let registration = registry.get(ComponentTypeId::of::<String>()).unwrap();
+        let source = "hello".to_owned();
+        let mut value = std::mem::MaybeUninit::<String>::uninit();
+        unsafe {
+            let json = registration
+                .serialize(&source as *const String as *const u8)
+                .unwrap()
+                .unwrap();
+            assert_eq!(serde_json::json!("hello"), json);
+
+            registration
+                .deserialize(&json, value.as_mut_ptr() as *mut u8)
+                .unwrap()
+                .unwrap();
+            assert_eq!(source, value.assume_init());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "stable-type-ids")]
+    fn register_stable() {
+        struct Reloaded(u32);
+
+        let unsalted = ComponentTypeId::of::<Reloaded>();
+        let mut registry = Registry::new();
+        registry.register_stable::<Reloaded>(7);
+
+        let type_id = ComponentTypeId::of::<Reloaded>();
+        assert_ne!(unsalted, type_id);
+        assert_eq!(type_id, ComponentTypeId::of::<Reloaded>());
+        assert!(registry.is_registered(type_id));
+    }
+
+    #[test]
+    #[cfg(feature = "stable-type-ids")]
+    fn stable_type_id_includes_layout() {
+        use crate::storage::stable_type_id;
+        use std::alloc::Layout;
+
+        assert_ne!(
+            stable_type_id("Reloaded", Layout::new::<u32>()),
+            stable_type_id("Reloaded", Layout::new::<u64>())
+        );
+        assert_eq!(
+            stable_type_id("Reloaded", Layout::new::<u32>()),
+            stable_type_id("Reloaded", Layout::new::<u32>())
+        );
+    }
+
+    #[test]
+    fn register_non_sync() {
+        let mut registry = Registry::new();
+        registry.register_non_sync::<std::rc::Rc<u32>>();
+
+        assert!(registry.is_non_sync::<std::rc::Rc<u32>>());
+        assert!(!registry.is_non_sync::<u32>());
+    }
+}
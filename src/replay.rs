@@ -0,0 +1,237 @@
+//! Deterministic recording and replay of simulations.
+//!
+//! A `Recording` captures a fork of a world, then records the inputs applied to the world on
+//! each tick along with the world's checksum after the tick. Inputs are any type describing
+//! what happened during the tick, such as player commands, random seeds and the structural
+//! changes made by the simulation, and are applied by a step function supplied by the caller.
+//!
+//! Replaying the recording applies the same inputs to a new fork of the initial world, and
+//! compares the checksums of each tick. The first tick whose checksum differs is reported as
+//! a `Desync`, which narrows down where two runs of a simulation diverged. Replaying only part
+//! of a recording with `replay_until` produces a world from which alternate futures can be
+//! explored, such as for AI training rollouts.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! use legion::replay::Recording;
+//!
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! struct Position(f32);
+//!
+//! #[derive(Clone)]
+//! enum Input {
+//!     Spawn(f32),
+//!     Move(f32),
+//! }
+//!
+//! fn step(world: &mut World, inputs: &[Input]) {
+//!     for input in inputs {
+//!         match input {
+//!             Input::Spawn(x) => {
+//!                 world.insert((), vec![(Position(*x),)]);
+//!             }
+//!             Input::Move(dx) => {
+//!                 for mut pos in Write::<Position>::query().iter(world) {
+//!                     pos.0 += dx;
+//!                 }
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let universe = Universe::new();
+//! let mut world = universe.create_world();
+//! world.registry.register_pod::<Position>();
+//! let registry = world.registry.clone();
+//!
+//! let mut recording = Recording::new(&mut world, &registry).unwrap();
+//! recording.record(&mut world, vec![Input::Spawn(1.0)], step).unwrap();
+//! recording.record(&mut world, vec![Input::Move(2.0)], step).unwrap();
+//!
+//! let replayed = recording.replay(step).unwrap();
+//! assert_eq!(world.checksum(&registry).unwrap(), replayed.checksum(&registry).unwrap());
+//! ```
+use crate::error::Error;
+use crate::registry::Registry;
+use crate::world::World;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The inputs applied to a world during a single recorded tick.
+#[derive(Debug, Clone)]
+pub struct RecordedTick<I> {
+    /// The inputs passed to the step function.
+    pub inputs: Vec<I>,
+    /// The checksum of the world after the tick, as computed by `World::checksum`.
+    pub checksum: u64,
+}
+
+/// The first tick of a replay whose checksum differed from the recording.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Desync {
+    /// The index of the tick within the recording.
+    pub tick: usize,
+    /// The checksum recorded for the tick.
+    pub expected: u64,
+    /// The checksum of the replayed world after the tick.
+    pub actual: u64,
+}
+
+impl Display for Desync {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "replay desynchronized at tick {}: expected checksum {:#x}, found {:#x}",
+            self.tick, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for Desync {}
+
+/// A fork of a world, and the inputs applied to it on each tick since.
+pub struct Recording<I> {
+    start: World,
+    registry: Registry,
+    ticks: Vec<RecordedTick<I>>,
+}
+
+impl<I> Recording<I> {
+    /// Begins a recording of `world`, capturing its current state.
+    ///
+    /// `registry` is used both to clone the world and to compute checksums, and so every
+    /// component type stored in the world must be registered as cloneable, and as either
+    /// hashable or plain old data. Component types which may be added by later ticks should
+    /// also be registered.
+    ///
+    /// Forks of the recording allocate the same entity IDs as the recorded world, provided
+    /// no other world in the universe claims entity blocks while the recording is made.
+    pub fn new(world: &mut World, registry: &Registry) -> Result<Self, Error> {
+        Ok(Recording {
+            start: world.try_clone_detached(registry)?,
+            registry: registry.clone(),
+            ticks: Vec::new(),
+        })
+    }
+
+    /// Gets the ticks recorded so far.
+    pub fn ticks(&self) -> &[RecordedTick<I>] { &self.ticks }
+
+    /// Applies `inputs` to `world` with `step`, and records them along with the resulting
+    /// checksum of the world. Returns the checksum.
+    ///
+    /// `world` should be the world the recording was created from, or a world which has
+    /// followed the same sequence of ticks.
+    ///
+    /// Returns an error without recording the tick if the checksum cannot be computed, as
+    /// described by `World::checksum`. The step has still been applied to `world`.
+    pub fn record<F: FnMut(&mut World, &[I])>(
+        &mut self,
+        world: &mut World,
+        inputs: Vec<I>,
+        mut step: F,
+    ) -> Result<u64, Error> {
+        step(world, &inputs);
+        let checksum = world.checksum(&self.registry)?;
+        self.ticks.push(RecordedTick { inputs, checksum });
+        Ok(checksum)
+    }
+
+    /// Creates a new fork of the world as it was when the recording began.
+    ///
+    /// Each fork allocates entity IDs independently of the universe, and so the entities
+    /// of a fork should not be mixed with those of other worlds.
+    pub fn fork(&mut self) -> World {
+        self.start
+            .try_clone_detached(&self.registry)
+            .expect("recorded world is no longer cloneable")
+    }
+
+    /// Replays every recorded tick into a new fork of the initial world, returning the
+    /// resulting world.
+    ///
+    /// Returns the first tick whose checksum differs from the recording, if any.
+    pub fn replay<F: FnMut(&mut World, &[I])>(&mut self, step: F) -> Result<World, Desync> {
+        self.replay_until(self.ticks.len(), step)
+    }
+
+    /// Replays the first `ticks` recorded ticks into a new fork of the initial world, returning
+    /// the resulting world.
+    ///
+    /// Returns the first tick whose checksum differs from the recording, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ticks` is greater than the number of recorded ticks, or if the replayed world
+    /// contains a component type whose checksum cannot be computed.
+    pub fn replay_until<F: FnMut(&mut World, &[I])>(
+        &mut self,
+        ticks: usize,
+        mut step: F,
+    ) -> Result<World, Desync> {
+        let mut world = self.fork();
+        for (tick, recorded) in self.ticks[..ticks].iter().enumerate() {
+            step(&mut world, &recorded.inputs);
+            let actual = world
+                .checksum(&self.registry)
+                .expect("replayed world is not checksummable");
+            if actual != recorded.checksum {
+                return Err(Desync {
+                    tick,
+                    expected: recorded.checksum,
+                    actual,
+                });
+            }
+        }
+
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32);
+
+    fn step(world: &mut World, inputs: &[f32]) {
+        for input in inputs {
+            world.insert((), vec![(Pos(*input),)]);
+        }
+    }
+
+    #[test]
+    fn detects_desync() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.registry.register_pod::<Pos>();
+        let registry = world.registry.clone();
+
+        let mut recording = Recording::new(&mut world, &registry).unwrap();
+        for tick in 0..4 {
+            recording
+                .record(&mut world, vec![tick as f32], step)
+                .unwrap();
+        }
+        assert_eq!(4, recording.ticks().len());
+
+        let partial = recording.replay_until(2, step).unwrap();
+        assert_eq!(2, Read::<Pos>::query().iter_immutable(&partial).count());
+
+        // a step which diverges from the recorded run on the third tick
+        let desync = recording
+            .replay(|world, inputs| {
+                let offset = if inputs[0] >= 2.0 { 0.5 } else { 0.0 };
+                world.insert((), vec![(Pos(inputs[0] + offset),)]);
+            })
+            .err()
+            .unwrap();
+        assert_eq!(2, desync.tick);
+        assert_eq!(recording.ticks()[2].checksum, desync.expected);
+        assert_ne!(desync.expected, desync.actual);
+    }
+}
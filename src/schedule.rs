@@ -1,3 +1,4 @@
+use crate::job_graph::JobGraph;
 use crate::system::SystemId;
 use crate::{
     borrow::{Exclusive, RefMut},
@@ -16,11 +17,14 @@ use tracing::{span, Level};
 #[cfg(feature = "par-schedule")]
 use tracing::trace;
 
+#[cfg(feature = "async")]
+use crate::async_system::YieldNow;
+
+#[cfg(feature = "async")]
+use tracing::debug;
+
 #[cfg(feature = "par-schedule")]
-use std::{
-    collections::HashSet,
-    sync::atomic::{AtomicUsize, Ordering},
-};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[cfg(feature = "par-schedule")]
 use rayon::prelude::*;
@@ -103,92 +107,29 @@ impl StageExecutor {
     /// Systems are provided in the order in which side-effects (e.g. writes to resources or entities)
     /// are to be observed.
     #[cfg(feature = "par-schedule")]
-    #[allow(clippy::cognitive_complexity)]
-    // TODO: we should break this up
     pub fn new(systems: Vec<Box<dyn Schedulable>>) -> Self {
         if systems.len() > 1 {
-            let mut static_dependency_counts = Vec::with_capacity(systems.len());
+            let graph = JobGraph::new(&systems);
 
+            let mut static_dependency_counts = vec![0; systems.len()];
             let mut static_dependants: Vec<Vec<_>> =
                 repeat(Vec::with_capacity(64)).take(systems.len()).collect();
             let mut dynamic_dependants: Vec<Vec<_>> =
                 repeat(Vec::with_capacity(64)).take(systems.len()).collect();
 
-            let mut resource_last_mutated = HashMap::<ResourceTypeId, usize>::with_capacity(64);
-            let mut resource_last_read = HashMap::<ResourceTypeId, usize>::with_capacity(64);
-            let mut component_mutated = HashMap::<ComponentTypeId, Vec<usize>>::with_capacity(64);
-
-            for (i, system) in systems.iter().enumerate() {
-                let span = span!(
-                    Level::TRACE,
-                    "Building system dependencies",
-                    system = %system.name(),
-                    index = i,
+            for edge in graph.edges() {
+                trace!(
+                    system = %systems[edge.dependant()].name(),
+                    dependency = %systems[edge.dependency()].name(),
+                    conflicts = ?edge.conflicts(),
+                    "Added dependency"
                 );
-                let _guard = span.enter();
-
-                let (read_res, read_comp) = system.reads();
-                let (write_res, write_comp) = system.writes();
-
-                // find resource access dependencies
-                let mut dependencies = HashSet::with_capacity(64);
-                for res in read_res {
-                    trace!(resource = ?res, "Read resource");
-                    if let Some(n) = resource_last_mutated.get(res) {
-                        trace!(system_index = n, "Added write dependency");
-                        dependencies.insert(*n);
-                    }
-                    resource_last_read.insert(*res, i);
-                }
-                for res in write_res {
-                    trace!(resource = ?res, "Write resource");
-                    // Writes have to be exclusive, so we are dependent on reads too
-                    if let Some(n) = resource_last_read.get(res) {
-                        trace!(system_index = n, "Added read dependency");
-                        dependencies.insert(*n);
-                    }
-
-                    if let Some(n) = resource_last_mutated.get(res) {
-                        trace!(system_index = n, "Added write dependency");
-                        dependencies.insert(*n);
-                    }
-
-                    resource_last_mutated.insert(*res, i);
-                }
-
-                static_dependency_counts.push(AtomicUsize::from(dependencies.len()));
-                trace!(dependants = ?dependencies, "Computed static dependants");
-                for dep in dependencies {
-                    static_dependants[dep].push(i);
-                }
-
-                // find component access dependencies
-                let mut comp_dependencies = HashSet::new();
-                for comp in read_comp {
-                    if let Some(ns) = component_mutated.get(comp) {
-                        for n in ns {
-                            comp_dependencies.insert(*n);
-                        }
-                    }
-                }
-                for comp in write_comp {
-                    if let Some(ns) = component_mutated.get(comp) {
-                        for n in ns {
-                            comp_dependencies.insert(*n);
-                        }
-                    }
-                    component_mutated
-                        .entry(*comp)
-                        .or_insert_with(Vec::new)
-                        .push(i);
+                if edge.is_static() {
+                    static_dependants[edge.dependency()].push(edge.dependant());
+                    static_dependency_counts[edge.dependant()] += 1;
                 }
-
-                trace!(depentants = ?comp_dependencies, "Computed dynamic dependants");
-                for dep in comp_dependencies {
-                    if dep != i {
-                        // dont be dependent on ourselves
-                        dynamic_dependants[dep].push(i);
-                    }
+                if edge.is_dynamic() {
+                    dynamic_dependants[edge.dependency()].push(edge.dependant());
                 }
             }
 
@@ -207,7 +148,10 @@ impl StageExecutor {
                 awaiting,
                 static_dependants,
                 dynamic_dependants,
-                static_dependency_counts,
+                static_dependency_counts: static_dependency_counts
+                    .into_iter()
+                    .map(AtomicUsize::new)
+                    .collect(),
                 systems,
             }
         } else {
@@ -221,6 +165,10 @@ impl StageExecutor {
         }
     }
 
+    /// Computes the dependency graph between the systems of this stage, as used by the
+    /// parallel executor to order them.
+    pub fn job_graph(&self) -> JobGraph { JobGraph::new(&self.systems) }
+
     /// Converts this executor into a vector of its component systems.
     pub fn into_vec(self) -> Vec<Box<dyn Schedulable>> { self.systems }
 
@@ -309,6 +257,26 @@ impl StageExecutor {
         });
     }
 
+    /// Executes this stage as a future, running systems one at a time in their given order and
+    /// yielding to the runtime between each system. Command buffers are flushed once all
+    /// systems have run.
+    ///
+    /// Async systems are polled once; if their future is still pending, it is resumed the next
+    /// time the stage executes.
+    #[cfg(feature = "async")]
+    pub async fn execute_async(&mut self, world: &mut World) {
+        for system in self.systems.iter_mut() {
+            system.prepare(world);
+            system.run(world);
+            YieldNow::new().await;
+        }
+
+        // Flush the command buffers of all the systems
+        self.systems.iter().for_each(|system| {
+            system.command_buffer_mut().write(world);
+        });
+    }
+
     /// Recursively execute through the generated depedency cascade and exhaust it.
     #[cfg(feature = "par-schedule")]
     fn run_recursive(&self, i: usize, world: &World) {
@@ -523,6 +491,35 @@ impl<S: Stage> SystemScheduler<S> {
         }
     }
 
+    /// Executes all scheduled systems as a future, yielding to the runtime between systems.
+    ///
+    /// Systems within a stage run sequentially in their scheduled order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if scheduled systems have impossible schedule constraints.
+    #[cfg(feature = "async")]
+    pub async fn execute_async(&mut self, world: &mut World) {
+        self.construct_stages();
+        for (stage, executor) in &mut self.scheduled {
+            debug!(%stage, "Running stage");
+            executor.execute_async(world).await;
+        }
+    }
+
+    /// Computes the dependency graph between the systems of each stage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if scheduled systems have impossible schedule constraints.
+    pub fn job_graphs(&mut self) -> Vec<(S, JobGraph)> {
+        self.construct_stages();
+        self.scheduled
+            .iter()
+            .map(|(stage, executor)| (*stage, executor.job_graph()))
+            .collect()
+    }
+
     fn construct_stages(&mut self) {
         // check if stages need to be rebuilt
         if self.unscheduled.is_empty() {
@@ -870,4 +867,225 @@ mod tests {
 
         scheduler.execute(&mut world);
     }
+
+    // Polls a future to completion, returning the number of times it yielded.
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> usize {
+        let waker = crate::async_system::noop_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        let mut yields = 0;
+        while future.as_mut().poll(&mut cx).is_pending() {
+            yields += 1;
+        }
+        yields
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn execute_async_yields_between_systems() {
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let order_clone = order.clone();
+        let system_one = SystemBuilder::new("one")
+            .build(move |_, _, _, _| order_clone.lock().unwrap().push(1usize));
+        let order_clone = order.clone();
+        let system_two = SystemBuilder::new("two")
+            .build(move |_, _, _, _| order_clone.lock().unwrap().push(2usize));
+        let order_clone = order.clone();
+        let system_three = SystemBuilder::new("three")
+            .build(move |_, _, _, _| order_clone.lock().unwrap().push(3usize));
+
+        let mut scheduler = SystemScheduler::new();
+        scheduler.add_system(Stages::Begin, system_one);
+        scheduler.add_system(Stages::Update, system_two);
+        scheduler.add_system(Stages::End, system_three);
+
+        let yields = block_on(scheduler.execute_async(&mut world));
+
+        assert_eq!(yields, 3);
+        assert_eq!(*order.lock().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_system_resumes_across_executions() {
+        use crate::async_system::AsyncWorld;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        // Simulates IO which completes after being polled a number of times.
+        struct Load(usize);
+
+        impl Future for Load {
+            type Output = usize;
+
+            fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<usize> {
+                if self.0 == 0 {
+                    Poll::Ready(5)
+                } else {
+                    self.0 -= 1;
+                    Poll::Pending
+                }
+            }
+        }
+
+        #[derive(Default)]
+        struct Loaded(usize);
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.resources.insert(Loaded::default());
+
+        let leaked: Arc<Mutex<Option<AsyncWorld<_>>>> = Arc::new(Mutex::new(None));
+        let leaked_clone = leaked.clone();
+
+        let system = SystemBuilder::new("load")
+            .write_resource::<Loaded>()
+            .build_async(move |handle| {
+                *leaked_clone.lock().unwrap() = Some(handle.clone());
+                async move {
+                    handle.with(|_, _, loaded| loaded.0 += 1);
+                    let value = Load(2).await;
+                    handle.with(|_, _, loaded| loaded.0 += value);
+                }
+            });
+
+        let mut scheduler = SystemScheduler::new();
+        scheduler.add_system(Stages::Update, system);
+
+        block_on(scheduler.execute_async(&mut world));
+        assert_eq!(world.resources.get::<Loaded>().unwrap().0, 1);
+
+        scheduler.execute(&mut world);
+        assert_eq!(world.resources.get::<Loaded>().unwrap().0, 1);
+
+        block_on(scheduler.execute_async(&mut world));
+        assert_eq!(world.resources.get::<Loaded>().unwrap().0, 6);
+
+        // outside of a poll the handle cannot reach the world
+        let handle = leaked.lock().unwrap().take().unwrap();
+        assert!(!handle.is_available());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            handle.with(|_, _, loaded| loaded.0 += 1)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_system_waits_for_other_threads() {
+        use std::sync::mpsc::channel;
+        use std::thread;
+        use std::time::Duration;
+
+        #[derive(Default)]
+        struct Loaded(usize);
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.resources.insert(Loaded::default());
+
+        let worker = Arc::new(Mutex::new(None));
+        let worker_clone = worker.clone();
+
+        let system = SystemBuilder::new("load")
+            .write_resource::<Loaded>()
+            .build_async(move |handle| {
+                let worker = worker_clone.clone();
+                async move {
+                    // move a handle to another thread, which holds the world past the poll
+                    let (taken_tx, taken_rx) = channel();
+                    let remote = handle.clone();
+                    let thread = thread::spawn(move || {
+                        remote.with(|_, _, loaded| {
+                            taken_tx.send(()).unwrap();
+                            thread::sleep(Duration::from_millis(50));
+                            loaded.0 += 1;
+                        });
+                        remote
+                    });
+                    taken_rx.recv().unwrap();
+                    *worker.lock().unwrap() = Some(thread);
+                    crate::async_system::YieldNow::new().await;
+                }
+            });
+
+        let mut scheduler = SystemScheduler::new();
+        scheduler.add_system(Stages::Update, system);
+        scheduler.execute(&mut world);
+
+        // the system did not finish running until the other thread released the world
+        assert_eq!(world.resources.get::<Loaded>().unwrap().0, 1);
+
+        // the world cannot be reached once the poll has ended
+        let remote = worker.lock().unwrap().take().unwrap().join().unwrap();
+        assert!(!remote.is_available());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            remote.with(|_, _, loaded| loaded.0 += 1)
+        }));
+        assert!(result.is_err());
+        assert_eq!(world.resources.get::<Loaded>().unwrap().0, 1);
+    }
+
+    #[test]
+    fn job_graph_conflicts() {
+        use crate::job_graph::Conflict;
+
+        #[derive(Default)]
+        struct Time;
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Pos;
+
+        let tick = SystemBuilder::new("tick")
+            .write_resource::<Time>()
+            .build(|_, _, _, _| {});
+        let clock = SystemBuilder::new("clock")
+            .read_resource::<Time>()
+            .build(|_, _, _, _| {});
+        let movement = SystemBuilder::new("movement")
+            .with_query(Write::<Pos>::query())
+            .build(|_, _, _, _| {});
+        let render = SystemBuilder::new("render")
+            .with_query(Read::<Pos>::query())
+            .build(|_, _, _, _| {});
+
+        let executor = StageExecutor::new(vec![tick, clock, movement, render]);
+        let graph = executor.job_graph();
+
+        let names: Vec<_> = graph.nodes().iter().map(|n| n.name()).collect();
+        assert_eq!(names, vec!["tick", "clock", "movement", "render"]);
+
+        assert_eq!(graph.edges().len(), 2);
+
+        let edge = &graph.edges()[0];
+        assert_eq!((edge.dependency(), edge.dependant()), (0, 1));
+        assert_eq!(
+            edge.conflicts(),
+            &[Conflict::ResourceRead(ResourceTypeId::of::<Time>())]
+        );
+        assert!(edge.is_static());
+
+        let edge = &graph.edges()[1];
+        assert_eq!((edge.dependency(), edge.dependant()), (2, 3));
+        assert_eq!(
+            edge.conflicts(),
+            &[Conflict::Component(ComponentTypeId::of::<Pos>())]
+        );
+        assert!(edge.is_dynamic());
+        assert!(!edge.is_static());
+
+        let dot = graph.to_dot("update");
+        assert!(dot.starts_with("digraph \"update\" {"));
+        assert!(dot.contains("0 -> 1"));
+        assert!(dot.contains("style=dashed"));
+
+        let json = graph.to_json();
+        assert!(json.starts_with("{\"nodes\":[\"tick\",\"clock\",\"movement\",\"render\"]"));
+        assert!(json.contains("\"dependency\":2,\"dependant\":3,\"static\":false"));
+    }
 }
@@ -0,0 +1,227 @@
+//! A spatial index over entity positions.
+//!
+//! Implement `Spatial` for a position component and enable the index with
+//! `World::set_spatial_index`. Entities can then be found by location with `World::query_aabb`.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! use legion::spatial::Spatial;
+//!
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! struct Position(f32, f32, f32);
+//!
+//! impl Spatial for Position {
+//!     fn position(&self) -> [f32; 3] { [self.0, self.1, self.2] }
+//! }
+//!
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! world.set_spatial_index::<Position>(10.0);
+//!
+//! let entities = world
+//!     .insert((), vec![(Position(1.0, 1.0, 0.0),), (Position(50.0, 0.0, 0.0),)])
+//!     .to_vec();
+//!
+//! let found = world.query_aabb([0.0, 0.0, -1.0], [5.0, 5.0, 1.0]).collect::<Vec<_>>();
+//! assert_eq!(vec![entities[0]], found);
+//! ```
+use crate::entity::Entity;
+use crate::storage::Component;
+use crate::storage::ComponentTypeId;
+use crate::storage::Storage;
+use crate::tracker::ChunkTracker;
+use std::collections::HashMap;
+
+/// A component which describes the location of an entity.
+pub trait Spatial: Component {
+    /// Gets the position of the entity.
+    fn position(&self) -> [f32; 3];
+}
+
+type Cell = (i32, i32, i32);
+
+/// A uniform grid of entity positions.
+///
+/// The index is refreshed lazily from the change versions of each chunk's position column, so
+/// only chunks whose positions may have changed since the last query are re-read.
+#[derive(Debug)]
+pub(crate) struct SpatialIndex {
+    position_fn: fn(*const u8) -> [f32; 3],
+    cell_size: f32,
+    chunks: ChunkTracker<Vec<(Entity, [f32; 3])>>,
+    cells: HashMap<Cell, Vec<(Entity, [f32; 3])>>,
+}
+
+impl SpatialIndex {
+    /// Creates an index over the positions of component type `T`, with grid cells of
+    /// `cell_size` along each axis.
+    pub fn new<T: Spatial>(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "spatial index cell size must be positive");
+        SpatialIndex {
+            position_fn: |ptr| unsafe { (*(ptr as *const T)).position() },
+            cell_size,
+            chunks: ChunkTracker::new(ComponentTypeId::of::<T>()),
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, position: [f32; 3]) -> Cell {
+        (
+            (position[0] / self.cell_size).floor() as i32,
+            (position[1] / self.cell_size).floor() as i32,
+            (position[2] / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Re-indexes all chunks in `storage` whose positions may have changed.
+    pub fn refresh(&mut self, storage: &Storage) {
+        let changes = self.chunks.refresh(storage);
+        for entries in changes.removed {
+            self.remove_entries(entries);
+        }
+
+        for chunk in changes.changed {
+            let column = chunk.components(self.chunks.type_id()).unwrap();
+            let (ptr, element_size, count) = column.data_raw();
+            let entries = (0..count)
+                .map(|i| unsafe { (self.position_fn)(ptr.add(element_size * i)) })
+                .zip(chunk.entities().iter().copied())
+                .map(|(position, entity)| (entity, position))
+                .collect::<Vec<_>>();
+            for (entity, position) in entries.iter() {
+                let cell = self.cell(*position);
+                self.cells
+                    .entry(cell)
+                    .or_default()
+                    .push((*entity, *position));
+            }
+            self.chunks.insert(chunk, entries);
+        }
+    }
+
+    /// Gets all entities whose positions lie within the axis aligned box between `min` and
+    /// `max`, inclusive.
+    pub fn query_aabb(&self, min: [f32; 3], max: [f32; 3]) -> Vec<Entity> {
+        let (min_cell, max_cell) = (self.cell(min), self.cell(max));
+        let contains = |p: &[f32; 3]| (0..3).all(|i| p[i] >= min[i] && p[i] <= max[i]);
+
+        let mut entities = Vec::new();
+        let mut visit = |cell: &Cell| {
+            if let Some(entries) = self.cells.get(cell) {
+                entities.extend(entries.iter().filter(|(_, p)| contains(p)).map(|(e, _)| *e));
+            }
+        };
+
+        // visit whichever is smaller; the cells overlapping the box, or the occupied cells
+        let volume = (0..3).fold(1u64, |volume, axis| {
+            let extent = match axis {
+                0 => max_cell.0 - min_cell.0,
+                1 => max_cell.1 - min_cell.1,
+                _ => max_cell.2 - min_cell.2,
+            };
+            volume.saturating_mul(extent.max(0) as u64 + 1)
+        });
+        if volume <= self.cells.len() as u64 {
+            for x in min_cell.0..=max_cell.0 {
+                for y in min_cell.1..=max_cell.1 {
+                    for z in min_cell.2..=max_cell.2 {
+                        visit(&(x, y, z));
+                    }
+                }
+            }
+        } else {
+            let in_range = |c: &Cell| {
+                c.0 >= min_cell.0
+                    && c.0 <= max_cell.0
+                    && c.1 >= min_cell.1
+                    && c.1 <= max_cell.1
+                    && c.2 >= min_cell.2
+                    && c.2 <= max_cell.2
+            };
+            for cell in self.cells.keys().filter(|c| in_range(c)) {
+                visit(cell);
+            }
+        }
+
+        entities
+    }
+
+    fn remove_entries(&mut self, entries: Vec<(Entity, [f32; 3])>) {
+        for (entity, position) in entries {
+            let cell = self.cell(position);
+            if let Some(entries) = self.cells.get_mut(&cell) {
+                entries.retain(|(e, _)| *e != entity);
+                if entries.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::spatial::Spatial;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32, f32, f32);
+
+    impl Spatial for Pos {
+        fn position(&self) -> [f32; 3] { [self.0, self.1, self.2] }
+    }
+
+    fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+        entities.sort_by_key(|e| e.index());
+        entities
+    }
+
+    #[test]
+    fn query_aabb() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.set_spatial_index::<Pos>(4.0);
+
+        let entities = world
+            .insert(
+                (),
+                vec![
+                    (Pos(0.0, 0.0, 0.0),),
+                    (Pos(-3.0, 2.0, 0.0),),
+                    (Pos(10.0, 10.0, 0.0),),
+                    (Pos(100.0, -100.0, 5.0),),
+                ],
+            )
+            .to_vec();
+
+        let found = world.query_aabb([-5.0, -5.0, -1.0], [5.0, 5.0, 1.0]);
+        assert_eq!(vec![entities[0], entities[1]], sorted(found.collect()));
+
+        // large boxes visit occupied cells rather than every overlapping cell
+        let found = world.query_aabb([-1000.0, -1000.0, -1000.0], [1000.0, 1000.0, 1000.0]);
+        assert_eq!(entities, sorted(found.collect()));
+
+        // moves are picked up from component changes
+        *world.get_component_mut::<Pos>(entities[2]).unwrap() = Pos(1.0, 1.0, 0.0);
+        let found = world.query_aabb([-5.0, -5.0, -1.0], [5.0, 5.0, 1.0]);
+        assert_eq!(entities[..3].to_vec(), sorted(found.collect()));
+
+        for mut pos in Write::<Pos>::query().iter(&mut world) {
+            pos.0 += 50.0;
+        }
+        assert_eq!(0, world.query_aabb([-5.0, -5.0, -1.0], [5.0, 5.0, 1.0]).count());
+
+        world.delete(entities[0]);
+        world.add_tag(entities[1], 5u32);
+        let found = world.query_aabb([40.0, -5.0, -1.0], [60.0, 15.0, 1.0]);
+        assert_eq!(entities[1..3].to_vec(), sorted(found.collect()));
+    }
+
+    #[test]
+    fn no_index() {
+        let world = World::new();
+        assert_eq!(0, world.query_aabb([0.0; 3], [1.0; 3]).count());
+    }
+}
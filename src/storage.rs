@@ -5,31 +5,54 @@ use crate::entity::Entity;
 use crate::entity::EntityLocation;
 use crate::filter::ArchetypeFilterData;
 use crate::filter::Filter;
+use crate::iterator::FissileIterator;
 use crate::iterator::FissileZip;
 use crate::iterator::SliceVecIter;
+use crate::registry::Registry;
 use crate::world::TagSet;
 use crate::world::WorldId;
+use bit_set::BitSet;
 use derivative::Derivative;
 use smallvec::Drain;
 use smallvec::SmallVec;
+use std::any::Any;
 use std::any::TypeId;
+use std::cell::RefCell;
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::fmt::Display;
 use std::fmt::Formatter;
+use std::hash::Hash;
+use std::marker::PhantomData;
 use std::mem::size_of;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::ops::Range;
 use std::ops::RangeBounds;
 use std::ptr::NonNull;
 use std::slice::Iter;
 use std::slice::IterMut;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
+#[cfg(feature = "stable-type-ids")]
+use std::sync::RwLock;
+use std::thread::ThreadId;
 use tracing::trace;
 
 static VERSION_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Gets the most recently issued component version.
+///
+/// Component versions are drawn from a single global counter which is incremented each time
+/// component data is mutably accessed. Comparing against a version captured earlier (such as at
+/// the start of a frame) allows `filter_fns::version` predicates to implement custom change
+/// tracking policies.
+pub fn current_version() -> u64 { VERSION_COUNTER.load(Ordering::Relaxed) }
+
 fn next_version() -> u64 {
     VERSION_COUNTER
         .fetch_add(1, Ordering::Relaxed)
@@ -37,93 +60,499 @@ fn next_version() -> u64 {
         .unwrap()
 }
 
-#[cfg(not(feature = "ffi"))]
-/// A type ID identifying a component type.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
-pub struct ComponentTypeId(TypeId);
+// the key which identifies a type within type IDs and metas
+#[cfg(not(feature = "stable-type-ids"))]
+type TypeKey = TypeId;
 
-#[cfg(not(feature = "ffi"))]
-impl ComponentTypeId {
-    /// Gets the component type ID that represents type `T`.
-    pub fn of<T: Component>() -> Self { Self(TypeId::of::<T>()) }
+#[cfg(feature = "stable-type-ids")]
+type TypeKey = u64;
+
+#[cfg(not(feature = "stable-type-ids"))]
+fn type_key<T: 'static>() -> TypeKey { TypeId::of::<T>() }
+
+#[cfg(feature = "stable-type-ids")]
+fn type_key<T: 'static>() -> TypeKey {
+    let salts_version = TYPE_SALTS_VERSION.load(Ordering::Acquire);
+    TYPE_KEYS.with(|keys| {
+        let mut keys = keys.borrow_mut();
+        if keys.0 != salts_version {
+            keys.0 = salts_version;
+            keys.1.clear();
+        }
+        *keys.1.entry(TypeId::of::<T>()).or_insert_with(|| {
+            stable_type_id(std::any::type_name::<T>(), std::alloc::Layout::new::<T>())
+        })
+    })
+}
+
+#[cfg(feature = "stable-type-ids")]
+thread_local! {
+    // the keys computed on this thread, along with the salts version they were computed with
+    static TYPE_KEYS: RefCell<(u64, HashMap<TypeId, TypeKey>)> = RefCell::new((0, HashMap::new()));
+}
+
+// salts assigned to type names via `Registry::register_stable`
+#[cfg(feature = "stable-type-ids")]
+static TYPE_SALTS: RwLock<Vec<(String, u64)>> = RwLock::new(Vec::new());
+
+// incremented each time a salt is assigned, invalidating cached keys; zero while there are no
+// salts, so that unsalted lookups need not take the lock
+#[cfg(feature = "stable-type-ids")]
+static TYPE_SALTS_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// Computes the stable ID of the type with the given name and layout, as used to identify
+/// component and tag types when the `stable-type-ids` feature is enabled.
+///
+/// The ID is a hash of the type name, its layout and the salt assigned to the name via
+/// `Registry::register_stable`, if any. Unlike a `TypeId`, it is the same for a type compiled
+/// into separately loaded dynamic libraries, provided both are built by the same compiler.
+#[cfg(feature = "stable-type-ids")]
+pub fn stable_type_id(name: &str, layout: std::alloc::Layout) -> u64 {
+    let salt = if TYPE_SALTS_VERSION.load(Ordering::Acquire) > 0 {
+        TYPE_SALTS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(salted, _)| salted == name)
+            .map_or(0, |(_, salt)| *salt)
+    } else {
+        0
+    };
+
+    // 64-bit FNV-1a
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let salt = salt.to_le_bytes();
+    let size = (layout.size() as u64).to_le_bytes();
+    let align = (layout.align() as u64).to_le_bytes();
+    let bytes = name
+        .bytes()
+        .chain(salt.iter().chain(size.iter()).chain(align.iter()).copied());
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(feature = "stable-type-ids")]
+pub(crate) fn set_type_salt(name: &str, salt: u64) {
+    let mut salts = TYPE_SALTS.write().unwrap();
+    match salts.iter_mut().find(|(salted, _)| salted == name) {
+        Some(entry) => entry.1 = salt,
+        None => salts.push((name.to_owned(), salt)),
+    }
+    TYPE_SALTS_VERSION.fetch_add(1, Ordering::AcqRel);
 }
 
-#[cfg(feature = "ffi")]
 /// A type ID identifying a component type.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
-pub struct ComponentTypeId(TypeId, u32);
+///
+/// With the `stable-type-ids` feature enabled, the ID is derived from the name of the type
+/// rather than its `TypeId`. See `stable_type_id`.
+///
+/// With the `type-names` feature enabled, the ID also carries the name of the component type,
+/// which is shown in its `Debug` output.
+#[derive(Copy, Clone)]
+pub struct ComponentTypeId {
+    type_id: TypeKey,
+    #[cfg(feature = "ffi")]
+    external_id: u32,
+    #[cfg(feature = "type-names")]
+    name: &'static str,
+}
 
-#[cfg(feature = "ffi")]
 impl ComponentTypeId {
     /// Gets the component type ID that represents type `T`.
-    pub fn of<T: Component>() -> Self { Self(TypeId::of::<T>(), 0) }
+    pub fn of<T: Component>() -> Self {
+        Self {
+            type_id: type_key::<T>(),
+            #[cfg(feature = "ffi")]
+            external_id: 0,
+            #[cfg(feature = "type-names")]
+            name: std::any::type_name::<T>(),
+        }
+    }
+
+    /// Gets the component type ID that represents an externally defined component type,
+    /// such as one declared by a scripting host.
+    #[cfg(feature = "ffi")]
+    pub fn external(id: u32) -> Self {
+        Self {
+            type_id: type_key::<ExternalComponent>(),
+            external_id: id,
+            #[cfg(feature = "type-names")]
+            name: "<external>",
+        }
+    }
 }
 
-#[cfg(not(feature = "ffi"))]
+/// Marker type used to identify component types which are defined outside of Rust.
+#[cfg(feature = "ffi")]
+struct ExternalComponent;
+
 /// A type ID identifying a tag type.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
-pub struct TagTypeId(TypeId);
+///
+/// With the `type-names` feature enabled, the ID also carries the name of the tag type,
+/// which is shown in its `Debug` output.
+#[derive(Copy, Clone)]
+pub struct TagTypeId {
+    type_id: TypeKey,
+    #[cfg(feature = "ffi")]
+    external_id: u32,
+    #[cfg(feature = "type-names")]
+    name: &'static str,
+}
 
-#[cfg(not(feature = "ffi"))]
 impl TagTypeId {
     /// Gets the tag type ID that represents type `T`.
-    pub fn of<T: Component>() -> Self { Self(TypeId::of::<T>()) }
+    pub fn of<T: Component>() -> Self {
+        Self {
+            type_id: type_key::<T>(),
+            #[cfg(feature = "ffi")]
+            external_id: 0,
+            #[cfg(feature = "type-names")]
+            name: std::any::type_name::<T>(),
+        }
+    }
 }
 
-#[cfg(feature = "ffi")]
-/// A type ID identifying a tag type.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
-pub struct TagTypeId(TypeId, u32);
+macro_rules! impl_type_id {
+    ($ty:ident) => {
+        impl $ty {
+            /// Gets the name of the type, if the `type-names` feature is enabled.
+            #[cfg(feature = "type-names")]
+            pub fn name(&self) -> Option<&'static str> { Some(self.name) }
 
-#[cfg(feature = "ffi")]
-impl TagTypeId {
-    /// Gets the tag type ID that represents type `T`.
-    pub fn of<T: Component>() -> Self { Self(TypeId::of::<T>(), 0) }
+            /// Gets the name of the type, if the `type-names` feature is enabled.
+            #[cfg(not(feature = "type-names"))]
+            pub fn name(&self) -> Option<&'static str> { None }
+
+            #[cfg(feature = "ffi")]
+            fn key(&self) -> (TypeKey, u32) { (self.type_id, self.external_id) }
+
+            #[cfg(not(feature = "ffi"))]
+            fn key(&self) -> TypeKey { self.type_id }
+        }
+
+        // the name is derived from the type, so only the key takes part in comparisons
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool { self.key() == other.key() }
+        }
+
+        impl Eq for $ty {}
+
+        impl std::hash::Hash for $ty {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.key().hash(state) }
+        }
+
+        impl PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $ty {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.key().cmp(&other.key()) }
+        }
+
+        impl Debug for $ty {
+            fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+                let mut tuple = f.debug_tuple(stringify!($ty));
+                match self.name() {
+                    Some(name) => tuple.field(&format_args!("{}", name)),
+                    None => tuple.field(&self.type_id),
+                };
+                #[cfg(feature = "ffi")]
+                {
+                    if self.external_id != 0 {
+                        tuple.field(&self.external_id);
+                    }
+                }
+                tuple.finish()
+            }
+        }
+    };
 }
 
+impl_type_id!(ComponentTypeId);
+impl_type_id!(TagTypeId);
+
 /// A `Component` is per-entity data that can be attached to a single entity.
 pub trait Component: Send + Sync + 'static {}
 
+/// Declares a component type as a transparent alias of another component type, such that
+/// `ReadAs` and `WriteAs` views of the alias iterate the columns in which the target type is
+/// stored, without copying.
+///
+/// This allows gameplay code to use its own wrapper types over components stored as engine
+/// types, such as a `Position2D` over a `Vec2`.
+///
+/// # Safety
+///
+/// `Self` must have the same size, alignment and valid bit patterns as `Target`, for example by
+/// being a `#[repr(transparent)]` wrapper around it.
+pub unsafe trait ComponentAlias: Component {
+    /// The component type which is stored.
+    type Target: Component;
+}
+
+/// Declares that a component type contains no padding bytes, such that its values may be copied
+/// word by word, as `StreamSlot::stream` does.
+///
+/// # Safety
+///
+/// Every byte of every value of `Self` must be initialized. Types containing padding between or
+/// after their fields, or enums with fields, must not implement this trait.
+pub unsafe trait Streamable: Component {}
+
+macro_rules! impl_streamable {
+    ($( $ty:ty ),*) => {
+        $( unsafe impl Streamable for $ty {} )*
+    };
+}
+
+impl_streamable!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
 /// A `Tag` is shared data that can be attached to multiple entities at once.
 pub trait Tag: Clone + Send + Sync + PartialEq + 'static {}
 
 impl<T: Send + Sync + 'static> Component for T {}
+
+/// A `NonSyncComponent` is per-entity data which is not required to be `Send` or `Sync`.
+///
+/// Such components are stored outside of chunk storage and can only be accessed on the
+/// thread which created their world.
+pub trait NonSyncComponent: 'static {}
+impl<T: 'static> NonSyncComponent for T {}
 impl<T: Clone + Send + Sync + PartialEq + 'static> Tag for T {}
 
 /// Stores slices of `ComponentTypeId`, each of which identifies the type of components
 /// contained within the archetype of the same index.
 #[derive(Derivative)]
 #[derivative(Default(bound = ""))]
-pub struct ComponentTypes(SliceVec<ComponentTypeId>);
+pub struct ComponentTypes(TypeSets<ComponentTypeId>);
 
 /// Stores slices of `TagTypeId`, each of which identifies the type of tags
 /// contained within the archetype of the same index.
 #[derive(Derivative)]
 #[derivative(Default(bound = ""))]
-pub struct TagTypes(SliceVec<TagTypeId>);
+pub struct TagTypes(TypeSets<TagTypeId>);
 
 impl ComponentTypes {
-    /// Gets an iterator over all type ID slices.
-    pub fn iter(&self) -> SliceVecIter<ComponentTypeId> { self.0.iter() }
+    /// Gets an iterator over all type ID sets.
+    pub fn iter(&self) -> TypeSetIter<'_, ComponentTypeId> { self.0.iter() }
 
     /// Gets the number of slices stored within the set.
     pub fn len(&self) -> usize { self.0.len() }
 
     /// Determines if the set is empty.
     pub fn is_empty(&self) -> bool { self.len() < 1 }
+
+    /// Gets an iterator which yields `true` for each archetype containing exactly `types`.
+    pub fn iter_exact(&self, types: &[ComponentTypeId]) -> ExactTypeSetIter<'_> {
+        self.0.iter_exact(types)
+    }
 }
 
 impl TagTypes {
-    /// Gets an iterator over all type ID slices.
-    pub fn iter(&self) -> SliceVecIter<TagTypeId> { self.0.iter() }
+    /// Gets an iterator over all type ID sets.
+    pub fn iter(&self) -> TypeSetIter<'_, TagTypeId> { self.0.iter() }
 
     /// Gets the number of slices stored within the set.
     pub fn len(&self) -> usize { self.0.len() }
 
     /// Determines if the set is empty.
     pub fn is_empty(&self) -> bool { self.len() < 1 }
+
+    /// Gets an iterator which yields `true` for each archetype containing exactly `types`.
+    pub fn iter_exact(&self, types: &[TagTypeId]) -> ExactTypeSetIter<'_> { self.0.iter_exact(types) }
+}
+
+/// Stores a set of types for each archetype, both as a slice of type IDs and as a bitset.
+///
+/// Each type is assigned a dense bit index the first time it is seen, allowing type
+/// membership and set equality to be tested without scanning the type slices.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct TypeSets<T> {
+    types: SliceVec<T>,
+    bits: Vec<BitSet>,
+    indices: HashMap<T, usize>,
+}
+
+impl<T: Copy + Eq + Hash> TypeSets<T> {
+    /// Gets the number of sets stored.
+    pub fn len(&self) -> usize { self.types.len() }
+
+    /// Determines if no sets are stored.
+    pub fn is_empty(&self) -> bool { self.len() < 1 }
+
+    /// Pushes the set of types for a new archetype.
+    pub fn push<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        let start = self.types.data.len();
+        self.types.push(items);
+
+        let mut bits = BitSet::new();
+        for type_id in &self.types.data[start..] {
+            let next = self.indices.len();
+            bits.insert(*self.indices.entry(*type_id).or_insert(next));
+        }
+        self.bits.push(bits);
+    }
+
+    /// Gets an iterator over all type sets.
+    pub fn iter(&self) -> TypeSetIter<'_, T> {
+        TypeSetIter {
+            types: self.types.iter(),
+            bits: self.bits.iter(),
+            indices: &self.indices,
+        }
+    }
+
+    /// Builds the bitset representing `types`.
+    ///
+    /// Returns `None` if any of the types have never been stored in a set.
+    pub fn bits_of(&self, types: &[T]) -> Option<BitSet> {
+        let mut bits = BitSet::new();
+        for type_id in types {
+            bits.insert(*self.indices.get(type_id)?);
+        }
+        Some(bits)
+    }
+
+    /// Gets an iterator which yields `true` for each set which contains exactly `types`.
+    pub fn iter_exact(&self, types: &[T]) -> ExactTypeSetIter<'_> {
+        ExactTypeSetIter {
+            bits: self.bits.iter(),
+            target: self.bits_of(types),
+        }
+    }
+}
+
+/// Determines if two bitsets contain the same bits, comparing whole blocks at a time.
+fn same_bits(a: &BitSet, b: &BitSet) -> bool { a.is_subset(b) && b.is_subset(a) }
+
+/// The set of types stored for a single archetype.
+pub struct TypeSet<'a, T> {
+    types: &'a [T],
+    bits: &'a BitSet,
+    indices: &'a HashMap<T, usize>,
+}
+
+impl<'a, T: Eq + Hash> TypeSet<'a, T> {
+    /// Gets the types in the set.
+    pub fn types(&self) -> &'a [T] { self.types }
+
+    /// Gets the bitset representation of the set.
+    pub fn bits(&self) -> &'a BitSet { self.bits }
+
+    /// Determines if the set contains the given type.
+    pub fn contains(&self, type_id: &T) -> bool {
+        self.indices
+            .get(type_id)
+            .map(|i| self.bits.contains(*i))
+            .unwrap_or(false)
+    }
+
+    /// Determines if the set contains exactly the types in `bits`.
+    pub fn is(&self, bits: &BitSet) -> bool { same_bits(self.bits, bits) }
+}
+
+impl<'a, T> Deref for TypeSet<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] { self.types }
+}
+
+/// An iterator over the type sets of each archetype.
+pub struct TypeSetIter<'a, T> {
+    types: SliceVecIter<'a, T>,
+    bits: Iter<'a, BitSet>,
+    indices: &'a HashMap<T, usize>,
+}
+
+impl<'a, T> Iterator for TypeSetIter<'a, T> {
+    type Item = TypeSet<'a, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(TypeSet {
+            types: self.types.next()?,
+            bits: self.bits.next()?,
+            indices: self.indices,
+        })
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        Some(TypeSet {
+            types: self.types.nth(n)?,
+            bits: self.bits.nth(n)?,
+            indices: self.indices,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.types.size_hint() }
+}
+
+impl<'a, T> ExactSizeIterator for TypeSetIter<'a, T> {}
+
+impl<'a, T> FissileIterator for TypeSetIter<'a, T> {
+    fn split(self) -> (Self, Self, usize) {
+        let (left_types, right_types, left_len) = self.types.split();
+        let (left_bits, right_bits) = self.bits.as_slice().split_at(left_len);
+        (
+            Self {
+                types: left_types,
+                bits: left_bits.iter(),
+                indices: self.indices,
+            },
+            Self {
+                types: right_types,
+                bits: right_bits.iter(),
+                indices: self.indices,
+            },
+            left_len,
+        )
+    }
+}
+
+/// An iterator which yields `true` for each archetype whose type set is exactly a given set.
+pub struct ExactTypeSetIter<'a> {
+    bits: Iter<'a, BitSet>,
+    target: Option<BitSet>,
+}
+
+impl<'a> ExactTypeSetIter<'a> {
+    fn is_target(&self, bits: &BitSet) -> bool {
+        match self.target {
+            Some(ref target) => same_bits(bits, target),
+            None => false,
+        }
+    }
+}
+
+impl<'a> Iterator for ExactTypeSetIter<'a> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let bits = self.bits.next()?;
+        Some(self.is_target(bits))
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let bits = self.bits.nth(n)?;
+        Some(self.is_target(bits))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.bits.size_hint() }
 }
 
+impl<'a> ExactSizeIterator for ExactTypeSetIter<'a> {}
+
 /// A vector of slices.
 ///
 /// Each slice is stored inline so as to be efficiently iterated through linearly.
@@ -160,12 +589,34 @@ impl<T> SliceVec<T> {
     }
 }
 
+/// Maps each component type to the indexes of the archetypes which contain it.
+///
+/// Indexes are stored in ascending order.
+#[derive(Default, Debug)]
+pub struct ComponentIndex(HashMap<ComponentTypeId, Vec<usize>>);
+
+impl ComponentIndex {
+    /// Gets the indexes of all archetypes which contain components of the given type.
+    pub fn archetypes(&self, type_id: ComponentTypeId) -> &[usize] {
+        self.0.get(&type_id).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    fn insert(&mut self, archetype_index: usize, types: impl Iterator<Item = ComponentTypeId>) {
+        for type_id in types {
+            self.0.entry(type_id).or_default().push(archetype_index);
+        }
+    }
+}
+
 /// Stores all entity data for a `World`.
 pub struct Storage {
     world_id: WorldId,
     component_types: ComponentTypes,
     tag_types: TagTypes,
+    component_index: ComponentIndex,
     archetypes: Vec<ArchetypeData>,
+    component_padding: usize,
+    small_chunk_capacity: Option<usize>,
 }
 
 impl Storage {
@@ -175,10 +626,45 @@ impl Storage {
             world_id,
             component_types: ComponentTypes::default(),
             tag_types: TagTypes::default(),
+            component_index: ComponentIndex::default(),
             archetypes: Vec::default(),
+            component_padding: 1,
+            small_chunk_capacity: None,
         }
     }
 
+    /// Gets the number of elements which the component columns of new archetypes are padded
+    /// to a multiple of.
+    pub fn component_padding(&self) -> usize { self.component_padding }
+
+    /// Sets the number of elements which the component columns of archetypes created after this
+    /// call are padded to a multiple of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lanes` is not a power of two.
+    pub fn set_component_padding(&mut self, lanes: usize) {
+        assert!(
+            lanes.is_power_of_two(),
+            "component padding must be a power of two"
+        );
+        self.component_padding = lanes;
+    }
+
+    /// Gets the reduced capacity with which the first chunk of each chunk set in new archetypes
+    /// is created, if any.
+    pub fn small_chunk_capacity(&self) -> Option<usize> { self.small_chunk_capacity }
+
+    /// Sets the reduced capacity with which the first chunk of each chunk set in archetypes
+    /// created after this call is created.
+    ///
+    /// Such chunks are grown to the archetype's full chunk capacity once they are full, before
+    /// any further chunks are created. Capacities at least as large as the full capacity have no
+    /// effect.
+    pub fn set_small_chunk_capacity(&mut self, capacity: Option<usize>) {
+        self.small_chunk_capacity = capacity;
+    }
+
     /// Creates a new archetype.
     ///
     /// Returns the index of the newly created archetype and an exclusive reference to the
@@ -191,7 +677,16 @@ impl Storage {
         self.tag_types
             .0
             .push(desc.tags.iter().map(|(type_id, _)| *type_id));
-        self.archetypes.push(ArchetypeData::new(id, desc));
+        self.component_index.insert(
+            self.archetypes.len(),
+            desc.components.iter().map(|(type_id, _)| *type_id),
+        );
+        self.archetypes.push(ArchetypeData::new(
+            id,
+            desc,
+            self.component_padding,
+            self.small_chunk_capacity,
+        ));
 
         let index = self.archetypes.len() - 1;
         (index, unsafe {
@@ -199,12 +694,54 @@ impl Storage {
         })
     }
 
+    /// Creates a copy of the storage which shares the chunks of plain old data components, as
+    /// determined by `registry`. Shared chunks are copied when either copy first writes to them.
+    ///
+    /// Returns the type of the first component which could not be cloned, if any. Zero sized
+    /// components need not be cloneable unless they implement `Drop`.
+    pub(crate) fn share(&mut self, registry: &Registry) -> Result<Storage, ComponentTypeId> {
+        for archetype in self.archetypes.iter() {
+            for (type_id, meta) in archetype.description().components() {
+                let cloneable = registry.get(*type_id).is_some_and(|r| r.has_clone());
+                if (meta.size > 0 || meta.drop_fn.is_some()) && !cloneable {
+                    return Err(*type_id);
+                }
+            }
+        }
+
+        let mut shared = Storage::new(self.world_id);
+        shared.component_padding = self.component_padding;
+        shared.small_chunk_capacity = self.small_chunk_capacity;
+        for archetype in self.archetypes.iter_mut() {
+            shared.push(archetype.share(registry));
+        }
+        Ok(shared)
+    }
+
+    /// Reassigns the storage to a different world, updating the IDs of all archetypes and
+    /// chunks.
+    pub(crate) fn set_world_id(&mut self, world_id: WorldId) {
+        self.world_id = world_id;
+        for (index, archetype) in self.archetypes.iter_mut().enumerate() {
+            archetype.id = ArchetypeId(world_id, index);
+            for (set_index, set) in archetype.chunk_sets.iter_mut().enumerate() {
+                for (chunk_index, chunk) in set.chunks.iter_mut().enumerate() {
+                    chunk.id = ChunkId(archetype.id, set_index, chunk_index);
+                }
+            }
+        }
+    }
+
     pub(crate) fn push(&mut self, archetype: ArchetypeData) {
         let desc = archetype.description();
         self.component_types
             .0
             .push(desc.components.iter().map(|(t, _)| *t));
         self.tag_types.0.push(desc.tags.iter().map(|(t, _)| *t));
+        self.component_index.insert(
+            self.archetypes.len(),
+            desc.components.iter().map(|(t, _)| *t),
+        );
         self.archetypes.push(archetype);
     }
 
@@ -218,6 +755,9 @@ impl Storage {
     /// Each slice contains the tag types for the archetype at the corresponding index.
     pub fn tag_types(&self) -> &TagTypes { &self.tag_types }
 
+    /// Gets the index from component types to the archetypes which contain them.
+    pub fn component_index(&self) -> &ComponentIndex { &self.component_index }
+
     /// Gets a slice reference to all archetypes.
     pub fn archetypes(&self) -> &[ArchetypeData] { &self.archetypes }
 
@@ -235,6 +775,11 @@ impl Storage {
 /// Stores metadata decribing the type of a tag.
 #[derive(Copy, Clone)]
 pub struct TagMeta {
+    type_id: TypeId,
+    #[cfg(feature = "stable-type-ids")]
+    stable_id: u64,
+    #[cfg(debug_assertions)]
+    type_name: &'static str,
     size: usize,
     align: usize,
     drop_fn: Option<(fn(*mut u8))>,
@@ -246,6 +791,11 @@ impl TagMeta {
     /// Gets the tag meta of tag type `T`.
     pub fn of<T: Tag>() -> Self {
         TagMeta {
+            type_id: TypeId::of::<T>(),
+            #[cfg(feature = "stable-type-ids")]
+            stable_id: type_key::<T>(),
+            #[cfg(debug_assertions)]
+            type_name: std::any::type_name::<T>(),
             size: size_of::<T>(),
             align: std::mem::align_of::<T>(),
             drop_fn: Some(|ptr| unsafe { std::ptr::drop_in_place(ptr as *mut T) }),
@@ -257,10 +807,50 @@ impl TagMeta {
         }
     }
 
-    pub(crate) fn equals(&self, a: *const u8, b: *const u8) -> bool { (self.eq_fn)(a, b) }
+    /// Gets the `TypeId` of the tag type.
+    pub fn type_id(&self) -> TypeId { self.type_id }
+
+    #[cfg(not(feature = "stable-type-ids"))]
+    fn key(&self) -> TypeKey { self.type_id }
+
+    #[cfg(feature = "stable-type-ids")]
+    fn key(&self) -> TypeKey { self.stable_id }
+
+    /// Determines if `T` is the tag type described by this meta.
+    ///
+    /// Stable IDs are hashes, so the layout is also compared to catch collisions.
+    pub(crate) fn is<T: 'static>(&self) -> bool {
+        self.key() == type_key::<T>()
+            && self.size == size_of::<T>()
+            && self.align == std::mem::align_of::<T>()
+    }
+
+    /// Panics if `T` is not the tag type described by this meta.
+    pub(crate) fn assert_type<T: 'static>(&self) {
+        if !self.is::<T>() {
+            #[cfg(debug_assertions)]
+            panic!(
+                "tag type mismatch: accessed {} as {}",
+                self.type_name,
+                std::any::type_name::<T>()
+            );
+            #[cfg(not(debug_assertions))]
+            panic!(
+                "tag type mismatch: accessed as {}",
+                std::any::type_name::<T>()
+            );
+        }
+    }
+
+    pub(crate) fn equals(&self, a: *const u8, b: *const u8) -> bool {
+        // a value is assumed to equal itself, which spares the comparison of interned tags
+        std::ptr::eq(a, b) || (self.eq_fn)(a, b)
+    }
 
     pub(crate) fn clone(&self, src: *const u8, dst: *mut u8) { (self.clone_fn)(src, dst) }
 
+    pub(crate) fn drop_fn(&self) -> Option<fn(*mut u8)> { self.drop_fn }
+
     pub(crate) fn layout(&self) -> std::alloc::Layout {
         unsafe { std::alloc::Layout::from_size_align_unchecked(self.size, self.align) }
     }
@@ -271,6 +861,11 @@ impl TagMeta {
 /// Stores metadata describing the type of a component.
 #[derive(Copy, Clone)]
 pub struct ComponentMeta {
+    type_id: Option<TypeId>,
+    #[cfg(feature = "stable-type-ids")]
+    stable_id: Option<u64>,
+    #[cfg(debug_assertions)]
+    type_name: &'static str,
     size: usize,
     align: usize,
     drop_fn: Option<(fn(*mut u8))>,
@@ -280,11 +875,93 @@ impl ComponentMeta {
     /// Gets the component meta of component type `T`.
     pub fn of<T: Component>() -> Self {
         ComponentMeta {
+            type_id: Some(TypeId::of::<T>()),
+            #[cfg(feature = "stable-type-ids")]
+            stable_id: Some(type_key::<T>()),
+            #[cfg(debug_assertions)]
+            type_name: std::any::type_name::<T>(),
             size: size_of::<T>(),
             align: std::mem::align_of::<T>(),
-            drop_fn: Some(|ptr| unsafe { std::ptr::drop_in_place(ptr as *mut T) }),
+            drop_fn: if std::mem::needs_drop::<T>() {
+                Some(|ptr| unsafe { std::ptr::drop_in_place(ptr as *mut T) })
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Gets the component meta of a plain data type with the given size and alignment.
+    ///
+    /// Components described by such metas are never dropped.
+    ///
+    /// Returns `None` if `align` is not a power of two, or `size` is not a multiple of `align`.
+    pub fn raw(size: usize, align: usize) -> Option<Self> {
+        let layout = std::alloc::Layout::from_size_align(size, align).ok()?;
+        if layout.pad_to_align().size() != size {
+            return None;
+        }
+
+        Some(ComponentMeta {
+            type_id: None,
+            #[cfg(feature = "stable-type-ids")]
+            stable_id: None,
+            #[cfg(debug_assertions)]
+            type_name: "<raw>",
+            size,
+            align,
+            drop_fn: None,
+        })
+    }
+
+    /// Gets the `TypeId` of the component type, or `None` if the meta describes raw data.
+    pub fn type_id(&self) -> Option<TypeId> { self.type_id }
+
+    #[cfg(not(feature = "stable-type-ids"))]
+    fn key(&self) -> Option<TypeKey> { self.type_id }
+
+    #[cfg(feature = "stable-type-ids")]
+    fn key(&self) -> Option<TypeKey> { self.stable_id }
+
+    /// Gets the size of the component type, in bytes.
+    pub fn size(&self) -> usize { self.size }
+
+    /// Panics if `T` is not the component type described by this meta. Raw components may be
+    /// accessed as any type of the same size.
+    pub(crate) fn assert_type<T: 'static>(&self) {
+        // stable IDs are hashes, so the layout is also compared to catch collisions
+        let valid = match self.key() {
+            Some(key) => {
+                key == type_key::<T>()
+                    && size_of::<T>() == self.size
+                    && std::mem::align_of::<T>() == self.align
+            }
+            None => size_of::<T>() == self.size,
+        };
+        if !valid {
+            #[cfg(debug_assertions)]
+            panic!(
+                "component type mismatch: accessed {} as {}",
+                self.type_name,
+                std::any::type_name::<T>()
+            );
+            #[cfg(not(debug_assertions))]
+            panic!(
+                "component type mismatch: accessed as {}",
+                std::any::type_name::<T>()
+            );
         }
     }
+
+    /// Gets the alignment of the component type, in bytes.
+    pub fn align(&self) -> usize { self.align }
+
+    pub(crate) fn layout(&self) -> std::alloc::Layout {
+        unsafe { std::alloc::Layout::from_size_align_unchecked(self.size, self.align) }
+    }
+
+    pub(crate) fn drop_fn(&self) -> Option<fn(*mut u8)> { self.drop_fn }
+
+    pub(crate) fn is_zero_sized(&self) -> bool { self.size == 0 }
 }
 
 /// Describes the layout of an archetype, including what components
@@ -295,6 +972,7 @@ pub struct ArchetypeDescription {
     components: Vec<(ComponentTypeId, ComponentMeta)>,
     tag_names: Vec<&'static str>,
     component_names: Vec<&'static str>,
+    tag_capacity: Option<usize>,
 }
 
 impl ArchetypeDescription {
@@ -304,10 +982,46 @@ impl ArchetypeDescription {
     /// Gets a slice of the components in the description.
     pub fn components(&self) -> &[(ComponentTypeId, ComponentMeta)] { &self.components }
 
+    /// Gets the names of the tags in the description.
+    pub fn tag_names(&self) -> &[&'static str] { &self.tag_names }
+
+    /// Gets the names of the components in the description.
+    pub fn component_names(&self) -> &[&'static str] { &self.component_names }
+
+    /// Gets the number of chunk sets for which the archetype's tag storage is initially
+    /// allocated.
+    pub fn tag_capacity(&self) -> usize { self.tag_capacity.unwrap_or(TAG_STORAGE_CAPACITY) }
+
+    /// Sets the number of chunk sets for which the archetype's tag storage is initially
+    /// allocated.
+    ///
+    /// Archetypes which are expected to hold many distinct sets of tag values can avoid
+    /// repeatedly growing their tag storage by providing a hint here. The hint only applies
+    /// when the archetype is first created.
+    pub fn set_tag_capacity(&mut self, capacity: usize) { self.tag_capacity = Some(capacity); }
+
+    /// Determines if the description contains the given component type.
+    pub fn has_component<T: Component>(&self) -> bool {
+        self.has_component_raw(ComponentTypeId::of::<T>())
+    }
+
+    /// Determines if the description contains the given component type.
+    pub fn has_component_raw(&self, type_id: ComponentTypeId) -> bool {
+        self.components.iter().any(|(t, _)| *t == type_id)
+    }
+
+    /// Determines if the description contains the given tag type.
+    pub fn has_tag<T: Tag>(&self) -> bool { self.has_tag_raw(TagTypeId::of::<T>()) }
+
+    /// Determines if the description contains the given tag type.
+    pub fn has_tag_raw(&self, type_id: TagTypeId) -> bool {
+        self.tags.iter().any(|(t, _)| *t == type_id)
+    }
+
     /// Adds a tag to the description.
     pub fn register_tag_raw(&mut self, type_id: TagTypeId, type_meta: TagMeta) {
         self.tags.push((type_id, type_meta));
-        self.tag_names.push("<unknown>");
+        self.tag_names.push(type_id.name().unwrap_or("<unknown>"));
     }
 
     /// Adds a tag to the description.
@@ -317,9 +1031,20 @@ impl ArchetypeDescription {
     }
 
     /// Adds a component to the description.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component type has already been added, as each entity can only hold one
+    /// value of each component type.
     pub fn register_component_raw(&mut self, type_id: ComponentTypeId, type_meta: ComponentMeta) {
+        assert!(
+            self.components.iter().all(|(t, _)| *t != type_id),
+            "component type {:?} is already registered",
+            type_id
+        );
         self.components.push((type_id, type_meta));
-        self.component_names.push("<unknown>");
+        self.component_names
+            .push(type_id.name().unwrap_or("<unknown>"));
     }
 
     /// Adds a component to the description.
@@ -331,25 +1056,51 @@ impl ArchetypeDescription {
 }
 
 impl<'a> Filter<ArchetypeFilterData<'a>> for ArchetypeDescription {
-    type Iter = FissileZip<SliceVecIter<'a, TagTypeId>, SliceVecIter<'a, ComponentTypeId>>;
+    type Iter = FissileZip<ExactTypeSetIter<'a>, ExactTypeSetIter<'a>>;
 
     fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
-        FissileZip::new(source.tag_types.iter(), source.component_types.iter())
+        let tags = self.tags.iter().map(|(t, _)| *t).collect::<Vec<_>>();
+        let components = self.components.iter().map(|(t, _)| *t).collect::<Vec<_>>();
+        FissileZip::new(
+            source.tag_types.iter_exact(&tags),
+            source.component_types.iter_exact(&components),
+        )
     }
 
     fn is_match(&self, (tags, components): &<Self::Iter as Iterator>::Item) -> Option<bool> {
-        Some(
-            tags.len() == self.tags.len()
-                && self.tags.iter().all(|(t, _)| tags.contains(t))
-                && components.len() == self.components.len()
-                && self.components.iter().all(|(t, _)| components.contains(t)),
-        )
+        Some(*tags && *components)
     }
 }
 
 const MAX_CHUNK_SIZE: usize = 16 * 1024;
 const COMPONENT_STORAGE_ALIGNMENT: usize = 64;
 
+/// The size of a CPU cache line, assumed when issuing prefetches.
+const CACHE_LINE_SIZE: usize = 64;
+
+/// The number of bytes at the start of a component slice which are prefetched by
+/// `ComponentResourceSet::prefetch`. Hardware prefetchers pick up the remainder of the
+/// slice once it is being read sequentially.
+const PREFETCH_BYTES: usize = 4 * CACHE_LINE_SIZE;
+
+/// Hints to the CPU that the cache line containing `ptr` will soon be read.
+#[inline(always)]
+fn prefetch_line(ptr: *const u8) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse"))]
+    unsafe {
+        std::arch::x86_64::_mm_prefetch::<{ std::arch::x86_64::_MM_HINT_T0 }>(ptr as *const i8)
+    };
+    #[cfg(all(target_arch = "x86", target_feature = "sse"))]
+    unsafe {
+        std::arch::x86::_mm_prefetch::<{ std::arch::x86::_MM_HINT_T0 }>(ptr as *const i8)
+    };
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse"
+    )))]
+    let _ = ptr;
+}
+
 /// Unique ID of an archetype.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct ArchetypeId(WorldId, usize);
@@ -357,12 +1108,13 @@ pub struct ArchetypeId(WorldId, usize);
 impl ArchetypeId {
     pub(crate) fn new(world_id: WorldId, index: usize) -> Self { ArchetypeId(world_id, index) }
 
-    fn index(self) -> usize { self.1 }
+    pub(crate) fn index(self) -> usize { self.1 }
 
     fn world(self) -> WorldId { self.0 }
 }
 
 /// Contains all of the tags attached to the entities in each chunk.
+#[derive(Clone)]
 pub struct Tags(SmallVec<[(TagTypeId, TagStorage); 3]>);
 
 impl Tags {
@@ -377,8 +1129,17 @@ impl Tags {
         }
     }
 
-    /// Gets the set of tag values of the specified type attached to all chunks.
-    #[inline]
+    #[cfg(feature = "alloc-tracking")]
+    pub(crate) fn allocated_bytes(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|(_, tags)| tags.element.size > 0)
+            .map(|(_, tags)| tags.capacity * tags.element.size)
+            .sum()
+    }
+
+    /// Gets the set of tag values of the specified type attached to all chunks.
+    #[inline]
     pub fn get(&self, type_id: TagTypeId) -> Option<&TagStorage> {
         self.0
             .binary_search_by_key(&type_id, |(t, _)| *t)
@@ -395,13 +1156,14 @@ impl Tags {
             .map(move |i| unsafe { &mut self.0.get_unchecked_mut(i).1 })
     }
 
-    pub(crate) fn tag_set(&self, chunk: usize) -> DynamicTagSet {
-        let mut tags = DynamicTagSet { tags: Vec::new() };
+    pub(crate) fn tag_set(&self, set: usize) -> DynamicTagSet {
+        let mut tags = DynamicTagSet::new();
 
         unsafe {
             for (type_id, storage) in self.0.iter() {
-                let (ptr, _, count) = storage.data_raw();
-                debug_assert!(chunk < count, "chunk index out of bounds");
+                let (ptr, element_size, count) = storage.data_raw();
+                debug_assert!(set < count, "chunkset index out of bounds");
+                let ptr = NonNull::new_unchecked(ptr.as_ptr().add(set * element_size));
                 tags.push(*type_id, *storage.element(), ptr);
             }
         }
@@ -420,6 +1182,8 @@ unsafe impl Send for DynamicTagSet {}
 unsafe impl Sync for DynamicTagSet {}
 
 impl DynamicTagSet {
+    pub fn new() -> Self { DynamicTagSet { tags: Vec::new() } }
+
     pub fn push(&mut self, type_id: TagTypeId, meta: TagMeta, value: NonNull<u8>) {
         // we clone the value here and take ownership of the copy
         unsafe {
@@ -428,6 +1192,8 @@ impl DynamicTagSet {
                     .push((type_id, meta, NonNull::new(meta.align as *mut u8).unwrap()));
             } else {
                 let copy = std::alloc::alloc(meta.layout());
+                #[cfg(feature = "alloc-tracking")]
+                TAG_SET_ALLOCATIONS.alloc(meta.size);
                 meta.clone(value.as_ptr(), copy);
                 self.tags.push((type_id, meta, NonNull::new(copy).unwrap()));
             }
@@ -450,6 +1216,8 @@ impl DynamicTagSet {
 
                 if !meta.is_zero_sized() {
                     std::alloc::dealloc(ptr.as_ptr(), meta.layout());
+                    #[cfg(feature = "alloc-tracking")]
+                    TAG_SET_ALLOCATIONS.free(meta.size);
                 }
             }
         }
@@ -466,9 +1234,13 @@ impl TagSet for DynamicTagSet {
                     // we can dealloc the copy without dropping because the value
                     // is considered moved and will be dropped by the tag storage later
                     let copy = std::alloc::alloc(meta.layout());
+                    #[cfg(feature = "alloc-tracking")]
+                    TAG_SET_ALLOCATIONS.alloc(meta.size);
                     meta.clone(ptr.as_ptr(), copy);
                     storage.push_raw(copy);
                     std::alloc::dealloc(copy, meta.layout());
+                    #[cfg(feature = "alloc-tracking")]
+                    TAG_SET_ALLOCATIONS.free(meta.size);
                 } else {
                     // copy the value directly into the tag storage
                     // if the value has no drop fn, then it is safe for us to make
@@ -491,12 +1263,18 @@ impl Drop for DynamicTagSet {
                 }
                 if !meta.is_zero_sized() {
                     std::alloc::dealloc(ptr.as_ptr(), layout);
+                    #[cfg(feature = "alloc-tracking")]
+                    TAG_SET_ALLOCATIONS.free(meta.size);
                 }
             }
         }
     }
 }
 
+/// Arbitrary data attached to an archetype or chunk set by the user, such as derived data
+/// cached by a renderer.
+pub type UserData = Box<dyn Any + Send + Sync>;
+
 /// Stores entity data in chunks. All entities within an archetype have the same data layout
 /// (component and tag types).
 pub struct ArchetypeData {
@@ -505,15 +1283,22 @@ pub struct ArchetypeData {
     tags: Tags,
     component_layout: ComponentStorageLayout,
     chunk_sets: Vec<Chunkset>,
+    user_data: Option<UserData>,
 }
 
 impl ArchetypeData {
-    fn new(id: ArchetypeId, desc: ArchetypeDescription) -> Self {
+    fn new(
+        id: ArchetypeId,
+        desc: ArchetypeDescription,
+        padding: usize,
+        small_capacity: Option<usize>,
+    ) -> Self {
         // create tag storage
+        let capacity = desc.tag_capacity();
         let tags = desc
             .tags
             .iter()
-            .map(|(type_id, meta)| (*type_id, TagStorage::new(*meta)))
+            .map(|(type_id, meta)| (*type_id, TagStorage::with_capacity(*meta, capacity)))
             .collect();
 
         // create component data layout
@@ -527,24 +1312,16 @@ impl ArchetypeData {
             1,
             MAX_CHUNK_SIZE / std::cmp::max(max_component_size, size_of::<Entity>()),
         );
-        let mut data_capacity = 0usize;
-        let mut component_data_offsets = Vec::new();
-        for (type_id, meta) in desc.components.iter() {
-            data_capacity = align_up(
-                align_up(data_capacity, COMPONENT_STORAGE_ALIGNMENT),
-                meta.align,
-            );
-            component_data_offsets.push((*type_id, data_capacity, *meta));
-            data_capacity += meta.size * entity_capacity;
-        }
-        let data_alignment =
-            std::alloc::Layout::from_size_align(data_capacity, COMPONENT_STORAGE_ALIGNMENT)
-                .expect("invalid component data size/alignment");
+        let component_layout = ComponentStorageLayout::new(&desc, entity_capacity, padding);
+        let small = small_capacity
+            .filter(|capacity| *capacity > 0 && *capacity < entity_capacity)
+            .map(|capacity| Box::new(ComponentStorageLayout::new(&desc, capacity, padding)));
 
         trace!(
             world = id.world().index(),
             archetype = id.index(),
             chunk_entity_capacity = entity_capacity,
+            component_padding = padding,
             components = ?desc.component_names,
             tags = ?desc.tag_names,
             "Created archetype"
@@ -555,19 +1332,69 @@ impl ArchetypeData {
             id,
             tags: Tags::new(tags),
             component_layout: ComponentStorageLayout {
-                capacity: entity_capacity,
-                alloc_layout: data_alignment,
-                data_layout: component_data_offsets,
+                small,
+                ..component_layout
             },
             chunk_sets: Vec::new(),
+            user_data: None,
         }
     }
 
     /// Gets the unique ID of this archetype.
     pub fn id(&self) -> ArchetypeId { self.id }
 
+    /// Gets the user data attached to the archetype.
+    pub fn user_data(&self) -> Option<&(dyn Any + Send + Sync)> { self.user_data.as_deref() }
+
+    /// Mutably borrows the user data attached to the archetype.
+    pub fn user_data_mut(&mut self) -> Option<&mut (dyn Any + Send + Sync)> {
+        self.user_data.as_deref_mut()
+    }
+
+    /// Attaches user data to the archetype, returning the data previously attached.
+    ///
+    /// User data is not included in world snapshots.
+    pub fn set_user_data(&mut self, data: UserData) -> Option<UserData> {
+        self.user_data.replace(data)
+    }
+
+    /// Detaches the user data from the archetype.
+    pub fn take_user_data(&mut self) -> Option<UserData> { self.user_data.take() }
+
+    fn share(&mut self, registry: &Registry) -> ArchetypeData {
+        let chunk_sets = self
+            .chunk_sets
+            .iter_mut()
+            .map(|set| Chunkset::new(set.chunks.iter_mut().map(|c| c.share(registry)).collect()))
+            .collect();
+
+        ArchetypeData {
+            id: self.id,
+            desc: self.desc.clone(),
+            tags: self.tags.clone(),
+            component_layout: self.component_layout.clone(),
+            chunk_sets,
+            user_data: None,
+        }
+    }
+
+    /// Marks every chunk in the archetype as structurally changed, and all of their components
+    /// as modified.
+    pub(crate) fn mark_modified(&mut self) {
+        for set in self.chunk_sets.iter_mut() {
+            for chunk in set.chunks.iter_mut() {
+                chunk.structural_version = next_version();
+                for (_, component) in unsafe { &mut *chunk.component_info.get() }.iter_mut() {
+                    let version = next_version();
+                    *component.version.get_mut() = version;
+                    *component.added_version.get_mut() = version;
+                }
+            }
+        }
+    }
+
     pub(crate) fn merge(&mut self, mut other: ArchetypeData) {
-        for (i, mut set) in other.chunk_sets.drain(..).enumerate() {
+        for (i, set) in other.chunk_sets.drain(..).enumerate() {
             let mut set_match = None;
             for index in 0..self.chunk_sets.len() {
                 let mut matches = true;
@@ -587,17 +1414,18 @@ impl ArchetypeData {
                 }
 
                 if matches {
-                    set_match = Some(i);
+                    set_match = Some(index);
                     break;
                 }
             }
 
             if let Some(chunk_set) = set_match {
                 let target = &mut self.chunk_sets[chunk_set];
-                for chunk in set.drain(..) {
+                for chunk in set.into_chunks() {
                     target.push(chunk);
                 }
             } else {
+                other.tags.tag_set(i).write_tags(&mut self.tags);
                 self.chunk_sets.push(set);
             }
         }
@@ -615,20 +1443,47 @@ impl ArchetypeData {
 
     /// Finds a chunk with space free for at least one entity, creating one if needed.
     pub(crate) fn get_free_chunk(&mut self, set_index: usize) -> usize {
-        let count = {
-            let chunks = &mut self.chunk_sets[set_index];
-            let len = chunks.len();
-            for (i, chunk) in chunks.iter_mut().enumerate() {
-                if !chunk.is_full() {
-                    return i;
-                }
-            }
-            len
-        };
+        if let Some(i) = self.find_free_chunk(set_index) {
+            i
+        } else if let Some(i) = self.find_small_chunk(set_index) {
+            self.chunk_sets[set_index].chunks[i].grow(&self.component_layout);
+            i
+        } else {
+            self.alloc_chunk(set_index)
+        }
+    }
+
+    /// Finds a chunk which was created with less than the archetype's full chunk capacity.
+    fn find_small_chunk(&self, set_index: usize) -> Option<usize> {
+        let capacity = self.component_layout.capacity;
+        self.chunk_sets[set_index]
+            .iter()
+            .position(|chunk| chunk.capacity() < capacity)
+    }
+
+    /// Finds a chunk with space free for at least one entity.
+    pub(crate) fn find_free_chunk(&self, set_index: usize) -> Option<usize> {
+        self.chunk_sets[set_index]
+            .iter()
+            .position(|chunk| !chunk.is_full())
+    }
+
+    /// Gets the number of entities which can be added to the given set without creating a chunk.
+    pub(crate) fn free_space(&self, set_index: usize) -> usize {
+        self.chunk_sets[set_index]
+            .iter()
+            .map(|chunk| chunk.capacity() - chunk.len())
+            .sum()
+    }
 
-        let chunk = self
-            .component_layout
-            .alloc_storage(ChunkId(self.id, set_index, count));
+    /// Creates a new empty chunk in the given set. Returns the index of the new chunk.
+    pub(crate) fn alloc_chunk(&mut self, set_index: usize) -> usize {
+        let count = self.chunk_sets[set_index].len();
+        let layout = match &self.component_layout.small {
+            Some(small) if count == 0 => small,
+            _ => &self.component_layout,
+        };
+        let chunk = layout.alloc_storage(ChunkId(self.id, set_index, count));
         unsafe { self.chunk_sets.get_unchecked_mut(set_index).push(chunk) };
 
         trace!(
@@ -644,15 +1499,45 @@ impl ArchetypeData {
         count
     }
 
+    /// Removes a chunk from the given set by swapping it with the last chunk in the set.
+    pub(crate) fn swap_remove_chunk(
+        &mut self,
+        set_index: usize,
+        chunk_index: usize,
+    ) -> ComponentStorage {
+        let chunks = &mut self.chunk_sets[set_index];
+        let chunk = chunks.swap_remove(chunk_index);
+        if let Some(swapped) = chunks.get_mut(chunk_index) {
+            swapped.id = ChunkId(self.id, set_index, chunk_index);
+        }
+        chunk
+    }
+
+    /// Pushes an existing chunk into the given set. Returns the index of the chunk within the set.
+    pub(crate) fn push_chunk(&mut self, set_index: usize, mut chunk: ComponentStorage) -> usize {
+        let chunks = &mut self.chunk_sets[set_index];
+        let index = chunks.len();
+        chunk.id = ChunkId(self.id, set_index, index);
+        chunks.push(chunk);
+        index
+    }
+
     /// Gets the number of chunk sets stored within this archetype.
     pub fn len(&self) -> usize { self.chunk_sets.len() }
 
     /// Determines whether this archetype has any chunks.
     pub fn is_empty(&self) -> bool { self.len() < 1 }
 
+    /// Gets the number of entities stored within this archetype.
+    pub fn entity_count(&self) -> usize {
+        self.chunk_sets.iter().map(|set| set.entity_count()).sum()
+    }
+
     /// Gets the tag storage for all chunks in the archetype.
     pub fn tags(&self) -> &Tags { &self.tags }
 
+    pub(crate) fn tags_mut(&mut self) -> &mut Tags { &mut self.tags }
+
     /// Gets a slice of chunksets.
     pub fn chunksets(&self) -> &[Chunkset] { &self.chunk_sets }
 
@@ -662,9 +1547,23 @@ impl ArchetypeData {
     /// Gets a description of the component types in the archetype.
     pub fn description(&self) -> &ArchetypeDescription { &self.desc }
 
+    /// Gets the data layout of the archetype's chunks.
+    pub fn layout(&self) -> &ComponentStorageLayout { &self.component_layout }
+
+    /// Gets an iterator over all chunksets in the archetype.
+    ///
+    /// Yields a tuple of `(chunkset_index, tag_values, occupied_chunks)` for each chunkset.
+    pub fn iter_chunksets(&self) -> ChunksetIter<'_> {
+        ChunksetIter {
+            tags: &self.tags,
+            chunksets: self.chunk_sets.iter().enumerate(),
+        }
+    }
+
     pub(crate) fn defrag<F: FnMut(Entity, EntityLocation)>(
         &mut self,
         budget: &mut usize,
+        freed: &mut usize,
         mut on_moved: F,
     ) -> bool {
         trace!(
@@ -674,7 +1573,7 @@ impl ArchetypeData {
         );
         let arch_index = self.id.index();
         for (i, chunkset) in self.chunk_sets.iter_mut().enumerate() {
-            let complete = chunkset.defrag(budget, |e, chunk, component| {
+            let complete = chunkset.defrag(budget, freed, |e, chunk, component| {
                 on_moved(e, EntityLocation::new(arch_index, i, chunk, component));
             });
             if !complete {
@@ -686,19 +1585,109 @@ impl ArchetypeData {
     }
 }
 
+/// An iterator over the chunksets in an archetype.
+pub struct ChunksetIter<'a> {
+    tags: &'a Tags,
+    chunksets: std::iter::Enumerate<Iter<'a, Chunkset>>,
+}
+
+impl<'a> Iterator for ChunksetIter<'a> {
+    type Item = (usize, TagValuesView<'a>, &'a [ComponentStorage]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunksets.next().map(|(set, chunkset)| {
+            (
+                set,
+                TagValuesView {
+                    tags: self.tags,
+                    set,
+                },
+                chunkset.occupied(),
+            )
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.chunksets.size_hint() }
+}
+
+impl<'a> ExactSizeIterator for ChunksetIter<'a> {}
+
+/// Provides typed access to the tag values attached to a single chunkset.
+#[derive(Copy, Clone)]
+pub struct TagValuesView<'a> {
+    tags: &'a Tags,
+    set: usize,
+}
+
+impl<'a> TagValuesView<'a> {
+    /// Gets the index of the chunkset within its archetype.
+    pub fn index(&self) -> usize { self.set }
+
+    /// Gets the value of tag `T` attached to the chunkset.
+    ///
+    /// Returns `None` if the chunkset's archetype does not contain the tag type.
+    pub fn get<T: Tag>(&self) -> Option<&'a T> {
+        self.tags
+            .get(TagTypeId::of::<T>())
+            .and_then(|tags| tags.downcast::<T>())
+            .and_then(|tags| tags.as_slice().get(self.set))
+    }
+}
+
 fn align_up(addr: usize, align: usize) -> usize { (addr + (align - 1)) & align.wrapping_neg() }
 
 /// Describes the data layout for a chunk.
+#[derive(Clone)]
 pub struct ComponentStorageLayout {
     capacity: usize,
+    padding: usize,
     alloc_layout: std::alloc::Layout,
     data_layout: Vec<(ComponentTypeId, usize, ComponentMeta)>,
+    // the layout of the first chunk in each chunk set, if it is created at reduced capacity
+    small: Option<Box<ComponentStorageLayout>>,
 }
 
 impl ComponentStorageLayout {
+    fn new(desc: &ArchetypeDescription, capacity: usize, padding: usize) -> Self {
+        let padded_capacity = align_up(capacity, padding);
+        let mut data_capacity = 0usize;
+        let mut component_data_offsets = Vec::new();
+        for (type_id, meta) in desc.components.iter() {
+            data_capacity = align_up(
+                align_up(data_capacity, COMPONENT_STORAGE_ALIGNMENT),
+                meta.align,
+            );
+            component_data_offsets.push((*type_id, data_capacity, *meta));
+            data_capacity += meta.size * padded_capacity;
+        }
+        let data_alignment =
+            std::alloc::Layout::from_size_align(data_capacity, COMPONENT_STORAGE_ALIGNMENT)
+                .expect("invalid component data size/alignment");
+
+        ComponentStorageLayout {
+            capacity,
+            padding,
+            alloc_layout: data_alignment,
+            data_layout: component_data_offsets,
+            small: None,
+        }
+    }
+
     /// The maximum number of entities that can be stored in each chunk.
     pub fn capacity(&self) -> usize { self.capacity }
 
+    /// The number of entities that can be stored in the first chunk of each chunk set before
+    /// it is grown to `capacity`.
+    pub fn initial_capacity(&self) -> usize {
+        self.small
+            .as_ref()
+            .map(|small| small.capacity)
+            .unwrap_or(self.capacity)
+    }
+
+    /// The number of elements which each component column is padded to a multiple of.
+    pub fn padding(&self) -> usize { self.padding }
+
     /// The components in each chunk.
     pub fn components(&self) -> &[(ComponentTypeId, usize, ComponentMeta)] { &self.data_layout }
 
@@ -711,11 +1700,16 @@ impl ComponentStorageLayout {
                     *ty,
                     ComponentResourceSet {
                         ptr: AtomicRefCell::new(meta.align as *mut u8),
+                        type_id: *ty,
+                        meta: *meta,
                         capacity: self.capacity,
+                        padding: self.padding,
+                        memory: UnsafeCell::new(None),
                         count: UnsafeCell::new(0),
                         element_size: meta.size,
                         drop_fn: meta.drop_fn,
                         version: UnsafeCell::new(0),
+                        added_version: UnsafeCell::new(0),
                     },
                 )
             })
@@ -724,23 +1718,36 @@ impl ComponentStorageLayout {
         ComponentStorage {
             id,
             capacity: self.capacity,
-            entities: Vec::with_capacity(self.capacity),
+            entities: Vec::new(),
             component_offsets: self
                 .data_layout
                 .iter()
                 .map(|(ty, offset, _)| (*ty, *offset))
                 .collect(),
             component_layout: self.alloc_layout,
+            component_padding: self.padding,
             component_info: UnsafeCell::new(Components::new(storage_info)),
             component_data: None,
+            retain_memory: false,
+            structural_version: next_version(),
         }
     }
 }
 
 /// Contains chunks with the same layout and tag values.
+///
+/// Each chunkset tracks which of its chunks contain entities, such that iteration can skip
+/// empty chunks without reading them.
 #[derive(Default)]
 pub struct Chunkset {
     chunks: Vec<ComponentStorage>,
+    user_data: Option<UserData>,
+    // a bit for each chunk, set if the chunk contains entities
+    occupancy: Vec<AtomicU64>,
+    // the number of entities in all chunks
+    entity_count: AtomicUsize,
+    // set when the chunks may have changed since the occupancy was last computed
+    occupancy_stale: AtomicBool,
 }
 
 impl Deref for Chunkset {
@@ -750,35 +1757,126 @@ impl Deref for Chunkset {
 }
 
 impl DerefMut for Chunkset {
-    fn deref_mut(&mut self) -> &mut Self::Target { self.chunks.as_mut_slice() }
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        *self.occupancy_stale.get_mut() = true;
+        self.chunks.as_mut_slice()
+    }
 }
 
 impl Chunkset {
+    fn new(chunks: Vec<ComponentStorage>) -> Self {
+        let mut set = Chunkset {
+            chunks,
+            user_data: None,
+            occupancy: Vec::new(),
+            entity_count: AtomicUsize::new(0),
+            occupancy_stale: AtomicBool::new(true),
+        };
+        set.resize_occupancy();
+        set
+    }
+
     /// Pushes a new chunk into the set.
-    pub fn push(&mut self, chunk: ComponentStorage) { self.chunks.push(chunk); }
+    pub fn push(&mut self, chunk: ComponentStorage) {
+        self.chunks.push(chunk);
+        self.resize_occupancy();
+    }
 
-    pub(crate) fn drain<R: RangeBounds<usize>>(
-        &mut self,
-        range: R,
-    ) -> std::vec::Drain<ComponentStorage> {
-        self.chunks.drain(range)
+    /// Removes a chunk from the set, replacing it with the last chunk in the set.
+    pub(crate) fn swap_remove(&mut self, index: usize) -> ComponentStorage {
+        let chunk = self.chunks.swap_remove(index);
+        self.resize_occupancy();
+        chunk
+    }
+
+    /// Determines if the chunk at the given index contains any entities.
+    ///
+    /// Unlike `ComponentStorage::is_empty`, this does not need to read the chunk.
+    pub fn is_occupied(&self, index: usize) -> bool {
+        self.refresh_occupancy();
+        self.occupancy
+            .get(index / 64)
+            .is_some_and(|word| word.load(Ordering::Relaxed) & (1 << (index % 64)) != 0)
+    }
+
+    /// Gets the number of entities in all chunks in the set.
+    ///
+    /// The count is only recomputed after the chunks have been modified.
+    pub fn entity_count(&self) -> usize {
+        self.refresh_occupancy();
+        self.entity_count.load(Ordering::Relaxed)
+    }
+
+    // Resizes the occupancy mask after chunks are added or removed.
+    fn resize_occupancy(&mut self) {
+        let words = self.chunks.len().div_ceil(64);
+        self.occupancy.resize_with(words, AtomicU64::default);
+        *self.occupancy_stale.get_mut() = true;
+    }
+
+    // Recomputes the occupancy mask and entity count if the chunks may have changed since they
+    // were last computed.
+    fn refresh_occupancy(&self) {
+        if !self.occupancy_stale.load(Ordering::Acquire) {
+            return;
+        }
+
+        let count = self.chunks.iter().map(|chunk| chunk.len()).sum();
+        self.entity_count.store(count, Ordering::Relaxed);
+        for (word, chunks) in self.occupancy.iter().zip(self.chunks.chunks(64)) {
+            let bits = chunks
+                .iter()
+                .enumerate()
+                .filter(|(_, chunk)| !chunk.is_empty())
+                .fold(0u64, |bits, (i, _)| bits | (1 << i));
+            word.store(bits, Ordering::Relaxed);
+        }
+
+        self.occupancy_stale.store(false, Ordering::Release);
+    }
+
+    /// Gets the user data attached to the chunk set.
+    pub fn user_data(&self) -> Option<&(dyn Any + Send + Sync)> { self.user_data.as_deref() }
+
+    /// Mutably borrows the user data attached to the chunk set.
+    pub fn user_data_mut(&mut self) -> Option<&mut (dyn Any + Send + Sync)> {
+        self.user_data.as_deref_mut()
     }
 
+    /// Attaches user data to the chunk set, returning the data previously attached.
+    ///
+    /// User data is not included in world snapshots, and is discarded if the chunk set is
+    /// merged into a matching chunk set of another world.
+    pub fn set_user_data(&mut self, data: UserData) -> Option<UserData> {
+        self.user_data.replace(data)
+    }
+
+    /// Detaches the user data from the chunk set.
+    pub fn take_user_data(&mut self) -> Option<UserData> { self.user_data.take() }
+
+    pub(crate) fn into_chunks(self) -> Vec<ComponentStorage> { self.chunks }
+
     /// Gets a slice reference to occupied chunks.
+    ///
+    /// Only trailing empty chunks are excluded. Empty chunks between occupied chunks can be
+    /// identified with `is_occupied`.
     pub fn occupied(&self) -> &[ComponentStorage] {
-        let mut len = self.chunks.len();
-        while len > 0 {
-            if unsafe { !self.chunks.get_unchecked(len - 1).is_empty() } {
-                break;
-            }
-            len -= 1;
-        }
+        self.refresh_occupancy();
+        let len = self
+            .occupancy
+            .iter()
+            .rposition(|word| word.load(Ordering::Relaxed) != 0)
+            .map_or(0, |i| {
+                let word = self.occupancy[i].load(Ordering::Relaxed);
+                i * 64 + 64 - word.leading_zeros() as usize
+            });
         let (some, _) = self.chunks.as_slice().split_at(len);
         some
     }
 
     /// Gets a mutable slice reference to occupied chunks.
     pub fn occupied_mut(&mut self) -> &mut [ComponentStorage] {
+        *self.occupancy_stale.get_mut() = true;
         let mut len = self.chunks.len();
         while len > 0 {
             if unsafe { !self.chunks.get_unchecked(len - 1).is_empty() } {
@@ -805,16 +1903,17 @@ impl Chunkset {
     fn defrag<F: FnMut(Entity, usize, usize)>(
         &mut self,
         budget: &mut usize,
+        freed: &mut usize,
         mut on_moved: F,
     ) -> bool {
         let slice = self.occupied_mut();
-        let mut first = 0;
-        let mut last = slice.len() - 1;
-
         if slice.is_empty() {
             return true;
         }
 
+        let mut first = 0;
+        let mut last = slice.len() - 1;
+
         trace!("Defragmenting chunkset");
 
         loop {
@@ -841,13 +1940,14 @@ impl Chunkset {
             // move as many entities as we can from the last chunk into the first
             loop {
                 if *budget == 0 {
+                    source.shrink();
                     return false;
                 }
 
                 *budget -= 1;
 
                 // move the last entity
-                let swapped = source.move_entity(target, source.len() - 1);
+                let swapped = source.move_entity(target, source.len() - 1, true);
                 assert!(swapped.is_none());
 
                 // notify move
@@ -855,6 +1955,10 @@ impl Chunkset {
 
                 // exit if we cant move any more
                 if target.is_full() || source.is_empty() {
+                    source.shrink();
+                    if !source.is_allocated() {
+                        *freed += 1;
+                    }
                     break;
                 }
             }
@@ -918,9 +2022,12 @@ pub struct ComponentStorage {
     capacity: usize,
     entities: Vec<Entity>,
     component_layout: std::alloc::Layout,
+    component_padding: usize,
     component_offsets: HashMap<ComponentTypeId, usize>,
     component_info: UnsafeCell<Components>,
-    component_data: Option<NonNull<u8>>,
+    component_data: Option<Arc<ChunkMemory>>,
+    retain_memory: bool,
+    structural_version: u64,
 }
 
 pub struct StorageWriter<'a> {
@@ -953,12 +2060,49 @@ impl ComponentStorage {
     /// Determines if the chunk is empty.
     pub fn is_empty(&self) -> bool { self.entities.len() == 0 }
 
+    /// Releases any memory reserved for entity IDs beyond those currently stored in the chunk.
+    ///
+    /// The chunk's entity list grows incrementally as entities are added, rather than being
+    /// reserved for the chunk's full capacity, such that partially filled chunks do not hold
+    /// memory for entities they do not contain. Defragmentation shrinks the chunks it removes
+    /// entities from.
+    pub fn shrink(&mut self) { self.entities.shrink_to_fit(); }
+
     /// Determines if the internal memory for this chunk has been allocated.
     pub fn is_allocated(&self) -> bool { self.component_data.is_some() }
 
+    #[cfg(feature = "alloc-tracking")]
+    pub(crate) fn allocated_bytes(&self) -> usize {
+        self.component_data
+            .as_ref()
+            .map(|memory| memory.layout.size())
+            .unwrap_or(0)
+    }
+
+    /// Determines if the chunk keeps its internal memory allocated while it is empty.
+    pub fn retains_memory(&self) -> bool { self.retain_memory }
+
+    /// Sets whether the chunk keeps its internal memory allocated while it is empty.
+    ///
+    /// Retaining memory allocates the chunk immediately if it is not already allocated.
+    pub(crate) fn set_retain_memory(&mut self, retain: bool) {
+        self.retain_memory = retain;
+        if retain && !self.is_allocated() {
+            self.allocate();
+        } else if !retain && self.is_allocated() && self.is_empty() {
+            self.free();
+        }
+    }
+
     /// Gets a slice reference containing the IDs of all entities stored in the chunk.
     pub fn entities(&self) -> &[Entity] { self.entities.as_slice() }
 
+    /// Gets the version of the chunk's entity layout.
+    ///
+    /// The version changes whenever entities are added to, removed from, or reordered within
+    /// the chunk.
+    pub fn structural_version(&self) -> u64 { self.structural_version }
+
     /// Gets a component accessor for the specified component type.
     pub fn components(&self, component_type: ComponentTypeId) -> Option<&ComponentResourceSet> {
         unsafe { &*self.component_info.get() }.get(component_type)
@@ -968,6 +2112,7 @@ impl ComponentStorage {
     ///
     /// Returns the ID of the entity which was swapped into the removed entity's position.
     pub fn swap_remove(&mut self, index: usize, drop: bool) -> Option<Entity> {
+        self.structural_version = next_version();
         self.entities.swap_remove(index);
         for (_, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
             component.writer().swap_remove(index, drop);
@@ -978,7 +2123,7 @@ impl ComponentStorage {
         if self.entities.len() > index {
             Some(*self.entities.get(index).unwrap())
         } else {
-            if self.is_empty() {
+            if self.is_empty() && !self.retain_memory {
                 self.free();
             }
 
@@ -986,11 +2131,81 @@ impl ComponentStorage {
         }
     }
 
+    /// Removes an entity from the chunk by shifting all following entities down by one,
+    /// preserving the relative order of the remaining entities.
+    ///
+    /// Returns the IDs of the entities which were shifted, which now begin at `index`.
+    pub fn shift_remove(&mut self, index: usize, drop: bool) -> &[Entity] {
+        self.structural_version = next_version();
+        self.entities.remove(index);
+        for (_, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
+            component.writer().shift_remove(index, drop);
+        }
+
+        self.update_count_gauge();
+
+        if self.is_empty() && !self.retain_memory {
+            self.free();
+        }
+
+        &self.entities[index..]
+    }
+
+    /// Removes all entities from the chunk, releasing its memory unless the chunk retains it.
+    ///
+    /// Returns the IDs of the removed entities.
+    pub fn clear(&mut self, drop: bool) -> Vec<Entity> {
+        self.structural_version = next_version();
+        let entities = self.entities.drain(..).collect();
+        for (_, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
+            component.writer().clear(drop);
+        }
+
+        self.update_count_gauge();
+
+        if self.is_allocated() && !self.retain_memory {
+            self.free();
+        }
+
+        entities
+    }
+
+    /// Reorders the entities in the chunk such that the entity at index `i` is moved from index
+    /// `order[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is not a permutation of the indices of all entities in the chunk.
+    pub fn permute(&mut self, order: &[usize]) {
+        assert_eq!(self.len(), order.len(), "permutation length mismatch");
+        let mut seen = vec![false; order.len()];
+        for i in order {
+            assert!(
+                !std::mem::replace(&mut seen[*i], true),
+                "invalid permutation"
+            );
+        }
+
+        self.structural_version = next_version();
+        let previous = self.entities.clone();
+        for (entity, source) in self.entities.iter_mut().zip(order) {
+            *entity = previous[*source];
+        }
+        for (_, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
+            unsafe { component.writer().permute(order) };
+        }
+    }
+
     /// Moves an entity from this chunk into a target chunk, moving all compatable components into
-    /// the target chunk. Any components left over will be dropped.
+    /// the target chunk. Any components left over will be dropped if `drop` is `true`.
     ///
     /// Returns the ID of the entity which was swapped into the removed entity's position.
-    pub fn move_entity(&mut self, target: &mut ComponentStorage, index: usize) -> Option<Entity> {
+    pub fn move_entity(
+        &mut self,
+        target: &mut ComponentStorage,
+        index: usize,
+        drop: bool,
+    ) -> Option<Entity> {
         debug_assert!(index < self.len());
         debug_assert!(!target.is_full());
         if !target.is_allocated() {
@@ -1000,6 +2215,7 @@ impl ComponentStorage {
         trace!(index, source = ?self.id, destination = ?target.id, "Moving entity");
 
         let entity = unsafe { *self.entities.get_unchecked(index) };
+        target.structural_version = next_version();
         target.entities.push(entity);
 
         let self_components = unsafe { &mut *self.component_info.get() };
@@ -1013,9 +2229,9 @@ impl ComponentStorage {
                     let component = ptr.add(element_size * index);
                     target_accessor
                         .writer()
-                        .push_raw(NonNull::new_unchecked(component), 1);
+                        .push_moved_raw(NonNull::new_unchecked(component), 1);
                 }
-            } else {
+            } else if drop {
                 // drop the component rather than move it
                 unsafe { accessor.writer().drop_in_place(index) };
             }
@@ -1027,11 +2243,47 @@ impl ComponentStorage {
         self.swap_remove(index, false)
     }
 
+    // Creates a copy of the chunk which shares its plain old data components.
+    fn share(&mut self, registry: &Registry) -> ComponentStorage {
+        let mut components = SmallVec::new();
+        if let Some(memory) = &self.component_data {
+            for (type_id, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
+                components.push((*type_id, component.share(registry, memory)));
+            }
+        } else {
+            for (type_id, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
+                let component = ComponentResourceSet {
+                    ptr: AtomicRefCell::new(*component.ptr.get_mut()),
+                    memory: UnsafeCell::new(None),
+                    count: UnsafeCell::new(0),
+                    version: UnsafeCell::new(component.version()),
+                    added_version: UnsafeCell::new(component.added_version()),
+                    ..*component
+                };
+                components.push((*type_id, component));
+            }
+        }
+
+        ComponentStorage {
+            id: self.id,
+            capacity: self.capacity,
+            entities: self.entities.clone(),
+            component_layout: self.component_layout,
+            component_padding: self.component_padding,
+            component_offsets: self.component_offsets.clone(),
+            component_info: UnsafeCell::new(Components::new(components)),
+            component_data: self.component_data.clone(),
+            retain_memory: self.retain_memory,
+            structural_version: self.structural_version,
+        }
+    }
+
     /// Gets mutable references to the internal data of the chunk.
     pub fn writer(&mut self) -> StorageWriter {
         if !self.is_allocated() {
             self.allocate();
         }
+        self.structural_version = next_version();
         StorageWriter { storage: self }
     }
 
@@ -1055,17 +2307,18 @@ impl ComponentStorage {
         // the slices returned from these accessors will be empty though, so no code
         // should ever dereference these pointers
 
-        // free component memory
-        unsafe {
-            let ptr = self.component_data.take().unwrap();
-            std::alloc::dealloc(ptr.as_ptr(), self.component_layout);
+        // free component memory, which may outlive the chunk if it is shared with a snapshot
+        for (_, component) in unsafe { &mut *self.component_info.get() }.iter_mut() {
+            *component.memory.get_mut() = None;
         }
+        self.component_data = None;
 
         self.update_mem_gauge();
     }
 
     fn allocate(&mut self) {
         debug_assert!(!self.is_allocated());
+        profile_scope!("Chunk allocation");
 
         trace!(
             world = self.id.archetype_id().world().index(),
@@ -1075,12 +2328,12 @@ impl ComponentStorage {
             layout = ?self.component_layout,
             "Allocating chunk memory"
         );
-        self.entities.reserve_exact(self.capacity);
 
         unsafe {
-            // allocating backing store
-            let ptr = std::alloc::alloc(self.component_layout);
-            self.component_data = Some(NonNull::new_unchecked(ptr));
+            // allocating backing store, zeroing the padding at the end of each column
+            let memory = ChunkMemory::new(self.component_layout, self.component_padding > 1);
+            let ptr = memory.ptr.as_ptr();
+            self.component_data = Some(Arc::new(memory));
 
             // update accessor pointers
             for (type_id, component) in (&mut *self.component_info.get()).iter_mut() {
@@ -1092,6 +2345,66 @@ impl ComponentStorage {
         self.update_mem_gauge();
     }
 
+    /// Grows a chunk created at reduced capacity to the capacity of `layout`, moving its
+    /// components into a new allocation.
+    fn grow(&mut self, layout: &ComponentStorageLayout) {
+        debug_assert!(layout.capacity > self.capacity);
+        trace!(
+            world = self.id.archetype_id().world().index(),
+            archetype = self.id.archetype_id().index(),
+            chunkset = self.id.set(),
+            chunk = self.id.index(),
+            from = self.capacity,
+            to = layout.capacity,
+            "Growing chunk"
+        );
+
+        let memory = if self.is_allocated() {
+            Some(ChunkMemory::new(
+                layout.alloc_layout,
+                self.component_padding > 1,
+            ))
+        } else {
+            None
+        };
+
+        for (type_id, offset, _) in layout.data_layout.iter() {
+            let component = unsafe { &mut *self.component_info.get() }
+                .get_mut(*type_id)
+                .unwrap();
+            if let Some(memory) = &memory {
+                if component.element_size > 0 {
+                    unsafe {
+                        let ptr = memory.ptr.as_ptr().add(*offset);
+                        std::ptr::copy_nonoverlapping(
+                            *component.ptr.get_mut(),
+                            ptr,
+                            component.element_size * *component.count.get_mut(),
+                        );
+                        *component.ptr.get_mut() = ptr;
+                    }
+                }
+            }
+
+            // the components now live in the chunk's own allocation
+            *component.memory.get_mut() = None;
+            component.capacity = layout.capacity;
+        }
+
+        self.capacity = layout.capacity;
+        self.component_layout = layout.alloc_layout;
+        self.component_offsets = layout
+            .data_layout
+            .iter()
+            .map(|(ty, offset, _)| (*ty, *offset))
+            .collect();
+        if let Some(memory) = memory {
+            self.component_data = Some(Arc::new(memory));
+        }
+        self.structural_version = next_version();
+        self.update_mem_gauge();
+    }
+
     fn update_mem_gauge(&self) {
         #[cfg(feature = "metrics")]
         {
@@ -1129,8 +2442,9 @@ unsafe impl Send for ComponentStorage {}
 
 impl Drop for ComponentStorage {
     fn drop(&mut self) {
-        if let Some(ptr) = self.component_data {
+        if self.is_allocated() {
             // run the drop functions of all components
+            // shared components are plain old data, and so never have drop functions
             for (_, info) in unsafe { &mut *self.component_info.get() }.drain() {
                 if let Some(drop_fn) = info.drop_fn {
                     let ptr = info.ptr.get_mut();
@@ -1141,31 +2455,171 @@ impl Drop for ComponentStorage {
                     }
                 }
             }
-
-            // free the chunk's memory
-            unsafe {
-                std::alloc::dealloc(ptr.as_ptr(), self.component_layout);
-            }
         }
+
+        // the chunk's memory is freed once it is no longer shared
     }
 }
 
-/// Provides raw access to component data slices.
-#[repr(align(64))]
-pub struct ComponentResourceSet {
-    ptr: AtomicRefCell<*mut u8>,
-    element_size: usize,
-    count: UnsafeCell<usize>,
-    capacity: usize,
-    drop_fn: Option<fn(*mut u8)>,
-    version: UnsafeCell<u64>,
+/// An allocation holding component data, which may be shared between a chunk and its snapshots.
+pub(crate) struct ChunkMemory {
+    ptr: NonNull<u8>,
+    layout: std::alloc::Layout,
 }
 
-impl ComponentResourceSet {
-    /// Gets the version of the component slice.
-    pub fn version(&self) -> u64 { unsafe { (*self.version.get()) } }
-
-    /// Gets a raw pointer to the start of the component slice.
+impl ChunkMemory {
+    fn new(layout: std::alloc::Layout, zeroed: bool) -> Self {
+        let ptr = unsafe {
+            if zeroed {
+                std::alloc::alloc_zeroed(layout)
+            } else {
+                std::alloc::alloc(layout)
+            }
+        };
+        match NonNull::new(ptr) {
+            Some(ptr) => {
+                #[cfg(feature = "alloc-tracking")]
+                CHUNK_ALLOCATIONS.alloc(layout.size());
+                ChunkMemory { ptr, layout }
+            }
+            None => std::alloc::handle_alloc_error(layout),
+        }
+    }
+}
+
+// This is safe because the memory is only accessed through the component sets which point into
+// it, which are borrow checked at runtime.
+unsafe impl Send for ChunkMemory {}
+
+unsafe impl Sync for ChunkMemory {}
+
+impl Drop for ChunkMemory {
+    fn drop(&mut self) {
+        unsafe { std::alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+        #[cfg(feature = "alloc-tracking")]
+        CHUNK_ALLOCATIONS.free(self.layout.size());
+    }
+}
+
+/// Counts the bytes allocated and freed for one kind of storage.
+#[cfg(feature = "alloc-tracking")]
+struct AllocationTracker {
+    allocated: AtomicUsize,
+    freed: AtomicUsize,
+    allocations: AtomicUsize,
+    frees: AtomicUsize,
+}
+
+#[cfg(feature = "alloc-tracking")]
+impl AllocationTracker {
+    const fn new() -> Self {
+        AllocationTracker {
+            allocated: AtomicUsize::new(0),
+            freed: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+            frees: AtomicUsize::new(0),
+        }
+    }
+
+    fn alloc(&self, bytes: usize) {
+        self.allocated.fetch_add(bytes, Ordering::Relaxed);
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn free(&self, bytes: usize) {
+        self.freed.fetch_add(bytes, Ordering::Relaxed);
+        self.frees.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn counters(&self) -> AllocationCounters {
+        AllocationCounters {
+            allocated: self.allocated.load(Ordering::Relaxed),
+            freed: self.freed.load(Ordering::Relaxed),
+            allocations: self.allocations.load(Ordering::Relaxed),
+            frees: self.frees.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(feature = "alloc-tracking")]
+static CHUNK_ALLOCATIONS: AllocationTracker = AllocationTracker::new();
+#[cfg(feature = "alloc-tracking")]
+static TAG_ALLOCATIONS: AllocationTracker = AllocationTracker::new();
+#[cfg(feature = "alloc-tracking")]
+static TAG_SET_ALLOCATIONS: AllocationTracker = AllocationTracker::new();
+
+/// The bytes allocated and freed for one kind of storage, counted across all worlds since the
+/// process started.
+#[cfg(feature = "alloc-tracking")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocationCounters {
+    /// The total number of bytes allocated.
+    pub allocated: usize,
+    /// The total number of bytes freed.
+    pub freed: usize,
+    /// The number of allocations made.
+    pub allocations: usize,
+    /// The number of allocations freed.
+    pub frees: usize,
+}
+
+#[cfg(feature = "alloc-tracking")]
+impl AllocationCounters {
+    /// Gets the number of bytes currently allocated.
+    pub fn live(&self) -> usize { self.allocated - self.freed }
+}
+
+/// Gets the process-wide counters for chunk, tag storage and dynamic tag set allocations.
+#[cfg(feature = "alloc-tracking")]
+pub(crate) fn allocation_counters() -> (AllocationCounters, AllocationCounters, AllocationCounters)
+{
+    (
+        CHUNK_ALLOCATIONS.counters(),
+        TAG_ALLOCATIONS.counters(),
+        TAG_SET_ALLOCATIONS.counters(),
+    )
+}
+
+/// Provides raw access to component data slices.
+#[repr(align(64))]
+pub struct ComponentResourceSet {
+    ptr: AtomicRefCell<*mut u8>,
+    type_id: ComponentTypeId,
+    meta: ComponentMeta,
+    element_size: usize,
+    count: UnsafeCell<usize>,
+    capacity: usize,
+    padding: usize,
+    // the allocation holding the components, if it is not exclusively owned by the chunk
+    memory: UnsafeCell<Option<Arc<ChunkMemory>>>,
+    drop_fn: Option<fn(*mut u8)>,
+    version: UnsafeCell<u64>,
+    added_version: UnsafeCell<u64>,
+}
+
+impl ComponentResourceSet {
+    /// Gets the version of the component slice.
+    ///
+    /// The version is incremented whenever the slice is mutably accessed, and whenever
+    /// components are pushed into it, such that newly inserted components are always reported
+    /// as changed.
+    pub fn version(&self) -> u64 { unsafe { (*self.version.get()) } }
+
+    /// Gets the version at which components were last attached to entities in the slice, either
+    /// by inserting new entities or by adding the component type to existing entities.
+    ///
+    /// Unlike `version`, this is not incremented when existing components are modified, or when
+    /// an entity's existing components are moved into the slice from another archetype. The
+    /// added version is never greater than `version`.
+    pub fn added_version(&self) -> u64 { unsafe { *self.added_version.get() } }
+
+    /// Gets the type ID of the components in the slice.
+    pub fn type_id(&self) -> ComponentTypeId { self.type_id }
+
+    /// Gets the number of components in the slice.
+    pub(crate) fn len(&self) -> usize { unsafe { *self.count.get() } }
+
+    /// Gets a raw pointer to the start of the component slice.
     ///
     /// Returns a tuple containing `(pointer, element_size, count)`.
     ///
@@ -1174,9 +2628,11 @@ impl ComponentResourceSet {
     /// Access to the component data within the slice is runtime borrow checked.
     /// This call will panic if borrowing rules are broken.
     pub fn data_raw(&self) -> (Ref<Shared, *mut u8>, usize, usize) {
-        (self.ptr.get(), self.element_size, unsafe {
-            *self.count.get()
-        })
+        let ptr = self
+            .ptr
+            .try_get()
+            .unwrap_or_else(|err| panic!("{}: {:?}", err, self.type_id));
+        (ptr, self.element_size, unsafe { *self.count.get() })
     }
 
     /// Gets a raw pointer to the start of the component slice.
@@ -1195,8 +2651,12 @@ impl ComponentResourceSet {
     pub fn data_raw_mut(&self) -> (RefMut<Exclusive, *mut u8>, usize, usize) {
         // this version increment is not thread safe
         // - but the pointer `get_mut` ensures exclusive access at runtime
-        let ptr = self.ptr.get_mut();
+        let mut ptr = self
+            .ptr
+            .try_get_mut()
+            .unwrap_or_else(|err| panic!("{}: {:?}", err, self.type_id));
         unsafe {
+            self.unshare(&mut ptr);
             *self.version.get() = next_version();
         };
         (ptr, self.element_size, unsafe { *self.count.get() })
@@ -1210,7 +2670,12 @@ impl ComponentResourceSet {
     ///
     /// Access to the component data within the slice is runtime borrow checked.
     /// This call will panic if borrowing rules are broken.
-    pub unsafe fn data_slice<T>(&self) -> RefMap<Shared, &[T]> {
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored components.
+    pub unsafe fn data_slice<T: 'static>(&self) -> RefMap<Shared, &[T]> {
+        self.meta.assert_type::<T>();
         let (ptr, _size, count) = self.data_raw();
         ptr.map_into(|ptr| std::slice::from_raw_parts(*ptr as *const _ as *const T, count))
     }
@@ -1228,20 +2693,201 @@ impl ComponentResourceSet {
     ///
     /// Will panic when an internal u64 counter overflows.
     /// It will happen in 50000 years if you do 10000 mutations a millisecond.
-    pub unsafe fn data_slice_mut<T>(&self) -> RefMapMut<Exclusive, &mut [T]> {
+    ///
+    /// This function will panic if `T` is not the type of the stored components.
+    pub unsafe fn data_slice_mut<T: 'static>(&self) -> RefMapMut<Exclusive, &mut [T]> {
+        self.meta.assert_type::<T>();
         let (ptr, _size, count) = self.data_raw_mut();
         ptr.map_into(|ptr| std::slice::from_raw_parts_mut(*ptr as *mut _ as *mut T, count))
     }
 
+    /// Gets a shared reference to a range of the slice of components.
+    ///
+    /// # Safety
+    ///
+    /// Ensure that `T` is representative of the component data actually stored.
+    ///
+    /// Access to the component data within the slice is runtime borrow checked.
+    /// This call will panic if borrowing rules are broken.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored components, or if the range
+    /// is out of bounds.
+    pub unsafe fn data_slice_range<T: 'static>(
+        &self,
+        range: Range<usize>,
+    ) -> RefMap<'_, Shared<'_>, &[T]> {
+        self.data_slice::<T>().map_into(|slice| {
+            let slice: &[T] = slice;
+            &slice[range.clone()]
+        })
+    }
+
+    /// Gets a mutable reference to a range of the slice of components. Unlike `data_slice_mut`,
+    /// references to disjoint ranges of the slice may be held at the same time.
+    ///
+    /// This does not update the version of the slice or copy shared components into memory
+    /// owned by the chunk, and so the slice must have been mutably accessed via
+    /// `data_raw_mut` since it was last shared.
+    ///
+    /// # Safety
+    ///
+    /// Ensure that `T` is representative of the component data actually stored, and that no
+    /// other reference obtained by this function overlaps the range.
+    ///
+    /// Access to the component data within the slice is runtime borrow checked.
+    /// This call will panic if borrowing rules are broken.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored components, or if the range
+    /// is out of bounds.
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn data_slice_range_mut<T: 'static>(
+        &self,
+        range: Range<usize>,
+    ) -> RefMapMut<'_, Exclusive<'_>, &mut [T]> {
+        self.meta.assert_type::<T>();
+        assert!(range.start <= range.end && range.end <= self.len());
+        debug_assert!(!self.is_shared());
+        let (borrow, ptr) = self
+            .ptr
+            .try_get_disjoint()
+            .unwrap_or_else(|err| panic!("{}: {:?}", err, self.type_id));
+        let slice = std::slice::from_raw_parts_mut((*ptr as *mut T).add(range.start), range.len());
+        RefMapMut::new(borrow, slice)
+    }
+
+    /// Gets the number of components in the slice, rounded up to a multiple of the archetype's
+    /// component padding.
+    pub fn padded_len(&self) -> usize { align_up(unsafe { *self.count.get() }, self.padding) }
+
+    /// Gets a shared reference to the slice of components, extended with the zeroed padding
+    /// which follows them up to `padded_len`.
+    ///
+    /// # Safety
+    ///
+    /// Ensure that `T` is representative of the component data actually stored, and that the
+    /// all-zero bit pattern is a valid value of `T`.
+    ///
+    /// Access to the component data within the slice is runtime borrow checked.
+    /// This call will panic if borrowing rules are broken.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored components.
+    pub unsafe fn data_slice_padded<T: 'static>(&self) -> RefMap<'_, Shared<'_>, &[T]> {
+        self.meta.assert_type::<T>();
+        let (ptr, _size, _count) = self.data_raw();
+        let len = self.padded_len();
+        ptr.map_into(|ptr| std::slice::from_raw_parts(*ptr as *const _ as *const T, len))
+    }
+
     /// Creates a writer for pushing components into or removing from the vec.
     pub fn writer(&mut self) -> ComponentWriter { ComponentWriter::new(self) }
+
+    /// Determines if the components are shared with a snapshot, and will be copied when they
+    /// are next written to.
+    pub fn is_shared(&self) -> bool {
+        unsafe { &*self.memory.get() }
+            .as_ref()
+            .map(|memory| Arc::strong_count(memory) > 1)
+            .unwrap_or(false)
+    }
+
+    // Copies the components into memory exclusively owned by this set if they are shared.
+    //
+    // Safety: the caller must hold the exclusive borrow of the component pointer.
+    unsafe fn unshare(&self, ptr: &mut *mut u8) {
+        if !self.is_shared() {
+            return;
+        }
+
+        trace!(component = ?self.type_id, "Copying shared components");
+        let copy = self.alloc_memory();
+        std::ptr::copy_nonoverlapping(
+            *ptr,
+            copy.ptr.as_ptr(),
+            self.element_size * *self.count.get(),
+        );
+        *ptr = copy.ptr.as_ptr();
+        *self.memory.get() = Some(Arc::new(copy));
+    }
+
+    // Allocates memory for the components outside of the chunk's own allocation.
+    fn alloc_memory(&self) -> ChunkMemory {
+        let size = self.element_size * align_up(self.capacity, self.padding);
+        let align = std::cmp::max(self.meta.align, COMPONENT_STORAGE_ALIGNMENT);
+        let layout = std::alloc::Layout::from_size_align(size, align)
+            .expect("invalid component data size/alignment");
+        ChunkMemory::new(layout, self.padding > 1)
+    }
+
+    // Creates a set which shares the components of this set if they are plain old data, or
+    // otherwise holds clones of them. `chunk` is the allocation of the chunk containing the set.
+    fn share(&mut self, registry: &Registry, chunk: &Arc<ChunkMemory>) -> ComponentResourceSet {
+        let count = unsafe { *self.count.get() };
+        let ptr = *self.ptr.get_mut();
+        let registration = registry.get(self.type_id);
+        let (ptr, memory) = if self.element_size == 0 {
+            // each copy is dropped, so values with drop glue must be cloned
+            if self.drop_fn.is_some() {
+                let registration = registration.expect("component type is not cloneable");
+                for _ in 0..count {
+                    unsafe { registration.clone_into(ptr, ptr) };
+                }
+            }
+            (ptr, None)
+        } else if registration.is_some_and(|r| r.is_pod()) {
+            let memory = self.memory.get_mut().get_or_insert_with(|| chunk.clone());
+            (ptr, Some(memory.clone()))
+        } else {
+            let registration = registration.expect("component type is not cloneable");
+            let copy = self.alloc_memory();
+            for i in 0..count {
+                let offset = self.element_size * i;
+                unsafe { registration.clone_into(ptr.add(offset), copy.ptr.as_ptr().add(offset)) };
+            }
+            (copy.ptr.as_ptr(), Some(Arc::new(copy)))
+        };
+
+        ComponentResourceSet {
+            ptr: AtomicRefCell::new(ptr),
+            type_id: self.type_id,
+            meta: self.meta,
+            element_size: self.element_size,
+            count: UnsafeCell::new(count),
+            capacity: self.capacity,
+            padding: self.padding,
+            memory: UnsafeCell::new(memory),
+            drop_fn: self.drop_fn,
+            version: UnsafeCell::new(self.version()),
+            added_version: UnsafeCell::new(self.added_version()),
+        }
+    }
+
+    /// Hints to the CPU that the start of the component slice will soon be accessed.
+    ///
+    /// This does not borrow the slice, and has no effect on targets without a prefetch
+    /// instruction.
+    #[inline]
+    pub fn prefetch(&self) {
+        // the pointer is only changed while the storage is mutably borrowed
+        let ptr = unsafe { *self.ptr.get_unchecked() };
+        let len = (self.element_size * unsafe { *self.count.get() }).min(PREFETCH_BYTES);
+        for offset in (0..len).step_by(CACHE_LINE_SIZE) {
+            prefetch_line(unsafe { ptr.add(offset) });
+        }
+    }
 }
 
 impl Debug for ComponentResourceSet {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         write!(
             f,
-            "ComponentResourceSet {{ ptr: {:?}, element_size: {}, count: {}, capacity: {}, version: {} }}",
+            "ComponentResourceSet {{ type_id: {:?}, ptr: {:?}, element_size: {}, count: {}, capacity: {}, version: {} }}",
+            self.type_id,
             *self.ptr.get(),
             self.element_size,
             unsafe { *self.count.get() },
@@ -1259,10 +2905,10 @@ pub struct ComponentWriter<'a> {
 
 impl<'a> ComponentWriter<'a> {
     fn new(accessor: &'a ComponentResourceSet) -> ComponentWriter<'a> {
-        Self {
-            accessor,
-            ptr: accessor.ptr.get_mut(),
-        }
+        let mut ptr = accessor.ptr.get_mut();
+        // safe because we hold the exclusive borrow of the pointer
+        unsafe { accessor.unshare(&mut ptr) };
+        Self { accessor, ptr }
     }
 
     /// Pushes new components onto the end of the vec.
@@ -1289,7 +2935,23 @@ impl<'a> ComponentWriter<'a> {
             count * self.accessor.element_size,
         );
         *self.accessor.count.get() += count;
-        *self.accessor.version.get() = next_version();
+        let version = next_version();
+        *self.accessor.version.get() = version;
+        *self.accessor.added_version.get() = version;
+    }
+
+    /// Pushes components which are being moved from another slice onto the end of the vec.
+    ///
+    /// This is identical to `push_raw`, except that the components are not considered to have
+    /// been newly attached, and so the slice's `added_version` is left unchanged.
+    ///
+    /// # Safety
+    ///
+    /// See `push_raw`.
+    pub unsafe fn push_moved_raw(&mut self, components: NonNull<u8>, count: usize) {
+        let added_version = *self.accessor.added_version.get();
+        self.push_raw(components, count);
+        *self.accessor.added_version.get() = added_version;
     }
 
     /// Pushes new components onto the end of the vec.
@@ -1301,7 +2963,12 @@ impl<'a> ComponentWriter<'a> {
     /// This function will _copy_ all elements of `T` into the chunk. If `T` is not `Copy`,
     /// the caller must then `mem::forget` the source such that the destructor does not run
     /// on the original data.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored components.
     pub unsafe fn push<T: Component>(&mut self, components: &[T]) {
+        self.accessor.meta.assert_type::<T>();
         self.push_raw(
             NonNull::new_unchecked(components.as_ptr() as *mut u8),
             components.len(),
@@ -1326,9 +2993,89 @@ impl<'a> ComponentWriter<'a> {
             }
 
             *self.accessor.count.get() -= 1;
+            self.zero_padding();
+        }
+    }
+
+    /// Removes all components, running their drop functions in a single pass if `drop` is set.
+    pub fn clear(&mut self, drop: bool) {
+        unsafe {
+            let size = self.accessor.element_size;
+            let count = *self.accessor.count.get();
+            if drop {
+                if let Some(drop_fn) = self.accessor.drop_fn {
+                    for i in 0..count {
+                        drop_fn(self.ptr.add(size * i));
+                    }
+                }
+            }
+
+            *self.accessor.count.get() = 0;
+            if self.accessor.padding > 1 && count > 0 {
+                std::ptr::write_bytes(*self.ptr, 0, size * count);
+            }
+        }
+    }
+
+    /// Removes the component at the specified index by shifting all following components
+    /// down by one, preserving their relative order.
+    pub fn shift_remove(&mut self, index: usize, drop: bool) {
+        unsafe {
+            let size = self.accessor.element_size;
+            let to_remove = self.ptr.add(size * index);
+            if drop {
+                if let Some(drop_fn) = self.accessor.drop_fn {
+                    drop_fn(to_remove);
+                }
+            }
+
+            let count = *self.accessor.count.get();
+            if index < count - 1 {
+                let tail = self.ptr.add(size * (index + 1));
+                std::ptr::copy(tail, to_remove, size * (count - index - 1));
+            }
+
+            *self.accessor.count.get() -= 1;
+            self.zero_padding();
         }
     }
 
+    // Clears the slot vacated by a removal, such that the padding past the end of the
+    // components remains zeroed.
+    unsafe fn zero_padding(&mut self) {
+        if self.accessor.padding > 1 {
+            let size = self.accessor.element_size;
+            let vacated = self.ptr.add(size * *self.accessor.count.get());
+            std::ptr::write_bytes(vacated, 0, size);
+        }
+    }
+
+    /// Reorders the components such that the component at index `i` is moved from index
+    /// `order[i]`.
+    ///
+    /// # Safety
+    ///
+    /// `order` must be a permutation of the indices of all components in the vec.
+    pub unsafe fn permute(&mut self, order: &[usize]) {
+        let size = self.accessor.element_size;
+        debug_assert_eq!(*self.accessor.count.get(), order.len());
+        if size == 0 {
+            return;
+        }
+
+        let mut buffer = Vec::<u8>::with_capacity(size * order.len());
+        std::ptr::copy_nonoverlapping(*self.ptr, buffer.as_mut_ptr(), size * order.len());
+        for (i, source) in order.iter().enumerate() {
+            std::ptr::copy_nonoverlapping(
+                buffer.as_ptr().add(size * source),
+                self.ptr.add(size * i),
+                size,
+            );
+        }
+
+        *self.accessor.version.get() = next_version();
+    }
+
     /// Drops the component stored at `index` without moving any other data or
     /// altering the number of elements.
     pub unsafe fn drop_in_place(&mut self, index: usize) {
@@ -1340,6 +3087,33 @@ impl<'a> ComponentWriter<'a> {
     }
 }
 
+// The number of tags for which a `TagStorage` is initially allocated, unless hinted otherwise.
+const TAG_STORAGE_CAPACITY: usize = 4;
+
+/// The reason that a `TagStorage` could not reserve capacity for more tags.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TagReserveError {
+    /// The required capacity exceeds the maximum size of an allocation.
+    CapacityOverflow,
+    /// The allocator failed to provide memory of the given size and alignment.
+    AllocError { size: usize, align: usize },
+}
+
+impl Display for TagReserveError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            TagReserveError::CapacityOverflow => write!(f, "tag storage capacity overflow"),
+            TagReserveError::AllocError { size, align } => write!(
+                f,
+                "failed to allocate {} bytes of tag storage with alignment {}",
+                size, align
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TagReserveError {}
+
 /// A vector of tag values of a single type.
 ///
 /// Each element in the vector represents the value of tag for
@@ -1352,14 +3126,22 @@ pub struct TagStorage {
 }
 
 impl TagStorage {
-    fn new(element: TagMeta) -> Self {
-        let capacity = if element.size == 0 { !0 } else { 4 };
+    fn new(element: TagMeta) -> Self { Self::with_capacity(element, TAG_STORAGE_CAPACITY) }
+
+    fn with_capacity(element: TagMeta, capacity: usize) -> Self {
+        let capacity = if element.size == 0 {
+            !0
+        } else {
+            capacity.max(1)
+        };
 
         let ptr = unsafe {
             if element.size > 0 {
                 let layout =
                     std::alloc::Layout::from_size_align(capacity * element.size, element.align)
                         .unwrap();
+                #[cfg(feature = "alloc-tracking")]
+                TAG_ALLOCATIONS.alloc(layout.size());
                 NonNull::new_unchecked(std::alloc::alloc(layout))
             } else {
                 NonNull::new_unchecked(element.align as *mut u8)
@@ -1383,6 +3165,45 @@ impl TagStorage {
     /// Determines if the vector is empty.
     pub fn is_empty(&self) -> bool { self.len() < 1 }
 
+    /// Gets the number of tags the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Reserves capacity for at least `additional` more tags.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`, and aborts if the allocation fails.
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(err) = self.try_reserve(additional) {
+            self.handle_reserve_error(err);
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more tags, returning an error rather than
+    /// aborting if the memory could not be allocated.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TagReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TagReserveError::CapacityOverflow)?;
+        if required <= self.capacity {
+            return Ok(());
+        }
+
+        // grow geometrically, such that repeated pushes are amortized
+        let capacity = required.max(self.capacity.saturating_mul(2));
+        self.resize(capacity)
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        if self.element.size > 0 && self.capacity > self.len.max(1) {
+            if let Err(err) = self.resize(self.len.max(1)) {
+                self.handle_reserve_error(err);
+            }
+        }
+    }
+
     /// Pushes a new tag onto the end of the vector.
     ///
     /// # Safety
@@ -1398,7 +3219,7 @@ impl TagStorage {
     /// twice.
     pub unsafe fn push_raw(&mut self, ptr: *const u8) {
         if self.len == self.capacity {
-            self.grow();
+            self.reserve(1);
         }
 
         if self.element.size > 0 {
@@ -1409,16 +3230,40 @@ impl TagStorage {
         self.len += 1;
     }
 
+    /// Gets a typed view of the tags, if they are of type `T`.
+    pub fn downcast<T: Tag>(&self) -> Option<TypedTagStorage<'_, T>> {
+        if self.element.is::<T>() {
+            Some(TypedTagStorage {
+                tags: unsafe { self.data_slice::<T>() },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable typed view of the tags, if they are of type `T`.
+    pub fn downcast_mut<T: Tag>(&mut self) -> Option<TypedTagStorageMut<'_, T>> {
+        if self.element.is::<T>() {
+            Some(TypedTagStorageMut {
+                storage: self,
+                _phantom: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
     /// Pushes a new tag onto the end of the vector.
     ///
     /// # Safety
     ///
     /// Ensure that the type `T` is representative of the tag type stored in the vec.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored tags.
     pub unsafe fn push<T: Tag>(&mut self, value: T) {
-        debug_assert!(
-            size_of::<T>() == self.element.size,
-            "incompatible element data size"
-        );
+        self.element.assert_type::<T>();
         self.push_raw(&value as *const T as *const u8);
         std::mem::forget(value);
     }
@@ -1430,6 +3275,30 @@ impl TagStorage {
         (self.ptr, self.element.size, self.len)
     }
 
+    /// Replaces the tag at `index` with a clone of the tag at `src`, dropping the original.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to a valid tag of this storage's tag type, which is not stored at `index`.
+    pub(crate) unsafe fn replace_with_clone(&mut self, index: usize, src: *const u8) {
+        assert!(index < self.len, "tag index out of bounds");
+        if self.element.size == 0 {
+            return;
+        }
+
+        // clone before dropping the original, such that a panicking clone leaves it intact
+        let layout = self.element.layout();
+        let temp = std::alloc::alloc(layout);
+        self.element.clone(src, temp);
+
+        let dst = self.ptr.as_ptr().add(index * self.element.size);
+        if let Some(drop_fn) = self.element.drop_fn {
+            drop_fn(dst);
+        }
+        std::ptr::copy_nonoverlapping(temp, dst, self.element.size);
+        std::alloc::dealloc(temp, layout);
+    }
+
     /// Gets a shared reference to the slice of tags.
     ///
     /// # Safety
@@ -1438,73 +3307,286 @@ impl TagStorage {
     ///
     /// Access to the tag data within the slice is runtime borrow checked.
     /// This call will panic if borrowing rules are broken.
-    pub unsafe fn data_slice<T>(&self) -> &[T] {
-        debug_assert!(
-            size_of::<T>() == self.element.size,
-            "incompatible element data size"
-        );
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` is not the type of the stored tags.
+    pub unsafe fn data_slice<T: 'static>(&self) -> &[T] {
+        self.element.assert_type::<T>();
         std::slice::from_raw_parts(self.ptr.as_ptr() as *const T, self.len)
     }
 
-    fn grow(&mut self) {
-        assert!(self.element.size != 0, "capacity overflow");
+    // Reallocates the tag memory to hold exactly `capacity` tags.
+    fn resize(&mut self, capacity: usize) -> Result<(), TagReserveError> {
+        if self.element.size == 0 {
+            return Err(TagReserveError::CapacityOverflow);
+        }
+
+        let size = capacity
+            .checked_mul(self.element.size)
+            .ok_or(TagReserveError::CapacityOverflow)?;
+        let layout = std::alloc::Layout::from_size_align(size, self.element.align)
+            .map_err(|_| TagReserveError::CapacityOverflow)?;
         unsafe {
-            let (new_cap, ptr) = {
-                let layout = std::alloc::Layout::from_size_align(
-                    self.capacity * self.element.size,
+            let current = std::alloc::Layout::from_size_align_unchecked(
+                self.capacity * self.element.size,
+                self.element.align,
+            );
+            let ptr = std::alloc::realloc(self.ptr.as_ptr(), current, layout.size());
+            if ptr.is_null() {
+                return Err(TagReserveError::AllocError {
+                    size: layout.size(),
+                    align: layout.align(),
+                });
+            }
+
+            #[cfg(feature = "alloc-tracking")]
+            {
+                TAG_ALLOCATIONS.free(current.size());
+                TAG_ALLOCATIONS.alloc(layout.size());
+            }
+
+            self.ptr = NonNull::new_unchecked(ptr);
+            self.capacity = capacity;
+        }
+
+        Ok(())
+    }
+
+    fn handle_reserve_error(&self, err: TagReserveError) -> ! {
+        match err {
+            TagReserveError::CapacityOverflow => panic!("capacity overflow"),
+            TagReserveError::AllocError { size, align } => {
+                tracing::error!("out of memory");
+                std::alloc::handle_alloc_error(unsafe {
+                    std::alloc::Layout::from_size_align_unchecked(size, align)
+                })
+            }
+        }
+    }
+}
+
+impl Clone for TagStorage {
+    fn clone(&self) -> Self {
+        let mut clone = TagStorage::new(self.element);
+        clone.reserve(self.len);
+
+        let size = self.element.size;
+        for i in 0..self.len {
+            unsafe {
+                self.element.clone(
+                    self.ptr.as_ptr().add(i * size),
+                    clone.ptr.as_ptr().add(i * size),
+                );
+            }
+        }
+        clone.len = self.len;
+        clone
+    }
+}
+
+/// A typed view of the values in a `TagStorage`. Created by `TagStorage::downcast`.
+pub struct TypedTagStorage<'a, T: Tag> {
+    tags: &'a [T],
+}
+
+impl<'a, T: Tag> TypedTagStorage<'a, T> {
+    /// Gets the tag values as a slice, indexed by chunkset.
+    pub fn as_slice(&self) -> &'a [T] { self.tags }
+}
+
+impl<'a, T: Tag> Deref for TypedTagStorage<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target { self.tags }
+}
+
+/// A mutable typed view of the values in a `TagStorage`. Created by `TagStorage::downcast_mut`.
+pub struct TypedTagStorageMut<'a, T: Tag> {
+    storage: &'a mut TagStorage,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T: Tag> TypedTagStorageMut<'a, T> {
+    /// Pushes a new tag onto the end of the vector.
+    pub fn push(&mut self, value: T) {
+        // safe because the storage's element type was verified to be `T`
+        unsafe { self.storage.push(value) }
+    }
+}
+
+impl<'a, T: Tag> Deref for TypedTagStorageMut<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target { unsafe { self.storage.data_slice::<T>() } }
+}
+
+unsafe impl Sync for TagStorage {}
+
+unsafe impl Send for TagStorage {}
+
+impl Drop for TagStorage {
+    fn drop(&mut self) {
+        if self.element.size > 0 {
+            let ptr = self.ptr.as_ptr();
+
+            unsafe {
+                if let Some(drop_fn) = self.element.drop_fn {
+                    for i in 0..self.len {
+                        drop_fn(ptr.add(i * self.element.size));
+                    }
+                }
+                let layout = std::alloc::Layout::from_size_align_unchecked(
+                    self.element.size * self.capacity,
                     self.element.align,
-                )
-                .unwrap();
-                let new_cap = 2 * self.capacity;
-                let ptr =
-                    std::alloc::realloc(self.ptr.as_ptr(), layout, new_cap * self.element.size);
+                );
+                std::alloc::dealloc(ptr, layout);
+                #[cfg(feature = "alloc-tracking")]
+                TAG_ALLOCATIONS.free(layout.size());
+            }
+        }
+    }
+}
+
+impl Debug for TagStorage {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "TagStorage {{ element_size: {}, count: {}, capacity: {} }}",
+            self.element.size, self.len, self.capacity
+        )
+    }
+}
+
+/// Stores `NonSyncComponent`s, bound to the thread on which the storage was created.
+pub(crate) struct NonSyncStorage {
+    owner: ThreadId,
+    components: HashMap<TypeId, HashMap<Entity, RefCell<Box<dyn Any>>>>,
+}
+
+impl NonSyncStorage {
+    pub fn new() -> Self {
+        Self {
+            owner: std::thread::current().id(),
+            components: HashMap::new(),
+        }
+    }
+
+    /// Exchanges the components stored in two storages, leaving each bound to its own thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either storage is non-empty and either is owned by another thread.
+    pub fn swap_components(&mut self, other: &mut NonSyncStorage) {
+        if self.is_empty() && other.is_empty() {
+            return;
+        }
+
+        self.assert_owner_thread();
+        other.assert_owner_thread();
+        std::mem::swap(&mut self.components, &mut other.components);
+    }
+
+    /// Determines if the calling thread is the thread which owns this storage.
+    pub fn is_owner_thread(&self) -> bool { std::thread::current().id() == self.owner }
+
+    fn assert_owner_thread(&self) {
+        assert!(
+            self.is_owner_thread(),
+            "non-sync components can only be accessed on the thread which created the world"
+        );
+    }
 
-                (new_cap, ptr)
-            };
+    pub fn is_empty(&self) -> bool { self.components.values().all(|c| c.is_empty()) }
 
-            if ptr.is_null() {
-                tracing::error!("out of memory");
-                std::process::abort()
-            }
+    pub fn insert<T: NonSyncComponent>(&mut self, entity: Entity, component: T) {
+        self.assert_owner_thread();
+        self.components
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .insert(entity, RefCell::new(Box::new(component)));
+    }
 
-            self.ptr = NonNull::new_unchecked(ptr);
-            self.capacity = new_cap;
-        }
+    pub fn remove<T: NonSyncComponent>(&mut self, entity: Entity) -> Option<T> {
+        self.assert_owner_thread();
+        self.components
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|c| c.remove(&entity))
+            .map(|c| *c.into_inner().downcast::<T>().unwrap())
     }
-}
 
-unsafe impl Sync for TagStorage {}
+    pub fn get<T: NonSyncComponent>(&self, entity: Entity) -> Option<std::cell::Ref<'_, T>> {
+        self.assert_owner_thread();
+        self.components
+            .get(&TypeId::of::<T>())
+            .and_then(|c| c.get(&entity))
+            .map(|c| std::cell::Ref::map(c.borrow(), |c| c.downcast_ref::<T>().unwrap()))
+    }
 
-unsafe impl Send for TagStorage {}
+    pub fn get_mut<T: NonSyncComponent>(&self, entity: Entity) -> Option<std::cell::RefMut<'_, T>> {
+        self.assert_owner_thread();
+        self.components
+            .get(&TypeId::of::<T>())
+            .and_then(|c| c.get(&entity))
+            .map(|c| {
+                std::cell::RefMut::map(c.borrow_mut(), |c| c.downcast_mut::<T>().unwrap())
+            })
+    }
 
-impl Drop for TagStorage {
-    fn drop(&mut self) {
-        if self.element.size > 0 {
-            let ptr = self.ptr.as_ptr();
+    /// Removes all non-sync components attached to the given entity.
+    pub fn remove_entity(&mut self, entity: Entity) {
+        if self.components.values().any(|c| c.contains_key(&entity)) {
+            self.assert_owner_thread();
+            for components in self.components.values_mut() {
+                components.remove(&entity);
+            }
+        }
+    }
 
-            unsafe {
-                if let Some(drop_fn) = self.element.drop_fn {
-                    for i in 0..self.len {
-                        drop_fn(ptr.add(i * self.element.size));
-                    }
+    /// Moves all non-sync components attached to `entity` into `target`, attaching them
+    /// to `new_entity`.
+    pub fn move_entity(&mut self, entity: Entity, target: &mut NonSyncStorage, new_entity: Entity) {
+        if self.components.values().any(|c| c.contains_key(&entity)) {
+            self.assert_owner_thread();
+            target.assert_owner_thread();
+            for (type_id, components) in self.components.iter_mut() {
+                if let Some(component) = components.remove(&entity) {
+                    target
+                        .components
+                        .entry(*type_id)
+                        .or_default()
+                        .insert(new_entity, component);
                 }
-                let layout = std::alloc::Layout::from_size_align_unchecked(
-                    self.element.size * self.capacity,
-                    self.element.align,
-                );
-                std::alloc::dealloc(ptr, layout);
             }
         }
     }
+
+    /// Moves all components out of `other` and into this storage.
+    pub fn merge(&mut self, mut other: NonSyncStorage) {
+        if other.is_empty() {
+            return;
+        }
+
+        self.assert_owner_thread();
+        other.assert_owner_thread();
+        for (type_id, components) in other.components.drain() {
+            self.components
+                .entry(type_id)
+                .or_default()
+                .extend(components);
+        }
+    }
 }
 
-impl Debug for TagStorage {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        write!(
-            f,
-            "TagStorage {{ element_size: {}, count: {}, capacity: {} }}",
-            self.element.size, self.len, self.capacity
-        )
+impl Drop for NonSyncStorage {
+    fn drop(&mut self) {
+        if !self.is_owner_thread() && !self.is_empty() {
+            // the components cannot be safely dropped on this thread, so they are leaked instead
+            tracing::warn!("Leaking non-sync components dropped outside of their owning thread");
+            for (_, components) in self.components.drain() {
+                std::mem::forget(components);
+            }
+        }
     }
 }
 
@@ -1528,7 +3610,7 @@ mod test {
 
         let (_arch_id, data) = archetypes.alloc_archetype(desc);
         let set = data.alloc_chunk_set(|tags| unsafe {
-            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1isize)
+            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1usize)
         });
 
         let chunk_index = data.get_free_chunk(set);
@@ -1547,7 +3629,7 @@ mod test {
                 .get_mut(ComponentTypeId::of::<isize>())
                 .unwrap()
                 .writer()
-                .push(&[1usize]);
+                .push(&[1isize]);
         }
     }
 
@@ -1563,7 +3645,7 @@ mod test {
 
         let (_arch_id, data) = archetypes.alloc_archetype(desc);
         let set = data.alloc_chunk_set(|tags| unsafe {
-            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1isize)
+            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1usize)
         });
 
         let chunk_index = data.get_free_chunk(set);
@@ -1593,7 +3675,7 @@ mod test {
 
         let (_arch_id, data) = archetypes.alloc_archetype(desc);
         let set = data.alloc_chunk_set(|tags| unsafe {
-            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1isize)
+            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1usize)
         });
 
         let chunk_index = data.get_free_chunk(set);
@@ -1616,7 +3698,7 @@ mod test {
                     .get_mut(ComponentTypeId::of::<isize>())
                     .unwrap()
                     .writer()
-                    .push(&[1usize]);
+                    .push(&[1isize]);
             }
         }
 
@@ -1627,6 +3709,45 @@ mod test {
         assert!(!chunk.is_allocated());
     }
 
+    #[test]
+    pub fn entities_grow_incrementally() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_component::<isize>();
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+        let set = data.alloc_chunk_set(|_| {});
+        let chunk_index = data.get_free_chunk(set);
+        let chunk = &mut data.chunksets_mut()[set][chunk_index];
+        assert!(chunk.capacity() > 8);
+
+        {
+            let mut writer = chunk.writer();
+            let (chunk_entities, chunk_components) = writer.get();
+            for i in 0..8 {
+                chunk_entities.push(Entity::new(i, Wrapping(0)));
+                unsafe {
+                    (&mut *chunk_components.get())
+                        .get_mut(ComponentTypeId::of::<isize>())
+                        .unwrap()
+                        .writer()
+                        .push(&[i as isize]);
+                }
+            }
+        }
+        assert!(chunk.entities.capacity() < chunk.capacity());
+
+        for _ in 0..6 {
+            chunk.swap_remove(0, true);
+        }
+        chunk.shrink();
+        assert_eq!(2, chunk.entities.capacity());
+        assert_eq!(2, chunk.entities().len());
+    }
+
     #[test]
     pub fn read_components() {
         let _ = tracing_subscriber::fmt::try_init();
@@ -1754,6 +3875,232 @@ mod test {
         }
     }
 
+    #[test]
+    pub fn typed_tags() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_tag::<isize>();
+        desc.register_tag::<ZeroSize>();
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+
+        for t in 0..3isize {
+            data.alloc_chunk_set(|tags| {
+                let mut typed = tags
+                    .get_mut(TagTypeId::of::<isize>())
+                    .unwrap()
+                    .downcast_mut::<isize>()
+                    .unwrap();
+                typed.push(t);
+                assert_eq!(Some(&t), typed.last());
+
+                tags.get_mut(TagTypeId::of::<ZeroSize>())
+                    .unwrap()
+                    .downcast_mut::<ZeroSize>()
+                    .unwrap()
+                    .push(ZeroSize);
+            });
+        }
+
+        let tags = data.tags().get(TagTypeId::of::<isize>()).unwrap();
+        assert!(tags.downcast::<usize>().is_none());
+        assert!(tags.downcast::<ZeroSize>().is_none());
+        let typed = tags.downcast::<isize>().unwrap();
+        assert_eq!(&[0isize, 1, 2], typed.as_slice());
+        assert_eq!(Some(&1), typed.get(1));
+        assert_eq!(3, typed.iter().count());
+
+        let tags = data.tags().get(TagTypeId::of::<ZeroSize>()).unwrap();
+        assert_eq!(3, tags.downcast::<ZeroSize>().unwrap().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "tag type mismatch")]
+    pub fn read_tags_wrong_type() {
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_tag::<isize>();
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+        data.alloc_chunk_set(|tags| unsafe {
+            tags.get_mut(TagTypeId::of::<isize>()).unwrap().push(1isize)
+        });
+
+        let tags = data.tags().get(TagTypeId::of::<isize>()).unwrap();
+        let _ = unsafe { tags.data_slice::<usize>() };
+    }
+
+    #[test]
+    #[should_panic(expected = "is already registered")]
+    pub fn register_component_twice() {
+        let mut desc = ArchetypeDescription::default();
+        desc.register_component::<isize>();
+        desc.register_component_raw(ComponentTypeId::of::<isize>(), ComponentMeta::of::<isize>());
+    }
+
+    #[test]
+    #[should_panic(expected = "component type mismatch")]
+    pub fn push_components_wrong_type() {
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_component::<isize>();
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+        let set = data.alloc_chunk_set(|_| {});
+        let chunk_index = data.get_free_chunk(set);
+        let components = data
+            .chunksets_mut()
+            .get_mut(set)
+            .unwrap()
+            .get_mut(chunk_index)
+            .unwrap();
+
+        let mut writer = components.writer();
+        let (_, chunk_components) = writer.get();
+        unsafe {
+            (&mut *chunk_components.get())
+                .get_mut(ComponentTypeId::of::<isize>())
+                .unwrap()
+                .writer()
+                .push(&[1usize]);
+        }
+    }
+
+    #[test]
+    pub fn type_id_names() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        assert_eq!(
+            ComponentTypeId::of::<usize>(),
+            ComponentTypeId::of::<usize>()
+        );
+        assert_ne!(
+            ComponentTypeId::of::<usize>(),
+            ComponentTypeId::of::<isize>()
+        );
+
+        #[cfg(feature = "type-names")]
+        {
+            assert_eq!(Some("usize"), ComponentTypeId::of::<usize>().name());
+            assert_eq!(
+                "TagTypeId(usize)",
+                format!("{:?}", TagTypeId::of::<usize>())
+            );
+
+            let mut desc = ArchetypeDescription::default();
+            desc.register_component_raw(ComponentTypeId::of::<f32>(), ComponentMeta::of::<f32>());
+            assert_eq!(&["f32"], desc.component_names());
+        }
+        #[cfg(not(feature = "type-names"))]
+        assert_eq!(None, ComponentTypeId::of::<usize>().name());
+    }
+
+    #[test]
+    pub fn type_sets() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let usize_id = ComponentTypeId::of::<usize>();
+        let isize_id = ComponentTypeId::of::<isize>();
+        let f32_id = ComponentTypeId::of::<f32>();
+
+        let mut types = ComponentTypes::default();
+        types.0.push(vec![usize_id]);
+        types.0.push(vec![isize_id, usize_id]);
+        types.0.push(vec![f32_id]);
+
+        let contains_usize = types
+            .iter()
+            .map(|set| set.contains(&usize_id))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![true, true, false], contains_usize);
+
+        let exact = types.iter_exact(&[usize_id, isize_id]).collect::<Vec<_>>();
+        assert_eq!(vec![false, true, false], exact);
+
+        let missing = types
+            .iter_exact(&[ComponentTypeId::of::<u8>()])
+            .collect::<Vec<_>>();
+        assert_eq!(vec![false, false, false], missing);
+
+        let third = types.iter().nth(2).unwrap();
+        assert_eq!(&[f32_id], third.types());
+        assert!(third.is(&types.0.bits_of(&[f32_id]).unwrap()));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "resource already borrowed as mutable: ComponentTypeId")]
+    pub fn borrow_conflict_names_component() {
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_component::<isize>();
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+        let set = data.alloc_chunk_set(|_| {});
+        let chunk_index = data.get_free_chunk(set);
+        let chunk = data
+            .chunksets_mut()
+            .get_mut(set)
+            .unwrap()
+            .get_mut(chunk_index)
+            .unwrap();
+
+        let components = chunk.components(ComponentTypeId::of::<isize>()).unwrap();
+        let _write = components.data_raw_mut();
+        let _read = components.data_raw();
+    }
+
+    #[test]
+    pub fn iter_chunkset_tags() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_tag::<isize>();
+        desc.register_component::<usize>();
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+
+        for t in 0..3isize {
+            let set = data.alloc_chunk_set(|tags| {
+                unsafe { tags.get_mut(TagTypeId::of::<isize>()).unwrap().push(t) };
+            });
+            let chunk_index = data.get_free_chunk(set);
+            let chunk = data
+                .chunksets_mut()
+                .get_mut(set)
+                .unwrap()
+                .get_mut(chunk_index)
+                .unwrap();
+            let mut writer = chunk.writer();
+            let (chunk_entities, chunk_components) = writer.get();
+            chunk_entities.push(Entity::new(t as u32, Wrapping(0)));
+            unsafe {
+                (&mut *chunk_components.get())
+                    .get_mut(ComponentTypeId::of::<usize>())
+                    .unwrap()
+                    .writer()
+                    .push(&[t as usize]);
+            }
+        }
+
+        assert_eq!(3, data.iter_chunksets().len());
+        for (i, tags, chunks) in data.iter_chunksets() {
+            assert_eq!(i, tags.index());
+            assert_eq!(Some(&(i as isize)), tags.get::<isize>());
+            assert_eq!(None, tags.get::<f32>());
+            assert_eq!(1, chunks.len());
+            assert_eq!(1, chunks[0].len());
+        }
+    }
+
     #[test]
     pub fn create_zero_size_tags() {
         let _ = tracing_subscriber::fmt::try_init();
@@ -1787,10 +4134,45 @@ mod test {
                 .get_mut(ComponentTypeId::of::<isize>())
                 .unwrap()
                 .writer()
-                .push(&[1usize]);
+                .push(&[1isize]);
         }
     }
 
+    #[test]
+    pub fn reserve_and_shrink_tags() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut archetypes = Storage::new(WorldId::default());
+
+        let mut desc = ArchetypeDescription::default();
+        desc.register_tag::<usize>();
+        desc.register_component::<isize>();
+        desc.set_tag_capacity(16);
+
+        let (_arch_id, data) = archetypes.alloc_archetype(desc);
+        for i in 0..3usize {
+            data.alloc_chunk_set(|tags| unsafe {
+                tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(i)
+            });
+        }
+
+        let tags = data.tags_mut().get_mut(TagTypeId::of::<usize>()).unwrap();
+        assert_eq!(16, tags.capacity());
+
+        tags.shrink_to_fit();
+        assert_eq!(3, tags.capacity());
+        assert_eq!(&[0, 1, 2], unsafe { tags.data_slice::<usize>() });
+
+        tags.reserve(10);
+        assert!(tags.capacity() >= 13);
+        assert_eq!(&[0, 1, 2], unsafe { tags.data_slice::<usize>() });
+
+        assert_eq!(
+            Err(TagReserveError::CapacityOverflow),
+            tags.try_reserve(std::usize::MAX)
+        );
+    }
+
     #[test]
     pub fn create_zero_size_components() {
         let _ = tracing_subscriber::fmt::try_init();
@@ -1803,7 +4185,7 @@ mod test {
 
         let (_arch_id, data) = archetypes.alloc_archetype(desc);
         let set = data.alloc_chunk_set(|tags| unsafe {
-            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1isize);
+            tags.get_mut(TagTypeId::of::<usize>()).unwrap().push(1usize);
         });
 
         let chunk_index = data.get_free_chunk(set);
@@ -1825,4 +4207,78 @@ mod test {
                 .push(&[ZeroSize]);
         }
     }
+
+    #[test]
+    pub fn share_copy_on_write() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut registry = Registry::new();
+        registry.register_pod::<isize>();
+        registry.register_clone::<String>();
+
+        let mut archetypes = Storage::new(WorldId::default());
+        let mut desc = ArchetypeDescription::default();
+        desc.register_component::<isize>();
+        desc.register_component::<String>();
+
+        let (_, data) = archetypes.alloc_archetype(desc);
+        let set = data.alloc_chunk_set(|_| {});
+        let chunk_index = data.get_free_chunk(set);
+        let chunk = &mut data.chunksets_mut()[set][chunk_index];
+        let mut writer = chunk.writer();
+        let (entities, components) = writer.get();
+        for i in 0..3 {
+            entities.push(Entity::new(i, Wrapping(0)));
+            unsafe {
+                let components = &mut *components.get();
+                components
+                    .get_mut(ComponentTypeId::of::<isize>())
+                    .unwrap()
+                    .writer()
+                    .push(&[i as isize]);
+                let name = std::mem::ManuallyDrop::new(format!("{}", i));
+                components
+                    .get_mut(ComponentTypeId::of::<String>())
+                    .unwrap()
+                    .writer()
+                    .push(std::slice::from_ref(&*name));
+            }
+        }
+        drop(writer);
+
+        let shared = archetypes.share(&registry).unwrap();
+        let original = &archetypes.archetypes()[0].chunksets()[set][chunk_index];
+        let copy = &shared.archetypes()[0].chunksets()[set][chunk_index];
+
+        let ints = ComponentTypeId::of::<isize>();
+        let names = ComponentTypeId::of::<String>();
+        assert!(original.components(ints).unwrap().is_shared());
+        assert!(copy.components(ints).unwrap().is_shared());
+        assert!(!original.components(names).unwrap().is_shared());
+        assert!(!copy.components(names).unwrap().is_shared());
+
+        unsafe {
+            original.components(ints).unwrap().data_slice_mut::<isize>()[1] = 10;
+            copy.components(names).unwrap().data_slice_mut::<String>()[1].push('!');
+
+            let original_ints = original.components(ints).unwrap();
+            assert!(!original_ints.is_shared());
+            assert_eq!(&[0, 10, 2], &*original_ints.data_slice::<isize>());
+            let copy_ints = copy.components(ints).unwrap();
+            assert_eq!(&[0, 1, 2], &*copy_ints.data_slice::<isize>());
+            let original_names = original.components(names).unwrap();
+            assert_eq!("1", original_names.data_slice::<String>()[1]);
+            let copy_names = copy.components(names).unwrap();
+            assert_eq!("1!", copy_names.data_slice::<String>()[1]);
+        }
+
+        let mut unregistered = Storage::new(WorldId::default());
+        let mut desc = ArchetypeDescription::default();
+        desc.register_component::<usize>();
+        unregistered.alloc_archetype(desc);
+        assert_eq!(
+            ComponentTypeId::of::<usize>(),
+            unregistered.share(&registry).err().unwrap()
+        );
+    }
 }
@@ -0,0 +1,29 @@
+//! Locks used by legion's internal machinery.
+//!
+//! With the `single-thread` feature enabled, locks are plain `RefCell`s. This makes the types
+//! which contain them, such as `Universe` and `World`, neither `Send` nor `Sync`, so that a
+//! non-atomic lock can never be reached from more than one thread.
+
+#[cfg(not(feature = "single-thread"))]
+pub(crate) use parking_lot::Mutex;
+
+#[cfg(feature = "single-thread")]
+pub(crate) use self::cell::Mutex;
+
+#[cfg(feature = "single-thread")]
+mod cell {
+    use std::cell::RefCell;
+    use std::cell::RefMut;
+
+    /// A non-atomic lock with the same interface as `parking_lot::Mutex`.
+    ///
+    /// Locking a value which is already locked panics rather than deadlocking.
+    #[derive(Debug, Default)]
+    pub(crate) struct Mutex<T>(RefCell<T>);
+
+    impl<T> Mutex<T> {
+        pub(crate) fn new(value: T) -> Self { Mutex(RefCell::new(value)) }
+
+        pub(crate) fn lock(&self) -> RefMut<'_, T> { self.0.borrow_mut() }
+    }
+}
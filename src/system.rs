@@ -9,7 +9,7 @@ use crate::resource::{Resource, ResourceSet, ResourceTypeId};
 use crate::schedule::ArchetypeAccess;
 use crate::schedule::{Runnable, Schedulable};
 use crate::storage::Tag;
-use crate::storage::{Component, ComponentTypeId, TagTypeId};
+use crate::storage::{Component, ComponentTypeId, NonSyncComponent, TagTypeId};
 use crate::world::World;
 use bit_set::BitSet;
 use derivative::Derivative;
@@ -28,12 +28,18 @@ use crate::iterator::FissileIterator;
 #[cfg(feature = "par-iter")]
 use crate::query::Chunk;
 
+#[cfg(feature = "async")]
+use crate::async_system::{AsyncSystem, AsyncWorld};
+
+#[cfg(feature = "async")]
+use std::future::Future;
+
 /// Structure used by `SystemAccess` for describing access to the provided `T`
 #[derive(Derivative, Debug, Clone)]
 #[derivative(Default(bound = ""))]
 pub struct Access<T> {
-    reads: Vec<T>,
-    writes: Vec<T>,
+    pub(crate) reads: Vec<T>,
+    pub(crate) writes: Vec<T>,
 }
 
 /// Structure describing the resource and component access conditions of the system.
@@ -572,7 +578,7 @@ pub struct PreparedWorld {
     archetypes: Option<*const BitSet>,
 }
 impl PreparedWorld {
-    unsafe fn new(
+    pub(crate) unsafe fn new(
         world: &World,
         access: &Access<ComponentTypeId>,
         archetypes: &ArchetypeAccess,
@@ -695,9 +701,53 @@ impl PreparedWorld {
         unsafe { (*self.world).get_tag(entity) }
     }
 
+    /// Borrows non-sync component data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the system is not running on the world's owning thread.
+    /// Non-sync components should only be accessed from systems built via `build_thread_local`.
+    #[inline]
+    pub fn get_non_sync_component<T: NonSyncComponent>(
+        &self,
+        entity: Entity,
+    ) -> Option<std::cell::Ref<'_, T>> {
+        unsafe { (*self.world).get_non_sync_component(entity) }
+    }
+
+    /// Mutably borrows non-sync component data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the system is not running on the world's owning thread.
+    /// Non-sync components should only be accessed from systems built via `build_thread_local`.
+    #[inline]
+    pub fn get_non_sync_component_mut<T: NonSyncComponent>(
+        &mut self,
+        entity: Entity,
+    ) -> Option<std::cell::RefMut<'_, T>> {
+        unsafe { (*self.world).get_non_sync_component_mut_unchecked(entity) }
+    }
+
     /// Determines if the given `Entity` is alive within this `World`.
     #[inline]
     pub fn is_alive(&self, entity: Entity) -> bool { unsafe { (*self.world).is_alive(entity) } }
+
+    /// Gets the underlying world, bypassing access validation.
+    pub(crate) fn world(&self) -> &World { unsafe { &*self.world } }
+
+    /// Gets the entities from which a component of type `T` has been removed. See
+    /// `World::removed`.
+    #[inline]
+    pub fn removed<T: Component>(&self) -> impl Iterator<Item = Entity> + '_ {
+        unsafe { (*self.world).removed::<T>() }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -709,6 +759,9 @@ pub struct SystemId {
 struct Unspecified;
 
 impl SystemId {
+    /// Gets the name of the system.
+    pub fn name(&self) -> &str { &self.name }
+
     pub fn of<T: 'static>(name: Option<String>) -> Self {
         Self {
             name: name
@@ -797,6 +850,7 @@ where
     fn run(&self, world: &World) {
         let span = span!(Level::INFO, "System", system = %self.name);
         let _guard = span.enter();
+        profile_scope!(self.name.name());
 
         debug!("Initializing");
         let mut resources = R::fetch(&world.resources);
@@ -809,6 +863,9 @@ where
         // This should usually just pull a free block, or allocate a new one...
         // TODO: The BlockAllocator should *ensure* keeping at least 1 free block so this prevents an allocation
 
+        #[cfg(feature = "access-audit")]
+        let audit_scope = crate::audit::scope(self.name.to_string());
+
         info!("Running");
         use std::ops::DerefMut;
         let mut borrow = self.run_fn.get_mut();
@@ -819,6 +876,16 @@ where
             &mut resources,
             &mut prepared_queries,
         );
+
+        #[cfg(feature = "access-audit")]
+        {
+            drop(audit_scope);
+            crate::audit::validate(
+                &self.name.to_string(),
+                &self.access.components.reads,
+                &self.access.components.writes,
+            );
+        }
     }
 
     fn dispose(self: Box<Self>, world: &mut World) {
@@ -1197,6 +1264,32 @@ where
         self.build_system_disposable(SystemDisposableFnMut(run_fn, Default::default()))
     }
 
+    /// Builds a system whose body is a future. The closure is called to create a new future
+    /// whenever the previous one has completed, and the future is polled once each time the
+    /// system runs. See the `async_system` module for details.
+    ///
+    /// The future may only access the world and resources through the provided `AsyncWorld`
+    /// handle. Queries added to the builder are not available to async systems; declare
+    /// component access with `read_component` and `write_component` instead.
+    #[cfg(feature = "async")]
+    pub fn build_async<F, Fut>(self, run_fn: F) -> Box<dyn Schedulable>
+    where
+        <R as ConsFlatten>::Output: ResourceSet + Send + Sync,
+        F: FnMut(AsyncWorld<<R as ConsFlatten>::Output>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Box::new(AsyncSystem::new(
+            self.name,
+            self.resources.flatten(),
+            SystemAccess {
+                resources: self.resource_access,
+                components: self.component_access,
+                tags: Access::default(),
+            },
+            run_fn,
+        ))
+    }
+
     /// Builds a system which is not `Schedulable`, as it is not thread safe (!Send and !Sync),
     /// but still implements all the calling infrastructure of the `Runnable` trait. This provides
     /// a way for legion consumers to leverage the `System` construction and type-handling of
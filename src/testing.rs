@@ -0,0 +1,490 @@
+//! Utilities for stress testing worlds.
+//!
+//! This module is only available with the `testing` feature enabled. A `Fuzzer` applies a random
+//! sequence of structural operations to a world, such as inserting and deleting entities, adding
+//! and removing components and tags, defragmenting and merging worlds. After each operation, the
+//! contents of the world are compared against a simple model of what they should be.
+//!
+//! The fuzzer is generic over a user component type, so that downstream crates can check that
+//! their components (for example, those with custom `Drop` implementations or interior pointers)
+//! survive being moved around by legion's storage.
+//!
+//! ```
+//! # use legion::testing::Fuzzer;
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct Health(Vec<u32>);
+//!
+//! let mut fuzzer = Fuzzer::new(42, |rng| Health(vec![rng.below(100) as u32; 3]));
+//! fuzzer.run(500).unwrap();
+//! ```
+use crate::entity::Entity;
+use crate::query::IntoQuery;
+use crate::query::Read;
+use crate::storage::Component;
+use crate::world::Universe;
+use crate::world::World;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// A small, deterministic pseudo-random number generator.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator from a seed. Equal seeds produce equal sequences.
+    pub fn new(seed: u64) -> Self { Rng(seed ^ 0x9E37_79B9_7F4A_7C15) }
+
+    /// Generates the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        // splitmix64
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generates a random number less than `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize { (self.next_u64() % n as u64) as usize }
+}
+
+/// A component which the fuzzer adds and removes to move entities between archetypes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FuzzMarker(pub u64);
+
+/// A tag which the fuzzer changes to move entities between chunk sets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FuzzTag(pub u8);
+
+/// An operation applied to a world by a `Fuzzer`.
+///
+/// Entities are identified by their position in the fuzzer's list of live entities.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation<C> {
+    /// Inserts entities with the given tag value, and with the user component if present.
+    Insert { tag: u8, components: Vec<Option<C>> },
+    /// Deletes an entity.
+    Delete(usize),
+    /// Adds or replaces the user component of an entity.
+    AddComponent(usize, C),
+    /// Removes the user component from an entity.
+    RemoveComponent(usize),
+    /// Overwrites the user component of an entity in place, if it has one.
+    WriteComponent(usize, C),
+    /// Adds a `FuzzMarker` to an entity.
+    AddMarker(usize),
+    /// Removes the `FuzzMarker` from an entity.
+    RemoveMarker(usize),
+    /// Sets the `FuzzTag` of an entity.
+    SetTag(usize, u8),
+    /// Removes the `FuzzTag` from an entity.
+    RemoveTag(usize),
+    /// Defragments the world.
+    Defrag,
+    /// Inserts entities into a second world, and then merges it into the world.
+    Merge { tag: u8, components: Vec<Option<C>> },
+}
+
+/// The expected state of an entity.
+#[derive(Debug, Clone, PartialEq)]
+struct Expected<C> {
+    component: Option<C>,
+    marker: Option<FuzzMarker>,
+    tag: Option<FuzzTag>,
+}
+
+/// A mismatch between a world and the fuzzer's model of it.
+#[derive(Debug, Clone)]
+pub struct FuzzFailure<C> {
+    /// The seed the fuzzer was created with.
+    pub seed: u64,
+    /// The operations applied, ending with the operation after which the mismatch was found.
+    pub operations: Vec<Operation<C>>,
+    /// A description of the mismatch.
+    pub message: String,
+}
+
+impl<C: Debug> Display for FuzzFailure<C> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "fuzzing with seed {} failed after {} operations: {}",
+            self.seed,
+            self.operations.len(),
+            self.message
+        )?;
+        if let Some(last) = self.operations.last() {
+            writeln!(f, "last operation: {:?}", last)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Debug> std::error::Error for FuzzFailure<C> {}
+
+/// Applies random operations to a world, checking its contents against a model after each one.
+pub struct Fuzzer<C, G> {
+    seed: u64,
+    rng: Rng,
+    generate: G,
+    universe: Universe,
+    world: World,
+    entities: Vec<Entity>,
+    expected: HashMap<Entity, Expected<C>>,
+    operations: Vec<Operation<C>>,
+    next_marker: u64,
+}
+
+impl<C, G> Fuzzer<C, G>
+where
+    C: Component + Clone + PartialEq + Debug,
+    G: FnMut(&mut Rng) -> C,
+{
+    /// Creates a fuzzer which generates values of the user component with `generate`.
+    ///
+    /// Fuzzers created with the same seed and an equivalent generator apply the same operations.
+    pub fn new(seed: u64, generate: G) -> Self {
+        let universe = Universe::new();
+        let world = universe.create_world();
+        Fuzzer {
+            seed,
+            rng: Rng::new(seed),
+            generate,
+            universe,
+            world,
+            entities: Vec::new(),
+            expected: HashMap::new(),
+            operations: Vec::new(),
+            next_marker: 0,
+        }
+    }
+
+    /// Gets the world being fuzzed.
+    pub fn world(&self) -> &World { &self.world }
+
+    /// Gets the operations applied so far.
+    pub fn operations(&self) -> &[Operation<C>] { &self.operations }
+
+    /// Applies `steps` random operations, checking the world after each one.
+    ///
+    /// Returns the first mismatch found between the world and the model.
+    pub fn run(&mut self, steps: usize) -> Result<(), FuzzFailure<C>> {
+        for _ in 0..steps {
+            let operation = self.generate_operation();
+            self.apply(operation)?;
+        }
+        Ok(())
+    }
+
+    /// Applies a single operation, checking the world afterwards.
+    ///
+    /// This allows a failing sequence from `FuzzFailure::operations` to be replayed in a new
+    /// fuzzer. Operations which refer to entities beyond the number of live entities are ignored.
+    pub fn apply(&mut self, operation: Operation<C>) -> Result<(), FuzzFailure<C>> {
+        self.operations.push(operation.clone());
+        self.execute(operation);
+        self.check().map_err(|message| FuzzFailure {
+            seed: self.seed,
+            operations: self.operations.clone(),
+            message,
+        })
+    }
+
+    fn generate_components(&mut self) -> Vec<Option<C>> {
+        let count = 1 + self.rng.below(8);
+        (0..count)
+            .map(|_| {
+                if self.rng.below(4) == 0 {
+                    None
+                } else {
+                    Some((self.generate)(&mut self.rng))
+                }
+            })
+            .collect()
+    }
+
+    fn generate_operation(&mut self) -> Operation<C> {
+        let tag = self.rng.below(4) as u8;
+        if self.entities.is_empty() {
+            let components = self.generate_components();
+            return Operation::Insert { tag, components };
+        }
+
+        let entity = self.rng.below(self.entities.len());
+        match self.rng.below(12) {
+            0 | 1 => Operation::Insert {
+                tag,
+                components: self.generate_components(),
+            },
+            2 => Operation::Delete(entity),
+            3 => Operation::AddComponent(entity, (self.generate)(&mut self.rng)),
+            4 => Operation::RemoveComponent(entity),
+            5 => Operation::WriteComponent(entity, (self.generate)(&mut self.rng)),
+            6 => Operation::AddMarker(entity),
+            7 => Operation::RemoveMarker(entity),
+            8 => Operation::SetTag(entity, tag),
+            9 => Operation::RemoveTag(entity),
+            10 => Operation::Defrag,
+            _ => Operation::Merge {
+                tag,
+                components: self.generate_components(),
+            },
+        }
+    }
+
+    fn insert(
+        world: &mut World,
+        tag: u8,
+        components: Vec<Option<C>>,
+    ) -> Vec<(Entity, Expected<C>)> {
+        components
+            .into_iter()
+            .map(|component| {
+                let entity = match &component {
+                    Some(component) => world.insert((FuzzTag(tag),), vec![(component.clone(),)])[0],
+                    None => world.insert((FuzzTag(tag),), vec![(FuzzMarker(0),)])[0],
+                };
+                let marker = if component.is_none() {
+                    Some(FuzzMarker(0))
+                } else {
+                    None
+                };
+                (
+                    entity,
+                    Expected {
+                        component,
+                        marker,
+                        tag: Some(FuzzTag(tag)),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn execute(&mut self, operation: Operation<C>) {
+        let entity = match &operation {
+            Operation::Delete(i)
+            | Operation::AddComponent(i, _)
+            | Operation::RemoveComponent(i)
+            | Operation::WriteComponent(i, _)
+            | Operation::AddMarker(i)
+            | Operation::RemoveMarker(i)
+            | Operation::SetTag(i, _)
+            | Operation::RemoveTag(i) => match self.entities.get(*i) {
+                Some(entity) => Some(*entity),
+                None => return,
+            },
+            _ => None,
+        };
+
+        match operation {
+            Operation::Insert { tag, components } => {
+                for (entity, expected) in Self::insert(&mut self.world, tag, components) {
+                    self.entities.push(entity);
+                    self.expected.insert(entity, expected);
+                }
+            }
+            Operation::Merge { tag, components } => {
+                let mut other = self.universe.create_world();
+                let inserted = Self::insert(&mut other, tag, components);
+                self.world.merge(other);
+                for (entity, expected) in inserted {
+                    self.entities.push(entity);
+                    self.expected.insert(entity, expected);
+                }
+            }
+            Operation::Defrag => {
+                self.world.defrag(None);
+            }
+            operation => {
+                let entity = entity.unwrap();
+                let expected = self.expected.get_mut(&entity).unwrap();
+                match operation {
+                    Operation::Delete(i) => {
+                        self.world.delete(entity);
+                        self.entities.swap_remove(i);
+                        self.expected.remove(&entity);
+                    }
+                    Operation::AddComponent(_, component) => {
+                        self.world.add_component(entity, component.clone());
+                        expected.component = Some(component);
+                    }
+                    Operation::RemoveComponent(_) => {
+                        self.world.remove_component::<C>(entity);
+                        expected.component = None;
+                    }
+                    Operation::WriteComponent(_, component) => {
+                        if let Some(mut current) = self.world.get_component_mut::<C>(entity) {
+                            *current = component.clone();
+                        }
+                        if expected.component.is_some() {
+                            expected.component = Some(component);
+                        }
+                    }
+                    Operation::AddMarker(_) => {
+                        self.next_marker += 1;
+                        let marker = FuzzMarker(self.next_marker);
+                        self.world.add_component(entity, marker);
+                        expected.marker = Some(marker);
+                    }
+                    Operation::RemoveMarker(_) => {
+                        self.world.remove_component::<FuzzMarker>(entity);
+                        expected.marker = None;
+                    }
+                    Operation::SetTag(_, tag) => {
+                        self.world.add_tag(entity, FuzzTag(tag));
+                        expected.tag = Some(FuzzTag(tag));
+                    }
+                    Operation::RemoveTag(_) => {
+                        self.world.remove_tag::<FuzzTag>(entity);
+                        expected.tag = None;
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    fn check(&mut self) -> Result<(), String> {
+        for (entity, expected) in &self.expected {
+            if !self.world.is_alive(*entity) {
+                return Err(format!("entity {} is not alive", entity));
+            }
+
+            let component = self.world.get_component::<C>(*entity).map(|c| (*c).clone());
+            if component != expected.component {
+                return Err(format!(
+                    "entity {} has component {:?}, expected {:?}",
+                    entity, component, expected.component
+                ));
+            }
+
+            let marker = self.world.get_component::<FuzzMarker>(*entity).map(|c| *c);
+            if marker != expected.marker {
+                return Err(format!(
+                    "entity {} has marker {:?}, expected {:?}",
+                    entity, marker, expected.marker
+                ));
+            }
+
+            let tag = self.world.get_tag::<FuzzTag>(*entity).copied();
+            if tag != expected.tag {
+                return Err(format!(
+                    "entity {} has tag {:?}, expected {:?}",
+                    entity, tag, expected.tag
+                ));
+            }
+        }
+
+        let with_component = self.expected.values().filter(|e| e.component.is_some());
+        let mut query = Read::<C>::query();
+        let found = query.iter_entities(&mut self.world).count();
+        if found != with_component.count() {
+            return Err(format!(
+                "query found {} entities with the user component",
+                found
+            ));
+        }
+
+        let live = self.world.entity_stats().live;
+        if live != self.expected.len() {
+            return Err(format!(
+                "world contains {} entities, expected {}",
+                live,
+                self.expected.len()
+            ));
+        }
+
+        #[cfg(feature = "validation")]
+        {
+            let report = self.world.validate();
+            if !report.is_valid() {
+                return Err(report.to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicIsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    /// Counts the number of live instances, to detect leaked or doubly dropped components.
+    #[derive(Debug)]
+    struct Counted(u64, Arc<AtomicIsize>);
+
+    impl Counted {
+        fn new(value: u64, live: Arc<AtomicIsize>) -> Self {
+            live.fetch_add(1, Ordering::SeqCst);
+            Counted(value, live)
+        }
+    }
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self { Counted::new(self.0, self.1.clone()) }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) { self.1.fetch_sub(1, Ordering::SeqCst); }
+    }
+
+    #[test]
+    fn fuzz_world() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        for seed in 0..8 {
+            let mut fuzzer = Fuzzer::new(seed, |rng| vec![rng.next_u64(); rng.below(4)]);
+            if let Err(failure) = fuzzer.run(300) {
+                panic!("{}", failure);
+            }
+        }
+    }
+
+    #[test]
+    fn fuzz_drops() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let live = Arc::new(AtomicIsize::new(0));
+        {
+            let live = live.clone();
+            let mut fuzzer = Fuzzer::new(7, move |rng| Counted::new(rng.next_u64(), live.clone()));
+            if let Err(failure) = fuzzer.run(300) {
+                panic!("{}", failure);
+            }
+        }
+
+        assert_eq!(0, live.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn replay_operations() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut fuzzer = Fuzzer::new(3, |rng| rng.next_u64());
+        fuzzer.run(100).unwrap();
+
+        let mut replay = Fuzzer::new(0, |rng| rng.next_u64());
+        for operation in fuzzer.operations().to_vec() {
+            replay.apply(operation).unwrap();
+        }
+        assert_eq!(
+            fuzzer.world().entity_stats().live,
+            replay.world().entity_stats().live
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Incremental tracking of the chunks which contain a component type.
+//!
+//! Used by the indexes which are built lazily from component data, such as the name index, the
+//! spatial index and transform propagation.
+use crate::storage::ChunkId;
+use crate::storage::ComponentStorage;
+use crate::storage::ComponentTypeId;
+use crate::storage::Storage;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// The entries read from a single chunk, along with the version and length of the chunk's
+/// column when they were read.
+#[derive(Debug)]
+struct TrackedChunk<T> {
+    version: u64,
+    len: usize,
+    entries: T,
+}
+
+/// The chunks found by `ChunkTracker::refresh` to have changed since they were last read.
+pub(crate) struct ChunkChanges<'a, T> {
+    /// The entries read from chunks which have since changed or no longer exist.
+    pub removed: Vec<T>,
+    /// The chunks which must be re-read and passed to `ChunkTracker::insert`.
+    pub changed: Vec<&'a ComponentStorage>,
+}
+
+/// Records the entries read from each chunk containing a component type, such that only the
+/// chunks whose components may have changed need to be re-read.
+///
+/// Every operation which adds or modifies components increments the column's version, and every
+/// operation which removes components changes the chunk's length.
+#[derive(Debug)]
+pub(crate) struct ChunkTracker<T> {
+    type_id: ComponentTypeId,
+    chunks: HashMap<ChunkId, TrackedChunk<T>>,
+}
+
+impl<T> ChunkTracker<T> {
+    /// Creates a tracker over the chunks containing components of the given type.
+    pub fn new(type_id: ComponentTypeId) -> Self {
+        ChunkTracker {
+            type_id,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Gets the component type whose chunks are tracked.
+    pub fn type_id(&self) -> ComponentTypeId { self.type_id }
+
+    /// Finds the chunks in `storage` which have changed since they were last read, and forgets
+    /// them along with any chunks which no longer exist.
+    ///
+    /// All changed chunks are forgotten before any is re-read, as entities may have moved
+    /// between chunks.
+    pub fn refresh<'a>(&mut self, storage: &'a Storage) -> ChunkChanges<'a, T> {
+        let type_id = self.type_id;
+        let mut visited = HashSet::new();
+        let mut changed = Vec::new();
+        for chunk in chunks_with(storage, type_id) {
+            visited.insert(chunk.id());
+            let version = chunk.components(type_id).unwrap().version();
+            let current = self
+                .chunks
+                .get(&chunk.id())
+                .map(|tracked| tracked.version == version && tracked.len == chunk.len())
+                .unwrap_or(false);
+            if !current {
+                changed.push(chunk);
+            }
+        }
+
+        let removed = self
+            .chunks
+            .keys()
+            .filter(|id| !visited.contains(id))
+            .copied()
+            .chain(changed.iter().map(|chunk| chunk.id()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter_map(|id| self.chunks.remove(&id))
+            .map(|tracked| tracked.entries)
+            .collect();
+
+        ChunkChanges { removed, changed }
+    }
+
+    /// Records the entries read from a chunk returned by `refresh`.
+    pub fn insert(&mut self, chunk: &ComponentStorage, entries: T) {
+        let version = chunk.components(self.type_id).unwrap().version();
+        self.chunks.insert(
+            chunk.id(),
+            TrackedChunk {
+                version,
+                len: chunk.len(),
+                entries,
+            },
+        );
+    }
+}
+
+fn chunks_with(
+    storage: &Storage,
+    type_id: ComponentTypeId,
+) -> impl Iterator<Item = &ComponentStorage> + '_ {
+    storage
+        .component_index()
+        .archetypes(type_id)
+        .iter()
+        .map(move |index| &storage.archetypes()[*index])
+        .flat_map(|archetype| archetype.chunksets().iter().flat_map(|set| set.iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::storage::ComponentTypeId;
+    use crate::tracker::ChunkTracker;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32);
+
+    fn refresh(tracker: &mut ChunkTracker<Vec<Entity>>, world: &World) -> (usize, usize) {
+        let changes = tracker.refresh(world.storage());
+        let removed = changes.removed.len();
+        let changed = changes.changed.len();
+        for chunk in changes.changed {
+            tracker.insert(chunk, chunk.entities().to_vec());
+        }
+        (removed, changed)
+    }
+
+    #[test]
+    fn refresh_changed_chunks() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        let mut tracker = ChunkTracker::new(ComponentTypeId::of::<Pos>());
+
+        let a = world
+            .insert((1u32,), vec![(Pos(1.0),), (Pos(2.0),)])
+            .to_vec();
+        world.insert((2u32,), vec![(Pos(3.0),)]);
+        world.insert((), vec![(0u8,)]);
+        assert_eq!((0, 2), refresh(&mut tracker, &world));
+        assert_eq!((0, 0), refresh(&mut tracker, &world));
+
+        // modified and shrunk chunks are re-read
+        *world.get_component_mut::<Pos>(a[0]).unwrap() = Pos(4.0);
+        assert_eq!((1, 1), refresh(&mut tracker, &world));
+        world.delete(a[1]);
+        assert_eq!((1, 1), refresh(&mut tracker, &world));
+
+        // chunks which no longer hold the component are forgotten
+        world.remove_component::<Pos>(a[0]);
+        world.defrag(None);
+        let (removed, _) = refresh(&mut tracker, &world);
+        assert_eq!(1, removed);
+        assert_eq!((0, 0), refresh(&mut tracker, &world));
+    }
+}
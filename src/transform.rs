@@ -0,0 +1,469 @@
+//! Hierarchical transforms.
+//!
+//! Entities are positioned relative to their `Parent` by a `LocalTransform`. The
+//! `TransformPropagation` pass computes each entity's `WorldTransform` by composing the local
+//! transforms along its chain of parents. Only entities with both a `LocalTransform` and a
+//! `WorldTransform` are updated.
+//!
+//! Propagation is incremental: the pass tracks the change versions of each chunk's local
+//! transforms and parents, and only recomputes the subtrees below entities whose local
+//! transform or parent has changed since the previous pass.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! use legion::transform::*;
+//!
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! let root = world.insert(
+//!     (),
+//!     vec![(
+//!         LocalTransform(Transform::from_translation([1.0, 0.0, 0.0])),
+//!         WorldTransform::default(),
+//!     )],
+//! )[0];
+//! let child = world.insert(
+//!     (),
+//!     vec![(
+//!         LocalTransform(Transform::from_translation([0.0, 2.0, 0.0])),
+//!         WorldTransform::default(),
+//!         Parent(root),
+//!     )],
+//! )[0];
+//!
+//! let mut propagation = TransformPropagation::new();
+//! propagation.run(&mut world);
+//!
+//! let transform = world.get_component::<WorldTransform>(child).unwrap();
+//! assert_eq!([1.0, 2.0, 0.0], transform.0.translation);
+//! ```
+//!
+//! Use `TransformPropagation::into_system` to run the pass within a schedule.
+use crate::entity::Entity;
+use crate::schedule::Schedulable;
+use crate::storage::ComponentTypeId;
+use crate::storage::Storage;
+use crate::system::SystemBuilder;
+use crate::tracker::ChunkTracker;
+use crate::world::World;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use tracing::{span, Level};
+
+/// A translation, rotation and uniform scale.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// The translation.
+    pub translation: [f32; 3],
+    /// The rotation, as a unit quaternion in `[x, y, z, w]` order.
+    pub rotation: [f32; 4],
+    /// The uniform scale.
+    pub scale: f32,
+}
+
+impl Transform {
+    /// The transform which leaves points unchanged.
+    pub const IDENTITY: Transform = Transform {
+        translation: [0.0, 0.0, 0.0],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+        scale: 1.0,
+    };
+
+    /// Creates a transform which only translates.
+    pub fn from_translation(translation: [f32; 3]) -> Self {
+        Transform {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Creates a transform which only rotates.
+    pub fn from_rotation(rotation: [f32; 4]) -> Self {
+        Transform {
+            rotation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Creates a transform which only scales.
+    pub fn from_scale(scale: f32) -> Self {
+        Transform {
+            scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Composes this transform with `child`, producing a transform which applies `child`
+    /// followed by this transform.
+    pub fn mul_transform(&self, child: &Transform) -> Transform {
+        Transform {
+            translation: self.transform_point(child.translation),
+            rotation: mul_quat(self.rotation, child.rotation),
+            scale: self.scale * child.scale,
+        }
+    }
+
+    /// Applies this transform to a point.
+    pub fn transform_point(&self, point: [f32; 3]) -> [f32; 3] {
+        let scaled = [
+            point[0] * self.scale,
+            point[1] * self.scale,
+            point[2] * self.scale,
+        ];
+        let rotated = rotate(self.rotation, scaled);
+        [
+            rotated[0] + self.translation[0],
+            rotated[1] + self.translation[1],
+            rotated[2] + self.translation[2],
+        ]
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self { Self::IDENTITY }
+}
+
+fn mul_quat(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [
+        a[3] * b[0] + a[0] * b[3] + a[1] * b[2] - a[2] * b[1],
+        a[3] * b[1] - a[0] * b[2] + a[1] * b[3] + a[2] * b[0],
+        a[3] * b[2] + a[0] * b[1] - a[1] * b[0] + a[2] * b[3],
+        a[3] * b[3] - a[0] * b[0] - a[1] * b[1] - a[2] * b[2],
+    ]
+}
+
+fn rotate(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    // v + 2w(u x v) + 2u x (u x v), where u is the vector part of q
+    let u = [q[0], q[1], q[2]];
+    let cross = |a: [f32; 3], b: [f32; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let uv = cross(u, v);
+    let uuv = cross(u, uv);
+    [
+        v[0] + 2.0 * (q[3] * uv[0] + uuv[0]),
+        v[1] + 2.0 * (q[3] * uv[1] + uuv[1]),
+        v[2] + 2.0 * (q[3] * uv[2] + uuv[2]),
+    ]
+}
+
+/// The transform of an entity relative to its parent, or to the world if it has no parent.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LocalTransform(pub Transform);
+
+/// The transform of an entity relative to the world. Written by `TransformPropagation`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct WorldTransform(pub Transform);
+
+/// The entity which an entity's `LocalTransform` is relative to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Parent(pub Entity);
+
+/// Computes `WorldTransform`s from `LocalTransform`s and `Parent`s.
+///
+/// The pass keeps a record of the hierarchy and of the chunk versions seen by the previous pass,
+/// so the same instance should be run against the same world each time.
+#[derive(Debug)]
+pub struct TransformPropagation {
+    locals: ChunkTracker<Vec<Entity>>,
+    parent_chunks: ChunkTracker<Vec<Entity>>,
+    parents: HashMap<Entity, Entity>,
+    children: HashMap<Entity, Vec<Entity>>,
+}
+
+impl Default for TransformPropagation {
+    fn default() -> Self {
+        TransformPropagation {
+            locals: ChunkTracker::new(ComponentTypeId::of::<LocalTransform>()),
+            parent_chunks: ChunkTracker::new(ComponentTypeId::of::<Parent>()),
+            parents: HashMap::new(),
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl TransformPropagation {
+    /// Creates a new propagation pass. The first run computes the world transforms of all
+    /// entities.
+    pub fn new() -> Self { Self::default() }
+
+    /// Recomputes the world transforms of all entities whose local transform, or the local
+    /// transform of one of their ancestors, has changed since the previous run.
+    ///
+    /// Returns the number of world transforms which were written.
+    pub fn run(&mut self, world: &mut World) -> usize {
+        // safe because the &mut World ensures exclusive access to the world transforms
+        unsafe { self.run_unchecked(world) }
+    }
+
+    /// Creates a system which runs this pass. The system declares reads of `LocalTransform`
+    /// and `Parent` and writes to `WorldTransform`.
+    pub fn into_system(mut self) -> Box<dyn Schedulable> {
+        SystemBuilder::new("transform_propagation")
+            .read_component::<LocalTransform>()
+            .read_component::<Parent>()
+            .write_component::<WorldTransform>()
+            .build(move |_, world, _, _| {
+                // safe because the system declares write access to the world transforms
+                unsafe { self.run_unchecked(world.world()) };
+            })
+    }
+
+    /// # Safety
+    ///
+    /// `WorldTransform` components must not be accessed elsewhere for the duration of the call.
+    unsafe fn run_unchecked(&mut self, world: &World) -> usize {
+        let span = span!(
+            Level::TRACE,
+            "Propagating transforms",
+            world = world.id().index()
+        );
+        let _guard = span.enter();
+
+        let mut dirty = HashSet::new();
+        self.refresh_parents(world.storage(), &mut dirty);
+        self.refresh_locals(world.storage(), &mut dirty);
+
+        let mut written = 0;
+        let mut visited = HashSet::new();
+        for root in dirty.iter() {
+            // the subtree will be updated from its dirty ancestor
+            if self.has_dirty_ancestor(*root, &dirty) {
+                continue;
+            }
+
+            let parent = self
+                .parents
+                .get(root)
+                .and_then(|parent| world.get_component::<WorldTransform>(*parent))
+                .map(|transform| transform.0)
+                .unwrap_or(Transform::IDENTITY);
+
+            let mut stack = vec![(*root, parent)];
+            while let Some((entity, parent)) = stack.pop() {
+                if !visited.insert(entity) {
+                    continue;
+                }
+
+                // entities without transforms position their children relative to the world
+                let local = world.get_component::<LocalTransform>(entity).map(|l| l.0);
+                let transform = match (
+                    local,
+                    world.get_component_mut_unchecked::<WorldTransform>(entity),
+                ) {
+                    (Some(local), Some(mut world_transform)) => {
+                        world_transform.0 = parent.mul_transform(&local);
+                        written += 1;
+                        world_transform.0
+                    }
+                    _ => Transform::IDENTITY,
+                };
+
+                if let Some(children) = self.children.get(&entity) {
+                    stack.extend(children.iter().map(|child| (*child, transform)));
+                }
+            }
+        }
+
+        written
+    }
+
+    fn has_dirty_ancestor(&self, entity: Entity, dirty: &HashSet<Entity>) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = entity;
+        while let Some(parent) = self.parents.get(&current) {
+            if !visited.insert(*parent) {
+                // the hierarchy contains a cycle
+                return false;
+            }
+            if dirty.contains(parent) {
+                return true;
+            }
+            current = *parent;
+        }
+        false
+    }
+
+    fn refresh_parents(&mut self, storage: &Storage, dirty: &mut HashSet<Entity>) {
+        let changes = self.parent_chunks.refresh(storage);
+        for entities in changes.removed {
+            self.remove_parents(entities, dirty);
+        }
+
+        for chunk in changes.changed {
+            let parents = unsafe {
+                chunk
+                    .components(self.parent_chunks.type_id())
+                    .unwrap()
+                    .data_slice::<Parent>()
+            };
+            for (entity, parent) in chunk.entities().iter().zip(parents.iter()) {
+                self.parents.insert(*entity, parent.0);
+                self.children.entry(parent.0).or_default().push(*entity);
+                dirty.insert(*entity);
+            }
+            self.parent_chunks.insert(chunk, chunk.entities().to_vec());
+        }
+    }
+
+    fn remove_parents(&mut self, entities: Vec<Entity>, dirty: &mut HashSet<Entity>) {
+        for entity in entities {
+            if let Some(parent) = self.parents.remove(&entity) {
+                if let Some(children) = self.children.get_mut(&parent) {
+                    children.retain(|child| *child != entity);
+                    if children.is_empty() {
+                        self.children.remove(&parent);
+                    }
+                }
+            }
+            dirty.insert(entity);
+        }
+    }
+
+    fn refresh_locals(&mut self, storage: &Storage, dirty: &mut HashSet<Entity>) {
+        // the children of entities which left a chunk may have lost their parent
+        let changes = self.locals.refresh(storage);
+        for entities in changes.removed {
+            self.mark_children(&entities, dirty);
+        }
+
+        for chunk in changes.changed {
+            dirty.extend(chunk.entities().iter().copied());
+            self.locals.insert(chunk, chunk.entities().to_vec());
+        }
+    }
+
+    fn mark_children(&self, entities: &[Entity], dirty: &mut HashSet<Entity>) {
+        for entity in entities {
+            if let Some(children) = self.children.get(entity) {
+                dirty.extend(children.iter().copied());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::transform::*;
+
+    fn translation(world: &World, entity: Entity) -> [f32; 3] {
+        world
+            .get_component::<WorldTransform>(entity)
+            .unwrap()
+            .0
+            .translation
+    }
+
+    fn spawn(world: &mut World, local: [f32; 3], parent: Option<Entity>) -> Entity {
+        let local = LocalTransform(Transform::from_translation(local));
+        match parent {
+            Some(parent) => {
+                world.insert((), vec![(local, WorldTransform::default(), Parent(parent))])[0]
+            }
+            None => world.insert((), vec![(local, WorldTransform::default())])[0],
+        }
+    }
+
+    #[test]
+    fn compose() {
+        // a quarter turn about z
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let parent = Transform {
+            translation: [1.0, 0.0, 0.0],
+            rotation: [0.0, 0.0, half, half],
+            scale: 2.0,
+        };
+        let child = Transform::from_translation([1.0, 0.0, 0.0]);
+
+        let composed = parent.mul_transform(&child);
+        assert!((composed.translation[0] - 1.0).abs() < 1e-5);
+        assert!((composed.translation[1] - 2.0).abs() < 1e-5);
+        assert_eq!(2.0, composed.scale);
+
+        let point = [0.0, 1.0, 3.0];
+        let expected = parent.transform_point(child.transform_point(point));
+        let actual = composed.transform_point(point);
+        for (a, b) in expected.iter().zip(actual.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn propagate_dirty_subtrees() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+
+        let root = spawn(&mut world, [1.0, 0.0, 0.0], None);
+        let child = spawn(&mut world, [0.0, 1.0, 0.0], Some(root));
+        let grandchild = spawn(&mut world, [0.0, 0.0, 1.0], Some(child));
+        let other = world.insert(
+            (5u32,),
+            vec![(
+                LocalTransform(Transform::from_translation([5.0, 0.0, 0.0])),
+                WorldTransform::default(),
+            )],
+        )[0];
+
+        let mut propagation = TransformPropagation::new();
+        assert_eq!(4, propagation.run(&mut world));
+        assert_eq!([1.0, 1.0, 1.0], translation(&world, grandchild));
+        assert_eq!([5.0, 0.0, 0.0], translation(&world, other));
+
+        // nothing has changed
+        assert_eq!(0, propagation.run(&mut world));
+
+        // only the moved subtree is recomputed
+        world.get_component_mut::<LocalTransform>(root).unwrap().0 =
+            Transform::from_translation([2.0, 0.0, 0.0]);
+        assert_eq!(3, propagation.run(&mut world));
+        assert_eq!([2.0, 1.0, 0.0], translation(&world, child));
+        assert_eq!([2.0, 1.0, 1.0], translation(&world, grandchild));
+
+        // re-parenting moves the subtree
+        *world.get_component_mut::<Parent>(child).unwrap() = Parent(other);
+        propagation.run(&mut world);
+        assert_eq!([5.0, 1.0, 1.0], translation(&world, grandchild));
+
+        // removing the parent makes the entity a root
+        world.remove_component::<Parent>(child);
+        propagation.run(&mut world);
+        assert_eq!([0.0, 1.0, 0.0], translation(&world, child));
+        assert_eq!([0.0, 1.0, 1.0], translation(&world, grandchild));
+
+        // deleting the parent positions children relative to the world
+        world.delete(child);
+        propagation.run(&mut world);
+        assert_eq!([0.0, 0.0, 1.0], translation(&world, grandchild));
+    }
+
+    #[test]
+    fn system() {
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+
+        let root = spawn(&mut world, [1.0, 0.0, 0.0], None);
+        let child = spawn(&mut world, [0.0, 1.0, 0.0], Some(root));
+
+        let mut scheduler = crate::schedule::SystemScheduler::<Stage>::new();
+        scheduler.add_system(Stage, TransformPropagation::new().into_system());
+        scheduler.execute(&mut world);
+
+        assert_eq!([1.0, 1.0, 0.0], translation(&world, child));
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Stage;
+
+    impl crate::schedule::Stage for Stage {}
+
+    impl std::fmt::Display for Stage {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "stage") }
+    }
+}
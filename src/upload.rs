@@ -0,0 +1,215 @@
+//! Helpers for mirroring chunk data into GPU buffers.
+//!
+//! An `UploadTracker` assigns each allocated chunk a dense index which stays stable for as long
+//! as the chunk holds entities, such that a renderer can keep one buffer slot per chunk. Each
+//! call to `UploadTracker::update` reports the component columns which have been written since
+//! the previous call, so only changed columns need to be uploaded.
+//!
+//! ```
+//! # use legion::prelude::*;
+//! use legion::storage::ComponentTypeId;
+//! use legion::upload::UploadTracker;
+//!
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! struct Transform([f32; 16]);
+//!
+//! # let universe = Universe::new();
+//! # let mut world = universe.create_world();
+//! let entity = world.insert((), vec![(Transform([0.0; 16]),)])[0];
+//!
+//! let mut tracker = UploadTracker::new(&[ComponentTypeId::of::<Transform>()]);
+//! let dirty = tracker.update(&world);
+//! assert_eq!(1, dirty.len());
+//! assert_eq!(Some(0), tracker.index(dirty[0].chunk));
+//! assert_eq!(0..1, dirty[0].range);
+//!
+//! // nothing has changed since the last update
+//! assert!(tracker.update(&world).is_empty());
+//!
+//! world.get_component_mut::<Transform>(entity).unwrap().0[0] = 1.0;
+//! assert_eq!(1, tracker.update(&world).len());
+//! ```
+use crate::storage::ChunkId;
+use crate::storage::ComponentTypeId;
+use crate::world::World;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// A range of components within a chunk which must be uploaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirtyRange {
+    /// The chunk containing the components.
+    pub chunk: ChunkId,
+    /// The chunk's dense index, as returned by `UploadTracker::index`.
+    pub index: usize,
+    /// The type of the components.
+    pub component: ComponentTypeId,
+    /// The range of component indices within the chunk which have changed.
+    pub range: Range<usize>,
+}
+
+/// Tracks which chunk columns have changed since they were last uploaded.
+///
+/// Changes are tracked with component versions, which are recorded per column. A column which
+/// has been written is reported in its entirety.
+#[derive(Debug, Default)]
+pub struct UploadTracker {
+    types: Vec<ComponentTypeId>,
+    indices: HashMap<ChunkId, usize>,
+    free_indices: Vec<usize>,
+    next_index: usize,
+    // the version and length of each column at its last upload
+    uploaded: HashMap<(ChunkId, ComponentTypeId), (u64, usize)>,
+}
+
+impl UploadTracker {
+    /// Creates a tracker for the given component types.
+    pub fn new(types: &[ComponentTypeId]) -> Self {
+        UploadTracker {
+            types: types.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the dense index assigned to a chunk, if the chunk held entities at the last update.
+    ///
+    /// Indices are reused once their chunk is emptied, and never exceed `capacity`.
+    pub fn index(&self, chunk: ChunkId) -> Option<usize> { self.indices.get(&chunk).copied() }
+
+    /// Gets the number of chunk indices which have been assigned, such that all indices are
+    /// less than the returned value.
+    pub fn capacity(&self) -> usize { self.next_index }
+
+    /// Assigns indices to new chunks, releases the indices of emptied chunks, and returns the
+    /// component ranges which have changed since the previous update.
+    pub fn update(&mut self, world: &World) -> Vec<DirtyRange> {
+        let mut live = HashSet::new();
+        let mut dirty = Vec::new();
+        for archetype in world.storage().archetypes() {
+            let types = self
+                .types
+                .iter()
+                .filter(|t| {
+                    archetype
+                        .description()
+                        .components()
+                        .iter()
+                        .any(|(c, _)| c == *t)
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            if types.is_empty() {
+                continue;
+            }
+
+            for chunk in archetype.chunksets().iter().flat_map(|set| set.occupied()) {
+                if chunk.is_empty() {
+                    continue;
+                }
+
+                let id = chunk.id();
+                live.insert(id);
+                let index = match self.indices.get(&id) {
+                    Some(index) => *index,
+                    None => {
+                        let index = self.free_indices.pop().unwrap_or_else(|| {
+                            self.next_index += 1;
+                            self.next_index - 1
+                        });
+                        self.indices.insert(id, index);
+                        index
+                    }
+                };
+
+                for type_id in &types {
+                    let version = chunk.components(*type_id).unwrap().version();
+                    let state = (version, chunk.len());
+                    if self.uploaded.insert((id, *type_id), state) != Some(state) {
+                        dirty.push(DirtyRange {
+                            chunk: id,
+                            index,
+                            component: *type_id,
+                            range: 0..chunk.len(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // release the indices of chunks which no longer hold entities
+        let indices = &mut self.indices;
+        let free_indices = &mut self.free_indices;
+        indices.retain(|id, index| {
+            let retain = live.contains(id);
+            if !retain {
+                free_indices.push(*index);
+            }
+            retain
+        });
+        self.uploaded.retain(|(id, _), _| live.contains(id));
+
+        dirty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Pos(f32);
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Vel(f32);
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Model(u32);
+
+    #[test]
+    fn reuses_indices_of_emptied_chunks() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut world = World::new();
+        let a = world.insert((Model(1),), vec![(Pos(1.),)])[0];
+        world.insert((Model(2),), vec![(Pos(2.),)]);
+        world.insert((Model(3),), vec![(Vel(3.),)]);
+
+        let mut tracker = UploadTracker::new(&[ComponentTypeId::of::<Pos>()]);
+        let dirty = tracker.update(&world);
+        assert_eq!(2, dirty.len());
+        assert_eq!(2, tracker.capacity());
+        let freed = dirty[0].chunk;
+        let freed_index = tracker.index(freed).unwrap();
+
+        world.delete(a);
+        assert!(tracker.update(&world).is_empty());
+        assert_eq!(None, tracker.index(freed));
+
+        world.insert((Model(4),), vec![(Pos(4.),)]);
+        let dirty = tracker.update(&world);
+        assert_eq!(1, dirty.len());
+        assert_eq!(freed_index, dirty[0].index);
+        assert_eq!(2, tracker.capacity());
+    }
+
+    #[test]
+    fn reports_only_written_columns() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut world = World::new();
+        let entities = world
+            .insert((), vec![(Pos(1.), Vel(1.)), (Pos(2.), Vel(2.))])
+            .to_vec();
+
+        let mut tracker =
+            UploadTracker::new(&[ComponentTypeId::of::<Pos>(), ComponentTypeId::of::<Vel>()]);
+        assert_eq!(2, tracker.update(&world).len());
+
+        *world.get_component_mut::<Vel>(entities[1]).unwrap() = Vel(5.);
+        let dirty = tracker.update(&world);
+        assert_eq!(1, dirty.len());
+        assert_eq!(ComponentTypeId::of::<Vel>(), dirty[0].component);
+        assert_eq!(0..2, dirty[0].range);
+    }
+}
@@ -2,46 +2,103 @@ use crate::borrow::Exclusive;
 use crate::borrow::Ref;
 use crate::borrow::RefMut;
 use crate::borrow::Shared;
+use crate::command::CommandBuffer;
+use crate::command::CommandQueue;
+use crate::entity::AllocatorSnapshot;
 use crate::entity::BlockAllocator;
 use crate::entity::Entity;
+use crate::entity::EntityAllocationOrder;
 use crate::entity::EntityAllocator;
+use crate::entity::EntityAllocatorStats;
 use crate::entity::EntityLocation;
+use crate::entity::VersionWrapBehavior;
+use crate::error::EntityError;
+use crate::error::Error;
+use crate::filter::begin_pass;
+use crate::filter::filter_fns;
 use crate::filter::ArchetypeFilterData;
+use crate::filter::ChangeReader;
+use crate::filter::ChunkFilterData;
 use crate::filter::ChunksetFilterData;
+use crate::filter::EntityFilter;
 use crate::filter::Filter;
-use crate::iterator::SliceVecIter;
+use crate::filter::FilterResult;
+use crate::interpolate::Interpolate;
+use crate::interpolate::SnapshotHistory;
+use crate::interpolate::Snapshots;
+use crate::name::NameIndex;
+use crate::query::ChunkDataIter;
+use crate::query::ChunkEntityIter;
+use crate::query::ChunkViewIter;
+use crate::query::Query;
+use crate::query::ReadOnly;
+use crate::query::View;
+use crate::reflect::FieldNode;
+#[cfg(feature = "serialize")]
+use crate::reflect::PatchError;
+use crate::reflect::Reflect;
+use crate::registry::ComponentRegistration;
+use crate::registry::Registry;
 use crate::resource::Resources;
+#[cfg(feature = "spatial")]
+use crate::spatial::Spatial;
+#[cfg(feature = "spatial")]
+use crate::spatial::SpatialIndex;
+#[cfg(feature = "alloc-tracking")]
+use crate::storage::allocation_counters;
+use crate::storage::current_version;
+#[cfg(feature = "alloc-tracking")]
+use crate::storage::AllocationCounters;
 use crate::storage::ArchetypeData;
 use crate::storage::ArchetypeDescription;
+use crate::storage::ArchetypeId;
+use crate::storage::ChunkId;
 use crate::storage::Component;
 use crate::storage::ComponentMeta;
 use crate::storage::ComponentStorage;
 use crate::storage::ComponentTypeId;
+use crate::storage::DynamicTagSet;
+use crate::storage::ExactTypeSetIter;
+use crate::storage::NonSyncComponent;
+use crate::storage::NonSyncStorage;
 use crate::storage::Storage;
 use crate::storage::Tag;
 use crate::storage::TagMeta;
 use crate::storage::TagTypeId;
 use crate::storage::Tags;
+use crate::storage::UserData;
+use crate::sync::Mutex;
 use crate::tuple::TupleEq;
-use parking_lot::Mutex;
+use std::any::Any;
 use std::cell::UnsafeCell;
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::iter::Enumerate;
 use std::iter::Peekable;
 use std::iter::Repeat;
 use std::iter::Take;
 use std::marker::PhantomData;
+use std::mem::MaybeUninit;
 use std::ops::Deref;
+use std::ops::DerefMut;
+use std::ops::Index;
 use std::ptr::NonNull;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tracing::{info, span, trace, Level};
 
-#[cfg(feature = "events")]
+#[cfg(all(feature = "events", feature = "rayon", not(feature = "single-thread")))]
 use rayon::prelude::*;
 
 #[cfg(feature = "events")]
-use crate::event::{Channel, EntityEvent, WorldCreatedEvent};
+use crate::event::{Channel, EntityEvent, WorldCreatedEvent, WorldEvent};
 
 /// The `Universe` is a factory for creating `World`s.
 ///
@@ -49,8 +106,10 @@ use crate::event::{Channel, EntityEvent, WorldCreatedEvent};
 /// unique `Entity` IDs, even across worlds.
 #[derive(Debug)]
 pub struct Universe {
+    /// Component registrations which are copied into each world created by this universe.
+    pub registry: Registry,
     allocator: Arc<Mutex<BlockAllocator>>,
-    world_count: AtomicUsize,
+    world_ids: Arc<WorldIds>,
     #[cfg(feature = "events")]
     channel: Channel<WorldCreatedEvent>,
 }
@@ -59,21 +118,135 @@ impl Universe {
     /// Creates a new `Universe`.
     pub fn new() -> Self { Self::default() }
 
+    /// Creates a new `Universe` whose worlds claim entity IDs in blocks of `block_size`.
+    ///
+    /// Each world claims at least one block once it contains any entities, so smaller blocks
+    /// suit universes which create many small worlds, while larger blocks reduce contention on
+    /// the shared allocator when worlds grow quickly. The default block size is 1024.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn with_entity_block_size(block_size: usize) -> Self {
+        Self {
+            allocator: Arc::new(Mutex::new(BlockAllocator::with_block_size(block_size))),
+            ..Self::default()
+        }
+    }
+
+    /// Gets the number of entity IDs which worlds claim from the universe at a time.
+    pub fn entity_block_size(&self) -> usize { self.allocator.lock().block_size() }
+
+    /// Releases the memory held by entity blocks which have been returned by dropped worlds and
+    /// contain no live entities.
+    ///
+    /// The IDs of released blocks are not lost, and will be handed out again to worlds which
+    /// later need more entity blocks, without ever reissuing an `Entity` handle previously
+    /// issued to a dropped world.
+    ///
+    /// Returns the number of blocks released.
+    pub fn shrink(&self) -> usize { self.allocator.lock().shrink() }
+
     /// Creates a new `World` within this `Universe`.
     ///
     /// Entities inserted into worlds created within the same universe are guarenteed to have
     /// unique `Entity` IDs, even across worlds. See also `World::new`.
-    pub fn create_world(&self) -> World {
-        let id = self.world_count.fetch_add(1, Ordering::SeqCst);
-        let world =
-            World::new_in_universe(WorldId(id), EntityAllocator::new(self.allocator.clone()));
+    pub fn create_world(&self) -> World { self.create_world_registered(self.world_ids.register()) }
+
+    /// Creates a new `World` within this `Universe` with the given ID.
+    ///
+    /// This allows worlds to be identified by stable, caller-chosen IDs, such as one world per
+    /// server zone. Worlds created by `create_world` never take an ID which is already in use.
+    ///
+    /// Returns an error if a live world in this universe already has the ID.
+    pub fn create_world_with_id(&self, id: WorldId) -> Result<World, WorldIdInUse> {
+        let registration = self.world_ids.register_id(id).ok_or(WorldIdInUse(id))?;
+        Ok(self.create_world_registered(registration))
+    }
+
+    /// Gets the IDs of all live worlds created by this universe, in ascending order.
+    pub fn worlds(&self) -> Vec<WorldId> {
+        let mut worlds = self
+            .world_ids
+            .live
+            .lock()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        worlds.sort_by_key(|id| id.0);
+        worlds
+    }
+
+    /// Determines if a live world created by this universe has the given ID.
+    pub fn contains_world(&self, id: WorldId) -> bool { self.world_ids.live.lock().contains(&id) }
+
+    /// Moves the contents of a world created by another universe, or by `World::new`, into a
+    /// new world within this universe. See `adopt_world_with_remap`.
+    pub fn adopt_world(&self, world: World) -> World { self.adopt_world_with_remap(world).0 }
+
+    /// Moves the contents of a world created by another universe, or by `World::new`, into a
+    /// new world within this universe.
+    ///
+    /// This allows worlds to be built offline, such as by a level editor or a loading thread,
+    /// and then handed to the runtime. All entities are assigned new IDs allocated from this
+    /// universe, so they are unique among the universe's worlds. Chunks are moved in their
+    /// entirety, without copying their component data. Resources, registrations and the world's
+    /// deterministic ordering, defrag policy and fixed capacity settings are carried over.
+    /// Snapshot histories, removal tracking and queued command buffers are not.
+    ///
+    /// Returns the new world and a map from each entity's ID in `world` to its new ID. Components
+    /// which refer to other entities must be patched by the caller using this map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// let offline = Universe::new();
+    /// let mut level = offline.create_world();
+    /// let built = level.insert((), vec![(Position(1.0),)])[0];
+    ///
+    /// let universe = Universe::new();
+    /// let (world, remap) = universe.adopt_world_with_remap(level);
+    ///
+    /// let entity = remap[&built];
+    /// assert_eq!(Position(1.0), *world.get_component::<Position>(entity).unwrap());
+    /// ```
+    pub fn adopt_world_with_remap(&self, mut world: World) -> (World, HashMap<Entity, Entity>) {
+        let mut adopted = self.create_world();
+
+        let span = span!(Level::INFO, "Adopting world", source = world.id().0, destination = ?adopted.id());
+        let _guard = span.enter();
+
+        adopted.registry.merge(&world.registry);
+        adopted.resources = std::mem::take(&mut world.resources);
+        adopted.deterministic_order = world.deterministic_order;
+        adopted.intern_tags = world.intern_tags;
+        adopted.defrag_policy = world.defrag_policy;
+        adopted.set_component_padding(world.component_padding());
+        adopted.set_small_chunk_capacity(world.small_chunk_capacity());
+
+        let mut remap = HashMap::new();
+        world.move_chunks(&mut filter_fns::passthrough(), &mut adopted, |old, new| {
+            remap.insert(old, new);
+        });
+
+        adopted.fixed_capacity = world.fixed_capacity;
+        (adopted, remap)
+    }
+
+    fn create_world_registered(&self, registration: WorldRegistration) -> World {
+        let mut world =
+            World::new_in_universe(registration, EntityAllocator::new(self.allocator.clone()));
+        world.registry = self.registry.clone();
 
         info!(world = world.id().0, "Created world");
 
         #[cfg(feature = "events")]
         {
             self.channel
-                .write(WorldCreatedEvent(WorldId(id)))
+                .write(WorldCreatedEvent(world.id()))
                 .expect("Failed to write to WorldCreatedEvent channel.");
         }
 
@@ -89,7 +262,8 @@ impl Default for Universe {
         Self {
             #[cfg(feature = "events")]
             channel: Channel::default(),
-            world_count: AtomicUsize::from(0),
+            registry: Registry::default(),
+            world_ids: Arc::new(WorldIds::default()),
             allocator: Arc::new(Mutex::new(BlockAllocator::new())),
         }
     }
@@ -99,24 +273,465 @@ impl Default for Universe {
 pub struct WorldId(usize);
 
 impl WorldId {
+    /// Creates a world ID with the given index, for use with `Universe::create_world_with_id`.
+    pub fn new(index: usize) -> Self { WorldId(index) }
+
     pub fn index(self) -> usize { self.0 }
 }
 
+/// The error returned by `Universe::create_world_with_id` when the requested ID is already
+/// taken by a live world.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct WorldIdInUse(pub WorldId);
+
+impl Display for WorldIdInUse {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "world ID {} is already in use", self.0.index())
+    }
+}
+
+impl std::error::Error for WorldIdInUse {}
+
+/// The IDs of the live worlds within a universe.
+#[derive(Debug, Default)]
+struct WorldIds {
+    next: AtomicUsize,
+    live: Mutex<HashSet<WorldId>>,
+}
+
+impl WorldIds {
+    /// Registers the next unused ID.
+    fn register(self: &Arc<Self>) -> WorldRegistration {
+        loop {
+            let id = WorldId(self.next.fetch_add(1, Ordering::SeqCst));
+            if let Some(registration) = self.register_id(id) {
+                return registration;
+            }
+        }
+    }
+
+    /// Registers the given ID, if it is not already in use.
+    fn register_id(self: &Arc<Self>, id: WorldId) -> Option<WorldRegistration> {
+        if self.live.lock().insert(id) {
+            Some(WorldRegistration {
+                id,
+                ids: self.clone(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Holds a world's ID in its universe, releasing it when the world is dropped.
+#[derive(Debug)]
+struct WorldRegistration {
+    id: WorldId,
+    ids: Arc<WorldIds>,
+}
+
+impl Drop for WorldRegistration {
+    fn drop(&mut self) { self.ids.live.lock().remove(&self.id); }
+}
+
+/// The reason that a fixed capacity world could not store new entities without allocating.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CapacityError {
+    /// The entities require an archetype which was not reserved.
+    Archetype,
+    /// The entities require a set of tag values which was not reserved.
+    Chunkset,
+    /// The reserved chunks do not have space for the entities.
+    Chunks { required: usize, available: usize },
+    /// The reserved entity blocks do not have space for the entities.
+    Entities { required: usize, available: usize },
+    /// The component source could not report how many entities it contains.
+    UnknownLength,
+}
+
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            CapacityError::Archetype => write!(f, "archetype was not reserved"),
+            CapacityError::Chunkset => write!(f, "tag values were not reserved"),
+            CapacityError::Chunks {
+                required,
+                available,
+            } => write!(
+                f,
+                "chunk space exhausted: {} entities required, {} available",
+                required, available
+            ),
+            CapacityError::Entities {
+                required,
+                available,
+            } => write!(
+                f,
+                "entity blocks exhausted: {} entities required, {} available",
+                required, available
+            ),
+            CapacityError::UnknownLength => write!(f, "component source length is unknown"),
+        }
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+/// The storage used by a single archetype, as reported by `World::capacity_report`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ArchetypeCapacity {
+    /// The names of the archetype's component types.
+    pub components: Vec<&'static str>,
+    /// The names of the archetype's tag types.
+    pub tags: Vec<&'static str>,
+    /// The number of distinct sets of tag values.
+    pub chunksets: usize,
+    /// The number of chunks, including chunks whose memory has been freed.
+    pub chunks: usize,
+    /// The number of entities each chunk can hold.
+    pub chunk_capacity: usize,
+    /// The number of entities currently stored in the archetype.
+    pub entities: usize,
+}
+
+/// The capacities a world requires to hold its current contents.
+///
+/// Running a representative workload in a growable world and then inspecting this report
+/// describes what must be passed to `World::reserve` before enabling fixed capacity mode.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CapacityReport {
+    /// The storage used by each archetype.
+    pub archetypes: Vec<ArchetypeCapacity>,
+    /// The number of live entities.
+    pub entities: usize,
+    /// The number of entities which can be allocated without claiming another entity block.
+    pub free_entity_slots: usize,
+    /// The number of entity blocks owned by the world.
+    pub entity_blocks: usize,
+}
+
+/// An inconsistency in a world's internal bookkeeping, as reported by `World::validate`.
+#[cfg(feature = "validation")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// A chunk contains an entity which is not alive.
+    DeadEntity { entity: Entity, chunk: ChunkId },
+    /// The location recorded for an entity does not match where it is stored.
+    WrongLocation {
+        entity: Entity,
+        chunk: ChunkId,
+        index: usize,
+    },
+    /// The number of live entities does not match the number of entities stored in chunks.
+    EntityCount { live: usize, stored: usize },
+    /// A chunk is missing the storage for one of its archetype's component types.
+    MissingComponent {
+        chunk: ChunkId,
+        component: ComponentTypeId,
+    },
+    /// The number of components in a chunk does not match its number of entities.
+    ComponentCount {
+        chunk: ChunkId,
+        component: ComponentTypeId,
+        components: usize,
+        entities: usize,
+    },
+    /// The number of values of a tag type does not match the archetype's number of chunk sets.
+    TagCount {
+        archetype: ArchetypeId,
+        tag: TagTypeId,
+        tags: usize,
+        chunksets: usize,
+    },
+    /// A chunk's ID does not match its position within the world's storage.
+    WrongChunkId { expected: ChunkId, found: ChunkId },
+}
+
+#[cfg(feature = "validation")]
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ValidationIssue::DeadEntity { entity, chunk } => {
+                write!(f, "chunk {:?} contains dead entity {}", chunk, entity)
+            }
+            ValidationIssue::WrongLocation {
+                entity,
+                chunk,
+                index,
+            } => write!(
+                f,
+                "entity {} is stored at index {} of chunk {:?}, but its location differs",
+                entity, index, chunk
+            ),
+            ValidationIssue::EntityCount { live, stored } => write!(
+                f,
+                "{} entities are alive, but {} are stored in chunks",
+                live, stored
+            ),
+            ValidationIssue::MissingComponent { chunk, component } => write!(
+                f,
+                "chunk {:?} has no storage for component {:?}",
+                chunk, component
+            ),
+            ValidationIssue::ComponentCount {
+                chunk,
+                component,
+                components,
+                entities,
+            } => write!(
+                f,
+                "chunk {:?} contains {} entities, but {} {:?} components",
+                chunk, entities, components, component
+            ),
+            ValidationIssue::TagCount {
+                archetype,
+                tag,
+                tags,
+                chunksets,
+            } => write!(
+                f,
+                "archetype {:?} contains {} chunk sets, but {} {:?} tags",
+                archetype, chunksets, tags, tag
+            ),
+            ValidationIssue::WrongChunkId { expected, found } => {
+                write!(f, "chunk {:?} is stored at {:?}", found, expected)
+            }
+        }
+    }
+}
+
+/// The results of `World::validate`.
+#[cfg(feature = "validation")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    /// The inconsistencies found.
+    pub issues: Vec<ValidationIssue>,
+}
+
+#[cfg(feature = "validation")]
+impl ValidationReport {
+    /// Determines if no inconsistencies were found.
+    pub fn is_valid(&self) -> bool { self.issues.is_empty() }
+}
+
+#[cfg(feature = "validation")]
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        if self.issues.is_empty() {
+            return writeln!(f, "world is consistent");
+        }
+
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Allocation statistics, as reported by `World::allocation_report`.
+///
+/// The counters are process-wide; they include allocations made by every world, including
+/// worlds which have since been dropped. Once all worlds have been dropped, each counter's
+/// `live` bytes should return to zero.
+#[cfg(feature = "alloc-tracking")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationReport {
+    /// Allocations of chunk component memory.
+    pub chunks: AllocationCounters,
+    /// Allocations of per-archetype tag storage.
+    pub tags: AllocationCounters,
+    /// Allocations of temporary tag values, made while moving entities between archetypes.
+    pub tag_sets: AllocationCounters,
+    /// The number of bytes of chunk memory currently held by this world.
+    pub chunk_bytes: usize,
+    /// The number of bytes of tag storage currently held by this world.
+    pub tag_bytes: usize,
+}
+
+/// The entities from which a tracked component type was removed, double buffered across
+/// `World::update_removed` calls.
+#[derive(Debug, Default)]
+struct RemovedBuffer {
+    previous: Vec<Entity>,
+    current: Vec<Entity>,
+}
+
+/// The location an entity was moved to by a structural change, as reported to the callbacks
+/// registered with `World::on_relocated`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// The moved entity.
+    pub entity: Entity,
+    /// The chunk now containing the entity.
+    pub chunk: ChunkId,
+    /// The entity's index within its chunk.
+    pub index: usize,
+}
+
+type RelocationCallback = Box<dyn FnMut(&[Relocation]) + Send + Sync>;
+
+/// The entities moved since the last `World::flush_relocations` call, along with the callbacks
+/// which are notified of their new locations.
+#[derive(Default)]
+struct RelocationTracker {
+    callbacks: Vec<RelocationCallback>,
+    moved: Vec<Entity>,
+}
+
+impl RelocationTracker {
+    fn record(&mut self, entity: Entity) {
+        if !self.callbacks.is_empty() {
+            self.moved.push(entity);
+        }
+    }
+}
+
+/// A summary of a single archetype, as reported by `World::describe`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchetypeReport {
+    /// The ID of the archetype.
+    pub id: ArchetypeId,
+    /// The names of the archetype's component types.
+    pub components: Vec<&'static str>,
+    /// The names of the archetype's tag types.
+    pub tags: Vec<&'static str>,
+    /// The number of distinct sets of tag values.
+    pub chunksets: usize,
+    /// The number of entities stored in each allocated chunk.
+    pub chunk_occupancy: Vec<usize>,
+    /// The number of entities each chunk can hold.
+    pub chunk_capacity: usize,
+    /// The number of entities currently stored in the archetype.
+    pub entities: usize,
+    /// The percentage of entity slots in allocated chunks which are unused.
+    pub fragmentation: f32,
+}
+
+/// A summary of every archetype in a world.
+///
+/// The `Display` impl writes one line per archetype followed by the occupancy of each of its
+/// chunks, which is useful for spotting archetype explosion and poorly packed chunks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldReport {
+    /// The summary of each archetype.
+    pub archetypes: Vec<ArchetypeReport>,
+    /// The number of live entities.
+    pub entities: usize,
+}
+
+impl Display for WorldReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} archetypes, {} entities",
+            self.archetypes.len(),
+            self.entities
+        )?;
+        for archetype in self.archetypes.iter() {
+            writeln!(
+                f,
+                "archetype {}: components [{}] tags [{}]",
+                archetype.id.index(),
+                archetype.components.join(", "),
+                archetype.tags.join(", ")
+            )?;
+            write!(
+                f,
+                "  {} chunksets, {} chunks, {} entities, {:.1}% fragmented:",
+                archetype.chunksets,
+                archetype.chunk_occupancy.len(),
+                archetype.entities,
+                archetype.fragmentation
+            )?;
+            for occupancy in archetype.chunk_occupancy.iter() {
+                write!(f, " {}/{}", occupancy, archetype.chunk_capacity)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The entities and components of a world, captured by `World::snapshot` and restored by
+/// `World::rollback`.
+pub struct WorldSnapshot {
+    world: WorldId,
+    storage: Storage,
+    entities: AllocatorSnapshot,
+}
+
+impl WorldSnapshot {
+    /// Gets the ID of the world the snapshot was taken of.
+    pub fn world(&self) -> WorldId { self.world }
+}
+
+/// Describes how much defragmentation `World::maintain` performs.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum DefragPolicy {
+    /// No defragmentation is performed.
+    #[default]
+    Never,
+    /// Up to the given number of entities are moved per call, resuming from where the
+    /// previous call stopped.
+    Incremental(usize),
+    /// The whole world is defragmented on each call.
+    Full,
+}
+
+/// The work performed by a call to `World::defrag`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct DefragReport {
+    /// The number of entities moved between chunks.
+    pub entities_moved: usize,
+    /// The number of chunks emptied and released.
+    pub chunks_freed: usize,
+    /// The number of archetypes which were fully defragmented.
+    pub archetypes_completed: usize,
+    /// Whether every archetype in the world is now defragmented, such that further calls will
+    /// not move any entities until the world is next modified.
+    pub finished: bool,
+}
+
 /// Contains queryable collections of data associated with `Entity`s.
 pub struct World {
     id: WorldId,
     storage: UnsafeCell<Storage>,
     pub(crate) entity_allocator: EntityAllocator,
+    registration: WorldRegistration,
     defrag_progress: usize,
+    defrag_policy: DefragPolicy,
+    deterministic_order: bool,
+    intern_tags: bool,
+    fixed_capacity: bool,
+    queued_commands: Vec<CommandBuffer>,
+    command_queue: CommandQueue,
+    maintained_version: u64,
 
     #[cfg(feature = "events")]
     channel: Channel<EntityEvent>,
+    #[cfg(feature = "events")]
+    world_channel: Channel<WorldEvent>,
 
     pub resources: Resources,
+    pub registry: Registry,
+    non_sync: NonSyncStorage,
+    name_index: Mutex<NameIndex>,
+    snapshots: HashMap<ComponentTypeId, Box<dyn SnapshotHistory>>,
+    removed: HashMap<ComponentTypeId, RemovedBuffer>,
+    relocations: RelocationTracker,
+    change_readers: HashMap<String, ChangeReader>,
+    // chunks reported by `drain_new_chunks` which have not since been reported as freed
+    drained_chunks: HashSet<ChunkId>,
+    #[cfg(feature = "spatial")]
+    spatial_index: Mutex<Option<SpatialIndex>>,
 }
 
+// with the `single-thread` feature, worlds contain non-atomic locks and so must stay on the
+// thread which created them
+#[cfg(not(feature = "single-thread"))]
 unsafe impl Send for World {}
 
+#[cfg(not(feature = "single-thread"))]
 unsafe impl Sync for World {}
 
 impl World {
@@ -126,26 +741,50 @@ impl World {
     /// `Universe::create_world`.
     pub fn new() -> Self {
         Self::new_in_universe(
-            WorldId(0),
+            Arc::new(WorldIds::default()).register(),
             EntityAllocator::new(Arc::new(Mutex::new(BlockAllocator::new()))),
         )
     }
 
-    fn new_in_universe(id: WorldId, allocator: EntityAllocator) -> Self {
+    fn new_in_universe(registration: WorldRegistration, allocator: EntityAllocator) -> Self {
+        let id = registration.id;
         Self {
             id,
             storage: UnsafeCell::new(Storage::new(id)),
             entity_allocator: allocator,
+            registration,
             defrag_progress: 0,
+            defrag_policy: DefragPolicy::default(),
+            deterministic_order: false,
+            intern_tags: false,
+            fixed_capacity: false,
+            queued_commands: Vec::new(),
+            command_queue: CommandQueue::default(),
+            maintained_version: 0,
             #[cfg(feature = "events")]
             channel: Channel::default(),
+            #[cfg(feature = "events")]
+            world_channel: Channel::default(),
             resources: Resources::default(),
+            registry: Registry::default(),
+            non_sync: NonSyncStorage::new(),
+            name_index: Mutex::new(NameIndex::default()),
+            snapshots: HashMap::new(),
+            removed: HashMap::new(),
+            relocations: RelocationTracker::default(),
+            change_readers: HashMap::new(),
+            drained_chunks: HashSet::new(),
+            #[cfg(feature = "spatial")]
+            spatial_index: Mutex::new(None),
         }
     }
 
     #[cfg(feature = "events")]
     pub fn entity_channel(&mut self) -> &mut Channel<EntityEvent> { &mut self.channel }
 
+    #[cfg(feature = "events")]
+    pub fn world_channel(&mut self) -> &mut Channel<WorldEvent> { &mut self.world_channel }
+
     pub(crate) fn storage(&self) -> &Storage { unsafe { &*self.storage.get() } }
 
     pub(crate) fn storage_mut(&mut self) -> &mut Storage { unsafe { &mut *self.storage.get() } }
@@ -153,728 +792,4880 @@ impl World {
     /// Gets the unique ID of this world within its universe.
     pub fn id(&self) -> WorldId { self.id }
 
-    /// Inserts new entities into the world.
+    /// Gets entity allocation statistics for this world.
+    pub fn entity_stats(&self) -> EntityAllocatorStats { self.entity_allocator.stats() }
+
+    /// Gets the number of live entities in the world.
     ///
-    /// # Examples
+    /// The count is maintained as entities are created and deleted, and so this does not need
+    /// to visit any chunks. Per-archetype counts are available from
+    /// `ArchetypeData::entity_count`.
+    pub fn len(&self) -> usize { self.entity_allocator.stats().live }
+
+    /// Determines if the world contains no entities.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Sets the behavior used when the version counter of a recycled entity index wraps.
+    pub fn set_version_wrap_behavior(&mut self, behavior: VersionWrapBehavior) {
+        self.entity_allocator.set_version_wrap_behavior(behavior);
+    }
+
+    /// Sets the order in which the indices of deleted entities are recycled.
     ///
-    /// Inserting entity tuples:
+    /// See `EntityAllocationOrder::Clustered` for a mode which keeps the entities of each chunk
+    /// close together in the entity location table.
+    pub fn set_entity_allocation_order(&mut self, order: EntityAllocationOrder) {
+        self.entity_allocator.set_allocation_order(order);
+    }
+
+    /// Determines if entity deletion preserves the relative order of the remaining entities.
+    pub fn deterministic_order(&self) -> bool { self.deterministic_order }
+
+    /// Sets whether entity deletion preserves the relative order of the remaining entities.
+    ///
+    /// When enabled, `delete` shifts all following entities in the deleted entity's chunk down
+    /// to fill the gap, rather than moving the last entity in the chunk into its place. This
+    /// makes iteration order depend only upon insertion order, at the cost of slower deletes.
+    pub fn set_deterministic_order(&mut self, enabled: bool) { self.deterministic_order = enabled; }
+
+    /// Determines if tag values are interned when chunk sets are created.
+    pub fn tag_interning(&self) -> bool { self.intern_tags }
+
+    /// Sets whether tag values are interned when chunk sets are created.
+    ///
+    /// When enabled, each tag value of a new chunk set is replaced with a clone of an equal value
+    /// already stored by another chunk set, if there is one. Tags which share their data when
+    /// cloned, such as `Arc` wrappers around large resources, are then stored once no matter how
+    /// many archetypes they are attached to, and comparisons between chunk sets holding the same
+    /// interned value take a pointer equality fast path.
+    ///
+    /// Interning compares each new tag value against every stored value of the same type, so it
+    /// is best suited to worlds in which chunk sets are created infrequently.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use legion::prelude::*;
+    /// # use std::sync::Arc;
     /// # #[derive(Copy, Clone, Debug, PartialEq)]
     /// # struct Position(f32);
     /// # #[derive(Copy, Clone, Debug, PartialEq)]
-    /// # struct Rotation(f32);
+    /// # struct Velocity(f32);
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Mesh(Arc<Vec<f32>>);
+    ///
     /// # let universe = Universe::new();
     /// # let mut world = universe.create_world();
-    /// # let model = 0u8;
-    /// # let color = 0u16;
-    /// let tags = (model, color);
-    /// let data = vec![
-    ///     (Position(0.0), Rotation(0.0)),
-    ///     (Position(1.0), Rotation(1.0)),
-    ///     (Position(2.0), Rotation(2.0)),
-    /// ];
-    /// world.insert(tags, data);
+    /// world.set_tag_interning(true);
+    ///
+    /// let a = world.insert((Mesh(Arc::new(vec![0.0; 1024])),), vec![(Position(0.0),)])[0];
+    /// let b = world.insert(
+    ///     (Mesh(Arc::new(vec![0.0; 1024])),),
+    ///     vec![(Position(0.0), Velocity(0.0))],
+    /// )[0];
+    ///
+    /// // the second mesh was replaced with the first
+    /// let mesh_a = world.get_tag::<Mesh>(a).unwrap();
+    /// let mesh_b = world.get_tag::<Mesh>(b).unwrap();
+    /// assert!(Arc::ptr_eq(&mesh_a.0, &mesh_b.0));
     /// ```
-    pub fn insert<T, C>(&mut self, mut tags: T, components: C) -> &[Entity]
-    where
-        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
-        C: IntoComponentSource,
-    {
-        let span = span!(Level::TRACE, "Inserting entities", world = self.id().0);
-        let _guard = span.enter();
+    pub fn set_tag_interning(&mut self, enabled: bool) { self.intern_tags = enabled; }
 
-        // find or create archetype
-        let mut components = components.into();
-        let archetype_index = self.find_or_create_archetype(&mut tags, &mut components);
+    /// Gets the number of elements which component columns are padded to a multiple of.
+    pub fn component_padding(&self) -> usize { self.storage().component_padding() }
 
-        // find or create chunk set
-        let chunk_set_index = self.find_or_create_chunk(archetype_index, &mut tags);
+    /// Pads the component columns of archetypes created after this call to a multiple of
+    /// `lanes` elements.
+    ///
+    /// The padding past the end of each column is kept zeroed, and is exposed by
+    /// `Chunk::components_padded`. Each column is already aligned to 64 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lanes` is not a power of two.
+    pub fn set_component_padding(&mut self, lanes: usize) {
+        self.storage_mut().set_component_padding(lanes);
+    }
 
-        self.entity_allocator.clear_allocation_buffer();
+    /// Gets the reduced capacity with which the first chunk of each chunk set is created, if
+    /// small chunks are enabled.
+    pub fn small_chunk_capacity(&self) -> Option<usize> { self.storage().small_chunk_capacity() }
 
-        // insert components into chunks
-        while !components.is_empty() {
-            // get chunk component storage
-            let archetype = unsafe {
-                (&mut *self.storage.get())
-                    .archetypes_mut()
-                    .get_unchecked_mut(archetype_index)
-            };
-            let chunk_index = archetype.get_free_chunk(chunk_set_index);
-            let chunk = unsafe {
-                archetype
-                    .chunksets_mut()
-                    .get_unchecked_mut(chunk_set_index)
-                    .get_unchecked_mut(chunk_index)
-            };
+    /// Creates the first chunk of each chunk set in archetypes created after this call with
+    /// room for only `capacity` entities, rather than a full chunk.
+    ///
+    /// A small chunk is grown to full size once it is full, before any further chunks are
+    /// created. This greatly reduces the memory used by worlds which hold only a few entities
+    /// of each archetype, such as those created by tools and tests, at the cost of copying the
+    /// chunk's components when it first grows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// let mut world = World::new();
+    /// world.set_small_chunk_capacity(Some(64));
+    ///
+    /// let entity = world.insert((), vec![(Position(0.0),)])[0];
+    /// let (_, _, chunk) = world.entity_archetype(entity).unwrap();
+    /// assert_eq!(64, world.chunk(chunk).unwrap().capacity());
+    ///
+    /// // the chunk grows in place once more entities are inserted
+    /// world.insert((), vec![(Position(1.0),); 64]);
+    /// assert!(world.chunk(chunk).unwrap().capacity() > 64);
+    /// assert_eq!(Position(0.0), *world.get_component::<Position>(entity).unwrap());
+    /// ```
+    pub fn set_small_chunk_capacity(&mut self, capacity: Option<usize>) {
+        self.storage_mut().set_small_chunk_capacity(capacity);
+    }
 
-            // insert as many components as we can into the chunk
-            let allocated = components.write(&mut self.entity_allocator, chunk);
+    /// Determines if the world is in fixed capacity mode.
+    pub fn fixed_capacity(&self) -> bool { self.fixed_capacity }
 
-            // record new entity locations
-            let start = chunk.len() - allocated;
-            let added = chunk.entities().iter().enumerate().skip(start);
-            for (i, e) in added {
-                let location =
-                    EntityLocation::new(archetype_index, chunk_set_index, chunk_index, i);
-                self.entity_allocator.set_location(e.index(), location);
+    /// Sets whether the world is in fixed capacity mode.
+    ///
+    /// A fixed capacity world never allocates memory for entity storage. Enabling the mode
+    /// allocates every existing chunk and keeps chunk memory alive while chunks are empty.
+    /// While enabled, `try_insert` returns an error rather than creating a new archetype,
+    /// chunk set, chunk or entity block, and all other operations which would do so panic.
+    ///
+    /// Capacity is reserved up front with `reserve`.
+    pub fn set_fixed_capacity(&mut self, enabled: bool) {
+        self.fixed_capacity = enabled;
+        for archetype in self.storage_mut().archetypes_mut() {
+            for chunkset in archetype.chunksets_mut() {
+                for chunk in chunkset.iter_mut() {
+                    chunk.set_retain_memory(enabled);
+                }
             }
         }
 
-        let entities = self.entity_allocator.allocation_buffer();
-
-        trace!(count = entities.len(), "Inserted entities");
-
-        #[cfg(all(feature = "events"))]
-        {
-            entities.par_iter().for_each(|e| {
-                self.channel
-                    .write(EntityEvent::Created(*e))
-                    .expect("Failed to write to WorldCreatedEvent channel.");
-            });
+        if enabled {
+            let required = self.total_chunk_capacity() - self.entity_allocator.stats().live;
+            self.entity_allocator.reserve(required);
         }
-
-        entities
     }
 
-    pub(crate) fn insert_buffered<T, C>(&mut self, entity: Entity, tags: T, components: C)
+    /// Preallocates space for `additional` more entities with the given tags and the component
+    /// layout of `components`, such that they can later be inserted without allocating.
+    ///
+    /// The data within `components` is not inserted; only its layout is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.reserve((), Vec::<(Position,)>::new(), 1000);
+    /// world.set_fixed_capacity(true);
+    ///
+    /// let data = (0..1000).map(|i| (Position(i as f32),));
+    /// assert!(world.try_insert((), data.collect::<Vec<_>>()).is_ok());
+    /// assert!(world.try_insert((0u8,), vec![(Position(0.0),)]).is_err());
+    /// ```
+    pub fn reserve<T, C>(&mut self, mut tags: T, components: C, additional: usize)
     where
         T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
         C: IntoComponentSource,
     {
-        let _ = (entity, tags, components);
-        unimplemented!()
+        let mut components = components.into();
+        let archetype_index = self.find_or_create_archetype(&mut tags, &mut components);
+        let chunk_set_index = self.find_or_create_chunk(archetype_index, &mut tags);
+
+        let archetype = &mut self.storage_mut().archetypes_mut()[archetype_index];
+        while archetype.free_space(chunk_set_index) < additional {
+            archetype.alloc_chunk(chunk_set_index);
+        }
+
+        for chunk in archetype.chunksets_mut()[chunk_set_index].iter_mut() {
+            chunk.set_retain_memory(true);
+        }
+
+        let required = self.total_chunk_capacity() - self.entity_allocator.stats().live;
+        self.entity_allocator.reserve(required);
     }
 
-    /// Removes the given `Entity` from the `World`.
+    /// Cross-checks the world's internal bookkeeping, reporting any inconsistencies.
+    ///
+    /// This verifies that the location recorded for every entity matches where it is stored,
+    /// that every chunk holds one component of each type per entity, that every tag type holds
+    /// one value per chunk set, and that chunk IDs match their position in storage. A valid world
+    /// never reports any issues; this is intended for catching bugs in code which manipulates
+    /// storage directly, such as through the raw component APIs.
+    ///
+    /// This function is only available with the `validation` feature enabled. It inspects every
+    /// entity in the world, and so is too slow to run every frame in release builds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(Position(0.0),), (Position(1.0),)]);
+    ///
+    /// let report = world.validate();
+    /// assert!(report.is_valid(), "{}", report);
+    /// ```
+    #[cfg(feature = "validation")]
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = Vec::new();
+        let mut stored = 0;
+
+        for (archetype_index, archetype) in self.storage().archetypes().iter().enumerate() {
+            let description = archetype.description();
+            for (tag, _) in description.tags() {
+                let tags = archetype.tags().get(*tag).map(|t| t.len()).unwrap_or(0);
+                if tags != archetype.len() {
+                    issues.push(ValidationIssue::TagCount {
+                        archetype: archetype.id(),
+                        tag: *tag,
+                        tags,
+                        chunksets: archetype.len(),
+                    });
+                }
+            }
+
+            for (set_index, set) in archetype.chunksets().iter().enumerate() {
+                for (chunk_index, chunk) in set.iter().enumerate() {
+                    let expected = ChunkId::new(archetype.id(), set_index, chunk_index);
+                    if chunk.id() != expected {
+                        issues.push(ValidationIssue::WrongChunkId {
+                            expected,
+                            found: chunk.id(),
+                        });
+                    }
+
+                    for (component, _) in description.components() {
+                        match chunk.components(*component) {
+                            None => issues.push(ValidationIssue::MissingComponent {
+                                chunk: chunk.id(),
+                                component: *component,
+                            }),
+                            Some(components) if components.len() != chunk.len() => {
+                                issues.push(ValidationIssue::ComponentCount {
+                                    chunk: chunk.id(),
+                                    component: *component,
+                                    components: components.len(),
+                                    entities: chunk.len(),
+                                })
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    stored += chunk.len();
+                    for (index, entity) in chunk.entities().iter().enumerate() {
+                        if !self.is_alive(*entity) {
+                            issues.push(ValidationIssue::DeadEntity {
+                                entity: *entity,
+                                chunk: chunk.id(),
+                            });
+                            continue;
+                        }
+
+                        let location =
+                            EntityLocation::new(archetype_index, set_index, chunk_index, index);
+                        if self.entity_allocator.get_location(entity.index()) != Some(location) {
+                            issues.push(ValidationIssue::WrongLocation {
+                                entity: *entity,
+                                chunk: chunk.id(),
+                                index,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let live = self.entity_allocator.stats().live;
+        if live != stored {
+            issues.push(ValidationIssue::EntityCount { live, stored });
+        }
+
+        ValidationReport { issues }
+    }
+
+    /// Reports the bytes allocated and freed by chunk and tag storage, alongside the number of
+    /// bytes currently held by this world.
+    ///
+    /// The allocation counters are shared by all worlds in the process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(Position(0.0),), (Position(1.0),)]);
+    ///
+    /// let report = world.allocation_report();
+    /// assert!(report.chunk_bytes > 0);
+    /// assert!(report.chunks.live() >= report.chunk_bytes);
+    /// ```
+    #[cfg(feature = "alloc-tracking")]
+    pub fn allocation_report(&self) -> AllocationReport {
+        let (chunks, tags, tag_sets) = allocation_counters();
+        let mut chunk_bytes = 0;
+        let mut tag_bytes = 0;
+        for archetype in self.storage().archetypes() {
+            tag_bytes += archetype.tags().allocated_bytes();
+            chunk_bytes += archetype
+                .chunksets()
+                .iter()
+                .flat_map(|set| set.iter())
+                .map(|chunk| chunk.allocated_bytes())
+                .sum::<usize>();
+        }
+
+        AllocationReport {
+            chunks,
+            tags,
+            tag_sets,
+            chunk_bytes,
+            tag_bytes,
+        }
+    }
+
+    /// Reports the storage capacities required by the world's current contents.
+    pub fn capacity_report(&self) -> CapacityReport {
+        let archetypes = self
+            .storage()
+            .archetypes()
+            .iter()
+            .map(|archetype| {
+                let description = archetype.description();
+                ArchetypeCapacity {
+                    components: description.component_names().to_vec(),
+                    tags: description.tag_names().to_vec(),
+                    chunksets: archetype.len(),
+                    chunks: archetype.chunksets().iter().map(|set| set.len()).sum(),
+                    chunk_capacity: archetype.layout().capacity(),
+                    entities: archetype.entity_count(),
+                }
+            })
+            .collect();
+
+        let stats = self.entity_allocator.stats();
+        CapacityReport {
+            archetypes,
+            entities: stats.live,
+            free_entity_slots: self.entity_allocator.free_slots(),
+            entity_blocks: stats.blocks_in_use,
+        }
+    }
+
+    /// Gets the archetype with the given ID.
+    ///
+    /// Returns `None` if the archetype does not belong to this world.
+    pub fn archetype(&self, id: ArchetypeId) -> Option<&ArchetypeData> {
+        self.storage()
+            .archetypes()
+            .get(id.index())
+            .filter(|archetype| archetype.id() == id)
+    }
+
+    /// Gets the archetype an entity is stored in, along with the index of its chunk set within
+    /// the archetype and the ID of its chunk.
+    ///
+    /// Returns `None` if the entity is not alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # struct Model(u32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((Model(5),), vec![(Position(0.0),)])[0];
+    ///
+    /// let (archetype, _, chunk) = world.entity_archetype(entity).unwrap();
+    /// assert_eq!(archetype, chunk.archetype_id());
+    ///
+    /// let description = world.archetype(archetype).unwrap().description();
+    /// assert!(description.has_component::<Position>());
+    /// assert!(description.has_tag::<Model>());
+    /// ```
+    pub fn entity_archetype(&self, entity: Entity) -> Option<(ArchetypeId, usize, ChunkId)> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = &self.storage().archetypes()[location.archetype()];
+        let chunk = &archetype.chunksets()[location.set()][location.chunk()];
+        Some((archetype.id(), location.set(), chunk.id()))
+    }
+
+    /// Gets the description of the archetype an entity is stored in.
+    ///
+    /// Returns `None` if the entity is not alive.
+    pub fn entity_description(&self, entity: Entity) -> Option<&ArchetypeDescription> {
+        let (archetype, _, _) = self.entity_archetype(entity)?;
+        self.archetype(archetype)
+            .map(|archetype| archetype.description())
+    }
+
+    /// Attaches user data to an archetype, such as derived data cached by a renderer, returning
+    /// the data previously attached. The data is read with `ArchetypeData::user_data`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// struct RenderBatch(u32);
+    ///
+    /// world.insert((), vec![(Position(0.0),)]);
+    /// let mut query = Read::<Position>::query();
+    /// let archetype = query.iter_chunks(&mut world).next().unwrap().id().archetype_id();
+    ///
+    /// world.set_archetype_user_data(archetype, Box::new(RenderBatch(7)));
+    ///
+    /// for chunk in query.iter_chunks(&mut world) {
+    ///     let batch = chunk.archetype().user_data().unwrap().downcast_ref::<RenderBatch>();
+    ///     assert_eq!(7, batch.unwrap().0);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype does not belong to this world.
+    pub fn set_archetype_user_data(
+        &mut self,
+        archetype: ArchetypeId,
+        data: UserData,
+    ) -> Option<UserData> {
+        self.archetype_data_mut(archetype).set_user_data(data)
+    }
+
+    /// Mutably borrows the user data attached to an archetype.
+    ///
+    /// Returns `None` if the archetype does not belong to this world or has no user data.
+    pub fn archetype_user_data_mut(
+        &mut self,
+        archetype: ArchetypeId,
+    ) -> Option<&mut (dyn Any + Send + Sync)> {
+        self.archetype(archetype)?;
+        self.archetype_data_mut(archetype).user_data_mut()
+    }
+
+    /// Attaches user data to a chunk set of an archetype, returning the data previously attached.
+    /// The data is read with `Chunkset::user_data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype does not belong to this world, or does not have the chunk set.
+    pub fn set_chunkset_user_data(
+        &mut self,
+        archetype: ArchetypeId,
+        set: usize,
+        data: UserData,
+    ) -> Option<UserData> {
+        self.archetype_data_mut(archetype).chunksets_mut()[set].set_user_data(data)
+    }
+
+    /// Mutably borrows the user data attached to a chunk set of an archetype.
+    ///
+    /// Returns `None` if the archetype does not belong to this world, does not have the chunk
+    /// set, or the chunk set has no user data.
+    pub fn chunkset_user_data_mut(
+        &mut self,
+        archetype: ArchetypeId,
+        set: usize,
+    ) -> Option<&mut (dyn Any + Send + Sync)> {
+        self.archetype(archetype)?;
+        self.archetype_data_mut(archetype)
+            .chunksets_mut()
+            .get_mut(set)?
+            .user_data_mut()
+    }
+
+    /// Gets the chunk with the given ID.
+    ///
+    /// Returns `None` if the chunk does not belong to this world.
+    pub fn chunk(&self, id: ChunkId) -> Option<&ComponentStorage> {
+        self.archetype(id.archetype_id())?
+            .chunksets()
+            .get(id.set())?
+            .get(id.index())
+    }
+
+    /// Gets the IDs of all chunks which have allocated memory since the last call, such that
+    /// a renderer can create GPU-side buffers for them.
+    ///
+    /// Chunks allocate memory when their first entity is inserted, and release it when their
+    /// last entity is removed, which is reported by `drain_freed_chunks`. Both functions should
+    /// be called together, as a chunk is only reported as new again once it has been reported
+    /// as freed. Chunks are yielded in storage order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Position(0.0),)])[0];
+    ///
+    /// let created = world.drain_new_chunks();
+    /// assert_eq!(1, created.len());
+    /// assert_eq!(&[entity], world.chunk(created[0]).unwrap().entities());
+    /// assert!(world.drain_new_chunks().is_empty());
+    ///
+    /// world.delete(entity);
+    /// assert_eq!(created, world.drain_freed_chunks());
+    /// ```
+    pub fn drain_new_chunks(&mut self) -> Vec<ChunkId> {
+        let created = self
+            .allocated_chunks()
+            .into_iter()
+            .filter(|id| !self.drained_chunks.contains(id))
+            .collect::<Vec<_>>();
+        self.drained_chunks.extend(created.iter().copied());
+        created
+    }
+
+    /// Gets the IDs of all chunks reported by `drain_new_chunks` which have since released
+    /// their memory, such that a renderer can destroy the GPU-side buffers it created for them.
+    ///
+    /// A chunk which is freed and reallocated between calls is not reported. Chunks are
+    /// yielded in no particular order.
+    pub fn drain_freed_chunks(&mut self) -> Vec<ChunkId> {
+        let allocated = self.allocated_chunks().into_iter().collect::<HashSet<_>>();
+        let freed = self
+            .drained_chunks
+            .iter()
+            .filter(|id| !allocated.contains(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for id in &freed {
+            self.drained_chunks.remove(id);
+        }
+        freed
+    }
+
+    // Gets the IDs of all chunks which have their memory allocated, in storage order.
+    fn allocated_chunks(&self) -> Vec<ChunkId> {
+        self.storage()
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.chunksets().iter())
+            .flat_map(|set| set.iter())
+            .filter(|chunk| chunk.is_allocated())
+            .map(|chunk| chunk.id())
+            .collect()
+    }
+
+    fn archetype_data_mut(&mut self, id: ArchetypeId) -> &mut ArchetypeData {
+        assert!(
+            self.archetype(id).is_some(),
+            "archetype does not belong to this world"
+        );
+        &mut self.storage_mut().archetypes_mut()[id.index()]
+    }
+
+    /// Describes every archetype in the world, including its component and tag names and how
+    /// well its chunks are occupied.
+    pub fn describe(&self) -> WorldReport {
+        let archetypes = self
+            .storage()
+            .archetypes()
+            .iter()
+            .map(|archetype| {
+                let description = archetype.description();
+                let chunk_occupancy = archetype
+                    .chunksets()
+                    .iter()
+                    .flat_map(|set| set.iter())
+                    .filter(|chunk| chunk.is_allocated())
+                    .map(|chunk| chunk.len())
+                    .collect::<Vec<_>>();
+                let chunk_capacity = archetype.layout().capacity();
+                let entities = chunk_occupancy.iter().sum::<usize>();
+                let slots = chunk_occupancy.len() * chunk_capacity;
+                let fragmentation = if slots == 0 {
+                    0.0
+                } else {
+                    (slots - entities) as f32 / slots as f32 * 100.0
+                };
+
+                ArchetypeReport {
+                    id: archetype.id(),
+                    components: description.component_names().to_vec(),
+                    tags: description.tag_names().to_vec(),
+                    chunksets: archetype.len(),
+                    chunk_occupancy,
+                    chunk_capacity,
+                    entities,
+                    fragmentation,
+                }
+            })
+            .collect();
+
+        WorldReport {
+            archetypes,
+            entities: self.entity_allocator.stats().live,
+        }
+    }
+
+    fn total_chunk_capacity(&self) -> usize {
+        self.storage()
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.chunksets().iter())
+            .flat_map(|set| set.iter())
+            .map(|chunk| chunk.capacity())
+            .sum()
+    }
+
+    fn assert_growable(&self, error: CapacityError) {
+        if self.fixed_capacity {
+            panic!("fixed capacity world would need to allocate: {}", error);
+        }
+    }
+
+    /// Inserts new entities into the world.
+    ///
+    /// # Examples
+    ///
+    /// Inserting entity tuples:
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Rotation(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// # let model = 0u8;
+    /// # let color = 0u16;
+    /// let tags = (model, color);
+    /// let data = vec![
+    ///     (Position(0.0), Rotation(0.0)),
+    ///     (Position(1.0), Rotation(1.0)),
+    ///     (Position(2.0), Rotation(2.0)),
+    /// ];
+    /// world.insert(tags, data);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the world is in fixed capacity mode and the entities do not
+    /// fit within its reserved capacity. See `try_insert`.
+    pub fn insert<T, C>(&mut self, tags: T, components: C) -> InsertedEntities<'_>
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: IntoComponentSource,
+    {
+        self.insert_source(tags, components.into())
+    }
+
+    fn insert_source<T, C>(&mut self, mut tags: T, mut components: C) -> InsertedEntities<'_>
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: ComponentSource,
+    {
+        if self.fixed_capacity {
+            return self
+                .try_insert_source(tags, components)
+                .unwrap_or_else(|err| panic!("failed to insert entities: {}", err));
+        }
+
+        let span = span!(Level::TRACE, "Inserting entities", world = self.id().0);
+        let _guard = span.enter();
+
+        // find or create archetype
+        let archetype_index = self.find_or_create_archetype(&mut tags, &mut components);
+
+        // find or create chunk set
+        let chunk_set_index = self.find_or_create_chunk(archetype_index, &mut tags);
+
+        self.insert_into(archetype_index, chunk_set_index, components)
+    }
+
+    /// Inserts new entities with the given tags into the world.
+    ///
+    /// This is the tagged equivalent of extending the world with an iterator of component
+    /// tuples, for use in pipelines which do not need the newly created entities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Model(u32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.extend((0..10).map(|i| (Position(i as f32),)));
+    /// world.extend_tagged((Model(5),), (0..10).map(|i| (Position(i as f32),)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the world is in fixed capacity mode and the entities do not
+    /// fit within its reserved capacity.
+    pub fn extend_tagged<T, C>(&mut self, tags: T, components: C)
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: IntoComponentSource,
+    {
+        self.insert(tags, components);
+    }
+
+    /// Inserts new entities into the world, returning an error rather than allocating if the
+    /// world is in fixed capacity mode and the entities do not fit within its reserved capacity.
+    ///
+    /// In fixed capacity mode, the component source must report its length. Nothing is
+    /// inserted if an error is returned.
+    pub fn try_insert<T, C>(
+        &mut self,
+        tags: T,
+        components: C,
+    ) -> Result<InsertedEntities<'_>, CapacityError>
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: IntoComponentSource,
+    {
+        self.try_insert_source(tags, components.into())
+    }
+
+    fn try_insert_source<T, C>(
+        &mut self,
+        mut tags: T,
+        mut components: C,
+    ) -> Result<InsertedEntities<'_>, CapacityError>
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: ComponentSource,
+    {
+        if !self.fixed_capacity {
+            return Ok(self.insert_source(tags, components));
+        }
+
+        let span = span!(Level::TRACE, "Inserting entities", world = self.id().0);
+        let _guard = span.enter();
+
+        let count = components.remaining().ok_or(CapacityError::UnknownLength)?;
+
+        let archetype_index = self
+            .find_archetype(tags.get_filter(), components.get_filter())
+            .ok_or(CapacityError::Archetype)?;
+        let chunk_set_index = self
+            .find_chunk_set(archetype_index, &mut tags)
+            .ok_or(CapacityError::Chunkset)?;
+
+        let available = self.storage().archetypes()[archetype_index].free_space(chunk_set_index);
+        if available < count {
+            return Err(CapacityError::Chunks {
+                required: count,
+                available,
+            });
+        }
+
+        let available = self.entity_allocator.free_slots();
+        if available < count {
+            return Err(CapacityError::Entities {
+                required: count,
+                available,
+            });
+        }
+
+        Ok(self.insert_into(archetype_index, chunk_set_index, components))
+    }
+
+    /// Inserts new entities into the world, returning an error if the entities could not be
+    /// inserted.
+    ///
+    /// Unlike `insert`, this checks that the tag and component layout does not contain any
+    /// type more than once, and reports capacity exhaustion in fixed capacity mode as an
+    /// error rather than panicking. Nothing is inserted if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// use legion::error::Error;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// assert!(world.insert_checked((), vec![(Position(0.0),)]).is_ok());
+    ///
+    /// let duplicated = world.insert_checked((), vec![(Position(0.0), Position(1.0))]);
+    /// assert!(matches!(duplicated, Err(Error::DuplicateComponent(_))));
+    /// ```
+    pub fn insert_checked<T, C>(
+        &mut self,
+        tags: T,
+        components: C,
+    ) -> Result<InsertedEntities<'_>, Error>
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: IntoComponentSource,
+    {
+        let components = components.into();
+
+        let mut description = ArchetypeDescription::default();
+        tags.tailor_archetype(&mut description);
+        components.tailor_archetype(&mut description);
+
+        let tag_types = description.tags();
+        for (i, (type_id, _)) in tag_types.iter().enumerate() {
+            if tag_types[..i].iter().any(|(t, _)| t == type_id) {
+                return Err(Error::DuplicateTag(*type_id));
+            }
+        }
+
+        let component_types = description.components();
+        for (i, (type_id, _)) in component_types.iter().enumerate() {
+            if component_types[..i].iter().any(|(t, _)| t == type_id) {
+                return Err(Error::DuplicateComponent(*type_id));
+            }
+        }
+
+        Ok(self.try_insert_source(tags, components)?)
+    }
+
+    fn insert_into<C: ComponentSource>(
+        &mut self,
+        archetype_index: usize,
+        chunk_set_index: usize,
+        mut components: C,
+    ) -> InsertedEntities<'_> {
+        // the (chunk, start, count) of each run of new entities
+        let mut runs = Vec::new();
+
+        // insert components into chunks
+        while !components.is_empty() {
+            // get chunk component storage
+            let archetype = unsafe {
+                (&mut *self.storage.get())
+                    .archetypes_mut()
+                    .get_unchecked_mut(archetype_index)
+            };
+            let chunk_index = archetype.get_free_chunk(chunk_set_index);
+            let chunk = unsafe {
+                archetype
+                    .chunksets_mut()
+                    .get_unchecked_mut(chunk_set_index)
+                    .get_unchecked_mut(chunk_index)
+            };
+
+            // insert as many components as we can into the chunk
+            let allocated = components.write(&mut self.entity_allocator, chunk);
+
+            // record new entity locations
+            let start = chunk.len() - allocated;
+            let added = chunk.entities().iter().enumerate().skip(start);
+            for (i, e) in added {
+                let location =
+                    EntityLocation::new(archetype_index, chunk_set_index, chunk_index, i);
+                self.entity_allocator.set_location(e.index(), location);
+            }
+
+            runs.push((chunk_index, start, allocated));
+        }
+
+        let chunkset = &self.storage().archetypes()[archetype_index].chunksets()[chunk_set_index];
+        let entities = InsertedEntities::new(
+            runs.into_iter()
+                .map(|(chunk, start, count)| &chunkset[chunk].entities()[start..start + count])
+                .collect(),
+        );
+
+        trace!(count = entities.len(), "Inserted entities");
+
+        #[cfg(all(feature = "events", feature = "rayon", not(feature = "single-thread")))]
+        {
+            entities.slices.par_iter().for_each(|slice| {
+                slice.par_iter().for_each(|e| {
+                    self.channel
+                        .write(EntityEvent::Created(*e))
+                        .expect("Failed to write to WorldCreatedEvent channel.");
+                });
+            });
+        }
+
+        #[cfg(all(
+            feature = "events",
+            any(not(feature = "rayon"), feature = "single-thread")
+        ))]
+        {
+            for e in entities.iter() {
+                self.channel
+                    .write(EntityEvent::Created(*e))
+                    .expect("Failed to write to WorldCreatedEvent channel.");
+            }
+        }
+
+        entities
+    }
+
+    /// Starts building a single entity with any number of components and tags, which is
+    /// inserted into the world when the builder's `build` function is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Rotation(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Model(u32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world
+    ///     .spawn()
+    ///     .with(Position(1.0))
+    ///     .with(Rotation(0.5))
+    ///     .with_tag(Model(3))
+    ///     .build();
+    ///
+    /// assert_eq!(Position(1.0), *world.get_component::<Position>(entity).unwrap());
+    /// assert_eq!(Some(&Model(3)), world.get_tag::<Model>(entity));
+    /// ```
+    pub fn spawn(&mut self) -> EntitySpawner<'_> {
+        EntitySpawner {
+            world: self,
+            builder: DynamicEntityBuilder::new(),
+        }
+    }
+
+    /// Inserts new entities into the world, where each entity may have a different set of
+    /// components and tags.
+    ///
+    /// The builders are grouped by layout internally, such that all entities which share the
+    /// same components and tag values are written into their chunks together. The new entities
+    /// are returned in the same order as the builders they were created from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::world::DynamicEntityBuilder;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Rotation(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let scene = vec![
+    ///     DynamicEntityBuilder::new().with_component(Position(0.0)),
+    ///     DynamicEntityBuilder::new()
+    ///         .with_component(Position(1.0))
+    ///         .with_component(Rotation(1.0))
+    ///         .with_tag(5u32),
+    /// ];
+    /// let entities = world.insert_dynamic(scene);
+    ///
+    /// assert!(world.get_component::<Rotation>(entities[0]).is_none());
+    /// assert_eq!(Some(&5u32), world.get_tag::<u32>(entities[1]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the world is in fixed capacity mode and the entities do not
+    /// fit within its reserved capacity.
+    pub fn insert_dynamic<I>(&mut self, builders: I) -> Vec<Entity>
+    where
+        I: IntoIterator<Item = DynamicEntityBuilder>,
+    {
+        let span = span!(
+            Level::TRACE,
+            "Inserting dynamic entities",
+            world = self.id().0
+        );
+        let _guard = span.enter();
+
+        // group builders by their target chunkset, preserving the order each was first seen
+        let mut archetypes = HashMap::new();
+        let mut group_indices = HashMap::new();
+        let mut groups: Vec<(usize, usize, Vec<(usize, DynamicEntityBuilder)>)> = Vec::new();
+        let mut count = 0;
+        for (i, builder) in builders.into_iter().enumerate() {
+            let archetype = *archetypes
+                .entry(builder.layout_key())
+                .or_insert_with(|| self.find_or_create_dynamic_archetype(&builder));
+            let set = self.find_or_create_dynamic_chunk_set(archetype, &builder);
+
+            let group = *group_indices.entry((archetype, set)).or_insert_with(|| {
+                groups.push((archetype, set, Vec::new()));
+                groups.len() - 1
+            });
+            groups[group].2.push((i, builder));
+            count = i + 1;
+        }
+
+        let mut entities = Vec::with_capacity(count);
+        for (archetype, set, group) in groups {
+            if self.fixed_capacity {
+                let available = self.storage().archetypes()[archetype].free_space(set);
+                if available < group.len() {
+                    self.assert_growable(CapacityError::Chunks {
+                        required: group.len(),
+                        available,
+                    });
+                }
+            }
+
+            let (indices, builders): (Vec<_>, Vec<_>) = group.into_iter().unzip();
+            let source = DynamicComponentSource {
+                components: builders[0].components.clone(),
+                builders: builders.into_iter(),
+            };
+            let inserted = self.insert_into(archetype, set, source);
+            entities.extend(indices.into_iter().zip(inserted.iter().copied()));
+        }
+
+        entities.sort_by_key(|(i, _)| *i);
+        entities.into_iter().map(|(_, e)| e).collect()
+    }
+
+    fn find_or_create_dynamic_archetype(&mut self, builder: &DynamicEntityBuilder) -> usize {
+        let result = {
+            let mut component_layout = DynamicComponentLayout {
+                existing: &[],
+                add: &builder.components,
+                remove: &[],
+            };
+            let mut tag_layout = DynamicTagLayout {
+                storage: self.storage(),
+                archetype: 0,
+                set: 0,
+                existing: &[],
+                add: &builder.tags,
+                remove: &[],
+            };
+
+            match self.find_archetype(&mut tag_layout, &mut component_layout) {
+                Some(archetype) => Ok(archetype),
+                None => {
+                    let mut description = ArchetypeDescription::default();
+                    component_layout.tailor_archetype(&mut description);
+                    tag_layout.tailor_archetype(&mut description);
+                    Err(description)
+                }
+            }
+        };
+
+        match result {
+            Ok(archetype) => archetype,
+            Err(description) => {
+                self.assert_growable(CapacityError::Archetype);
+                let (index, _) = self.storage_mut().alloc_archetype(description);
+                index
+            }
+        }
+    }
+
+    fn find_or_create_dynamic_chunk_set(
+        &mut self,
+        archetype: usize,
+        builder: &DynamicEntityBuilder,
+    ) -> usize {
+        let mut tag_layout = DynamicTagLayout {
+            storage: self.storage(),
+            archetype,
+            set: 0,
+            existing: &[],
+            add: &builder.tags,
+            remove: &[],
+        };
+        if let Some(set) = self.find_chunk_set(archetype, &mut tag_layout) {
+            return set;
+        }
+
+        let mut tags = DynamicTagSet::new();
+        for (type_id, meta, ptr) in builder.tags.iter() {
+            tags.push(*type_id, *meta, *ptr);
+        }
+
+        self.assert_growable(CapacityError::Chunkset);
+        self.create_chunk_set(archetype, &tags)
+    }
+
+    /// Inserts new entities into the world, attaching a default value of each of the
+    /// component types in `defaults` to every entity.
+    ///
+    /// This allows entities to be created in an archetype that includes components which
+    /// are not present in the source data, such as when applying prefab overrides.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any of the types in `defaults` have not been registered with
+    /// `Registry::register_with_default`, or if they are already present in `components`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::storage::ComponentTypeId;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// #[derive(Default)]
+    /// struct Health(u32);
+    ///
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.registry.register_with_default::<Health>();
+    ///
+    /// let entities = world
+    ///     .insert_with_defaults(
+    ///         (),
+    ///         vec![(Position(0.0),), (Position(1.0),)],
+    ///         &[ComponentTypeId::of::<Health>()],
+    ///     )
+    ///     .to_vec();
+    ///
+    /// assert_eq!(0, world.get_component::<Health>(entities[0]).unwrap().0);
+    /// ```
+    pub fn insert_with_defaults<T, C>(
+        &mut self,
+        tags: T,
+        components: C,
+        defaults: &[ComponentTypeId],
+    ) -> InsertedEntities<'_>
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: IntoComponentSource,
+    {
+        let defaults = defaults
+            .iter()
+            .map(|type_id| {
+                let registration = self
+                    .registry
+                    .get(*type_id)
+                    .filter(|r| r.has_default())
+                    .unwrap_or_else(|| {
+                        panic!("component type {:?} has no registered default", type_id)
+                    });
+                (*type_id, *registration)
+            })
+            .collect();
+
+        let components = DefaultedComponentSource::new(components.into(), defaults);
+        self.insert(tags, components)
+    }
+
+    pub(crate) fn insert_buffered<T, C>(&mut self, entity: Entity, tags: T, components: C)
+    where
+        T: TagSet + TagLayout + for<'a> Filter<ChunksetFilterData<'a>>,
+        C: IntoComponentSource,
+    {
+        let _ = (entity, tags, components);
+        unimplemented!()
+    }
+
+    /// Removes the given `Entity` from the `World`.
     ///
     /// Returns `true` if the entity was deleted; else `false`.
-    pub fn delete(&mut self, entity: Entity) -> bool {
+    pub fn delete(&mut self, entity: Entity) -> bool { self.delete_entity(entity, true) }
+
+    /// Removes the given `Entity` from the `World`.
+    ///
+    /// Returns an error if the entity is not alive.
+    pub fn delete_checked(&mut self, entity: Entity) -> Result<(), Error> {
+        if self.delete(entity) {
+            Ok(())
+        } else {
+            Err(EntityError::NotAlive(entity).into())
+        }
+    }
+
+    /// Removes all entities which match `filter` from the `World`.
+    ///
+    /// Whole chunks are cleared at once, running each component type's drop function in a
+    /// single pass and releasing the chunk's memory, rather than removing entities one at a
+    /// time.
+    ///
+    /// Returns the number of entities deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Bullet;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((Bullet,), vec![(Position(0.0),), (Position(1.0),)]);
+    /// let player = world.insert((), vec![(Position(2.0),)])[0];
+    ///
+    /// assert_eq!(2, world.delete_all(tag::<Bullet>()));
+    /// assert!(world.is_alive(player));
+    /// ```
+    pub fn delete_all<F: EntityFilter>(&mut self, mut filter: F) -> usize {
+        let span = span!(Level::TRACE, "Deleting entities", world = self.id().0);
+        let _guard = span.enter();
+
+        let mut count = 0;
+        for (archetype, set, chunks) in self.matching_chunks(&mut filter) {
+            count += self.delete_chunkset(archetype, set, &chunks);
+        }
+
+        count
+    }
+
+    /// Removes each of the given entities from the `World`.
+    ///
+    /// This is faster than calling `delete` for each entity. The entities are freed in one pass
+    /// over the entity allocator, then grouped by chunk, such that each chunk is found once and
+    /// the locations of the entities moved to fill the gaps are written once per chunk.
+    ///
+    /// Entities which are not alive are ignored. Returns the number of entities deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entities = world
+    ///     .insert((), vec![(Position(0.0),), (Position(1.0),), (Position(2.0),)])
+    ///     .to_vec();
+    ///
+    /// assert_eq!(2, world.delete_all_entities(&entities[..2]));
+    /// assert!(!world.is_alive(entities[0]));
+    /// assert_eq!(Some(Position(2.0)), world.get_component(entities[2]).map(|p| *p));
+    /// ```
+    pub fn delete_all_entities(&mut self, entities: &[Entity]) -> usize {
+        let span = span!(Level::TRACE, "Deleting entities", world = self.id().0);
+        let _guard = span.enter();
+
+        let mut deleted = self.entity_allocator.delete_entities(entities);
+
+        // within each chunk, remove from the back first so that the entities moved to fill
+        // each gap are never among those still to be removed
+        deleted.sort_unstable_by_key(|(_, location)| {
+            (
+                location.archetype(),
+                location.set(),
+                location.chunk(),
+                Reverse(location.component()),
+            )
+        });
+
+        for group in deleted.chunk_by(|(_, a), (_, b)| {
+            (a.archetype(), a.set(), a.chunk()) == (b.archetype(), b.set(), b.chunk())
+        }) {
+            let location = group[0].1;
+            let components = if self.removed.is_empty() {
+                Vec::new()
+            } else {
+                self.storage().archetypes()[location.archetype()]
+                    .description()
+                    .components()
+                    .iter()
+                    .map(|(type_id, _)| *type_id)
+                    .collect::<Vec<_>>()
+            };
+
+            for (entity, _) in group {
+                #[cfg(feature = "events")]
+                {
+                    self.channel
+                        .write(EntityEvent::Deleted(*entity))
+                        .expect("Failed to write to EntityEvent::Deleted channel.");
+                }
+
+                self.record_removed(*entity, &components);
+                self.non_sync.remove_entity(*entity);
+            }
+
+            let chunk = &mut self.storage.get_mut().archetypes_mut()[location.archetype()]
+                .chunksets_mut()[location.set()][location.chunk()];
+            for (_, location) in group {
+                if self.deterministic_order {
+                    chunk.shift_remove(location.component(), true);
+                } else {
+                    chunk.swap_remove(location.component(), true);
+                }
+            }
+
+            // every entity at or after the first gap may have moved
+            let first = group[group.len() - 1].1.component();
+            for (i, entity) in chunk.entities().iter().enumerate().skip(first) {
+                self.entity_allocator.set_location(
+                    entity.index(),
+                    EntityLocation::new(location.archetype(), location.set(), location.chunk(), i),
+                );
+                self.relocations.record(*entity);
+            }
+        }
+
+        let count = deleted.len();
+        trace!(world = self.id().0, count, "Deleted entities");
+
+        count
+    }
+
+    // Deletes all entities stored in the given chunks of a chunk set.
+    fn delete_chunkset(&mut self, archetype: usize, set: usize, chunks: &[usize]) -> usize {
+        let components = self.storage().archetypes()[archetype]
+            .description()
+            .components()
+            .iter()
+            .map(|(type_id, _)| *type_id)
+            .collect::<Vec<_>>();
+
+        let mut count = 0;
+        for index in chunks {
+            let chunk = &mut self.storage.get_mut().archetypes_mut()[archetype].chunksets_mut()
+                [set][*index];
+            for entity in chunk.clear(true) {
+                #[cfg(feature = "events")]
+                {
+                    self.channel
+                        .write(EntityEvent::Deleted(entity))
+                        .expect("Failed to write to EntityEvent::Deleted channel.");
+                }
+
+                self.entity_allocator.delete_entity(entity);
+                self.record_removed(entity, &components);
+                self.non_sync.remove_entity(entity);
+                count += 1;
+            }
+        }
+
+        trace!(world = self.id().0, archetype, set, count, "Deleted chunks");
+
+        count
+    }
+
+    /// Removes the given `Entity` from the `World`, returning its component values.
+    ///
+    /// The components are moved into the returned bag rather than dropped, allowing the caller
+    /// to take ownership of any of them. The entity's tags are also copied into the bag, so that
+    /// it can be re-inserted as a new entity with `into_builder`.
+    ///
+    /// Returns `None` if the entity was not found. Non-`Sync` components are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # struct Sword(String);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let item = world.insert((), vec![(Sword("excalibur".to_string()), Position(0.0))])[0];
+    ///
+    /// let mut inventory = Vec::new();
+    /// if let Some(mut bag) = world.remove_entity(item) {
+    ///     inventory.extend(bag.take::<Sword>());
+    /// }
+    ///
+    /// assert!(!world.is_alive(item));
+    /// assert_eq!(vec![Sword("excalibur".to_string())], inventory);
+    /// ```
+    pub fn remove_entity(&mut self, entity: Entity) -> Option<EntityBag> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = &self.storage().archetypes()[location.archetype()];
+        let chunk = &archetype.chunksets()[location.set()][location.chunk()];
+
+        let mut builder = DynamicEntityBuilder::new();
+        unsafe {
+            for (type_id, meta) in archetype.description().components() {
+                let (ptr, element_size, _) = chunk.components(*type_id).unwrap().data_raw();
+                builder.move_component_raw(
+                    *type_id,
+                    *meta,
+                    ptr.add(location.component() * element_size),
+                );
+            }
+            for (type_id, meta) in archetype.description().tags() {
+                let (ptr, element_size, _) = archetype.tags().get(*type_id).unwrap().data_raw();
+                builder.clone_tag_raw(
+                    *type_id,
+                    *meta,
+                    ptr.as_ptr().add(location.set() * element_size),
+                );
+            }
+        }
+
+        // the components now belong to the bag, so they must not be dropped by the chunk
+        self.delete_entity(entity, false);
+
+        Some(EntityBag { entity, builder })
+    }
+
+    fn delete_entity(&mut self, entity: Entity, drop: bool) -> bool {
+        #[cfg(feature = "events")]
+        {
+            self.channel
+                .write(EntityEvent::Deleted(entity))
+                .expect("Failed to write to EntityEvent::Deleted channel.");
+        }
+
+        if let Some(location) = self.entity_allocator.delete_entity(entity) {
+            if !self.removed.is_empty() {
+                let archetype = &unsafe { &*self.storage.get() }.archetypes()[location.archetype()];
+                let components = archetype
+                    .description()
+                    .components()
+                    .iter()
+                    .map(|(type_id, _)| *type_id)
+                    .collect::<Vec<_>>();
+                self.record_removed(entity, &components);
+            }
+
+            // find entity's chunk
+            let chunk = unsafe { &mut *self.storage.get() }
+                .archetypes_mut()
+                .get_mut(location.archetype())
+                .unwrap()
+                .chunksets_mut()
+                .get_mut(location.set())
+                .unwrap()
+                .get_mut(location.chunk())
+                .unwrap();
+
+            if self.deterministic_order {
+                // shift all following entities down to fill the gap
+                let shifted = chunk.shift_remove(location.component(), drop);
+                for (i, e) in shifted.iter().enumerate() {
+                    let location = EntityLocation::new(
+                        location.archetype(),
+                        location.set(),
+                        location.chunk(),
+                        location.component() + i,
+                    );
+                    self.entity_allocator.set_location(e.index(), location);
+                    self.relocations.record(*e);
+                }
+            } else if let Some(swapped) = chunk.swap_remove(location.component(), drop) {
+                // record swapped entity's new location
+                self.entity_allocator
+                    .set_location(swapped.index(), location);
+                self.relocations.record(swapped);
+            }
+
+            self.non_sync.remove_entity(entity);
+
+            trace!(world = self.id().0, ?entity, "Deleted entity");
+
+            true
+        } else {
+            false
+        }
+    }
+
+    fn find_chunk_with_delta(
+        &mut self,
+        source_location: EntityLocation,
+        add_components: &[(ComponentTypeId, ComponentMeta)],
+        remove_components: &[ComponentTypeId],
+        add_tags: &[(TagTypeId, TagMeta, NonNull<u8>)],
+        remove_tags: &[TagTypeId],
+    ) -> (usize, usize) {
+        let archetype = {
+            let result = {
+                let source_archetype = self
+                    .storage()
+                    .archetypes()
+                    .get(source_location.archetype())
+                    .unwrap();
+
+                // find target chunk
+                let mut component_layout = DynamicComponentLayout {
+                    existing: source_archetype.description().components(),
+                    add: add_components,
+                    remove: remove_components,
+                };
+
+                let mut tag_layout = DynamicTagLayout {
+                    storage: self.storage(),
+                    archetype: source_location.archetype(),
+                    set: source_location.set(),
+                    existing: source_archetype.description().tags(),
+                    add: add_tags,
+                    remove: remove_tags,
+                };
+
+                let archetype = self.find_archetype(&mut tag_layout, &mut component_layout);
+                if let Some(archetype) = archetype.as_ref() {
+                    if let Some(chunk) = self.find_chunk_set(*archetype, &mut tag_layout) {
+                        // fast path: chunk already exists
+                        return (*archetype, chunk);
+                    }
+
+                    Ok(*archetype)
+                } else {
+                    let mut description = ArchetypeDescription::default();
+                    component_layout.tailor_archetype(&mut description);
+                    tag_layout.tailor_archetype(&mut description);
+
+                    Err(description)
+                }
+            };
+
+            match result {
+                Ok(arch) => arch,
+                Err(desc) => {
+                    self.assert_growable(CapacityError::Archetype);
+                    let (index, _) = self.storage_mut().alloc_archetype(desc);
+                    index
+                }
+            }
+        };
+
+        // slow path: create new chunk
+        let source_archetype = self
+            .storage()
+            .archetypes()
+            .get(source_location.archetype())
+            .unwrap();
+        let mut tags = source_archetype.tags().tag_set(source_location.set());
+        for type_id in remove_tags.iter() {
+            tags.remove(*type_id);
+        }
+        for (type_id, meta, ptr) in add_tags.iter() {
+            // added tags replace any existing value of the same type
+            tags.remove(*type_id);
+            tags.push(*type_id, *meta, *ptr);
+        }
+
+        self.assert_growable(CapacityError::Chunkset);
+        let chunk = self.create_chunk_set(archetype, &tags);
+
+        (archetype, chunk)
+    }
+
+    fn move_entity(
+        &mut self,
+        entity: Entity,
+        add_components: &[(ComponentTypeId, ComponentMeta)],
+        remove_components: &[ComponentTypeId],
+        add_tags: &[(TagTypeId, TagMeta, NonNull<u8>)],
+        remove_tags: &[TagTypeId],
+        drop: bool,
+    ) -> &mut ComponentStorage {
+        assert!(
+            self.is_alive(entity),
+            "entity {} is not alive in this world",
+            entity
+        );
+        let location = self
+            .entity_allocator
+            .get_location(entity.index())
+            .expect("entity not found");
+
+        self.record_removed(entity, remove_components);
+
+        // find or create the target chunk
+        let (target_arch_index, target_chunkset_index) = self.find_chunk_with_delta(
+            location,
+            add_components,
+            remove_components,
+            add_tags,
+            remove_tags,
+        );
+
+        self.move_entity_to(
+            entity,
+            location,
+            target_arch_index,
+            target_chunkset_index,
+            drop,
+        )
+    }
+
+    // Moves an entity into a free chunk of the given chunk set, which must be in a different
+    // archetype or chunk set to the entity's current location.
+    fn move_entity_to(
+        &mut self,
+        entity: Entity,
+        location: EntityLocation,
+        target_arch_index: usize,
+        target_chunkset_index: usize,
+        drop: bool,
+    ) -> &mut ComponentStorage {
+        // Safety Note:
+        // It is only safe for us to have 2 &mut references to storage here because
+        // we know we are only going to be modifying two chunks that are at different
+        // indexes.
+
+        // fetch entity's chunk
+        let current_chunk = unsafe { &mut *self.storage.get() }
+            .archetypes_mut()
+            .get_mut(location.archetype())
+            .unwrap()
+            .chunksets_mut()
+            .get_mut(location.set())
+            .unwrap()
+            .get_mut(location.chunk())
+            .unwrap();
+
+        // fetch target chunk
+        let archetype = unsafe { &mut *self.storage.get() }
+            .archetypes_mut()
+            .get_mut(target_arch_index)
+            .unwrap();
+        if archetype.find_free_chunk(target_chunkset_index).is_none() {
+            self.assert_growable(CapacityError::Chunks {
+                required: 1,
+                available: 0,
+            });
+        }
+        let target_chunk_index = archetype.get_free_chunk(target_chunkset_index);
+        let target_chunk = unsafe {
+            archetype
+                .chunksets_mut()
+                .get_unchecked_mut(target_chunkset_index)
+                .get_unchecked_mut(target_chunk_index)
+        };
+
+        // move existing data over into new chunk
+        if let Some(swapped) = current_chunk.move_entity(target_chunk, location.component(), drop) {
+            // update location of any entity that was moved into the previous location
+            self.entity_allocator
+                .set_location(swapped.index(), location);
+            self.relocations.record(swapped);
+        }
+
+        // record the entity's new location
+        self.entity_allocator.set_location(
+            entity.index(),
+            EntityLocation::new(
+                target_arch_index,
+                target_chunkset_index,
+                target_chunk_index,
+                target_chunk.len() - 1,
+            ),
+        );
+        self.relocations.record(entity);
+
+        target_chunk
+    }
+
+    /// Adds a component to an entity, or sets its value if the component is
+    /// already present.
+    pub fn add_component<T: Component>(&mut self, entity: Entity, component: T) {
+        if let Some(mut comp) = self.get_component_mut(entity) {
+            *comp = component;
+            return;
+        }
+
+        trace!(
+            world = self.id().0,
+            ?entity,
+            component = std::any::type_name::<T>(),
+            "Adding component to entity"
+        );
+
+        // move the entity into a suitable chunk
+        let target_chunk = self.move_entity(
+            entity,
+            &[(ComponentTypeId::of::<T>(), ComponentMeta::of::<T>())],
+            &[],
+            &[],
+            &[],
+            true,
+        );
+
+        // push new component into chunk
+        let mut writer = target_chunk.writer();
+        let (_, components) = writer.get();
+        let slice = [component];
+        unsafe {
+            let components = &mut *components.get();
+            components
+                .get_mut(ComponentTypeId::of::<T>())
+                .unwrap()
+                .writer()
+                .push(&slice);
+        }
+        std::mem::forget(slice);
+    }
+
+    /// Adds a component to an entity, or sets its value if the component is
+    /// already present.
+    ///
+    /// Returns an error, and drops `component`, if the entity is not alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// use legion::error::{EntityError, Error};
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Health(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Health(10.0),)])[0];
+    /// world.delete(entity);
+    ///
+    /// assert_eq!(
+    ///     Err(Error::Entity(EntityError::NotAlive(entity))),
+    ///     world.add_component_checked(entity, Health(5.0))
+    /// );
+    /// ```
+    pub fn add_component_checked<T: Component>(
+        &mut self,
+        entity: Entity,
+        component: T,
+    ) -> Result<(), Error> {
+        if !self.is_alive(entity) {
+            return Err(EntityError::NotAlive(entity).into());
+        }
+
+        self.add_component(entity, component);
+        Ok(())
+    }
+
+    /// Adds a default constructed component to an entity, or resets its value to the
+    /// default if the component is already present.
+    pub fn add_component_default<T: Component + Default>(&mut self, entity: Entity) {
+        self.add_component(entity, T::default());
+    }
+
+    /// Adds a component to an entity without compile-time knowledge of its type, or sets its
+    /// value if the component is already present.
+    ///
+    /// The value at `component` is moved into the world. The caller retains ownership of the
+    /// memory behind the pointer, but must not drop the value it contained.
+    ///
+    /// # Safety
+    ///
+    /// `component` must point to a valid value of the component type described by `type_id`
+    /// and `meta`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entity is not alive.
+    pub unsafe fn add_component_raw(
+        &mut self,
+        entity: Entity,
+        type_id: ComponentTypeId,
+        meta: ComponentMeta,
+        component: NonNull<u8>,
+    ) {
+        assert!(
+            self.is_alive(entity),
+            "entity {} is not alive in this world",
+            entity
+        );
+        let location = self.entity_allocator.get_location(entity.index()).unwrap();
+        let chunk = &self.storage().archetypes()[location.archetype()].chunksets()[location.set()]
+            [location.chunk()];
+        if let Some(components) = chunk.components(type_id) {
+            let (ptr, element_size, _) = components.data_raw_mut();
+            let dst = ptr.add(element_size * location.component());
+            if let Some(drop_fn) = meta.drop_fn() {
+                drop_fn(dst);
+            }
+            std::ptr::copy_nonoverlapping(component.as_ptr(), dst, meta.size());
+            return;
+        }
+
+        trace!(
+            world = self.id().0,
+            ?entity,
+            component = ?type_id,
+            "Adding component to entity"
+        );
+
+        // move the entity into a suitable chunk
+        let target_chunk = self.move_entity(entity, &[(type_id, meta)], &[], &[], &[], true);
+
+        // push new component into chunk
+        let mut writer = target_chunk.writer();
+        let (_, components) = writer.get();
+        let components = &mut *components.get();
+        components
+            .get_mut(type_id)
+            .unwrap()
+            .writer()
+            .push_raw(component, 1);
+    }
+
+    /// Adds a component to every entity which matches `filter`, or sets its value on entities
+    /// which already have the component. `value_fn` is called once per entity to produce its
+    /// component value.
+    ///
+    /// The target archetype is resolved once per source chunk set, and entities are then moved
+    /// chunk by chunk, which is much faster than calling `add_component` for each entity.
+    ///
+    /// Each value is built before its entity is moved. If `value_fn` panics, the entity being
+    /// visited and all entities not yet visited are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Health(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Burning(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(Health(10.0),), (Health(5.0),)]);
+    ///
+    /// world.add_component_to_filtered(component::<Health>(), |_| Burning(1.0));
+    ///
+    /// assert_eq!(2, Read::<Burning>::query().iter(&mut world).count());
+    /// ```
+    pub fn add_component_to_filtered<T, F, V>(&mut self, mut filter: F, mut value_fn: V)
+    where
+        T: Component,
+        F: EntityFilter,
+        V: FnMut(Entity) -> T,
+    {
+        let span = span!(
+            Level::TRACE,
+            "Adding component to filtered entities",
+            world = self.id().0,
+            component = std::any::type_name::<T>()
+        );
+        let _guard = span.enter();
+
+        let type_id = ComponentTypeId::of::<T>();
+
+        // find all chunks which match the filter
+        let matches = self.matching_chunks(&mut filter);
+
+        // update entities which already have the component first, so that entities moved into
+        // their chunks are not visited twice
+        let has_component = |world: &World, archetype: usize| {
+            world.storage().archetypes()[archetype]
+                .description()
+                .components()
+                .iter()
+                .any(|(t, _)| *t == type_id)
+        };
+        let (existing, moved): (Vec<_>, Vec<_>) = matches
+            .into_iter()
+            .partition(|(archetype, _, _)| has_component(self, *archetype));
+
+        for (archetype_index, set_index, chunks) in existing.into_iter().chain(moved) {
+            // overwrite existing values in place
+            if has_component(self, archetype_index) {
+                let chunkset = &self.storage().archetypes()[archetype_index].chunksets()[set_index];
+                for chunk_index in chunks {
+                    let chunk = &chunkset[chunk_index];
+                    let mut values =
+                        unsafe { chunk.components(type_id).unwrap().data_slice_mut::<T>() };
+                    for (value, entity) in values.iter_mut().zip(chunk.entities()) {
+                        *value = value_fn(*entity);
+                    }
+                }
+                continue;
+            }
+
+            // resolve the target chunk set once for the whole source chunk set
+            let (target_arch_index, target_set_index) = self.find_chunk_with_delta(
+                EntityLocation::new(archetype_index, set_index, 0, 0),
+                &[(type_id, ComponentMeta::of::<T>())],
+                &[],
+                &[],
+                &[],
+            );
+
+            // Safety Note:
+            // The source and target chunks are always in different archetypes, as the target
+            // archetype contains `T` and the source archetype does not.
+            for chunk_index in chunks {
+                let source = unsafe { &mut *self.storage.get() }.archetypes_mut()[archetype_index]
+                    .chunksets_mut()[set_index]
+                    .get_mut(chunk_index)
+                    .unwrap();
+
+                // move entities from the back of the chunk, so that no entities are swapped
+                while !source.is_empty() {
+                    let target_archetype = &mut unsafe { &mut *self.storage.get() }
+                        .archetypes_mut()[target_arch_index];
+                    if target_archetype.find_free_chunk(target_set_index).is_none() {
+                        self.assert_growable(CapacityError::Chunks {
+                            required: 1,
+                            available: 0,
+                        });
+                    }
+                    let target_chunk_index = target_archetype.get_free_chunk(target_set_index);
+                    let target =
+                        &mut target_archetype.chunksets_mut()[target_set_index][target_chunk_index];
+
+                    // build the value before moving, so that a panic leaves the entity where it was
+                    let entity = *source.entities().last().unwrap();
+                    let slice = [value_fn(entity)];
+
+                    let swapped = source.move_entity(target, source.len() - 1, true);
+                    debug_assert!(swapped.is_none());
+
+                    let mut writer = target.writer();
+                    let (_, components) = writer.get();
+                    unsafe {
+                        let components = &mut *components.get();
+                        components.get_mut(type_id).unwrap().writer().push(&slice);
+                    }
+                    std::mem::forget(slice);
+                    drop(writer);
+
+                    self.entity_allocator.set_location(
+                        entity.index(),
+                        EntityLocation::new(
+                            target_arch_index,
+                            target_set_index,
+                            target_chunk_index,
+                            target.len() - 1,
+                        ),
+                    );
+                    self.relocations.record(entity);
+                }
+            }
+        }
+    }
+
+    /// Converts every `Old` component in the world into a `New` component with `convert`,
+    /// moving each entity into the archetype with `New` in place of `Old`. Returns the number of
+    /// entities which were migrated.
+    ///
+    /// This allows live data to be migrated when the definition of a component type changes
+    /// during development, such as when game code is hot reloaded. As with
+    /// `add_component_to_filtered`, the target archetype is resolved once per source chunk set
+    /// and entities are then moved chunk by chunk. Entities which already have a `New`
+    /// component have its value replaced.
+    ///
+    /// Each entity is converted before it is moved, and its `Old` component is dropped once it
+    /// has moved. If `convert` panics, the entity being converted and all entities not yet
+    /// converted are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// #[derive(Copy, Clone, Debug, PartialEq)]
+    /// struct HealthV1(u32);
+    /// #[derive(Copy, Clone, Debug, PartialEq)]
+    /// struct Health { current: f32, max: f32 }
+    ///
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Position(0.0), HealthV1(50))])[0];
+    ///
+    /// world.migrate_component(|old: &HealthV1| Health { current: old.0 as f32, max: 100.0 });
+    ///
+    /// assert!(world.get_component::<HealthV1>(entity).is_none());
+    /// assert_eq!(50.0, world.get_component::<Health>(entity).unwrap().current);
+    /// assert_eq!(Position(0.0), *world.get_component::<Position>(entity).unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `Old` and `New` are the same type, or if the world is in
+    /// fixed capacity mode and the migrated entities would require an allocation.
+    pub fn migrate_component<Old, New, F>(&mut self, mut convert: F) -> usize
+    where
+        Old: Component,
+        New: Component,
+        F: FnMut(&Old) -> New,
+    {
+        let span = span!(
+            Level::TRACE,
+            "Migrating component",
+            world = self.id().0,
+            from = std::any::type_name::<Old>(),
+            to = std::any::type_name::<New>()
+        );
+        let _guard = span.enter();
+
+        let old_id = ComponentTypeId::of::<Old>();
+        let new_id = ComponentTypeId::of::<New>();
+        assert_ne!(old_id, new_id, "cannot migrate a component type to itself");
+
+        let matches = self.matching_chunks(&mut filter_fns::component::<Old>());
+
+        let mut migrated = 0;
+        for (archetype_index, set_index, chunks) in matches {
+            // entities which already have `New` keep their column, and only `Old` is removed
+            let replace = self.storage().archetypes()[archetype_index]
+                .description()
+                .components()
+                .iter()
+                .any(|(t, _)| *t == new_id);
+            let add = if replace {
+                Vec::new()
+            } else {
+                vec![(new_id, ComponentMeta::of::<New>())]
+            };
+
+            // resolve the target chunk set once for the whole source chunk set
+            let (target_arch_index, target_set_index) = self.find_chunk_with_delta(
+                EntityLocation::new(archetype_index, set_index, 0, 0),
+                &add,
+                &[old_id],
+                &[],
+                &[],
+            );
+
+            // Safety Note:
+            // The source and target chunks are always in different archetypes, as the source
+            // archetype contains `Old` and the target archetype does not.
+            for chunk_index in chunks {
+                let source = unsafe { &mut *self.storage.get() }.archetypes_mut()[archetype_index]
+                    .chunksets_mut()[set_index]
+                    .get_mut(chunk_index)
+                    .unwrap();
+
+                // move entities from the back of the chunk, so that no entities are swapped
+                while !source.is_empty() {
+                    // convert before moving, so that a panic leaves the entity where it was
+                    let index = source.len() - 1;
+                    let entity = source.entities()[index];
+                    let new = unsafe {
+                        let values = source.components(old_id).unwrap().data_slice::<Old>();
+                        convert(&values[index])
+                    };
+
+                    let target_archetype = &mut unsafe { &mut *self.storage.get() }
+                        .archetypes_mut()[target_arch_index];
+                    if target_archetype.find_free_chunk(target_set_index).is_none() {
+                        self.assert_growable(CapacityError::Chunks {
+                            required: 1,
+                            available: 0,
+                        });
+                    }
+                    let target_chunk_index = target_archetype.get_free_chunk(target_set_index);
+                    let target =
+                        &mut target_archetype.chunksets_mut()[target_set_index][target_chunk_index];
+
+                    // the old component is not in the target archetype, and so is dropped
+                    let swapped = source.move_entity(target, index, true);
+                    debug_assert!(swapped.is_none());
+
+                    if replace {
+                        let mut values =
+                            unsafe { target.components(new_id).unwrap().data_slice_mut::<New>() };
+                        values[target.len() - 1] = new;
+                    } else {
+                        let slice = [new];
+                        let mut writer = target.writer();
+                        let (_, components) = writer.get();
+                        unsafe {
+                            let components = &mut *components.get();
+                            components.get_mut(new_id).unwrap().writer().push(&slice);
+                        }
+                        std::mem::forget(slice);
+                    }
+
+                    self.entity_allocator.set_location(
+                        entity.index(),
+                        EntityLocation::new(
+                            target_arch_index,
+                            target_set_index,
+                            target_chunk_index,
+                            target.len() - 1,
+                        ),
+                    );
+                    self.relocations.record(entity);
+                    self.record_removed(entity, &[old_id]);
+                    migrated += 1;
+                }
+            }
+        }
+
+        migrated
+    }
+
+    /// Applies a batch of component additions and removals to existing entities.
+    ///
+    /// Deltas are grouped by the chunk set their entity is moved into, so the target archetype
+    /// of each distinct combination of source chunk set and delta layout is only resolved once,
+    /// and entities moving into the same chunks are written together. This is much faster than
+    /// calling `add_component` and `remove_component` for each change, such as when a
+    /// replication system applies hundreds of changes each tick.
+    ///
+    /// Multiple deltas for the same entity are applied in order. Deltas for entities which are
+    /// not alive are dropped. Returns the number of entities which were updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::world::EntityDelta;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Health(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Burning(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entities = world.insert((), vec![(Health(10.0),), (Health(5.0),)]).to_vec();
+    ///
+    /// world.apply_deltas(vec![
+    ///     (entities[0], EntityDelta::new().with_component(Burning(1.0))),
+    ///     (entities[1], EntityDelta::new().without_component::<Health>()),
+    /// ]);
+    ///
+    /// assert_eq!(Burning(1.0), *world.get_component::<Burning>(entities[0]).unwrap());
+    /// assert!(world.get_component::<Health>(entities[1]).is_none());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the world is in fixed capacity mode and an entity's new
+    /// location would require an allocation.
+    pub fn apply_deltas<I>(&mut self, deltas: I) -> usize
+    where
+        I: IntoIterator<Item = (Entity, EntityDelta)>,
+    {
+        let span = span!(Level::TRACE, "Applying entity deltas", world = self.id().0);
+        let _guard = span.enter();
+
+        // merge the deltas of each entity, preserving the order each entity was first seen
+        let mut indices = HashMap::<Entity, usize>::new();
+        let mut merged: Vec<(Entity, EntityDelta)> = Vec::new();
+        for (entity, delta) in deltas {
+            match indices.get(&entity) {
+                Some(i) => merged[*i].1.merge(delta),
+                None => {
+                    indices.insert(entity, merged.len());
+                    merged.push((entity, delta));
+                }
+            }
+        }
+
+        // resolve the target chunk set of each distinct source chunk set and delta layout
+        let mut targets = HashMap::new();
+        let mut operations = Vec::with_capacity(merged.len());
+        for (entity, delta) in merged {
+            if !self.is_alive(entity) {
+                continue;
+            }
+
+            let location = self.entity_allocator.get_location(entity.index()).unwrap();
+            let mut removed = delta.remove.clone();
+            removed.sort();
+            let key = (
+                location.archetype(),
+                location.set(),
+                delta.add.layout_key().0,
+                removed,
+            );
+            let target = *targets
+                .entry(key)
+                .or_insert_with(|| self.find_chunk_for_delta(location, &delta));
+            operations.push((target, entity, delta));
+        }
+
+        // apply the deltas grouped by target, so that entities are written chunk by chunk
+        operations.sort_by_key(|(target, _, _)| *target);
+        let count = operations.len();
+        for (target, entity, delta) in operations {
+            self.apply_delta(entity, target, delta);
+        }
+
+        count
+    }
+
+    // Finds the chunk set an entity at `location` is moved into by `delta`, or `None` if the
+    // delta only overwrites existing components.
+    fn find_chunk_for_delta(
+        &mut self,
+        location: EntityLocation,
+        delta: &EntityDelta,
+    ) -> Option<(usize, usize)> {
+        let existing = self.storage().archetypes()[location.archetype()]
+            .description()
+            .components();
+        let add = delta
+            .add
+            .components
+            .iter()
+            .filter(|(type_id, _)| existing.iter().all(|(t, _)| t != type_id))
+            .copied()
+            .collect::<Vec<_>>();
+        let remove = delta
+            .remove
+            .iter()
+            .filter(|type_id| existing.iter().any(|(t, _)| t == *type_id))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if add.is_empty() && remove.is_empty() {
+            None
+        } else {
+            Some(self.find_chunk_with_delta(location, &add, &remove, &[], &[]))
+        }
+    }
+
+    fn apply_delta(
+        &mut self,
+        entity: Entity,
+        target: Option<(usize, usize)>,
+        mut delta: EntityDelta,
+    ) {
+        let location = self.entity_allocator.get_location(entity.index()).unwrap();
+        let existing = self.storage().archetypes()[location.archetype()]
+            .description()
+            .components()
+            .iter()
+            .map(|(type_id, _)| *type_id)
+            .collect::<Vec<_>>();
+
+        let removed = delta
+            .remove
+            .iter()
+            .filter(|type_id| existing.contains(type_id))
+            .copied()
+            .collect::<Vec<_>>();
+        self.record_removed(entity, &removed);
+
+        // the values now belong to this function, so the delta must not drop them
+        let values = delta
+            .add
+            .components
+            .drain(..)
+            .zip(delta.add.values.drain(..))
+            .collect::<Vec<_>>();
+        let (overwritten, added): (Vec<_>, Vec<_>) = values
+            .into_iter()
+            .partition(|((type_id, _), _)| existing.contains(type_id));
+
+        if let Some((archetype, set)) = target {
+            let chunk = self.move_entity_to(entity, location, archetype, set, true);
+            let mut writer = chunk.writer();
+            let (_, components) = writer.get();
+            for ((type_id, meta), value) in added {
+                unsafe {
+                    let mut target = (&mut *components.get()).get_mut(type_id).unwrap().writer();
+                    target.push_raw(value, 1);
+                    if !meta.is_zero_sized() {
+                        std::alloc::dealloc(value.as_ptr(), meta.layout());
+                    }
+                }
+            }
+        }
+
+        // overwrite the values of components the entity already had
+        let location = self.entity_allocator.get_location(entity.index()).unwrap();
+        let chunk = &self.storage().archetypes()[location.archetype()].chunksets()[location.set()]
+            [location.chunk()];
+        for ((type_id, meta), value) in overwritten {
+            let (ptr, element_size, _) = chunk.components(type_id).unwrap().data_raw_mut();
+            unsafe {
+                let dst = ptr.add(element_size * location.component());
+                if let Some(drop_fn) = meta.drop_fn() {
+                    drop_fn(dst);
+                }
+                std::ptr::copy_nonoverlapping(value.as_ptr(), dst, meta.size());
+                if !meta.is_zero_sized() {
+                    std::alloc::dealloc(value.as_ptr(), meta.layout());
+                }
+            }
+        }
+    }
+
+    /// Removes a component from an entity.
+    pub fn remove_component<T: Component>(&mut self, entity: Entity) {
+        if self.get_component::<T>(entity).is_some() {
+            trace!(
+                world = self.id().0,
+                ?entity,
+                component = std::any::type_name::<T>(),
+                "Removing component from entity"
+            );
+
+            // move the entity into a suitable chunk
+            self.move_entity(entity, &[], &[ComponentTypeId::of::<T>()], &[], &[], true);
+        }
+    }
+
+    /// Removes a component from an entity.
+    ///
+    /// Returns an error if the entity is not alive or does not have the component.
+    pub fn remove_component_checked<T: Component>(&mut self, entity: Entity) -> Result<(), Error> {
+        if !self.is_alive(entity) {
+            return Err(EntityError::NotAlive(entity).into());
+        }
+
+        if self.get_component::<T>(entity).is_none() {
+            return Err(EntityError::MissingComponent(entity, ComponentTypeId::of::<T>()).into());
+        }
+
+        self.remove_component::<T>(entity);
+        Ok(())
+    }
+
+    /// Removes a component from an entity without compile-time knowledge of its type.
+    ///
+    /// Returns `true` if the component was removed, or `false` if the entity is not alive
+    /// or does not have the component.
+    pub fn remove_component_raw(&mut self, entity: Entity, type_id: ComponentTypeId) -> bool {
+        if self.get_component_raw(entity, type_id).is_none() {
+            return false;
+        }
+
+        trace!(
+            world = self.id().0,
+            ?entity,
+            component = ?type_id,
+            "Removing component from entity"
+        );
+
+        // move the entity into a suitable chunk
+        self.move_entity(entity, &[], &[type_id], &[], &[], true);
+        true
+    }
+
+    /// Removes a component from an entity, returning its value.
+    ///
+    /// Returns `None` if the entity was not found or does not have the component.
+    pub fn take_component<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        // the value is not owned until it has been moved out of the chunk
+        let component = unsafe {
+            let component = self.get_component::<T>(entity)?;
+            std::mem::ManuallyDrop::new(std::ptr::read(&*component as *const T))
+        };
+
+        trace!(
+            world = self.id().0,
+            ?entity,
+            component = std::any::type_name::<T>(),
+            "Taking component from entity"
+        );
+
+        // move the entity into a suitable chunk, without dropping the value we now own
+        self.move_entity(entity, &[], &[ComponentTypeId::of::<T>()], &[], &[], false);
+
+        Some(std::mem::ManuallyDrop::into_inner(component))
+    }
+
+    /// Replaces the value of an entity's component, returning the previous value.
+    ///
+    /// Unlike `add_component`, this does not move the entity between chunks. Returns `None`,
+    /// and drops `component`, if the entity was not found or does not have the component.
+    pub fn replace_component<T: Component>(&mut self, entity: Entity, component: T) -> Option<T> {
+        let mut current = self.get_component_mut::<T>(entity)?;
+        Some(std::mem::replace(&mut *current, component))
+    }
+
+    /// Adds a tag to an entity, or sets its value if the tag is
+    /// already present.
+    pub fn add_tag<T: Tag>(&mut self, entity: Entity, tag: T) {
+        if self.get_tag::<T>(entity) == Some(&tag) {
+            return;
+        }
+
+        trace!(
+            world = self.id().0,
+            ?entity,
+            tag = std::any::type_name::<T>(),
+            "Adding tag to entity"
+        );
+
+        // move the entity into a suitable chunk
+        self.move_entity(
+            entity,
+            &[],
+            &[],
+            &[(
+                TagTypeId::of::<T>(),
+                TagMeta::of::<T>(),
+                NonNull::new(&tag as *const _ as *mut u8).unwrap(),
+            )],
+            &[],
+            true,
+        );
+    }
+
+    /// Removes a tag from an entity.
+    pub fn remove_tag<T: Tag>(&mut self, entity: Entity) {
+        if self.get_tag::<T>(entity).is_some() {
+            trace!(
+                world = self.id().0,
+                ?entity,
+                tag = std::any::type_name::<T>(),
+                "Removing tag from entity"
+            );
+
+            // move the entity into a suitable chunk
+            self.move_entity(entity, &[], &[], &[], &[TagTypeId::of::<T>()], true);
+        }
+    }
+
+    /// Borrows component data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    pub fn get_component<T: Component>(&self, entity: Entity) -> Option<Ref<Shared, T>> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(false);
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = self.storage().archetypes().get(location.archetype())?;
+        let chunk = archetype
+            .chunksets()
+            .get(location.set())?
+            .get(location.chunk())?;
+        let (slice_borrow, slice) = unsafe {
+            chunk
+                .components(ComponentTypeId::of::<T>())?
+                .data_slice::<T>()
+                .deconstruct()
+        };
+        let component = slice.get(location.component())?;
+
+        Some(Ref::new(slice_borrow, component))
+    }
+
+    /// Borrows component data for the given entity without compile-time knowledge of its type.
+    ///
+    /// Returns `Some((borrow, pointer, element_size))` if the entity was found and contains
+    /// a component of the given type. The pointer addresses the entity's component and remains
+    /// valid for reads for as long as the returned borrow is held.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing the component type.
+    pub fn get_component_raw(
+        &self,
+        entity: Entity,
+        type_id: ComponentTypeId,
+    ) -> Option<(Ref<'_, Shared<'_>, ()>, *const u8, usize)> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = self.storage().archetypes().get(location.archetype())?;
+        let chunk = archetype
+            .chunksets()
+            .get(location.set())?
+            .get(location.chunk())?;
+        let (ptr, element_size, count) = chunk.components(type_id)?.data_raw();
+        if location.component() >= count {
+            return None;
+        }
+
+        // safe because the index was bounds checked against the column length above
+        let (borrow, ptr) = unsafe { ptr.deconstruct() };
+        let component = unsafe { ptr.add(location.component() * element_size) };
+
+        Some((Ref::new(borrow, &()), component as *const u8, element_size))
+    }
+
+    /// Mutably borrows entity data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Safety
+    ///
+    /// Accessing a component which is already being concurrently accessed elsewhere is undefined behavior.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently borrowing `T` (such as in a query).
+    pub unsafe fn get_component_mut_unchecked<T: Component>(
+        &self,
+        entity: Entity,
+    ) -> Option<RefMut<Exclusive, T>> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        #[cfg(feature = "access-audit")]
+        crate::audit::record::<T>(true);
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = self.storage().archetypes().get(location.archetype())?;
+        let chunk = archetype
+            .chunksets()
+            .get(location.set())?
+            .get(location.chunk())?;
+        let (slice_borrow, slice) = chunk
+            .components(ComponentTypeId::of::<T>())?
+            .data_slice_mut::<T>()
+            .deconstruct();
+        let component = slice.get_mut(location.component())?;
+
+        Some(RefMut::new(slice_borrow, component))
+    }
+
+    /// Mutably borrows entity data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    pub fn get_component_mut<T: Component>(
+        &mut self,
+        entity: Entity,
+    ) -> Option<RefMut<Exclusive, T>> {
+        // safe because the &mut self ensures exclusivity
+        unsafe { self.get_component_mut_unchecked(entity) }
+    }
+
+    /// Mutably borrows two different types of entity data for the given entity at once.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains both types of data.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `A` and `B` are the same type, as the data would be mutably borrowed twice.
+    /// The check is made at runtime, as rejecting the call at compile time would require
+    /// comparing `TypeId`s in a const context, which stable Rust does not support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Velocity(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Position(0.0), Velocity(1.0))])[0];
+    ///
+    /// if let Some((mut pos, mut vel)) = world.get_component_pair_mut::<Position, Velocity>(entity) {
+    ///     pos.0 += vel.0;
+    ///     vel.0 = 0.0;
+    /// }
+    ///
+    /// assert_eq!(Position(1.0), *world.get_component::<Position>(entity).unwrap());
+    /// assert_eq!(Velocity(0.0), *world.get_component::<Velocity>(entity).unwrap());
+    /// ```
+    pub fn get_component_pair_mut<A: Component, B: Component>(
+        &mut self,
+        entity: Entity,
+    ) -> Option<(RefMut<'_, Exclusive<'_>, A>, RefMut<'_, Exclusive<'_>, B>)> {
+        assert_ne!(
+            ComponentTypeId::of::<A>(),
+            ComponentTypeId::of::<B>(),
+            "cannot mutably borrow the same component type twice"
+        );
+
+        // safe because the &mut self ensures exclusivity, and the types are distinct
+        unsafe {
+            Some((
+                self.get_component_mut_unchecked(entity)?,
+                self.get_component_mut_unchecked(entity)?,
+            ))
+        }
+    }
+
+    /// Mutably borrows entity data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+
+    /// Gets tag data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    pub fn get_tag<T: Tag>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = self.storage().archetypes().get(location.archetype())?;
+        let tags = archetype.tags().get(TagTypeId::of::<T>())?;
+
+        tags.downcast::<T>()?.as_slice().get(location.set())
+    }
+
+    /// Mutably borrows tag data for the given entity.
+    ///
+    /// As tag values are shared by all entities in a chunk, the returned guard edits a copy
+    /// of the entity's tag. When the guard is dropped, the entity is moved into the chunk
+    /// matching the new tag value (which is created if needed). Other entities which share
+    /// the original value are unaffected.
+    ///
+    /// Returns `Some(guard)` if the entity was found and contains the specified tag.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Team(u32);
+    ///
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((Team(1),), vec![(Position(0.0),)])[0];
+    ///
+    /// world.get_tag_mut::<Team>(entity).unwrap().0 = 2;
+    ///
+    /// assert_eq!(Some(&Team(2)), world.get_tag::<Team>(entity));
+    /// ```
+    pub fn get_tag_mut<T: Tag>(&mut self, entity: Entity) -> Option<TagEditGuard<'_, T>> {
+        let value = self.get_tag::<T>(entity)?.clone();
+        Some(TagEditGuard {
+            world: self,
+            entity,
+            value,
+        })
+    }
+
+    /// Calls `f` with the raw bytes of every component column of every chunk in the world.
+    ///
+    /// `f` receives `(bytes, component_type, element_size, count)`, where `bytes` holds `count`
+    /// components of `element_size` bytes each. Columns are visited in storage order; archetype
+    /// by archetype, then chunk set by chunk set and chunk by chunk. Empty chunks are skipped.
+    ///
+    /// Padding bytes within a component type are uninitialized, so the bytes are yielded as
+    /// `MaybeUninit<u8>`. Only the bytes of types known to contain no padding may be assumed
+    /// to be initialized.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing a component column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::storage::ComponentTypeId;
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(1u32,), (2u32,)]);
+    ///
+    /// let mut hash = 0u64;
+    /// world.for_each_raw(|bytes, type_id, _, _| {
+    ///     // `u32` contains no padding
+    ///     if type_id == ComponentTypeId::of::<u32>() {
+    ///         for byte in bytes {
+    ///             hash = hash.wrapping_mul(31).wrapping_add(unsafe { byte.assume_init() } as u64);
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn for_each_raw<F>(&self, mut f: F)
+    where
+        F: FnMut(&[MaybeUninit<u8>], ComponentTypeId, usize, usize),
+    {
+        for archetype in self.storage().archetypes() {
+            let component_types = archetype.layout().components();
+            for chunk in archetype.chunksets().iter().flat_map(|set| set.iter()) {
+                if chunk.is_empty() {
+                    continue;
+                }
+
+                for (type_id, _, _) in component_types {
+                    let column = chunk.components(*type_id).unwrap();
+                    let (ptr, element_size, count) = column.data_raw();
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(
+                            *ptr as *const MaybeUninit<u8>,
+                            element_size * count,
+                        )
+                    };
+                    f(bytes, *type_id, element_size, count);
+                }
+            }
+        }
+    }
+
+    /// Gets an iterator over all entities with the given `Name` component.
+    ///
+    /// The order of the entities is unspecified. The name index is kept up to date from the
+    /// change versions of each chunk's names, so only chunks whose names have changed since the
+    /// last lookup are re-read.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing `Name` components.
+    pub fn find_by_name(&self, name: &str) -> impl Iterator<Item = Entity> {
+        let mut index = self.name_index.lock();
+        index.refresh(self.storage());
+        index.find(name).to_vec().into_iter()
+    }
+
+    /// Enables the spatial index over the positions of component type `T`, replacing any
+    /// existing index. The index is a uniform grid with cells of `cell_size` along each axis.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `cell_size` is not positive.
+    #[cfg(feature = "spatial")]
+    pub fn set_spatial_index<T: Spatial>(&mut self, cell_size: f32) {
+        *self.spatial_index.lock() = Some(SpatialIndex::new::<T>(cell_size));
+    }
+
+    /// Gets an iterator over all entities whose spatial index position lies within the axis
+    /// aligned box between `min` and `max`, inclusive.
+    ///
+    /// The order of the entities is unspecified. The index is kept up to date from the change
+    /// versions of each chunk's positions, so only chunks whose positions have changed since the
+    /// last query are re-read. Returns no entities if the spatial index is not enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing the indexed
+    /// position components.
+    #[cfg(feature = "spatial")]
+    pub fn query_aabb(&self, min: [f32; 3], max: [f32; 3]) -> impl Iterator<Item = Entity> {
+        let mut index = self.spatial_index.lock();
+        let entities = match index.as_mut() {
+            Some(index) => {
+                index.refresh(self.storage());
+                index.query_aabb(min, max)
+            }
+            None => Vec::new(),
+        };
+        entities.into_iter()
+    }
+
+    /// Captures the entities and components of the world, such that they can later be restored
+    /// with `rollback`.
+    ///
+    /// Every component type stored in the world must be registered in `registry` via
+    /// `Registry::register_pod` or `Registry::register_clone`. Plain old data components are
+    /// not copied; their chunks are shared by the world and the snapshot, and are only copied
+    /// when either first writes to them. Other components are cloned. This makes per-tick
+    /// snapshots affordable for rollback, as only the data which changes is copied.
+    ///
+    /// Tags are cloned. Resources, non-sync components, removal tracking and interpolation
+    /// histories are not captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.registry.register_pod::<Position>();
+    /// let entity = world.insert((), vec![(Position(1.0),)])[0];
+    ///
+    /// let snapshot = world.snapshot().unwrap();
+    /// *world.get_component_mut::<Position>(entity).unwrap() = Position(2.0);
+    /// world.delete(entity);
+    ///
+    /// world.rollback(snapshot);
+    /// assert_eq!(Position(1.0), *world.get_component::<Position>(entity).unwrap());
+    /// ```
+    pub fn snapshot(&mut self) -> Result<WorldSnapshot, Error> {
+        let span = span!(Level::INFO, "Snapshotting world", world = self.id().0);
+        let _guard = span.enter();
+
+        let storage = self
+            .storage
+            .get_mut()
+            .share(&self.registry)
+            .map_err(Error::NotCloneable)?;
+
+        Ok(WorldSnapshot {
+            world: self.id,
+            storage,
+            entities: self.entity_allocator.snapshot(),
+        })
+    }
+
+    /// Creates a deep copy of the world, in which every entity keeps its ID.
+    ///
+    /// Every component type stored in the world must be registered in `registry` via
+    /// `Registry::register_pod` or `Registry::register_clone`. As with `snapshot`, the chunks of
+    /// plain old data components are shared by both worlds until either first writes to them,
+    /// and all other components and tags are cloned. The copy is given a new `WorldId` within
+    /// the same universe, along with this world's registry and settings. Resources, non-sync
+    /// components, event channels and all change, removal and snapshot tracking are not copied.
+    ///
+    /// This is a simpler and cheaper way to fork a simulation than serializing and deserializing
+    /// the world. The copy allocates new entities from entity blocks it claims from the universe,
+    /// so entities created in either world after the copy never share IDs. The indices of
+    /// entities deleted from the copy are not recycled by it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.registry.register_pod::<Position>();
+    /// let entity = world.insert((), vec![(Position(1.0),)])[0];
+    ///
+    /// let registry = world.registry.clone();
+    /// let mut fork = world.try_clone(&registry).unwrap();
+    /// *fork.get_component_mut::<Position>(entity).unwrap() = Position(2.0);
+    ///
+    /// assert_eq!(Position(1.0), *world.get_component::<Position>(entity).unwrap());
+    /// assert_eq!(Position(2.0), *fork.get_component::<Position>(entity).unwrap());
+    /// assert_ne!(world.id(), fork.id());
+    /// ```
+    pub fn try_clone(&mut self, registry: &Registry) -> Result<World, Error> {
+        let allocator = self.entity_allocator.fork();
+        self.clone_with_allocator(registry, allocator)
+    }
+
+    /// Clones the world as `try_clone` does, but gives the clone a private copy of the universe's
+    /// entity block allocator. The clone allocates the same entity IDs as this world would,
+    /// which may collide with entities in other worlds of the universe.
+    pub(crate) fn try_clone_detached(&mut self, registry: &Registry) -> Result<World, Error> {
+        let allocator = self.entity_allocator.detach();
+        self.clone_with_allocator(registry, allocator)
+    }
+
+    fn clone_with_allocator(
+        &mut self,
+        registry: &Registry,
+        allocator: EntityAllocator,
+    ) -> Result<World, Error> {
+        let storage = self
+            .storage
+            .get_mut()
+            .share(registry)
+            .map_err(Error::NotCloneable)?;
+
+        let mut world = World::new_in_universe(self.registration.ids.register(), allocator);
+        let span =
+            span!(Level::INFO, "Cloning world", source = self.id().0, destination = ?world.id());
+        let _guard = span.enter();
+
+        let id = world.id;
+        *world.storage.get_mut() = storage;
+        world.storage.get_mut().set_world_id(id);
+        world.registry = self.registry.clone();
+        world.defrag_policy = self.defrag_policy;
+        world.deterministic_order = self.deterministic_order;
+        world.intern_tags = self.intern_tags;
+        world.fixed_capacity = self.fixed_capacity;
+
+        Ok(world)
+    }
+
+    /// Restores the entities and components of the world to the state captured in `snapshot`.
+    ///
+    /// Entities created since the snapshot was taken are deleted, and entities deleted since
+    /// are restored with their original IDs. The IDs of entities created after the snapshot
+    /// may be allocated again. All restored components are considered to have been modified
+    /// by change detection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot was taken of a different world.
+    pub fn rollback(&mut self, snapshot: WorldSnapshot) {
+        assert_eq!(
+            self.id, snapshot.world,
+            "attempted to roll back to a snapshot of a different world"
+        );
+
+        let span = span!(Level::INFO, "Rolling back world", world = self.id().0);
+        let _guard = span.enter();
+
+        *self.storage.get_mut() = snapshot.storage;
+        for archetype in self.storage_mut().archetypes_mut() {
+            archetype.mark_modified();
+        }
+        self.entity_allocator.restore(snapshot.entities);
+    }
+
+    /// Replaces all entities in the world with the contents of another world, such as when
+    /// transitioning between levels, and returns the previous contents in `world`.
+    ///
+    /// Entities keep their IDs, and all components are considered to have been modified by
+    /// change detection. Everything else configured on this world is retained, including its
+    /// resources, event channels, change readers, snapshot and removal tracking, and its defrag,
+    /// ordering, capacity and padding settings; `world`'s registrations are merged into it.
+    /// Recorded snapshots, removals and chunks reported by `drain_new_chunks` are discarded.
+    /// Rather than an event per entity, a single `WorldEvent::Reset` event is written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// let old = world.insert((), vec![(Position(0.0),)])[0];
+    ///
+    /// let mut level = universe.create_world();
+    /// let new = level.insert((), vec![(Position(1.0),)])[0];
+    ///
+    /// let previous = world.replace_with(level);
+    /// assert!(!world.is_alive(old));
+    /// assert_eq!(Position(1.0), *world.get_component::<Position>(new).unwrap());
+    /// assert!(previous.is_alive(old));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `world` was not created by the same universe as this world. Worlds created
+    /// elsewhere can first be moved into this world's universe with `Universe::adopt_world`.
+    ///
+    /// Panics if either world contains non-sync components and either world was created on
+    /// another thread.
+    pub fn replace_with(&mut self, mut world: World) -> World {
+        assert!(
+            self.entity_allocator.shares_blocks(&world.entity_allocator),
+            "attempted to replace a world with a world from a different universe"
+        );
+
+        let span = span!(
+            Level::INFO,
+            "Replacing world",
+            world = self.id().0,
+            source = world.id().0
+        );
+        let _guard = span.enter();
+
+        // swapped first, as this panics if either world's non-sync components are inaccessible
+        self.non_sync.swap_components(&mut world.non_sync);
+
+        let padding = self.component_padding();
+        let small_chunk_capacity = self.small_chunk_capacity();
+        std::mem::swap(self.storage.get_mut(), world.storage.get_mut());
+        self.storage.get_mut().set_world_id(self.id);
+        self.storage.get_mut().set_component_padding(padding);
+        self.storage
+            .get_mut()
+            .set_small_chunk_capacity(small_chunk_capacity);
+        world.storage.get_mut().set_world_id(world.id);
+        for archetype in self.storage_mut().archetypes_mut() {
+            archetype.mark_modified();
+        }
+
+        std::mem::swap(&mut self.entity_allocator, &mut world.entity_allocator);
+        self.registry.merge(&world.registry);
+
+        *self.name_index.lock() = NameIndex::default();
+        *world.name_index.lock() = NameIndex::default();
+        self.defrag_progress = 0;
+        world.defrag_progress = 0;
+        for history in self.snapshots.values_mut() {
+            history.clear();
+        }
+        for removed in self.removed.values_mut() {
+            *removed = RemovedBuffer::default();
+        }
+        self.relocations.moved.clear();
+        self.drained_chunks.clear();
+        #[cfg(feature = "spatial")]
+        {
+            *self.spatial_index.lock() = None;
+        }
+
+        #[cfg(feature = "events")]
+        {
+            self.world_channel
+                .write(WorldEvent::Reset(self.id))
+                .expect("Failed to write to WorldEvent::Reset channel.");
+        }
+
+        world
+    }
+
+    /// Starts recording the values of component type `T` each time `capture_snapshots` is
+    /// called, retaining the most recent `capacity` snapshots. Replaces any existing history
+    /// of `T`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `capacity` is less than two.
+    pub fn track_snapshots<T: Interpolate>(&mut self, capacity: usize) {
+        self.snapshots.insert(
+            ComponentTypeId::of::<T>(),
+            Box::new(Snapshots::<T>::new(capacity)),
+        );
+    }
+
+    /// Records a snapshot of the current values of every component type enabled with
+    /// `track_snapshots`. This should be called once at the end of each simulation tick.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing a tracked
+    /// component type.
+    pub fn capture_snapshots(&mut self) {
+        let storage = unsafe { &*self.storage.get() };
+        for history in self.snapshots.values_mut() {
+            history.capture(storage);
+        }
+    }
+
+    /// Gets the values of component type `T` blended between the two most recent snapshots,
+    /// where an `alpha` of `0.0` produces the values of the previous tick and an `alpha` of
+    /// `1.0` produces the values of the latest tick.
+    ///
+    /// Entities which were created in the latest tick take their latest value. Returns no
+    /// values if `T` is not tracked or no snapshots have yet been captured.
+    pub fn sample_interpolated<T: Interpolate>(&self, alpha: f32) -> Vec<(Entity, T)> {
+        self.snapshots
+            .get(&ComponentTypeId::of::<T>())
+            .and_then(|history| history.as_any().downcast_ref::<Snapshots<T>>())
+            .map(|history| history.sample(alpha))
+            .unwrap_or_default()
+    }
+
+    /// Begins recording the entities from which components of type `T` are removed, including
+    /// by deleting the entity. The recorded entities are retrieved with `removed`.
+    pub fn track_removed<T: Component>(&mut self) {
+        self.removed.entry(ComponentTypeId::of::<T>()).or_default();
+    }
+
+    /// Gets the entities from which a component of type `T` has been removed since the
+    /// `update_removed` call before last.
+    ///
+    /// Removals are only recorded for component types registered via `track_removed`. As with
+    /// `Events`, removals remain visible through the following update, such that systems run
+    /// after the update observe removals made by command buffers flushed before it.
+    pub fn removed<T: Component>(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.removed
+            .get(&ComponentTypeId::of::<T>())
+            .into_iter()
+            .flat_map(|buffer| buffer.previous.iter().chain(buffer.current.iter()))
+            .copied()
+    }
+
+    /// Discards the removals recorded before the previous update, and begins a new update.
+    ///
+    /// This is the world's maintenance point for removal tracking, and should be called once
+    /// per frame, typically after the frame's systems have executed.
+    pub fn update_removed(&mut self) {
+        for buffer in self.removed.values_mut() {
+            std::mem::swap(&mut buffer.previous, &mut buffer.current);
+            buffer.current.clear();
+        }
+    }
+
+    /// Registers a callback which is notified of the entities moved by structural changes, such
+    /// as adding or removing components, deleting a neighbouring entity, sorting, or
+    /// defragmentation.
+    ///
+    /// Moves are not reported as they happen. Instead, the entities moved since the previous
+    /// call to `flush_relocations` are passed to every registered callback in a single batch,
+    /// each entity appearing once with its final location. This allows external indices which
+    /// refer to entities by their chunk position, such as spatial hashes or physics body maps,
+    /// to fix up their handles without any per-move overhead during hot loops. Moves are only
+    /// recorded while at least one callback is registered, and newly inserted entities are
+    /// not reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use std::sync::{Arc, Mutex};
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Velocity(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Position(0.0),)])[0];
+    ///
+    /// let moved = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = moved.clone();
+    /// world.on_relocated(move |relocations| {
+    ///     sink.lock().unwrap().extend(relocations.iter().map(|r| r.entity))
+    /// });
+    ///
+    /// world.add_component(entity, Velocity(1.0));
+    /// assert!(moved.lock().unwrap().is_empty());
+    ///
+    /// world.maintain();
+    /// assert_eq!(vec![entity], *moved.lock().unwrap());
+    /// ```
+    pub fn on_relocated<F: FnMut(&[Relocation]) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.relocations.callbacks.push(Box::new(callback));
+    }
+
+    /// Passes the entities moved since the previous call to the callbacks registered with
+    /// `on_relocated`.
+    ///
+    /// This is the world's frame barrier for relocation tracking, and is called by `maintain`
+    /// after the world has been defragmented.
+    pub fn flush_relocations(&mut self) {
+        if self.relocations.moved.is_empty() {
+            return;
+        }
+
+        // report each live entity once, in the order of its last move
+        let mut seen = HashSet::new();
+        let mut relocations = Vec::with_capacity(self.relocations.moved.len());
+        let moved = std::mem::take(&mut self.relocations.moved);
+        for entity in moved.into_iter().rev() {
+            if !seen.insert(entity) {
+                continue;
+            }
+            if let Some(location) = self.entity_allocator.get_location(entity.index()) {
+                if self.entity_allocator.is_alive(entity) {
+                    let chunk = self.storage().archetypes()[location.archetype()].chunksets()
+                        [location.set()][location.chunk()]
+                    .id();
+                    relocations.push(Relocation {
+                        entity,
+                        chunk,
+                        index: location.component(),
+                    });
+                }
+            }
+        }
+        relocations.reverse();
+
+        for callback in &mut self.relocations.callbacks {
+            callback(&relocations);
+        }
+    }
+
+    fn record_removed(&mut self, entity: Entity, components: &[ComponentTypeId]) {
+        for type_id in components {
+            if let Some(buffer) = self.removed.get_mut(type_id) {
+                buffer.current.push(entity);
+            }
+        }
+    }
+
+    /// Gets the change reader registered with the given name, registering a new reader if
+    /// there is not one already.
+    ///
+    /// Each reader tracks which component changes it has observed independently, such that
+    /// one consumer marking changes as observed does not hide them from another. Changes are
+    /// observed through the `changed_for` filter, or with `ChangeReader::mark_unchanged`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Position(0.0),)])[0];
+    /// let renderer = world.change_reader("renderer");
+    /// let network = world.change_reader("network");
+    ///
+    /// renderer.mark_unchanged();
+    /// assert!(!world.get_component_changed::<Position>(entity, &renderer));
+    /// assert!(world.get_component_changed::<Position>(entity, &network));
+    ///
+    /// *world.get_component_mut::<Position>(entity).unwrap() = Position(1.0);
+    /// let mut query = Read::<Position>::query().filter(changed_for::<Position>(&renderer));
+    /// assert_eq!(1, query.iter(&mut world).count());
+    /// assert_eq!(0, query.iter(&mut world).count());
+    /// ```
+    pub fn change_reader(&mut self, name: &str) -> ChangeReader {
+        self.change_readers
+            .entry(name.to_owned())
+            .or_insert_with(|| ChangeReader::new(name))
+            .clone()
+    }
+
+    /// Determines if the given entity's component of type `T` may have changed since `reader`
+    /// last observed it. Changes are tracked per chunk, so this also reports changes to other
+    /// entities in the same chunk.
+    ///
+    /// Returns `false` if the entity was not found or does not have the component.
+    pub fn get_component_changed<T: Component>(
+        &self,
+        entity: Entity,
+        reader: &ChangeReader,
+    ) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        self.entity_allocator
+            .get_location(entity.index())
+            .map(|location| {
+                let chunk = &self.storage().archetypes()[location.archetype()].chunksets()
+                    [location.set()][location.chunk()];
+                reader.is_changed(chunk, ComponentTypeId::of::<T>())
+            })
+            .unwrap_or(false)
+    }
+
+    /// Computes a checksum of every entity and its components, such that two worlds which
+    /// contain the same entities with the same component values produce the same checksum.
+    ///
+    /// Components registered via `Registry::register_hash` are hashed by value. Other
+    /// components registered via `Registry::register_pod` are hashed by their bytes, and so
+    /// must not contain padding. Tag values are not included.
+    ///
+    /// The checksum does not depend upon how entities are laid out in chunks, but it is only
+    /// comparable between worlds running the same build of the program.
+    ///
+    /// Returns `Error::NotHashable` if the world contains a component type which is registered
+    /// as neither hashable nor plain old data.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing a component column.
+    pub fn checksum(&self, registry: &Registry) -> Result<u64, Error> {
+        let mut checksum = 0u64;
+        for archetype in self.storage().archetypes() {
+            let mut component_types = Vec::new();
+            for (type_id, _, _) in archetype.layout().components() {
+                match registry.get(*type_id) {
+                    Some(registration) if registration.has_hash() => {
+                        component_types.push((*type_id, Some(registration)))
+                    }
+                    Some(registration) if registration.is_pod() => {
+                        component_types.push((*type_id, None))
+                    }
+                    _ => return Err(Error::NotHashable(*type_id)),
+                }
+            }
+            component_types.sort_by_key(|(type_id, _)| *type_id);
+
+            for chunk in archetype.chunksets().iter().flat_map(|set| set.iter()) {
+                let columns = component_types
+                    .iter()
+                    .map(|(type_id, registration)| {
+                        (
+                            *type_id,
+                            *registration,
+                            chunk.components(*type_id).unwrap().data_raw(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                // combine entity hashes commutatively, so that the order of entities is irrelevant
+                for (i, entity) in chunk.entities().iter().enumerate() {
+                    let mut hasher = DefaultHasher::new();
+                    entity.hash(&mut hasher);
+                    for (type_id, registration, (ptr, element_size, _)) in columns.iter() {
+                        type_id.hash(&mut hasher);
+                        let component = unsafe { ptr.add(element_size * i) };
+                        match registration {
+                            Some(registration) => unsafe {
+                                registration.hash(component, &mut hasher);
+                            },
+                            None => hasher.write(unsafe {
+                                std::slice::from_raw_parts(component, *element_size)
+                            }),
+                        }
+                    }
+                    checksum = checksum.wrapping_add(hasher.finish());
+                }
+            }
+        }
+
+        Ok(checksum)
+    }
+
+    /// Builds a tree of the fields of each reflected component attached to the given entity.
+    ///
+    /// Only component types registered via `Registry::register_reflect` are included. Each
+    /// root node is named after its component type.
+    ///
+    /// Returns `None` if the entity is not alive.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if any other code is currently mutably borrowing the entity's components.
+    pub fn inspect(&self, entity: Entity) -> Option<Vec<FieldNode>> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = self.storage().archetypes().get(location.archetype())?;
+        let chunk = archetype
+            .chunksets()
+            .get(location.set())?
+            .get(location.chunk())?;
+
+        let mut nodes = Vec::new();
+        for (type_id, _) in archetype.description().components() {
+            let registration = match self.registry.get(*type_id) {
+                Some(registration) if registration.has_reflect() => registration,
+                _ => continue,
+            };
+            let (ptr, element_size, _) = chunk.components(*type_id)?.data_raw();
+            unsafe {
+                let component = ptr.add(element_size * location.component());
+                let reflect = &*registration.reflect(component)?;
+                nodes.push(reflect.to_node(registration.name()));
+            }
+        }
+
+        Some(nodes)
+    }
+
+    /// Runs `f` with a `Reflect` view of the given entity's component, allowing its fields
+    /// to be modified by name.
+    ///
+    /// Returns `None` if the entity is not alive, does not contain the component, or if the
+    /// component type was not registered via `Registry::register_reflect`.
+    pub fn reflect_component_mut<R, F: FnOnce(&mut dyn Reflect) -> R>(
+        &mut self,
+        entity: Entity,
+        type_id: ComponentTypeId,
+        f: F,
+    ) -> Option<R> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let registration = self.registry.get(type_id)?;
+        let location = self.entity_allocator.get_location(entity.index())?;
+        let archetype = self.storage().archetypes().get(location.archetype())?;
+        let chunk = archetype
+            .chunksets()
+            .get(location.set())?
+            .get(location.chunk())?;
+        let (ptr, element_size, _) = chunk.components(type_id)?.data_raw_mut();
+        unsafe {
+            let component = ptr.add(element_size * location.component());
+            let reflect = &mut *registration.reflect(component)?;
+            Some(f(reflect))
+        }
+    }
+
+    /// Sets fields of the given entity's reflected components from JSON, such as for console
+    /// commands or editor property panels which tweak live entities.
+    ///
+    /// `patch` must be an object whose members are named after component types, and are
+    /// applied to the entity's components via `apply_json` on `dyn Reflect`. Components are
+    /// named by their full type name, or by the last segment of their type path. Only component
+    /// types registered with `registry` via `Registry::register_reflect` can be patched.
+    ///
+    /// The patch is not applied atomically; fields visited before an error is encountered keep
+    /// their new values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # use legion::impl_reflect;
+    /// # use legion::registry::Registry;
+    /// # use serde_json::json;
+    /// struct Vec2 {
+    ///     x: f32,
+    ///     y: f32,
+    /// }
+    ///
+    /// struct Body {
+    ///     position: Vec2,
+    ///     mass: f32,
+    /// }
+    ///
+    /// impl_reflect!(Vec2 { x, y });
+    /// impl_reflect!(Body { position, mass });
+    ///
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let mut registry = Registry::new();
+    /// registry.register_reflect::<Body>();
+    ///
+    /// let body = Body { position: Vec2 { x: 0.0, y: 0.0 }, mass: 1.0 };
+    /// let entity = world.insert((), vec![(body,)])[0];
+    ///
+    /// let patch = json!({ "Body": { "position": { "y": 2.5 }, "mass": 4 } });
+    /// world.apply_json_patch(entity, &patch, &registry).unwrap();
+    ///
+    /// let body = world.get_component::<Body>(entity).unwrap();
+    /// assert_eq!(2.5, body.position.y);
+    /// assert_eq!(4.0, body.mass);
+    /// ```
+    #[cfg(feature = "serialize")]
+    pub fn apply_json_patch(
+        &mut self,
+        entity: Entity,
+        patch: &serde_json::Value,
+        registry: &Registry,
+    ) -> Result<(), PatchError> {
+        let location = match self.entity_allocator.get_location(entity.index()) {
+            Some(location) if self.is_alive(entity) => location,
+            _ => return Err(EntityError::NotAlive(entity).into()),
+        };
+        let components = match patch {
+            serde_json::Value::Object(components) => components,
+            _ => return Err(PatchError::InvalidValue(String::new())),
+        };
+
+        let archetype = &self.storage().archetypes()[location.archetype()];
+        let chunk = &archetype.chunksets()[location.set()][location.chunk()];
+        for (name, value) in components {
+            let names_type =
+                |type_name: &str| type_name == name || type_name.ends_with(&format!("::{}", name));
+            let (type_id, registration) = archetype
+                .description()
+                .components()
+                .iter()
+                .find_map(|(type_id, _)| {
+                    registry
+                        .get(*type_id)
+                        .filter(|r| r.has_reflect() && names_type(r.name()))
+                        .map(|r| (*type_id, r))
+                })
+                .ok_or_else(|| PatchError::UnknownComponent(name.clone()))?;
+
+            let (ptr, element_size, _) = chunk.components(type_id).unwrap().data_raw_mut();
+            unsafe {
+                let component = ptr.add(element_size * location.component());
+                let reflect = &mut *registration.reflect(component).unwrap();
+                reflect.apply_json_at(value, name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a non-sync component to an entity, or sets its value if the component is
+    /// already present.
+    ///
+    /// Non-sync components are not stored in chunks and cannot be accessed via queries. They
+    /// can only be accessed on the thread which created this world.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `T` has not been registered via `Registry::register_non_sync`,
+    /// if the entity is not alive, or if called from a thread other than the world's owning thread.
+    pub fn add_non_sync_component<T: NonSyncComponent>(&mut self, entity: Entity, component: T) {
+        assert!(
+            self.registry.is_non_sync::<T>(),
+            "non-sync component type {} has not been registered",
+            std::any::type_name::<T>()
+        );
+        assert!(self.is_alive(entity), "entity {} is not alive", entity);
+
+        trace!(
+            world = self.id().0,
+            ?entity,
+            component = std::any::type_name::<T>(),
+            "Adding non-sync component to entity"
+        );
+
+        self.non_sync.insert(entity, component);
+    }
+
+    /// Removes a non-sync component from an entity, returning its value.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if called from a thread other than the world's owning thread.
+    pub fn remove_non_sync_component<T: NonSyncComponent>(&mut self, entity: Entity) -> Option<T> {
+        self.non_sync.remove::<T>(entity)
+    }
+
+    /// Borrows non-sync component data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if called from a thread other than the world's owning thread, or
+    /// if the component is currently mutably borrowed.
+    pub fn get_non_sync_component<T: NonSyncComponent>(
+        &self,
+        entity: Entity,
+    ) -> Option<std::cell::Ref<'_, T>> {
+        self.non_sync.get::<T>(entity)
+    }
+
+    /// Mutably borrows non-sync component data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if called from a thread other than the world's owning thread.
+    pub fn get_non_sync_component_mut<T: NonSyncComponent>(
+        &mut self,
+        entity: Entity,
+    ) -> Option<std::cell::RefMut<'_, T>> {
+        self.non_sync.get_mut::<T>(entity)
+    }
+
+    /// Mutably borrows non-sync component data for the given entity, without requiring
+    /// exclusive access to the world.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if called from a thread other than the world's owning thread, or
+    /// if the component is currently borrowed.
+    pub(crate) fn get_non_sync_component_mut_unchecked<T: NonSyncComponent>(
+        &self,
+        entity: Entity,
+    ) -> Option<std::cell::RefMut<'_, T>> {
+        self.non_sync.get_mut::<T>(entity)
+    }
+
+    /// Determines if this world's non-sync components can be accessed from the current thread.
+    pub fn is_owner_thread(&self) -> bool { self.non_sync.is_owner_thread() }
+
+    /// Gets a read-only view of the world.
+    ///
+    /// The view is cheap to create and, unless the `single-thread` feature is enabled, may be
+    /// sent to other threads. It only provides immutable access to entity data, statically
+    /// preventing any structural changes to the world for as long as it is held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert((), vec![(Position(1.0),), (Position(2.0),)]);
+    ///
+    /// let view = world.read_view();
+    /// let mut query = Read::<Position>::query();
+    /// let total = view.iter(&mut query).map(|pos| pos.0).sum::<f32>();
+    /// assert_eq!(3.0, total);
+    /// ```
+    pub fn read_view(&self) -> WorldReadView<'_> { WorldReadView { world: self } }
+
+    /// Determines if the given `Entity` is alive within this `World`.
+    pub fn is_alive(&self, entity: Entity) -> bool { self.entity_allocator.is_alive(entity) }
+
+    /// Gets an iterator over all entities in `entities` which are alive within this world.
+    ///
+    /// This is considerably faster than calling `is_alive` for each entity when checking
+    /// many entities at once.
+    pub fn filter_alive<'a>(
+        &'a self,
+        entities: impl Iterator<Item = Entity> + 'a,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.entity_allocator.filter_alive(entities)
+    }
+
+    /// Gets the policy which determines how much defragmentation `maintain` performs.
+    pub fn defrag_policy(&self) -> DefragPolicy { self.defrag_policy }
+
+    /// Sets the policy which determines how much defragmentation `maintain` performs.
+    pub fn set_defrag_policy(&mut self, policy: DefragPolicy) { self.defrag_policy = policy; }
+
+    /// Queues a command buffer to be written into the world by the next `maintain` call.
+    pub fn queue_commands(&mut self, buffer: CommandBuffer) { self.queued_commands.push(buffer); }
+
+    /// Gets a handle to the world's shared command queue, which any thread may push command
+    /// buffers into. The queue is drained by the next `maintain` call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let queue = world.command_queue();
+    /// std::thread::spawn(move || {
+    ///     let commands = CommandBuffer::default();
+    ///     commands.insert((), vec![(Position(0.0),)]);
+    ///     queue.push(commands);
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// world.maintain();
+    /// assert_eq!(1, Read::<Position>::query().iter(&mut world).count());
+    /// ```
+    pub fn command_queue(&self) -> CommandQueue { self.command_queue.clone() }
+
+    /// Gets the component version recorded by the last `maintain` call.
+    ///
+    /// Components with a version greater than this have been written since the world was
+    /// last maintained, which can be tested with the `version` filter.
+    pub fn maintained_version(&self) -> u64 { self.maintained_version }
+
+    /// Performs the world's end of frame housekeeping, and should be called once per frame
+    /// after the frame's systems have executed.
+    ///
+    /// This writes all command buffers queued with `queue_commands` or pushed into the world's
+    /// `command_queue`, begins a new update of removal tracking, records the version baseline
+    /// returned by `maintained_version`, defragments the world according to its `DefragPolicy`,
+    /// reports moved entities to the `on_relocated` callbacks, and then writes a
+    /// `WorldEvent::Maintained` event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let entity = world.insert((), vec![(Position(0.0),)])[0];
+    ///
+    /// let commands = CommandBuffer::default();
+    /// commands.delete(entity);
+    /// world.queue_commands(commands);
+    /// assert!(world.is_alive(entity));
+    ///
+    /// world.maintain();
+    /// assert!(!world.is_alive(entity));
+    /// ```
+    pub fn maintain(&mut self) {
+        profile_scope!("World::maintain");
+        let span = span!(Level::TRACE, "Maintaining world", world = self.id().0);
+        let _guard = span.enter();
+
+        for buffer in std::mem::take(&mut self.queued_commands) {
+            buffer.write(self);
+        }
+        for buffer in self.command_queue.drain() {
+            buffer.write(self);
+        }
+
+        self.update_removed();
+        self.maintained_version = current_version();
+
+        match self.defrag_policy {
+            DefragPolicy::Never => {}
+            DefragPolicy::Incremental(budget) => {
+                self.defrag(Some(budget));
+            }
+            DefragPolicy::Full => {
+                self.defrag(None);
+            }
+        }
+        self.flush_relocations();
+
         #[cfg(feature = "events")]
         {
-            self.channel
-                .write(EntityEvent::Deleted(entity))
-                .expect("Failed to write to EntityEvent::Deleted channel.");
+            self.world_channel
+                .write(WorldEvent::Maintained(self.id))
+                .expect("Failed to write to WorldEvent::Maintained channel.");
+        }
+    }
+
+    /// Iteratively defragments the world's internal memory.
+    ///
+    /// This compacts entities into fewer more continuous chunks.
+    ///
+    /// `budget` describes the maximum number of entities that can be moved
+    /// in one call. Subsequent calls to `defrag` will resume progress from the
+    /// previous call.
+    ///
+    /// Returns a report of the work performed, which callers driving defragmentation with a
+    /// budget can use to adapt the budget of subsequent calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// let mut budget = 16;
+    /// loop {
+    ///     let report = world.defrag(Some(budget));
+    ///     if report.finished {
+    ///         break;
+    ///     }
+    ///
+    ///     // spend more time defragmenting while there is work left to do
+    ///     if report.entities_moved == budget {
+    ///         budget *= 2;
+    ///     }
+    /// }
+    /// ```
+    pub fn defrag(&mut self, budget: Option<usize>) -> DefragReport {
+        profile_scope!("World::defrag");
+        let span = span!(
+            Level::INFO,
+            "Defragmenting",
+            world = self.id().0,
+            start_archetype = self.defrag_progress
+        );
+        let _guard = span.enter();
+
+        let archetypes = unsafe { &mut *self.storage.get() }.archetypes_mut();
+        let mut budget = budget.unwrap_or(std::usize::MAX);
+        let start = self.defrag_progress;
+        let mut report = DefragReport::default();
+        let mut moved = 0;
+        while self.defrag_progress < archetypes.len() {
+            // defragment the next archetype
+            let complete = (&mut archetypes[self.defrag_progress]).defrag(
+                &mut budget,
+                &mut report.chunks_freed,
+                |e, location| {
+                    self.entity_allocator.set_location(e.index(), location);
+                    self.relocations.record(e);
+                    moved += 1;
+                },
+            );
+            if complete {
+                // increment the index, looping it once we get to the end
+                self.defrag_progress = (self.defrag_progress + 1) % archetypes.len();
+                report.archetypes_completed += 1;
+            }
+
+            // stop once we run out of budget or reach back to where we started
+            if budget == 0 || self.defrag_progress == start {
+                break;
+            }
         }
 
-        if let Some(location) = self.entity_allocator.delete_entity(entity) {
-            // find entity's chunk
-            let chunk = self
-                .storage_mut()
-                .archetypes_mut()
-                .get_mut(location.archetype())
-                .unwrap()
-                .chunksets_mut()
-                .get_mut(location.set())
-                .unwrap()
-                .get_mut(location.chunk())
-                .unwrap();
+        report.entities_moved = moved;
+        report.finished = report.archetypes_completed >= archetypes.len();
+        trace!(
+            world = self.id().0,
+            entities_moved = report.entities_moved,
+            chunks_freed = report.chunks_freed,
+            finished = report.finished,
+            "Defragmented world"
+        );
 
-            // swap remove with last entity in chunk
-            if let Some(swapped) = chunk.swap_remove(location.component(), true) {
-                // record swapped entity's new location
-                self.entity_allocator
-                    .set_location(swapped.index(), location);
+        report
+    }
+
+    pub fn merge(&mut self, world: World) {
+        let span =
+            span!(Level::INFO, "Merging worlds", source = world.id().0, destination = ?self.id());
+        let _guard = span.enter();
+
+        self.registry.merge(&world.registry);
+        self.non_sync.merge(world.non_sync);
+        self.entity_allocator.merge(world.entity_allocator);
+
+        let mut merged = Vec::new();
+        for archetype in unsafe { &mut *world.storage.get() }.drain(..) {
+            // use the description as an archetype filter
+            let mut desc = archetype.description().clone();
+            let archetype_data = ArchetypeFilterData {
+                component_types: self.storage().component_types(),
+                tag_types: self.storage().tag_types(),
+                archetypes: self.storage().archetypes(),
+            };
+            let matches = desc.matches(archetype_data).matching_indices().next();
+            if let Some(arch_index) = matches {
+                // similar archetype already exists, merge
+                self.storage_mut()
+                    .archetypes_mut()
+                    .get_mut(arch_index)
+                    .unwrap()
+                    .merge(archetype);
+                merged.push(arch_index);
+            } else {
+                // archetype does not already exist, append
+                self.storage_mut().push(archetype);
+                merged.push(self.storage().archetypes().len() - 1);
             }
+        }
 
-            trace!(world = self.id().0, ?entity, "Deleted entity");
+        // renumber the moved archetypes and chunks, and record the new entity locations
+        let id = self.id;
+        self.storage.get_mut().set_world_id(id);
+        let archetypes = unsafe { &*self.storage.get() }.archetypes();
+        for archetype_index in merged {
+            let archetype = &archetypes[archetype_index];
+            for (set_index, set) in archetype.chunksets().iter().enumerate() {
+                for (chunk_index, chunk) in set.iter().enumerate() {
+                    for (component_index, entity) in chunk.entities().iter().enumerate() {
+                        let location = EntityLocation::new(
+                            archetype_index,
+                            set_index,
+                            chunk_index,
+                            component_index,
+                        );
+                        self.entity_allocator.set_location(entity.index(), location);
+                    }
+                }
+            }
+        }
+    }
 
-            true
-        } else {
-            false
+    /// Finds all non-empty chunks which match `filter`, grouped by archetype and chunk set index.
+    fn matching_chunks<F: EntityFilter>(&self, filter: &mut F) -> Vec<(usize, usize, Vec<usize>)> {
+        let (arch_filter, chunkset_filter, chunk_filter) = filter.filters();
+        begin_pass(arch_filter, chunkset_filter, chunk_filter);
+
+        let sets = filter
+            .iter(self.storage())
+            .map(|(archetype, set)| (archetype.index(), set))
+            .collect::<Vec<_>>();
+        let (_, _, chunk_filter) = filter.filters();
+        let mut matches = Vec::new();
+        for (archetype, set) in sets {
+            let chunks = self.storage().archetypes()[archetype].chunksets()[set].occupied();
+            let matching = chunk_filter
+                .collect(ChunkFilterData { chunks })
+                .enumerate()
+                .take(chunks.len())
+                .filter(|(_, data)| chunk_filter.is_match(data).is_pass())
+                .map(|(index, _)| index)
+                .filter(|index| !chunks[*index].is_empty())
+                .collect::<Vec<_>>();
+            if !matching.is_empty() {
+                matches.push((archetype, set, matching));
+            }
         }
+
+        matches
     }
 
-    fn find_chunk_with_delta(
-        &mut self,
-        source_location: EntityLocation,
-        add_components: &[(ComponentTypeId, ComponentMeta)],
-        remove_components: &[ComponentTypeId],
-        add_tags: &[(TagTypeId, TagMeta, NonNull<u8>)],
-        remove_tags: &[TagTypeId],
-    ) -> (usize, usize) {
-        let archetype = {
-            let result = {
-                let source_archetype = self
-                    .storage()
-                    .archetypes()
-                    .get(source_location.archetype())
-                    .unwrap();
+    /// Reorders the entities within each chunk which matches `filter` such that they are
+    /// sorted by their `K` component. Entities do not move between chunks.
+    ///
+    /// Grouping entities with equal keys together improves branch prediction for systems
+    /// which branch on the value of `K`. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// # enum State { Idle, Moving }
+    /// # let universe = Universe::new();
+    /// # let mut world = universe.create_world();
+    /// world.insert(
+    ///     (),
+    ///     vec![(State::Moving,), (State::Idle,), (State::Moving,), (State::Idle,)],
+    /// );
+    ///
+    /// world.sort_chunk_entities::<State, _>(component::<State>());
+    ///
+    /// let states = Read::<State>::query()
+    ///     .iter(&mut world)
+    ///     .map(|state| *state)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     vec![State::Idle, State::Idle, State::Moving, State::Moving],
+    ///     states
+    /// );
+    /// ```
+    pub fn sort_chunk_entities<K, F>(&mut self, mut filter: F)
+    where
+        K: Component + Ord,
+        F: EntityFilter,
+    {
+        profile_scope!("World::sort_chunk_entities");
+        let span = span!(
+            Level::TRACE,
+            "Sorting chunk entities",
+            world = self.id().0,
+            key = std::any::type_name::<K>()
+        );
+        let _guard = span.enter();
 
-                // find target chunk
-                let mut component_layout = DynamicComponentLayout {
-                    existing: source_archetype.description().components(),
-                    add: add_components,
-                    remove: remove_components,
+        let type_id = ComponentTypeId::of::<K>();
+        for (archetype_index, set_index, chunks) in self.matching_chunks(&mut filter) {
+            for chunk_index in chunks {
+                let storage = unsafe { &mut *self.storage.get() };
+                let chunk = &mut storage.archetypes_mut()[archetype_index].chunksets_mut()
+                    [set_index][chunk_index];
+
+                let order = match chunk.components(type_id) {
+                    Some(components) => {
+                        let keys = unsafe { components.data_slice::<K>() };
+                        let mut order = (0..keys.len()).collect::<Vec<_>>();
+                        order.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+                        order
+                    }
+                    None => break,
                 };
 
-                let mut tag_layout = DynamicTagLayout {
-                    storage: self.storage(),
-                    archetype: source_location.archetype(),
-                    chunk: source_location.chunk(),
-                    existing: source_archetype.description().tags(),
-                    add: add_tags,
-                    remove: remove_tags,
-                };
+                if order.iter().enumerate().all(|(i, source)| i == *source) {
+                    continue;
+                }
 
-                let archetype = self.find_archetype(&mut tag_layout, &mut component_layout);
-                if let Some(archetype) = archetype.as_ref() {
-                    if let Some(chunk) = self.find_chunk_set(*archetype, &mut tag_layout) {
-                        // fast path: chunk already exists
-                        return (*archetype, chunk);
+                chunk.permute(&order);
+                for (i, entity) in chunk.entities().iter().enumerate() {
+                    let location = EntityLocation::new(archetype_index, set_index, chunk_index, i);
+                    self.entity_allocator.set_location(entity.index(), location);
+                    self.relocations.record(*entity);
+                }
+            }
+        }
+    }
+
+    /// Moves all entities which match `filter` out of this world and into a new `World`.
+    ///
+    /// The new world is created within the same universe as this world. Matching chunks are
+    /// moved in their entirety, without copying their component data. Moved entities are
+    /// assigned new `Entity` IDs within the new world. See `split_off_with_remap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Region(u32);
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// world.insert((Region(0),), vec![(Position(0.0),), (Position(1.0),)]);
+    /// world.insert((Region(1),), vec![(Position(2.0),)]);
+    ///
+    /// let region = world.split_off(tag_value(&Region(1)));
+    ///
+    /// let mut query = Read::<Position>::query();
+    /// assert_eq!(2, query.iter_immutable(&world).count());
+    /// assert_eq!(1, query.iter_immutable(&region).count());
+    /// ```
+    pub fn split_off<F: EntityFilter>(&mut self, filter: F) -> World {
+        self.split_off_with_remap(filter).0
+    }
+
+    /// Moves all entities which match `filter` out of this world and into a new `World`.
+    ///
+    /// Returns the new world and a map from each moved entity's ID in this world to its new ID
+    /// in the new world. Components which refer to moved entities must be patched by the caller
+    /// using this map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use legion::prelude::*;
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Position(f32);
+    /// # #[derive(Copy, Clone, Debug, PartialEq)]
+    /// # struct Region(u32);
+    /// let universe = Universe::new();
+    /// let mut world = universe.create_world();
+    /// let entity = world.insert((Region(1),), vec![(Position(2.0),)])[0];
+    ///
+    /// let (region, remap) = world.split_off_with_remap(tag_value(&Region(1)));
+    ///
+    /// assert!(!world.is_alive(entity));
+    /// let moved = remap[&entity];
+    /// assert_eq!(Position(2.0), *region.get_component::<Position>(moved).unwrap());
+    /// ```
+    pub fn split_off_with_remap<F: EntityFilter>(
+        &mut self,
+        mut filter: F,
+    ) -> (World, HashMap<Entity, Entity>) {
+        let mut world = World::new_in_universe(
+            self.registration.ids.register(),
+            EntityAllocator::new(self.entity_allocator.block_allocator()),
+        );
+        world.registry = self.registry.clone();
+
+        let span =
+            span!(Level::INFO, "Splitting world", source = self.id().0, destination = ?world.id());
+        let _guard = span.enter();
+
+        let mut remap = HashMap::new();
+        self.move_chunks(&mut filter, &mut world, |old, new| {
+            remap.insert(old, new);
+        });
+        (world, remap)
+    }
+
+    /// Moves all chunks which match `filter` into `world`, allocating new entity IDs for their
+    /// entities within `world`. `moved` is called with the old and new IDs of each entity.
+    fn move_chunks<F, M>(&mut self, filter: &mut F, world: &mut World, mut moved: M)
+    where
+        F: EntityFilter,
+        M: FnMut(Entity, Entity),
+    {
+        // find all chunks which match the filter
+        let matches = self.matching_chunks(filter);
+
+        let mut archetype_map = HashMap::new();
+        for (archetype_index, set_index, chunks) in matches {
+            // find or create the archetype and chunkset in the new world
+            let source_archetype = &self.storage().archetypes()[archetype_index];
+            let target_archetype_index = *archetype_map.entry(archetype_index).or_insert_with(|| {
+                world
+                    .storage_mut()
+                    .alloc_archetype(source_archetype.description().clone())
+                    .0
+            });
+            let tags = source_archetype.tags().tag_set(set_index);
+            let target_set_index = world.create_chunk_set(target_archetype_index, &tags);
+
+            // move chunks in descending order, so that chunks which have yet to be moved
+            // are never swapped into a new position
+            for chunk_index in chunks.into_iter().rev() {
+                let mut chunk = self.storage_mut().archetypes_mut()[archetype_index]
+                    .swap_remove_chunk(set_index, chunk_index);
+
+                // record the new locations of the entities in the chunk swapped into place
+                let storage = unsafe { &*self.storage.get() };
+                let chunkset = &storage.archetypes()[archetype_index].chunksets()[set_index];
+                if let Some(swapped) = chunkset.get(chunk_index) {
+                    for (i, entity) in swapped.entities().iter().enumerate() {
+                        let location =
+                            EntityLocation::new(archetype_index, set_index, chunk_index, i);
+                        self.entity_allocator.set_location(entity.index(), location);
+                        self.relocations.record(*entity);
                     }
+                }
 
-                    Ok(*archetype)
-                } else {
-                    let mut description = ArchetypeDescription::default();
-                    component_layout.tailor_archetype(&mut description);
-                    tag_layout.tailor_archetype(&mut description);
+                // re-allocate the moved entities within the new world
+                let target_chunk_index = world.storage().archetypes()[target_archetype_index]
+                    .chunksets()[target_set_index]
+                    .len();
+                let mut writer = chunk.writer();
+                let (entities, _) = writer.get();
+                for (i, entity) in entities.iter_mut().enumerate() {
+                    self.entity_allocator.delete_entity(*entity);
+
+                    #[cfg(feature = "events")]
+                    {
+                        self.channel
+                            .write(EntityEvent::Deleted(*entity))
+                            .expect("Failed to write to EntityEvent::Deleted channel.");
+                    }
 
-                    Err(description)
+                    let new_entity = world.entity_allocator.create_entity();
+                    let location = EntityLocation::new(
+                        target_archetype_index,
+                        target_set_index,
+                        target_chunk_index,
+                        i,
+                    );
+                    world
+                        .entity_allocator
+                        .set_location(new_entity.index(), location);
+                    self.non_sync
+                        .move_entity(*entity, &mut world.non_sync, new_entity);
+                    moved(*entity, new_entity);
+                    *entity = new_entity;
                 }
+                drop(writer);
+
+                world.storage_mut().archetypes_mut()[target_archetype_index]
+                    .push_chunk(target_set_index, chunk);
+            }
+        }
+    }
+
+    fn find_archetype<T, C>(&self, tags: &mut T, components: &mut C) -> Option<usize>
+    where
+        T: for<'a> Filter<ArchetypeFilterData<'a>>,
+        C: for<'a> Filter<ArchetypeFilterData<'a>>,
+    {
+        // search for an archetype with an exact match for the desired component layout
+        let archetype_data = ArchetypeFilterData {
+            component_types: self.storage().component_types(),
+            tag_types: self.storage().tag_types(),
+            archetypes: self.storage().archetypes(),
+        };
+
+        // zip the two filters together - find the first index that matches both
+        tags.matches(archetype_data)
+            .zip(components.matches(archetype_data))
+            .enumerate()
+            .take(self.storage().archetypes().len())
+            .filter(|(_, (a, b))| *a && *b)
+            .map(|(i, _)| i)
+            .next()
+    }
+
+    fn create_archetype<T, C>(&mut self, tags: &T, components: &C) -> usize
+    where
+        T: TagLayout,
+        C: ComponentLayout,
+    {
+        let mut description = ArchetypeDescription::default();
+        tags.tailor_archetype(&mut description);
+        components.tailor_archetype(&mut description);
+
+        let (index, _) = self.storage_mut().alloc_archetype(description);
+        index
+    }
+
+    fn find_or_create_archetype<T, C>(&mut self, tags: &mut T, components: &mut C) -> usize
+    where
+        T: TagLayout,
+        C: ComponentLayout,
+    {
+        if let Some(i) = self.find_archetype(tags.get_filter(), components.get_filter()) {
+            i
+        } else {
+            self.create_archetype(tags, components)
+        }
+    }
+
+    fn find_chunk_set<T>(&self, archetype: usize, tags: &mut T) -> Option<usize>
+    where
+        T: for<'a> Filter<ChunksetFilterData<'a>>,
+    {
+        // fetch the archetype, we can already assume that the archetype index is valid
+        let archetype_data = unsafe { self.storage().archetypes().get_unchecked(archetype) };
+
+        // find a chunk with the correct tags
+        let chunk_filter_data = ChunksetFilterData {
+            archetype_data: archetype_data.deref(),
+        };
+
+        if let Some(i) = tags.matches(chunk_filter_data).matching_indices().next() {
+            return Some(i);
+        }
+
+        None
+    }
+
+    fn create_chunk_set<T>(&mut self, archetype: usize, tags: &T) -> usize
+    where
+        T: TagSet,
+    {
+        let archetype_data = unsafe {
+            self.storage_mut()
+                .archetypes_mut()
+                .get_unchecked_mut(archetype)
+        };
+        let set = archetype_data.alloc_chunk_set(|chunk_tags| tags.write_tags(chunk_tags));
+        if self.intern_tags {
+            self.intern_chunk_set_tags(archetype, set);
+        }
+
+        set
+    }
+
+    // Replaces each tag value of a chunk set with a clone of an equal value stored elsewhere.
+    fn intern_chunk_set_tags(&mut self, archetype: usize, set: usize) {
+        let storage = self.storage.get_mut();
+        let tag_types = storage.archetypes()[archetype]
+            .description()
+            .tags()
+            .to_vec();
+        for (type_id, meta) in tag_types {
+            if meta.is_zero_sized() {
+                continue;
+            }
+
+            let canonical = unsafe {
+                let (ptr, size, _) = storage.archetypes()[archetype]
+                    .tags()
+                    .get(type_id)
+                    .unwrap()
+                    .data_raw();
+                let value = ptr.as_ptr().add(set * size) as *const u8;
+                storage
+                    .archetypes()
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, data)| data.tags().get(type_id).map(|tags| (i, tags)))
+                    .find_map(|(i, tags)| {
+                        let (ptr, size, len) = tags.data_raw();
+                        (0..len)
+                            .filter(|j| (i, *j) != (archetype, set))
+                            .map(|j| ptr.as_ptr().add(j * size) as *const u8)
+                            .find(|candidate| meta.equals(*candidate, value))
+                    })
             };
 
-            match result {
-                Ok(arch) => arch,
-                Err(desc) => {
-                    let (index, _) = self.storage_mut().alloc_archetype(desc);
-                    index
+            if let Some(canonical) = canonical {
+                // safe because the canonical value is stored by a different chunk set
+                unsafe {
+                    storage.archetypes_mut()[archetype]
+                        .tags_mut()
+                        .get_mut(type_id)
+                        .unwrap()
+                        .replace_with_clone(set, canonical);
                 }
             }
-        };
-
-        // slow path: create new chunk
-        let source_archetype = self
-            .storage()
-            .archetypes()
-            .get(source_location.archetype())
-            .unwrap();
-        let mut tags = source_archetype.tags().tag_set(source_location.chunk());
-        for type_id in remove_tags.iter() {
-            tags.remove(*type_id);
         }
-        for (type_id, meta, ptr) in add_tags.iter() {
-            tags.push(*type_id, *meta, *ptr);
+    }
+
+    fn find_or_create_chunk<T>(&mut self, archetype: usize, tags: &mut T) -> usize
+    where
+        T: TagSet + for<'a> Filter<ChunksetFilterData<'a>>,
+    {
+        if let Some(i) = self.find_chunk_set(archetype, tags) {
+            i
+        } else {
+            self.create_chunk_set(archetype, tags)
         }
+    }
+}
 
-        let chunk = self.create_chunk_set(archetype, &tags);
+impl Default for World {
+    fn default() -> Self { Self::new() }
+}
 
-        (archetype, chunk)
+impl std::fmt::Debug for World {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.debug_struct("World")
+            .field("id", &self.id)
+            .field("archetypes", &self.storage().archetypes().len())
+            .field("entities", &self.len())
+            .finish()
     }
+}
 
-    fn move_entity(
-        &mut self,
-        entity: Entity,
-        add_components: &[(ComponentTypeId, ComponentMeta)],
-        remove_components: &[ComponentTypeId],
-        add_tags: &[(TagTypeId, TagMeta, NonNull<u8>)],
-        remove_tags: &[TagTypeId],
-    ) -> &mut ComponentStorage {
-        let location = self
-            .entity_allocator
-            .get_location(entity.index())
-            .expect("entity not found");
+/// A read-only view of a world.
+///
+/// The view provides immutable access to component and tag data, and iteration of read-only
+/// queries. It cannot be used to make structural changes to the world or to write to
+/// components, so it can be safely handed to another thread (such as a render extraction
+/// thread) while the world is borrowed, unless the `single-thread` feature is enabled.
+/// Created by `World::read_view`.
+#[derive(Clone, Copy)]
+pub struct WorldReadView<'a> {
+    world: &'a World,
+}
 
-        // find or create the target chunk
-        let (target_arch_index, target_chunkset_index) = self.find_chunk_with_delta(
-            location,
-            add_components,
-            remove_components,
-            add_tags,
-            remove_tags,
-        );
+impl<'a> WorldReadView<'a> {
+    /// Gets the unique ID of the viewed world.
+    pub fn id(&self) -> WorldId { self.world.id() }
 
-        // Safety Note:
-        // It is only safe for us to have 2 &mut references to storage here because
-        // we know we are only going to be modifying two chunks that are at different
-        // indexes.
+    /// Determines if the given `Entity` is alive within the viewed world.
+    pub fn is_alive(&self, entity: Entity) -> bool { self.world.is_alive(entity) }
 
-        // fetch entity's chunk
-        let current_chunk = unsafe { &mut *self.storage.get() }
-            .archetypes_mut()
-            .get_mut(location.archetype())
-            .unwrap()
-            .chunksets_mut()
-            .get_mut(location.set())
-            .unwrap()
-            .get_mut(location.chunk())
-            .unwrap();
+    /// Borrows component data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    pub fn get_component<T: Component>(&self, entity: Entity) -> Option<Ref<'a, Shared<'a>, T>> {
+        self.world.get_component(entity)
+    }
 
-        // fetch target chunk
-        let archetype = unsafe { &mut *self.storage.get() }
-            .archetypes_mut()
-            .get_mut(target_arch_index)
-            .unwrap();
-        let target_chunk_index = archetype.get_free_chunk(target_chunkset_index);
-        let target_chunk = unsafe {
-            archetype
-                .chunksets_mut()
-                .get_unchecked_mut(target_chunkset_index)
-                .get_unchecked_mut(target_chunk_index)
-        };
+    /// Gets tag data for the given entity.
+    ///
+    /// Returns `Some(data)` if the entity was found and contains the specified data.
+    /// Otherwise `None` is returned.
+    pub fn get_tag<T: Tag>(&self, entity: Entity) -> Option<&'a T> { self.world.get_tag(entity) }
 
-        // move existing data over into new chunk
-        if let Some(swapped) = current_chunk.move_entity(target_chunk, location.component()) {
-            // update location of any entity that was moved into the previous location
-            self.entity_allocator
-                .set_location(swapped.index(), location);
-        }
+    /// Gets an iterator which iterates through all chunks that match a read-only query.
+    pub fn iter_chunks<'q, V, F>(
+        &self,
+        query: &'q mut Query<V, F>,
+    ) -> ChunkViewIter<'a, 'q, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>
+    where
+        V: for<'v> View<'v> + ReadOnly,
+        F: EntityFilter,
+    {
+        query.iter_chunks_immutable(self.world)
+    }
 
-        // record the entity's new location
-        self.entity_allocator.set_location(
-            entity.index(),
-            EntityLocation::new(
-                target_arch_index,
-                target_chunkset_index,
-                target_chunk_index,
-                target_chunk.len() - 1,
-            ),
-        );
+    /// Gets an iterator which iterates through all entity data that matches a read-only query.
+    pub fn iter<'q, V, F>(
+        &self,
+        query: &'q mut Query<V, F>,
+    ) -> ChunkDataIter<
+        'a,
+        V,
+        ChunkViewIter<'a, 'q, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    >
+    where
+        V: for<'v> View<'v> + ReadOnly,
+        F: EntityFilter,
+    {
+        query.iter_immutable(self.world)
+    }
 
-        target_chunk
+    /// Gets an iterator which iterates through all entity data that matches a read-only query,
+    /// and also yields the `Entity` IDs.
+    pub fn iter_entities<'q, V, F>(
+        &self,
+        query: &'q mut Query<V, F>,
+    ) -> ChunkEntityIter<
+        'a,
+        V,
+        ChunkViewIter<'a, 'q, V, F::ArchetypeFilter, F::ChunksetFilter, F::ChunkFilter>,
+    >
+    where
+        V: for<'v> View<'v> + ReadOnly,
+        F: EntityFilter,
+    {
+        query.iter_entities_immutable(self.world)
     }
+}
 
-    /// Adds a component to an entity, or sets its value if the component is
-    /// already present.
-    pub fn add_component<T: Component>(&mut self, entity: Entity, component: T) {
-        if let Some(mut comp) = self.get_component_mut(entity) {
-            *comp = component;
-            return;
-        }
+/// Describes the types of a set of components attached to an entity.
+pub trait ComponentLayout: Sized {
+    /// A filter type which filters archetypes to an exact match with this layout.
+    type Filter: for<'a> Filter<ArchetypeFilterData<'a>>;
 
-        trace!(
-            world = self.id().0,
-            ?entity,
-            component = std::any::type_name::<T>(),
-            "Adding component to entity"
-        );
+    /// Gets the archetype filter for this layout.
+    fn get_filter(&mut self) -> &mut Self::Filter;
 
-        // move the entity into a suitable chunk
-        let target_chunk = self.move_entity(
-            entity,
-            &[(ComponentTypeId::of::<T>(), ComponentMeta::of::<T>())],
-            &[],
-            &[],
-            &[],
-        );
+    /// Modifies an archetype description to include the components described by this layout.
+    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription);
+}
 
-        // push new component into chunk
-        let mut writer = target_chunk.writer();
-        let (_, components) = writer.get();
-        let slice = [component];
-        unsafe {
-            let components = &mut *components.get();
-            components
-                .get_mut(ComponentTypeId::of::<T>())
-                .unwrap()
-                .writer()
-                .push(&slice);
+/// Describes the types of a set of tags attached to an entity.
+pub trait TagLayout: Sized {
+    /// A filter type which filters archetypes to an exact match with this layout.
+    type Filter: for<'a> Filter<ArchetypeFilterData<'a>>;
+
+    /// Gets the archetype filter for this layout.
+    fn get_filter(&mut self) -> &mut Self::Filter;
+
+    /// Modifies an archetype description to include the tags described by this layout.
+    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription);
+}
+
+/// A set of tag values to be attached to an entity.
+pub trait TagSet {
+    /// Writes the tags in this set to a new chunk.
+    fn write_tags(&self, tags: &mut Tags);
+}
+
+/// A set of components to be attached to one or more entities.
+pub trait ComponentSource: ComponentLayout {
+    /// Determines if this component source has any more entity data to write.
+    fn is_empty(&mut self) -> bool;
+
+    /// Gets the number of entities remaining in the source, if known.
+    fn remaining(&mut self) -> Option<usize> { None }
+
+    /// Writes as many components as possible into a chunk.
+    fn write(&mut self, allocator: &mut EntityAllocator, chunk: &mut ComponentStorage) -> usize;
+}
+
+/// An object that can be converted into a `ComponentSource`.
+pub trait IntoComponentSource {
+    /// The component source type that can be converted into.
+    type Source: ComponentSource;
+
+    /// Converts `self` into a component source.
+    fn into(self) -> Self::Source;
+}
+
+/// A `ComponentSource` which can insert tuples of components representing each entity into a world.
+pub struct ComponentTupleSet<T, I>
+where
+    I: Iterator<Item = T>,
+{
+    iter: Peekable<I>,
+    filter: ComponentTupleFilter<T>,
+}
+
+impl<T, I> From<I> for ComponentTupleSet<T, I>
+where
+    I: Iterator<Item = T>,
+    ComponentTupleSet<T, I>: ComponentSource,
+{
+    fn from(iter: I) -> Self {
+        ComponentTupleSet {
+            iter: iter.peekable(),
+            filter: ComponentTupleFilter {
+                _phantom: PhantomData,
+            },
         }
-        std::mem::forget(slice);
     }
+}
 
-    /// Removes a component from an entity.
-    pub fn remove_component<T: Component>(&mut self, entity: Entity) {
-        if self.get_component::<T>(entity).is_some() {
-            trace!(
-                world = self.id().0,
-                ?entity,
-                component = std::any::type_name::<T>(),
-                "Removing component from entity"
-            );
+impl<I> IntoComponentSource for I
+where
+    I: IntoIterator,
+    ComponentTupleSet<I::Item, I::IntoIter>: ComponentSource,
+{
+    type Source = ComponentTupleSet<I::Item, I::IntoIter>;
 
-            // move the entity into a suitable chunk
-            self.move_entity(entity, &[], &[ComponentTypeId::of::<T>()], &[], &[]);
+    fn into(self) -> Self::Source {
+        ComponentTupleSet {
+            iter: self.into_iter().peekable(),
+            filter: ComponentTupleFilter {
+                _phantom: PhantomData,
+            },
         }
     }
+}
 
-    /// Adds a tag to an entity, or sets its value if the tag is
-    /// already present.
-    pub fn add_tag<T: Tag>(&mut self, entity: Entity, tag: T) {
-        if self.get_tag::<T>(entity).is_some() {
-            self.remove_tag::<T>(entity);
-        }
+/// A component source which generates the components for each entity by calling a closure
+/// with the entity's index, such that procedurally spawned entities need not first be
+/// collected into a `Vec`. Created by `from_fn`.
+///
+/// A tuple of `(count, closure)` cannot itself be used as a component source, as it would
+/// conflict with the implementation for all iterators.
+pub struct FromFn<F> {
+    count: usize,
+    f: F,
+}
+
+/// Creates a component source which yields `count` entities, calling `f` with the index of
+/// each entity to create its components.
+///
+/// # Examples
+///
+/// ```
+/// # use legion::prelude::*;
+/// # use legion::world::from_fn;
+/// # #[derive(Copy, Clone, Debug, PartialEq)]
+/// # struct Position(f32, f32);
+/// # let universe = Universe::new();
+/// # let mut world = universe.create_world();
+/// let grid = from_fn(100, |i| (Position((i % 10) as f32, (i / 10) as f32),));
+/// world.insert((), grid);
+/// ```
+pub fn from_fn<T, F: FnMut(usize) -> T>(count: usize, f: F) -> FromFn<F> { FromFn { count, f } }
+
+impl<T, F: FnMut(usize) -> T> IntoIterator for FromFn<F> {
+    type Item = T;
+    type IntoIter = std::iter::Map<std::ops::Range<usize>, F>;
+
+    fn into_iter(self) -> Self::IntoIter { (0..self.count).map(self.f) }
+}
 
-        trace!(
-            world = self.id().0,
-            ?entity,
-            tag = std::any::type_name::<T>(),
-            "Adding tag to entity"
-        );
+/// The entities created by an insertion into a world.
+///
+/// New entities are written contiguously into each chunk they are inserted into, so rather than
+/// copying them into a separate buffer, this refers to the run of entities created within each
+/// chunk. It can be indexed and iterated like a slice of entities.
+#[derive(Clone, Debug)]
+pub struct InsertedEntities<'a> {
+    slices: Vec<&'a [Entity]>,
+    len: usize,
+}
 
-        // move the entity into a suitable chunk
-        self.move_entity(
-            entity,
-            &[],
-            &[],
-            &[(
-                TagTypeId::of::<T>(),
-                TagMeta::of::<T>(),
-                NonNull::new(&tag as *const _ as *mut u8).unwrap(),
-            )],
-            &[],
-        );
+impl<'a> InsertedEntities<'a> {
+    fn new(slices: Vec<&'a [Entity]>) -> Self {
+        let len = slices.iter().map(|slice| slice.len()).sum();
+        InsertedEntities { slices, len }
     }
 
-    /// Removes a tag from an entity.
-    pub fn remove_tag<T: Tag>(&mut self, entity: Entity) {
-        if self.get_tag::<T>(entity).is_some() {
-            trace!(
-                world = self.id().0,
-                ?entity,
-                tag = std::any::type_name::<T>(),
-                "Removing tag from entity"
-            );
+    /// Gets the number of entities created.
+    pub fn len(&self) -> usize { self.len }
 
-            // move the entity into a suitable chunk
-            self.move_entity(entity, &[], &[], &[], &[TagTypeId::of::<T>()]);
-        }
-    }
+    /// Determines if no entities were created.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
 
-    /// Borrows component data for the given entity.
-    ///
-    /// Returns `Some(data)` if the entity was found and contains the specified data.
-    /// Otherwise `None` is returned.
-    pub fn get_component<T: Component>(&self, entity: Entity) -> Option<Ref<Shared, T>> {
-        if !self.is_alive(entity) {
-            return None;
+    /// Gets the entity at the given position, in the order the entities were created.
+    pub fn get(&self, mut index: usize) -> Option<&'a Entity> {
+        for slice in self.slices.iter() {
+            if index < slice.len() {
+                return slice.get(index);
+            }
+            index -= slice.len();
         }
 
-        let location = self.entity_allocator.get_location(entity.index())?;
-        let archetype = self.storage().archetypes().get(location.archetype())?;
-        let chunk = archetype
-            .chunksets()
-            .get(location.set())?
-            .get(location.chunk())?;
-        let (slice_borrow, slice) = unsafe {
-            chunk
-                .components(ComponentTypeId::of::<T>())?
-                .data_slice::<T>()
-                .deconstruct()
-        };
-        let component = slice.get(location.component())?;
-
-        Some(Ref::new(slice_borrow, component))
+        None
     }
 
-    /// Mutably borrows entity data for the given entity.
-    ///
-    /// Returns `Some(data)` if the entity was found and contains the specified data.
-    /// Otherwise `None` is returned.
-    ///
-    /// # Safety
-    ///
-    /// Accessing a component which is already being concurrently accessed elsewhere is undefined behavior.
-    ///
-    /// # Panics
-    ///
-    /// This function may panic if any other code is currently borrowing `T` (such as in a query).
-    pub unsafe fn get_component_mut_unchecked<T: Component>(
-        &self,
-        entity: Entity,
-    ) -> Option<RefMut<Exclusive, T>> {
-        if !self.is_alive(entity) {
-            return None;
+    /// Gets an iterator over the created entities.
+    pub fn iter(&self) -> InsertedEntitiesIter<'a> {
+        InsertedEntitiesIter {
+            slices: self.slices.clone().into_iter(),
+            current: [].iter(),
+            remaining: self.len,
         }
+    }
 
-        let location = self.entity_allocator.get_location(entity.index())?;
-        let archetype = self.storage().archetypes().get(location.archetype())?;
-        let chunk = archetype
-            .chunksets()
-            .get(location.set())?
-            .get(location.chunk())?;
-        let (slice_borrow, slice) = chunk
-            .components(ComponentTypeId::of::<T>())?
-            .data_slice_mut::<T>()
-            .deconstruct();
-        let component = slice.get_mut(location.component())?;
+    /// Copies the created entities into a `Vec`.
+    pub fn to_vec(&self) -> Vec<Entity> { self.iter().copied().collect() }
+}
 
-        Some(RefMut::new(slice_borrow, component))
-    }
+impl<'a> Index<usize> for InsertedEntities<'a> {
+    type Output = Entity;
 
-    /// Mutably borrows entity data for the given entity.
-    ///
-    /// Returns `Some(data)` if the entity was found and contains the specified data.
-    /// Otherwise `None` is returned.
-    pub fn get_component_mut<T: Component>(
-        &mut self,
-        entity: Entity,
-    ) -> Option<RefMut<Exclusive, T>> {
-        // safe because the &mut self ensures exclusivity
-        unsafe { self.get_component_mut_unchecked(entity) }
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: {} entities were inserted but the index is {}",
+                self.len, index
+            )
+        })
     }
+}
 
-    /// Mutably borrows entity data for the given entity.
-    ///
-    /// Returns `Some(data)` if the entity was found and contains the specified data.
-    /// Otherwise `None` is returned.
+impl<'a> IntoIterator for InsertedEntities<'a> {
+    type Item = &'a Entity;
+    type IntoIter = InsertedEntitiesIter<'a>;
 
-    /// Gets tag data for the given entity.
-    ///
-    /// Returns `Some(data)` if the entity was found and contains the specified data.
-    /// Otherwise `None` is returned.
-    pub fn get_tag<T: Tag>(&self, entity: Entity) -> Option<&T> {
-        if !self.is_alive(entity) {
-            return None;
+    fn into_iter(self) -> Self::IntoIter {
+        InsertedEntitiesIter {
+            slices: self.slices.into_iter(),
+            current: [].iter(),
+            remaining: self.len,
         }
+    }
+}
 
-        let location = self.entity_allocator.get_location(entity.index())?;
-        let archetype = self.storage().archetypes().get(location.archetype())?;
-        let tags = archetype.tags().get(TagTypeId::of::<T>())?;
+impl<'a> IntoIterator for &InsertedEntities<'a> {
+    type Item = &'a Entity;
+    type IntoIter = InsertedEntitiesIter<'a>;
 
-        unsafe { tags.data_slice::<T>().get(location.set()) }
-    }
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
 
-    /// Determines if the given `Entity` is alive within this `World`.
-    pub fn is_alive(&self, entity: Entity) -> bool { self.entity_allocator.is_alive(entity) }
+/// An iterator over the entities created by an insertion into a world.
+pub struct InsertedEntitiesIter<'a> {
+    slices: std::vec::IntoIter<&'a [Entity]>,
+    current: std::slice::Iter<'a, Entity>,
+    remaining: usize,
+}
 
-    /// Iteratively defragments the world's internal memory.
-    ///
-    /// This compacts entities into fewer more continuous chunks.
-    ///
-    /// `budget` describes the maximum number of entities that can be moved
-    /// in one call. Subsequent calls to `defrag` will resume progress from the
-    /// previous call.
-    pub fn defrag(&mut self, budget: Option<usize>) {
-        let span = span!(
-            Level::INFO,
-            "Defragmenting",
-            world = self.id().0,
-            start_archetype = self.defrag_progress
-        );
-        let _guard = span.enter();
+impl<'a> Iterator for InsertedEntitiesIter<'a> {
+    type Item = &'a Entity;
 
-        let archetypes = unsafe { &mut *self.storage.get() }.archetypes_mut();
-        let mut budget = budget.unwrap_or(std::usize::MAX);
-        let start = self.defrag_progress;
-        while self.defrag_progress < archetypes.len() {
-            // defragment the next archetype
-            let complete =
-                (&mut archetypes[self.defrag_progress]).defrag(&mut budget, |e, location| {
-                    self.entity_allocator.set_location(e.index(), location);
-                });
-            if complete {
-                // increment the index, looping it once we get to the end
-                self.defrag_progress = (self.defrag_progress + 1) % archetypes.len();
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entity) = self.current.next() {
+                self.remaining -= 1;
+                return Some(entity);
             }
 
-            // stop once we run out of budget or reach back to where we started
-            if budget == 0 || self.defrag_progress == start {
-                break;
-            }
+            self.current = self.slices.next()?.iter();
         }
     }
 
-    pub fn merge(&mut self, world: World) {
-        let span =
-            span!(Level::INFO, "Merging worlds", source = world.id().0, destination = ?self.id());
-        let _guard = span.enter();
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining, Some(self.remaining)) }
+}
 
-        self.entity_allocator.merge(world.entity_allocator);
+impl<'a> ExactSizeIterator for InsertedEntitiesIter<'a> {}
 
-        for archetype in unsafe { &mut *world.storage.get() }.drain(..) {
-            // use the description as an archetype filter
-            let mut desc = archetype.description().clone();
-            let archetype_data = ArchetypeFilterData {
-                component_types: self.storage().component_types(),
-                tag_types: self.storage().tag_types(),
-            };
-            let matches = desc.matches(archetype_data).matching_indices().next();
-            if let Some(arch_index) = matches {
-                // similar archetype already exists, merge
-                self.storage_mut()
-                    .archetypes_mut()
-                    .get_mut(arch_index)
-                    .unwrap()
-                    .merge(archetype);
-            } else {
-                // archetype does not already exist, append
-                self.storage_mut().push(archetype);
-            }
-        }
-    }
+/// Describes the components and tags of a single entity, for entities whose layouts are only
+/// known at runtime. Inserted with `World::insert_dynamic`.
+///
+/// Adding a component or tag of a type the builder already contains replaces the previous value.
+#[derive(Default)]
+pub struct DynamicEntityBuilder {
+    components: Vec<(ComponentTypeId, ComponentMeta)>,
+    // heap allocated component values owned by the builder, parallel to `components`
+    values: Vec<NonNull<u8>>,
+    // heap allocated tag values owned by the builder
+    tags: Vec<(TagTypeId, TagMeta, NonNull<u8>)>,
+}
 
-    fn find_archetype<T, C>(&self, tags: &mut T, components: &mut C) -> Option<usize>
-    where
-        T: for<'a> Filter<ArchetypeFilterData<'a>>,
-        C: for<'a> Filter<ArchetypeFilterData<'a>>,
-    {
-        // search for an archetype with an exact match for the desired component layout
-        let archetype_data = ArchetypeFilterData {
-            component_types: self.storage().component_types(),
-            tag_types: self.storage().tag_types(),
-        };
+unsafe impl Send for DynamicEntityBuilder {}
 
-        // zip the two filters together - find the first index that matches both
-        tags.matches(archetype_data)
-            .zip(components.matches(archetype_data))
-            .enumerate()
-            .take(self.storage().archetypes().len())
-            .filter(|(_, (a, b))| *a && *b)
-            .map(|(i, _)| i)
-            .next()
+unsafe impl Sync for DynamicEntityBuilder {}
+
+impl DynamicEntityBuilder {
+    /// Creates a new builder with no components or tags.
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds a component to the entity.
+    pub fn with_component<T: Component>(mut self, component: T) -> Self {
+        let type_id = ComponentTypeId::of::<T>();
+        let value = Self::alloc_value(component);
+        unsafe { self.set_component_raw(type_id, ComponentMeta::of::<T>(), value) };
+        self
     }
 
-    fn create_archetype<T, C>(&mut self, tags: &T, components: &C) -> usize
-    where
-        T: TagLayout,
-        C: ComponentLayout,
-    {
-        let mut description = ArchetypeDescription::default();
-        tags.tailor_archetype(&mut description);
-        components.tailor_archetype(&mut description);
+    /// Adds a tag to the entity.
+    pub fn with_tag<T: Tag>(mut self, tag: T) -> Self {
+        let type_id = TagTypeId::of::<T>();
+        let value = Self::alloc_value(tag);
+        if let Some(i) = self.tags.iter().position(|(t, _, _)| *t == type_id) {
+            let (_, meta, old) = self.tags[i];
+            unsafe { Self::drop_value(meta.drop_fn(), meta.layout(), old) };
+            self.tags[i].2 = value;
+        } else {
+            self.tags.push((type_id, TagMeta::of::<T>(), value));
+        }
+        self
+    }
 
-        let (index, _) = self.storage_mut().alloc_archetype(description);
-        index
+    /// Moves a component value into the builder by copying its bytes. The caller must ensure the
+    /// source is not dropped.
+    unsafe fn move_component_raw(
+        &mut self,
+        type_id: ComponentTypeId,
+        meta: ComponentMeta,
+        src: *const u8,
+    ) {
+        let value = if meta.is_zero_sized() {
+            NonNull::new_unchecked(meta.align() as *mut u8)
+        } else {
+            let ptr = std::alloc::alloc(meta.layout());
+            if ptr.is_null() {
+                std::alloc::handle_alloc_error(meta.layout());
+            }
+            std::ptr::copy_nonoverlapping(src, ptr, meta.size());
+            NonNull::new_unchecked(ptr)
+        };
+        self.components.push((type_id, meta));
+        self.values.push(value);
     }
 
-    fn find_or_create_archetype<T, C>(&mut self, tags: &mut T, components: &mut C) -> usize
-    where
-        T: TagLayout,
-        C: ComponentLayout,
-    {
-        if let Some(i) = self.find_archetype(tags.get_filter(), components.get_filter()) {
-            i
+    /// Takes ownership of a heap allocated component value, replacing and dropping any existing
+    /// value of the same type.
+    unsafe fn set_component_raw(
+        &mut self,
+        type_id: ComponentTypeId,
+        meta: ComponentMeta,
+        value: NonNull<u8>,
+    ) {
+        if let Some(i) = self.components.iter().position(|(t, _)| *t == type_id) {
+            let (_, meta) = self.components[i];
+            Self::drop_value(meta.drop_fn(), meta.layout(), self.values[i]);
+            self.values[i] = value;
         } else {
-            self.create_archetype(tags, components)
+            self.components.push((type_id, meta));
+            self.values.push(value);
         }
     }
 
-    fn find_chunk_set<T>(&self, archetype: usize, tags: &mut T) -> Option<usize>
-    where
-        T: for<'a> Filter<ChunksetFilterData<'a>>,
-    {
-        // fetch the archetype, we can already assume that the archetype index is valid
-        let archetype_data = unsafe { self.storage().archetypes().get_unchecked(archetype) };
+    /// Removes and drops the component of the given type, if present.
+    fn drop_component(&mut self, type_id: ComponentTypeId) {
+        if let Some(i) = self.components.iter().position(|(t, _)| *t == type_id) {
+            let (_, meta) = self.components.remove(i);
+            let value = self.values.remove(i);
+            unsafe { Self::drop_value(meta.drop_fn(), meta.layout(), value) };
+        }
+    }
 
-        // find a chunk with the correct tags
-        let chunk_filter_data = ChunksetFilterData {
-            archetype_data: archetype_data.deref(),
+    /// Clones a tag value into the builder.
+    unsafe fn clone_tag_raw(&mut self, type_id: TagTypeId, meta: TagMeta, src: *const u8) {
+        let value = if meta.is_zero_sized() {
+            NonNull::new_unchecked(meta.layout().align() as *mut u8)
+        } else {
+            let ptr = std::alloc::alloc(meta.layout());
+            if ptr.is_null() {
+                std::alloc::handle_alloc_error(meta.layout());
+            }
+            meta.clone(src, ptr);
+            NonNull::new_unchecked(ptr)
         };
+        self.tags.push((type_id, meta, value));
+    }
 
-        if let Some(i) = tags.matches(chunk_filter_data).matching_indices().next() {
-            return Some(i);
+    /// Removes a component from the builder, returning its value.
+    fn take_component<T: Component>(&mut self) -> Option<T> {
+        let type_id = ComponentTypeId::of::<T>();
+        let i = self.components.iter().position(|(t, _)| *t == type_id)?;
+        let (_, meta) = self.components.remove(i);
+        let value = self.values.remove(i);
+        unsafe {
+            let component = (value.as_ptr() as *const T).read();
+            if !meta.is_zero_sized() {
+                std::alloc::dealloc(value.as_ptr(), meta.layout());
+            }
+            Some(component)
+        }
+    }
+
+    fn alloc_value<T>(value: T) -> NonNull<u8> {
+        let layout = std::alloc::Layout::new::<T>();
+        if layout.size() == 0 {
+            std::mem::forget(value);
+            NonNull::<T>::dangling().cast()
+        } else {
+            unsafe {
+                let ptr = std::alloc::alloc(layout) as *mut T;
+                if ptr.is_null() {
+                    std::alloc::handle_alloc_error(layout);
+                }
+                ptr.write(value);
+                NonNull::new_unchecked(ptr as *mut u8)
+            }
+        }
+    }
+
+    unsafe fn drop_value(
+        drop_fn: Option<fn(*mut u8)>,
+        layout: std::alloc::Layout,
+        value: NonNull<u8>,
+    ) {
+        if let Some(drop_fn) = drop_fn {
+            drop_fn(value.as_ptr());
+        }
+        if layout.size() > 0 {
+            std::alloc::dealloc(value.as_ptr(), layout);
         }
-
-        None
     }
 
-    fn create_chunk_set<T>(&mut self, archetype: usize, tags: &T) -> usize
-    where
-        T: TagSet,
-    {
-        let archetype_data = unsafe {
-            self.storage_mut()
-                .archetypes_mut()
-                .get_unchecked_mut(archetype)
-        };
-        archetype_data.alloc_chunk_set(|chunk_tags| tags.write_tags(chunk_tags))
+    /// Gets the component and tag types of the entity, in a canonical order.
+    fn layout_key(&self) -> (Vec<ComponentTypeId>, Vec<TagTypeId>) {
+        let mut components = self.components.iter().map(|(t, _)| *t).collect::<Vec<_>>();
+        let mut tags = self.tags.iter().map(|(t, _, _)| *t).collect::<Vec<_>>();
+        components.sort();
+        tags.sort();
+        (components, tags)
     }
+}
 
-    fn find_or_create_chunk<T>(&mut self, archetype: usize, tags: &mut T) -> usize
-    where
-        T: TagSet + for<'a> Filter<ChunksetFilterData<'a>>,
-    {
-        if let Some(i) = self.find_chunk_set(archetype, tags) {
-            i
-        } else {
-            self.create_chunk_set(archetype, tags)
+impl Drop for DynamicEntityBuilder {
+    fn drop(&mut self) {
+        // we own all values which have not been moved into a chunk
+        unsafe {
+            for ((_, meta), value) in self.components.iter().zip(self.values.iter()) {
+                Self::drop_value(meta.drop_fn(), meta.layout(), *value);
+            }
+            for (_, meta, value) in self.tags.iter() {
+                Self::drop_value(meta.drop_fn(), meta.layout(), *value);
+            }
         }
     }
 }
 
-impl Default for World {
-    fn default() -> Self { Self::new() }
+/// A fluent builder for a single entity, created by `World::spawn`.
+///
+/// Components and tags are accumulated in the builder, and the entity is inserted into the
+/// world when `build` is called. Dropping the builder without building it inserts nothing.
+pub struct EntitySpawner<'a> {
+    world: &'a mut World,
+    builder: DynamicEntityBuilder,
 }
 
-/// Describes the types of a set of components attached to an entity.
-pub trait ComponentLayout: Sized {
-    /// A filter type which filters archetypes to an exact match with this layout.
-    type Filter: for<'a> Filter<ArchetypeFilterData<'a>>;
+impl<'a> EntitySpawner<'a> {
+    /// Adds a component to the entity, replacing any previous value of the same type.
+    pub fn with<T: Component>(mut self, component: T) -> Self {
+        self.builder = self.builder.with_component(component);
+        self
+    }
 
-    /// Gets the archetype filter for this layout.
-    fn get_filter(&mut self) -> &mut Self::Filter;
+    /// Adds a tag to the entity, replacing any previous value of the same type.
+    pub fn with_tag<T: Tag>(mut self, tag: T) -> Self {
+        self.builder = self.builder.with_tag(tag);
+        self
+    }
 
-    /// Modifies an archetype description to include the components described by this layout.
-    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription);
+    /// Inserts the entity into the world.
+    pub fn build(self) -> Entity { self.world.insert_dynamic(Some(self.builder))[0] }
 }
 
-/// Describes the types of a set of tags attached to an entity.
-pub trait TagLayout: Sized {
-    /// A filter type which filters archetypes to an exact match with this layout.
-    type Filter: for<'a> Filter<ArchetypeFilterData<'a>>;
+/// A set of component additions and removals to apply to an existing entity with
+/// `World::apply_deltas`.
+#[derive(Default)]
+pub struct EntityDelta {
+    add: DynamicEntityBuilder,
+    remove: Vec<ComponentTypeId>,
+}
 
-    /// Gets the archetype filter for this layout.
-    fn get_filter(&mut self) -> &mut Self::Filter;
+impl EntityDelta {
+    /// Creates a delta which makes no changes.
+    pub fn new() -> Self { Self::default() }
 
-    /// Modifies an archetype description to include the tags described by this layout.
-    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription);
+    /// Adds a component to the entity, or sets its value if the component is already present.
+    pub fn with_component<T: Component>(mut self, component: T) -> Self {
+        let type_id = ComponentTypeId::of::<T>();
+        self.remove.retain(|t| *t != type_id);
+        self.add = self.add.with_component(component);
+        self
+    }
+
+    /// Removes a component from the entity, if it is present.
+    pub fn without_component<T: Component>(mut self) -> Self {
+        self.remove_raw(ComponentTypeId::of::<T>());
+        self
+    }
+
+    /// Determines if the delta makes no changes.
+    pub fn is_empty(&self) -> bool { self.add.components.is_empty() && self.remove.is_empty() }
+
+    fn remove_raw(&mut self, type_id: ComponentTypeId) {
+        self.add.drop_component(type_id);
+        if !self.remove.contains(&type_id) {
+            self.remove.push(type_id);
+        }
+    }
+
+    /// Applies the changes of `other` after those of this delta.
+    fn merge(&mut self, mut other: EntityDelta) {
+        for type_id in other.remove.drain(..) {
+            self.remove_raw(type_id);
+        }
+
+        let add = &mut other.add;
+        for ((type_id, meta), value) in add.components.drain(..).zip(add.values.drain(..)) {
+            self.remove.retain(|t| *t != type_id);
+            unsafe { self.add.set_component_raw(type_id, meta, value) };
+        }
+    }
 }
 
-/// A set of tag values to be attached to an entity.
-pub trait TagSet {
-    /// Writes the tags in this set to a new chunk.
-    fn write_tags(&self, tags: &mut Tags);
+/// The components and tags of an entity which has been removed from a world. Created by
+/// `World::remove_entity`.
+///
+/// Any components which are not taken out of the bag are dropped with it.
+pub struct EntityBag {
+    entity: Entity,
+    builder: DynamicEntityBuilder,
 }
 
-/// A set of components to be attached to one or more entities.
-pub trait ComponentSource: ComponentLayout {
-    /// Determines if this component source has any more entity data to write.
-    fn is_empty(&mut self) -> bool;
+impl EntityBag {
+    /// Gets the removed entity.
+    pub fn entity(&self) -> Entity { self.entity }
 
-    /// Writes as many components as possible into a chunk.
-    fn write(&mut self, allocator: &mut EntityAllocator, chunk: &mut ComponentStorage) -> usize;
-}
+    /// Determines if the bag still contains a component of type `T`.
+    pub fn contains<T: Component>(&self) -> bool {
+        let type_id = ComponentTypeId::of::<T>();
+        self.builder.components.iter().any(|(t, _)| *t == type_id)
+    }
 
-/// An object that can be converted into a `ComponentSource`.
-pub trait IntoComponentSource {
-    /// The component source type that can be converted into.
-    type Source: ComponentSource;
+    /// Takes ownership of the entity's component of type `T`, if it is still in the bag.
+    pub fn take<T: Component>(&mut self) -> Option<T> { self.builder.take_component() }
 
-    /// Converts `self` into a component source.
-    fn into(self) -> Self::Source;
+    /// Converts the bag into a builder containing all remaining components and tags, such that
+    /// they may be inserted as a new entity with `World::insert_dynamic`.
+    pub fn into_builder(self) -> DynamicEntityBuilder { self.builder }
 }
 
-/// A `ComponentSource` which can insert tuples of components representing each entity into a world.
-pub struct ComponentTupleSet<T, I>
-where
-    I: Iterator<Item = T>,
-{
-    iter: Peekable<I>,
-    filter: ComponentTupleFilter<T>,
+/// A component source which moves the components out of dynamic entity builders which all
+/// share the same component layout.
+struct DynamicComponentSource {
+    components: Vec<(ComponentTypeId, ComponentMeta)>,
+    builders: std::vec::IntoIter<DynamicEntityBuilder>,
 }
 
-impl<T, I> From<I> for ComponentTupleSet<T, I>
-where
-    I: Iterator<Item = T>,
-    ComponentTupleSet<T, I>: ComponentSource,
-{
-    fn from(iter: I) -> Self {
-        ComponentTupleSet {
-            iter: iter.peekable(),
-            filter: ComponentTupleFilter {
-                _phantom: PhantomData,
-            },
+impl ComponentLayout for DynamicComponentSource {
+    type Filter = Self;
+
+    fn get_filter(&mut self) -> &mut Self::Filter { self }
+
+    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription) {
+        for (type_id, meta) in self.components.iter() {
+            archetype.register_component_raw(*type_id, *meta);
         }
     }
 }
 
-impl<I> IntoComponentSource for I
-where
-    I: IntoIterator,
-    ComponentTupleSet<I::Item, I::IntoIter>: ComponentSource,
-{
-    type Source = ComponentTupleSet<I::Item, I::IntoIter>;
+impl<'a> Filter<ArchetypeFilterData<'a>> for DynamicComponentSource {
+    type Iter = ExactTypeSetIter<'a>;
 
-    fn into(self) -> Self::Source {
-        ComponentTupleSet {
-            iter: self.into_iter().peekable(),
-            filter: ComponentTupleFilter {
-                _phantom: PhantomData,
-            },
+    fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
+        let types = self.components.iter().map(|(t, _)| *t).collect::<Vec<_>>();
+        source.component_types.iter_exact(&types)
+    }
+
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> { Some(*item) }
+}
+
+impl ComponentSource for DynamicComponentSource {
+    fn is_empty(&mut self) -> bool { self.builders.len() == 0 }
+
+    fn remaining(&mut self) -> Option<usize> { Some(self.builders.len()) }
+
+    fn write(&mut self, allocator: &mut EntityAllocator, chunk: &mut ComponentStorage) -> usize {
+        let space = chunk.capacity() - chunk.len();
+
+        let mut writer = chunk.writer();
+        let (entities, components) = writer.get();
+
+        let mut count = 0;
+        for mut builder in self.builders.by_ref().take(space) {
+            entities.push(allocator.create_entity());
+
+            // move each value into the chunk, then release the builder's copy without dropping it
+            let values = builder.components.drain(..).zip(builder.values.drain(..));
+            for ((type_id, meta), value) in values {
+                unsafe {
+                    let mut target = (&mut *components.get()).get_mut(type_id).unwrap().writer();
+                    target.push_raw(value, 1);
+                    if !meta.is_zero_sized() {
+                        std::alloc::dealloc(value.as_ptr(), meta.layout());
+                    }
+                }
+            }
+
+            count += 1;
         }
+
+        count
     }
 }
 
@@ -884,7 +5675,6 @@ pub struct ComponentTupleFilter<T> {
 
 mod tuple_impls {
     use super::*;
-    use crate::iterator::SliceVecIter;
     use crate::storage::Component;
     use crate::storage::ComponentTypeId;
     use crate::storage::Tag;
@@ -918,6 +5708,15 @@ mod tuple_impls {
                 }
             }
 
+            impl<$( $ty ),*> Extend<($( $ty, )*)> for World
+            where
+                $( $ty: Component ),*
+            {
+                fn extend<UWU: IntoIterator<Item = ($( $ty, )*)>>(&mut self, iter: UWU) {
+                    self.insert((), iter);
+                }
+            }
+
             impl<UWU, $( $ty ),*> ComponentSource for ComponentTupleSet<($( $ty, )*), UWU>
             where
                 UWU: Iterator<Item = ($( $ty, )*)>,
@@ -927,6 +5726,13 @@ mod tuple_impls {
                     self.iter.peek().is_none()
                 }
 
+                fn remaining(&mut self) -> Option<usize> {
+                    match self.iter.size_hint() {
+                        (lower, Some(upper)) if lower == upper => Some(lower),
+                        _ => None,
+                    }
+                }
+
                 fn write(&mut self, allocator: &mut EntityAllocator, chunk: &mut ComponentStorage) -> usize {
                     #![allow(unused_variables)]
                     #![allow(unused_unsafe)]
@@ -963,15 +5769,14 @@ mod tuple_impls {
             where
                 $( $ty: Component ),*
             {
-                type Iter = SliceVecIter<'a, ComponentTypeId>;
+                type Iter = ExactTypeSetIter<'a>;
 
                 fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
-                    source.component_types.iter()
+                    source.component_types.iter_exact(&[$( ComponentTypeId::of::<$ty>() ),*])
                 }
 
                 fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
-                    let types = &[$( ComponentTypeId::of::<$ty>() ),*];
-                    Some(types.len() == item.len() && types.iter().all(|t| item.contains(t)))
+                    Some(*item)
                 }
             }
         };
@@ -1018,15 +5823,14 @@ mod tuple_impls {
             where
                 $( $ty: Tag ),*
             {
-                type Iter = SliceVecIter<'a, TagTypeId>;
+                type Iter = ExactTypeSetIter<'a>;
 
                 fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
-                    source.tag_types.iter()
+                    source.tag_types.iter_exact(&[$( TagTypeId::of::<$ty>() ),*])
                 }
 
                 fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
-                    let types = &[$( TagTypeId::of::<$ty>() ),*];
-                    Some(types.len() == item.len() && types.iter().all(|t| item.contains(t)))
+                    Some(*item)
                 }
             }
         };
@@ -1040,14 +5844,13 @@ mod tuple_impls {
                 fn collect(&self, source: ChunksetFilterData<'a>) -> Self::Iter {
                     let iters = (
                         $(
-                            unsafe {
-                                source.archetype_data
-                                    .tags()
-                                    .get(TagTypeId::of::<$ty>())
-                                    .unwrap()
-                                    .data_slice::<$ty>()
-                                    .iter()
-                            },
+                            source.archetype_data
+                                .tags()
+                                .get(TagTypeId::of::<$ty>())
+                                .and_then(|tags| tags.downcast::<$ty>())
+                                .unwrap()
+                                .as_slice()
+                                .iter(),
                         )*
 
                     );
@@ -1106,6 +5909,129 @@ mod tuple_impls {
     impl_data_tuple!(A => a, B => b, C => c, D => d, E => e, F => f, G => g, H => h, I => i, J => j, K => k, L => l, M => m, N => n, O => o, P => p, Q => q, R => r, S => s, T => t, U => u, V => v, W => w, X => x, Y => y, Z => z);
 }
 
+/// Provides mutable access to a copy of an entity's tag value, moving the entity into the
+/// chunk matching the new value when dropped. See `World::get_tag_mut`.
+pub struct TagEditGuard<'a, T: Tag> {
+    world: &'a mut World,
+    entity: Entity,
+    value: T,
+}
+
+impl<'a, T: Tag> Deref for TagEditGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T { &self.value }
+}
+
+impl<'a, T: Tag> DerefMut for TagEditGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T { &mut self.value }
+}
+
+impl<'a, T: Tag> Drop for TagEditGuard<'a, T> {
+    fn drop(&mut self) { self.world.add_tag(self.entity, self.value.clone()); }
+}
+
+/// A `ComponentSource` which wraps another source and appends default constructed
+/// values of additional component types to each entity written.
+pub struct DefaultedComponentSource<S: ComponentSource> {
+    source: S,
+    filter: DefaultedComponentFilter,
+    defaults: Vec<(ComponentTypeId, ComponentRegistration)>,
+}
+
+impl<S: ComponentSource> DefaultedComponentSource<S> {
+    fn new(source: S, defaults: Vec<(ComponentTypeId, ComponentRegistration)>) -> Self {
+        let mut description = ArchetypeDescription::default();
+        source.tailor_archetype(&mut description);
+        let mut types: Vec<ComponentTypeId> =
+            description.components().iter().map(|(t, _)| *t).collect();
+
+        for (type_id, _) in defaults.iter() {
+            assert!(
+                !types.contains(type_id),
+                "defaulted component type {:?} is already present in the component source",
+                type_id
+            );
+            types.push(*type_id);
+        }
+
+        DefaultedComponentSource {
+            source,
+            filter: DefaultedComponentFilter { types },
+            defaults,
+        }
+    }
+}
+
+impl<S: ComponentSource> ComponentLayout for DefaultedComponentSource<S> {
+    type Filter = DefaultedComponentFilter;
+
+    fn get_filter(&mut self) -> &mut Self::Filter { &mut self.filter }
+
+    fn tailor_archetype(&self, archetype: &mut ArchetypeDescription) {
+        self.source.tailor_archetype(archetype);
+        for (type_id, registration) in self.defaults.iter() {
+            archetype.register_component_raw(*type_id, *registration.meta());
+        }
+    }
+}
+
+impl<S: ComponentSource> ComponentSource for DefaultedComponentSource<S> {
+    fn is_empty(&mut self) -> bool { self.source.is_empty() }
+
+    fn remaining(&mut self) -> Option<usize> { self.source.remaining() }
+
+    fn write(&mut self, allocator: &mut EntityAllocator, chunk: &mut ComponentStorage) -> usize {
+        let count = self.source.write(allocator, chunk);
+
+        let mut writer = chunk.writer();
+        let (_, components) = writer.get();
+        for (type_id, registration) in self.defaults.iter() {
+            let meta = registration.meta();
+            unsafe {
+                let mut target = (&mut *components.get()).get_mut(*type_id).unwrap().writer();
+
+                // construct each value in temp storage, then move it into the chunk
+                let temp = if meta.is_zero_sized() {
+                    meta.layout().align() as *mut u8
+                } else {
+                    std::alloc::alloc(meta.layout())
+                };
+                for _ in 0..count {
+                    registration.write_default(temp);
+                    target.push_raw(NonNull::new_unchecked(temp), 1);
+                }
+                if !meta.is_zero_sized() {
+                    std::alloc::dealloc(temp, meta.layout());
+                }
+            }
+        }
+
+        count
+    }
+}
+
+impl<S: ComponentSource> IntoComponentSource for DefaultedComponentSource<S> {
+    type Source = Self;
+
+    fn into(self) -> Self::Source { self }
+}
+
+/// An archetype filter which matches the layout of a `DefaultedComponentSource`.
+pub struct DefaultedComponentFilter {
+    types: Vec<ComponentTypeId>,
+}
+
+impl<'a> Filter<ArchetypeFilterData<'a>> for DefaultedComponentFilter {
+    type Iter = ExactTypeSetIter<'a>;
+
+    fn collect(&self, source: ArchetypeFilterData<'a>) -> Self::Iter {
+        source.component_types.iter_exact(&self.types)
+    }
+
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> { Some(*item) }
+}
+
 struct DynamicComponentLayout<'a> {
     existing: &'a [(ComponentTypeId, ComponentMeta)],
     add: &'a [(ComponentTypeId, ComponentMeta)],
@@ -1137,30 +6063,27 @@ impl<'a> ComponentLayout for DynamicComponentLayout<'a> {
 }
 
 impl<'a, 'b> Filter<ArchetypeFilterData<'b>> for DynamicComponentLayout<'a> {
-    type Iter = SliceVecIter<'b, ComponentTypeId>;
+    type Iter = ExactTypeSetIter<'b>;
 
     fn collect(&self, source: ArchetypeFilterData<'b>) -> Self::Iter {
-        source.component_types.iter()
-    }
-
-    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
-        Some(
-            item.len() == (self.existing.len() + self.add.len() - self.remove.len())
-                && item.iter().all(|t| {
-                    // all types are not in remove
-                    !self.remove.contains(t)
-                    // any are either in existing or add
-                        && (self.existing.iter().any(|(x, _)| x == t)
-                            || self.add.iter().any(|(x, _)| x == t))
-                }),
-        )
+        // existing types except for those in `remove`, followed by those in `add`
+        let types = self
+            .existing
+            .iter()
+            .map(|(t, _)| *t)
+            .filter(|t| !self.remove.contains(t))
+            .chain(self.add.iter().map(|(t, _)| *t))
+            .collect::<Vec<_>>();
+        source.component_types.iter_exact(&types)
     }
+
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> { Some(*item) }
 }
 
 struct DynamicTagLayout<'a> {
     storage: &'a Storage,
     archetype: usize,
-    chunk: usize,
+    set: usize,
     existing: &'a [(TagTypeId, TagMeta)],
     add: &'a [(TagTypeId, TagMeta, NonNull<u8>)],
     remove: &'a [TagTypeId],
@@ -1170,6 +6093,14 @@ unsafe impl<'a> Send for DynamicTagLayout<'a> {}
 
 unsafe impl<'a> Sync for DynamicTagLayout<'a> {}
 
+impl<'a> DynamicTagLayout<'a> {
+    /// Determines if an existing tag is carried over into the new layout.
+    /// Tags in `add` replace any existing tag of the same type.
+    fn is_retained(&self, type_id: &TagTypeId) -> bool {
+        !self.remove.contains(type_id) && !self.add.iter().any(|(t, _, _)| t == type_id)
+    }
+}
+
 impl<'a> TagLayout for DynamicTagLayout<'a> {
     type Filter = Self;
 
@@ -1177,11 +6108,8 @@ impl<'a> TagLayout for DynamicTagLayout<'a> {
 
     fn tailor_archetype(&self, archetype: &mut ArchetypeDescription) {
         // copy tags from existing archetype into new
-        // except for those in `remove`
-        let tags = self
-            .existing
-            .iter()
-            .filter(|(t, _)| !self.remove.contains(t));
+        // except for those in `remove` or `add`
+        let tags = self.existing.iter().filter(|(t, _)| self.is_retained(t));
 
         for (tag_type, meta) in tags {
             archetype.register_tag_raw(*tag_type, *meta);
@@ -1195,22 +6123,21 @@ impl<'a> TagLayout for DynamicTagLayout<'a> {
 }
 
 impl<'a, 'b> Filter<ArchetypeFilterData<'b>> for DynamicTagLayout<'a> {
-    type Iter = SliceVecIter<'b, TagTypeId>;
-
-    fn collect(&self, source: ArchetypeFilterData<'b>) -> Self::Iter { source.tag_types.iter() }
-
-    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> {
-        Some(
-            item.len() == (self.existing.len() + self.add.len() - self.remove.len())
-                && item.iter().all(|t| {
-                    // all types are not in remove
-                    !self.remove.contains(t)
-                    // any are either in existing or add
-                        && (self.existing.iter().any(|(x, _)| x == t)
-                            || self.add.iter().any(|(x, _, _)| x == t))
-                }),
-        )
+    type Iter = ExactTypeSetIter<'b>;
+
+    fn collect(&self, source: ArchetypeFilterData<'b>) -> Self::Iter {
+        // retained existing types, followed by those in `add`
+        let types = self
+            .existing
+            .iter()
+            .map(|(t, _)| *t)
+            .filter(|t| self.is_retained(t))
+            .chain(self.add.iter().map(|(t, _, _)| *t))
+            .collect::<Vec<_>>();
+        source.tag_types.iter_exact(&types)
     }
+
+    fn is_match(&self, item: &<Self::Iter as Iterator>::Item) -> Option<bool> { Some(*item) }
 }
 
 impl<'a, 'b> Filter<ChunksetFilterData<'b>> for DynamicTagLayout<'a> {
@@ -1224,7 +6151,7 @@ impl<'a, 'b> Filter<ChunksetFilterData<'b>> for DynamicTagLayout<'a> {
 
     fn is_match(&self, (chunk_index, arch): &<Self::Iter as Iterator>::Item) -> Option<bool> {
         for (type_id, meta) in self.existing {
-            if self.remove.contains(type_id) {
+            if !self.is_retained(type_id) {
                 continue;
             }
 
@@ -1239,7 +6166,7 @@ impl<'a, 'b> Filter<ChunksetFilterData<'b>> for DynamicTagLayout<'a> {
                     .get(*type_id)
                     .unwrap()
                     .data_raw();
-                let current = slice_ptr.as_ptr().add(self.chunk * element_size);
+                let current = slice_ptr.as_ptr().add(self.set * element_size);
 
                 // find the value of the tag in the candidate chunk
                 let (slice_ptr, element_size, _) = arch.tags().get(*type_id).unwrap().data_raw();
@@ -1327,9 +6254,9 @@ mod tests {
             (One, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten),
             (One, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten),
         ];
-        world.insert(shared, components);
+        let entities = world.insert(shared, components);
 
-        assert_eq!(2, world.entity_allocator.allocation_buffer().len());
+        assert_eq!(2, entities.len());
     }
 
     #[test]
@@ -1340,9 +6267,9 @@ mod tests {
 
         let shared = (1usize, 2f32, 3u16);
         let components = vec![(4f32, 5u64, 6u16), (4f32, 5u64, 6u16)];
-        world.insert(shared, components);
+        let entities = world.insert(shared, components);
 
-        assert_eq!(2, world.entity_allocator.allocation_buffer().len());
+        assert_eq!(2, entities.len());
     }
 
     #[test]
@@ -1357,15 +6284,9 @@ mod tests {
             (Pos(4., 5., 6.), Rot(0.4, 0.5, 0.6)),
         ];
 
-        world.insert(shared, components.clone());
+        let entities = world.insert(shared, components.clone()).to_vec();
 
-        for (i, e) in world
-            .entity_allocator
-            .allocation_buffer()
-            .to_vec()
-            .iter()
-            .enumerate()
-        {
+        for (i, e) in entities.iter().enumerate() {
             match world.get_component(*e) {
                 Some(x) => assert_eq!(components.get(i).map(|(x, _)| x), Some(&x as &Pos)),
                 None => assert_eq!(components.get(i).map(|(x, _)| x), None),
@@ -1383,9 +6304,7 @@ mod tests {
 
         let mut world = create();
 
-        world.insert((), vec![(0f64,)]);
-
-        let entity = *world.entity_allocator.allocation_buffer().get(0).unwrap();
+        let entity = world.insert((), vec![(0f64,)])[0];
 
         assert!(world.get_component::<i32>(entity).is_none());
     }
@@ -1402,9 +6321,9 @@ mod tests {
             (Pos(4., 5., 6.), Rot(0.4, 0.5, 0.6)),
         ];
 
-        world.insert(shared, components);
+        let entities = world.insert(shared, components).to_vec();
 
-        for e in world.entity_allocator.allocation_buffer().to_vec().iter() {
+        for e in entities.iter() {
             assert_eq!(&Static, world.get_tag::<Static>(*e).unwrap().deref());
             assert_eq!(&Model(5), world.get_tag::<Model>(*e).unwrap().deref());
         }
@@ -1416,9 +6335,7 @@ mod tests {
 
         let mut world = create();
 
-        world.insert((Static,), vec![(0f64,)]);
-
-        let entity = *world.entity_allocator.allocation_buffer().get(0).unwrap();
+        let entity = world.insert((Static,), vec![(0f64,)])[0];
 
         assert!(world.get_tag::<Model>(entity).is_none());
     }
@@ -1625,4 +6542,151 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn insertion_marks_components_added() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        // gets the (version, added_version) of the entity's column of component type T
+        fn versions<T: Component>(world: &World, entity: Entity) -> (u64, u64) {
+            let location = world.entity_allocator.get_location(entity.index()).unwrap();
+            let chunk = &world.storage().archetypes()[location.archetype()].chunksets()
+                [location.set()][location.chunk()];
+            let components = chunk.components(ComponentTypeId::of::<T>()).unwrap();
+            (components.version(), components.added_version())
+        }
+
+        let mut world = create();
+        let before = current_version();
+        let entity = world.insert((), vec![(Pos(1., 2., 3.), Rot(0., 0., 0.))])[0];
+        let (version, added) = versions::<Pos>(&world, entity);
+        assert!(added > before);
+        assert_eq!(version, added);
+
+        // modification changes the version, but not the added version
+        *world.get_component_mut::<Pos>(entity).unwrap() = Pos(4., 5., 6.);
+        let (modified, unchanged) = versions::<Pos>(&world, entity);
+        assert!(modified > version);
+        assert_eq!(added, unchanged);
+
+        // inserting into the same chunk marks the column as added again
+        let other = world.insert((), vec![(Pos(1., 2., 3.), Rot(0., 0., 0.))])[0];
+        let (_, reinserted) = versions::<Pos>(&world, other);
+        assert!(reinserted > modified);
+
+        // moving into a new archetype only marks the attached component as added
+        let before = current_version();
+        world.add_component(entity, Vel(0., 0., 0.));
+        let (moved, moved_added) = versions::<Pos>(&world, entity);
+        assert!(moved > before);
+        assert!(moved_added <= before);
+        let (_, vel_added) = versions::<Vel>(&world, entity);
+        assert!(vel_added > before);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc-tracking")]
+    fn allocation_report_tracks_frees() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        // counters are shared with concurrently running tests, so only compare deltas
+        let mut world = create();
+        let entities = world
+            .insert((Model(1),), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)])
+            .to_vec();
+        let report = world.allocation_report();
+        assert!(report.chunk_bytes > 0);
+        assert!(report.tag_bytes > 0);
+        assert!(report.chunks.live() >= report.chunk_bytes);
+        assert!(report.tags.live() >= report.tag_bytes);
+
+        for entity in entities {
+            world.delete(entity);
+        }
+        let emptied = world.allocation_report();
+        assert_eq!(0, emptied.chunk_bytes);
+        assert!(emptied.chunks.freed - report.chunks.freed >= report.chunk_bytes);
+
+        drop(world);
+        let (_, tags, _) = allocation_counters();
+        assert!(tags.freed - report.tags.freed >= report.tag_bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "validation")]
+    fn validate_detects_corruption() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut world = create();
+        let entities = world
+            .insert((Model(1),), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)])
+            .to_vec();
+        world.insert((Model(2),), vec![(Pos(1., 2., 3.), Rot(0., 0., 0.))]);
+        world.delete(entities[0]);
+        assert_eq!(ValidationReport::default(), world.validate());
+
+        // point the entity at the wrong slot of its chunk
+        let location = world
+            .entity_allocator
+            .get_location(entities[1].index())
+            .unwrap();
+        world.entity_allocator.set_location(
+            entities[1].index(),
+            EntityLocation::new(
+                location.archetype(),
+                location.set(),
+                location.chunk(),
+                location.component() + 1,
+            ),
+        );
+
+        let chunk = world.entity_archetype(entities[1]).unwrap().2;
+        let report = world.validate();
+        assert_eq!(
+            vec![ValidationIssue::WrongLocation {
+                entity: entities[1],
+                chunk,
+                index: 0
+            }],
+            report.issues
+        );
+        assert!(!report.is_valid());
+        assert!(report.to_string().contains("location differs"));
+    }
+
+    #[test]
+    #[cfg(feature = "validation")]
+    fn add_component_to_filtered_panic() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let mut world = create();
+        let entities = world
+            .insert((), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)])
+            .to_vec();
+
+        // a panic while building a value leaves the entity being visited unchanged
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut calls = 0;
+            world.add_component_to_filtered(filter_fns::component::<Pos>(), |_| {
+                calls += 1;
+                if calls == 2 {
+                    panic!("value failed");
+                }
+                Vel(0., 0., 0.)
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(ValidationReport::default(), world.validate());
+
+        for entity in entities.iter() {
+            assert!(world.get_component::<Pos>(*entity).is_some());
+        }
+        assert_eq!(
+            1,
+            entities
+                .iter()
+                .filter(|e| world.get_component::<Vel>(**e).is_some())
+                .count()
+        );
+    }
 }
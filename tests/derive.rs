@@ -0,0 +1,74 @@
+#![cfg(feature = "derive")]
+
+use legion::prelude::*;
+use legion::storage::ComponentTypeId;
+use legion::LegionComponent;
+
+#[derive(Clone, Debug, Default, PartialEq, Hash, LegionComponent)]
+#[legion(default, clone, hash, reflect)]
+struct Health {
+    current: u32,
+    max: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, LegionComponent)]
+#[legion(reflect)]
+struct Pos(f32, f32, f32);
+
+#[test]
+fn derive_registration() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut universe = Universe::new();
+    universe.registry.register_component::<Health>();
+    universe.registry.register_component::<Pos>();
+
+    let world = universe.create_world();
+
+    let health = world.registry.get(ComponentTypeId::of::<Health>()).unwrap();
+    assert!(health.has_default());
+    assert!(health.has_clone());
+    assert!(health.has_hash());
+    assert!(health.has_reflect());
+
+    let pos = world.registry.get(ComponentTypeId::of::<Pos>()).unwrap();
+    assert!(!pos.has_default());
+    assert!(!pos.has_clone());
+    assert!(!pos.has_hash());
+    assert!(pos.has_reflect());
+}
+
+#[test]
+fn derive_reflect() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut universe = Universe::new();
+    universe.registry.register_component::<Health>();
+    universe.registry.register_component::<Pos>();
+
+    let mut world = universe.create_world();
+    let entity = world.insert(
+        (),
+        vec![(Pos(1., 2., 3.), Health { current: 5, max: 10 })],
+    )[0];
+
+    let changed = world.reflect_component_mut(entity, ComponentTypeId::of::<Pos>(), |pos| {
+        pos.set("1", 4f32)
+    });
+    assert_eq!(Some(true), changed);
+    assert_eq!(Pos(1., 4., 3.), *world.get_component::<Pos>(entity).unwrap());
+
+    let tree = world.inspect(entity).unwrap();
+    let health = tree
+        .iter()
+        .find(|node| node.name == std::any::type_name::<Health>())
+        .unwrap();
+    assert_eq!(
+        vec!["current", "max"],
+        health
+            .children
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+    );
+}
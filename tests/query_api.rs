@@ -12,6 +12,9 @@ struct Rot(f32, f32, f32);
 struct Scale(f32, f32, f32);
 #[derive(Clone, Copy, Debug, PartialEq)]
 struct Vel(f32, f32, f32);
+
+// three `f32`s, with no padding
+unsafe impl legion::storage::Streamable for Vel {}
 #[derive(Clone, Copy, Debug, PartialEq)]
 struct Accel(f32, f32, f32);
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -91,6 +94,88 @@ fn query_try_write_entity_data() {
     );
 }
 
+#[test]
+fn query_try_read_mixed_immutable() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.insert((), Some((Pos(1., 2., 3.),)));
+    world.insert((), Some((Pos(4., 5., 6.), Rot(0.4, 0.5, 0.6))));
+    world.insert((), Some((Rot(0.7, 0.8, 0.9),)));
+
+    let mut query = <(Read<Pos>, TryRead<Rot>)>::query();
+    let mut results = query
+        .iter_immutable(&world)
+        .map(|(pos, rot)| (*pos, rot.map(|x| *x)))
+        .collect::<Vec<_>>();
+    results.sort_by(|a, b| a.0 .0.partial_cmp(&b.0 .0).unwrap());
+
+    assert_eq!(
+        vec![
+            (Pos(1., 2., 3.), None),
+            (Pos(4., 5., 6.), Some(Rot(0.4, 0.5, 0.6)))
+        ],
+        results
+    );
+}
+
+#[test]
+fn query_join() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    let both = world
+        .insert((Static,), (0..1000).map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.))))
+        .to_vec();
+    world.insert((), (0..1000).map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.))));
+    world.insert((Static,), (0..10).map(|i| (Rot(i as f32, 0., 0.),)));
+
+    let mut positions = Write::<Pos>::query();
+    let mut rotations = Read::<Rot>::query().filter(tag::<Static>());
+
+    let mut count = 0;
+    for (entity, mut pos, rot) in positions.join(&mut rotations, &mut world) {
+        assert!(both.contains(&entity));
+        pos.1 = rot.0 + 1.;
+        count += 1;
+    }
+    assert_eq!(both.len(), count);
+
+    for entity in both {
+        assert_eq!(1., world.get_component::<Pos>(entity).unwrap().1);
+    }
+}
+
+#[test]
+fn query_version_filter() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.insert((), vec![(Pos(1., 2., 3.),)]);
+    world.insert((), vec![(Pos(4., 5., 6.), Rot(0.4, 0.5, 0.6))]);
+
+    let frame_start = legion::storage::current_version();
+
+    let mut touch = Write::<Pos>::query().filter(component::<Rot>());
+    for mut pos in touch.iter(&mut world) {
+        pos.0 += 1.;
+    }
+
+    let mut changed_this_frame =
+        Read::<Pos>::query().filter(version::<Pos, _>(move |v| v > frame_start));
+    let changed = changed_this_frame
+        .iter(&mut world)
+        .map(|pos| *pos)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![Pos(5., 5., 6.)], changed);
+
+    // the filter is stateless, so matching chunks are yielded again
+    assert_eq!(1, changed_this_frame.iter(&mut world).count());
+}
+
 #[test]
 fn query_cached_read_entity_data() {
     let _ = tracing_subscriber::fmt::try_init();
@@ -188,6 +273,91 @@ fn query_read_entity_data_par_foreach() {
     assert_eq!(components.len(), count.load(Ordering::SeqCst));
 }
 
+#[test]
+fn query_split_chunk() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let shared = (Static, Model(5));
+    let components = (0..1000)
+        .map(|i| (Pos(i as f32, 0., 0.), Rot(i as f32, 0., 0.)))
+        .collect::<Vec<_>>();
+    world.insert(shared, components);
+
+    let mut query = <(Write<Pos>, Read<Rot>, Tagged<Model>)>::query();
+    for chunk in query.iter_chunks(&mut world) {
+        let len = chunk.entities().len();
+        let (left, mut right) = chunk.split_at(len / 3);
+        let (mut first, mut second) = left.split_at(len / 6);
+        assert_eq!(len, first.len() + second.len() + right.len());
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                for (mut pos, rot, model) in first.iter() {
+                    pos.1 = rot.0 + model.0 as f32;
+                }
+            });
+            scope.spawn(move || {
+                for (mut pos, rot, model) in second.iter() {
+                    pos.1 = rot.0 + model.0 as f32;
+                }
+            });
+            for (_, (mut pos, rot, model)) in right.iter_entities() {
+                pos.1 = rot.0 + model.0 as f32;
+            }
+        });
+    }
+
+    let mut query = Read::<Pos>::query();
+    let mut count = 0;
+    for pos in query.iter(&mut world) {
+        assert_eq!(pos.0 + 5., pos.1);
+        count += 1;
+    }
+    assert_eq!(1000, count);
+}
+
+#[test]
+fn query_skips_empty_chunks() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entities = world
+        .insert((), (0..10000).map(|i| (Pos(i as f32, 0., 0.),)))
+        .to_vec();
+
+    let mut query = Read::<Pos>::query();
+    let chunks = query
+        .iter_chunks(&mut world)
+        .map(|chunk| chunk.entities().to_vec())
+        .collect::<Vec<_>>();
+    assert!(chunks.len() >= 3);
+
+    // empty a chunk between two occupied chunks
+    for entity in chunks[1].iter() {
+        world.delete(*entity);
+    }
+
+    let chunkset = query.iter_chunks(&mut world).next().unwrap().chunkset();
+    assert!(chunkset.is_occupied(0));
+    assert!(!chunkset.is_occupied(1));
+    assert_eq!(chunks.len(), chunkset.occupied().len());
+
+    assert_eq!(chunks.len() - 1, query.iter_chunks(&mut world).count());
+    assert_eq!(
+        entities.len() - chunks[1].len(),
+        query.iter(&mut world).count()
+    );
+
+    // the empty chunk is occupied again once entities are inserted into it
+    world.insert((), vec![(Pos(0., 0., 0.),)]);
+    assert_eq!(chunks.len(), query.iter_chunks(&mut world).count());
+}
+
 #[test]
 fn query_read_entity_data_tuple() {
     let _ = tracing_subscriber::fmt::try_init();
@@ -499,3 +669,584 @@ fn query_on_changed_self_changes() {
 
     assert_eq!(components.len(), count);
 }
+
+#[test]
+fn query_iter_sorted_by_key() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1),), vec![(Pos(3., 0., 0.),), (Pos(1., 0., 0.),)]);
+    world.insert((Model(2),), vec![(Pos(2., 0., 0.),), (Pos(0., 0., 0.),)]);
+
+    let mut query = <(Read<Pos>, Tagged<Model>)>::query();
+    let sorted = query
+        .iter_sorted_by_key(&world, |(pos, _)| pos.0 as i32)
+        .map(|(pos, model)| (pos.0, model.0))
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec![(0., 2), (1., 1), (2., 2), (3., 1)], sorted);
+
+    // entities with equal keys are yielded in storage order
+    let sorted = query
+        .iter_sorted_by_key(&world, |(_, model)| std::cmp::Reverse(model.0))
+        .map(|(pos, model)| (pos.0, model.0))
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec![(2., 2), (0., 2), (3., 1), (1., 1)], sorted);
+}
+
+#[test]
+fn query_iter_by_tag() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1),), vec![(Pos(1., 0., 0.),), (Pos(2., 0., 0.),)]);
+    world.insert((Model(2), Static), vec![(Pos(3., 0., 0.),)]);
+    world.insert((Model(1), Static), vec![(Pos(4., 0., 0.),)]);
+    world.insert((), vec![(Pos(5., 0., 0.),)]);
+    let removed = world.insert((Model(3),), vec![(Pos(6., 0., 0.),)])[0];
+    world.delete(removed);
+
+    let mut query = Read::<Pos>::query();
+    let groups = query
+        .iter_by_tag::<Model>(&mut world)
+        .map(|(model, positions)| (*model, positions.map(|p| p.0).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![(Model(1), vec![1., 2., 4.]), (Model(2), vec![3.])],
+        groups
+    );
+}
+
+#[test]
+fn query_single() {
+    use legion::query::SingleError;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let mut query = Write::<Pos>::query().filter(tag::<Static>());
+    assert_eq!(
+        Some(SingleError::NoMatches),
+        query.single_mut(&mut world).err()
+    );
+
+    world.insert((Model(1),), vec![(Pos(1., 0., 0.),), (Pos(2., 0., 0.),)]);
+    world.insert((Static,), vec![(Pos(3., 0., 0.),)]);
+
+    *query.single_mut(&mut world).unwrap() = Pos(4., 0., 0.);
+    let mut query = Read::<Pos>::query().filter(tag::<Static>());
+    assert_eq!(Pos(4., 0., 0.), *query.single(&world).unwrap());
+
+    let mut query = Read::<Pos>::query();
+    assert_eq!(
+        Some(SingleError::MultipleMatches),
+        query.single(&world).err()
+    );
+
+    // matches spread across chunks are also detected
+    let mut query = Read::<Pos>::query().filter(tag::<Static>());
+    world.insert((Static, Model(2)), vec![(Pos(5., 0., 0.),)]);
+    assert_eq!(
+        Some(SingleError::MultipleMatches),
+        query.single(&world).err()
+    );
+}
+
+#[test]
+fn query_find() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let moving = world
+        .insert(
+            (Model(1),),
+            (0..2000).map(|i| (Pos(i as f32, 0., 0.), Vel(1., 0., 0.))),
+        )
+        .to_vec();
+    let still = world.insert((Static,), vec![(Pos(-1., 0., 0.),)])[0];
+
+    let mut query = <(Read<Pos>, Tagged<Model>)>::query();
+    {
+        let (pos, model) = query.find(&world, moving[1500]).unwrap();
+        assert_eq!(Pos(1500., 0., 0.), *pos);
+        assert_eq!(Model(1), *model);
+    }
+    assert!(query.find(&world, still).is_none());
+
+    let mut query = <(Read<Vel>, Write<Pos>)>::query().filter(changed::<Vel>());
+    {
+        let (vel, mut pos) = query.find_mut(&mut world, moving[3]).unwrap();
+        pos.0 += vel.0;
+    }
+    assert_eq!(Pos(4., 0., 0.), *world.get_component::<Pos>(moving[3]).unwrap());
+
+    // the chunk was observed by the changed filter
+    assert!(query.find_mut(&mut world, moving[3]).is_none());
+
+    world.delete(moving[0]);
+    let mut query = Read::<Pos>::query();
+    assert!(query.find(&world, moving[0]).is_none());
+    assert_eq!(Pos(-1., 0., 0.), *query.find(&world, still).unwrap());
+}
+
+#[test]
+fn query_join_worlds() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut predicted = World::new();
+    let mut authoritative = World::new();
+
+    let entities = predicted
+        .insert((), (0..10).map(|i| (Pos(i as f32, 0., 0.),)))
+        .to_vec();
+    let remote = authoritative
+        .insert((), (0..8).map(|i| (Pos(i as f32, 1., 0.), Vel(0., 0., 0.))))
+        .to_vec();
+    assert_eq!(&entities[..8], &remote[..]);
+
+    // the authoritative world deleted an entity which the client has not yet seen deleted
+    authoritative.delete(remote[2]);
+    // and has tagged another entity such that it no longer matches the remote query
+    authoritative.add_tag(remote[5], Static);
+
+    let mut local = Write::<Pos>::query();
+    let mut server = Read::<Pos>::query().filter(!tag::<Static>());
+    let mut corrected = Vec::new();
+    for (entity, mut pos, remote) in local.join_worlds(&mut predicted, &mut server, &authoritative) {
+        assert_eq!(pos.0, remote.0);
+        *pos = *remote;
+        corrected.push(entity);
+    }
+    corrected.sort_by_key(|e| entities.iter().position(|x| x == e));
+
+    let expected = [0, 1, 3, 4, 6, 7]
+        .iter()
+        .map(|i| entities[*i])
+        .collect::<Vec<_>>();
+    assert_eq!(expected, corrected);
+    assert_eq!(
+        Pos(0., 1., 0.),
+        *predicted.get_component::<Pos>(entities[0]).unwrap()
+    );
+    assert_eq!(
+        Pos(2., 0., 0.),
+        *predicted.get_component::<Pos>(entities[2]).unwrap()
+    );
+}
+
+#[test]
+fn query_rare_component() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((), vec![(Pos(1., 0., 0.),)]);
+    world.insert((), vec![(Pos(2., 0., 0.), Rot(0., 0., 0.))]);
+    world.insert((), vec![(Accel(3., 0., 0.), Pos(3., 0., 0.))]);
+    world.insert((), vec![(Rot(0., 0., 0.), Vel(0., 0., 0.))]);
+    world.insert((), vec![(Pos(4., 0., 0.), Vel(0., 0., 0.))]);
+    world.insert((), vec![(Accel(5., 0., 0.), Rot(0., 0., 0.))]);
+    world.insert(
+        (),
+        vec![(Accel(6., 0., 0.), Pos(6., 0., 0.), Vel(0., 0., 0.))],
+    );
+
+    let accels = Read::<Accel>::query()
+        .iter(&mut world)
+        .map(|a| a.0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![3., 5., 6.], accels);
+
+    let positions = <(Read<Pos>, Read<Accel>)>::query()
+        .iter(&mut world)
+        .map(|(p, _)| p.0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![3., 6.], positions);
+
+    let positions = Read::<Pos>::query()
+        .filter(component::<Vel>() & !component::<Accel>())
+        .iter(&mut world)
+        .map(|p| p.0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![4.], positions);
+
+    let positions = Read::<Pos>::query()
+        .filter(component::<Rot>() | component::<Accel>())
+        .iter(&mut world)
+        .map(|p| p.0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![2., 3., 6.], positions);
+
+    let count = Read::<Scale>::query().iter(&mut world).count();
+    assert_eq!(0, count);
+}
+
+#[test]
+fn query_paged() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1),), (0..5).map(|i| (Pos(i as f32, 0., 0.),)));
+    world.insert((Model(2),), (5..8).map(|i| (Pos(i as f32, 0., 0.),)));
+    world.insert((), vec![(Rot(0., 0., 0.),)]);
+
+    let mut query = Read::<Pos>::query().paged();
+    let mut seen = Vec::new();
+
+    assert_eq!(
+        3,
+        query.for_each_page_immutable(&world, 3, |p| seen.push(p.0))
+    );
+    assert!(!query.is_at_start());
+    assert_eq!(
+        3,
+        query.for_each_page_immutable(&world, 3, |p| seen.push(p.0))
+    );
+    assert_eq!(
+        2,
+        query.for_each_page_immutable(&world, 3, |p| seen.push(p.0))
+    );
+    assert!(query.is_at_start());
+    assert_eq!(vec![0., 1., 2., 3., 4., 5., 6., 7.], seen);
+
+    // the next page wraps around to the start
+    seen.clear();
+    query.for_each_page_immutable(&world, 2, |p| seen.push(p.0));
+    assert_eq!(vec![0., 1.], seen);
+
+    // a structural change to the current chunk restarts it, rather than skipping entities
+    let added = world.insert((Model(1),), vec![(Pos(8., 0., 0.),)])[0];
+    seen.clear();
+    query.for_each_page_immutable(&world, 2, |p| seen.push(p.0));
+    assert_eq!(vec![0., 1.], seen);
+
+    world.delete(added);
+    query.reset();
+    seen.clear();
+    assert_eq!(
+        8,
+        query.for_each_page_immutable(&world, 100, |p| seen.push(p.0))
+    );
+    assert_eq!(vec![0., 1., 2., 3., 4., 5., 6., 7.], seen);
+    assert!(query.is_at_start());
+}
+
+#[test]
+fn query_prefetch_write_only() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    // spread the entities over several chunks and chunk sets
+    for model in 0..4 {
+        let components = (0..3000)
+            .map(|i| (Pos(i as f32, 0., 0.), Vel(0., 0., 0.), String::new()))
+            .collect::<Vec<_>>();
+        world.insert((Model(model),), components);
+    }
+
+    let mut changed = Read::<Vel>::query().filter(changed::<Vel>());
+    assert_eq!(12000, changed.iter(&mut world).count());
+    assert_eq!(0, changed.iter(&mut world).count());
+
+    let mut query = <(Read<Pos>, WriteOnly<Vel>, WriteOnly<String>)>::query();
+    let mut chunks = 0;
+    for mut chunk in query.iter_chunks(&mut world).prefetch() {
+        chunks += 1;
+        for (pos, vel, name) in chunk.iter() {
+            vel.stream(Vel(pos.0, 1., 2.));
+            name.write(format!("{}", pos.0));
+        }
+    }
+    assert!(chunks > 4);
+
+    // writes are visible to change detection
+    assert_eq!(12000, changed.iter(&mut world).count());
+
+    let mut count = 0;
+    let mut query = <(Read<Pos>, Read<Vel>, Read<String>)>::query();
+    for (pos, vel, name) in query.iter(&mut world) {
+        assert_eq!(Vel(pos.0, 1., 2.), *vel);
+        assert_eq!(format!("{}", pos.0), *name);
+        count += 1;
+    }
+    assert_eq!(12000, count);
+}
+
+#[test]
+fn query_components_padded() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.set_component_padding(8);
+
+    let entities = world
+        .insert(
+            (),
+            (0..13).map(|i| (Pos(i as f32 + 1., 1., 1.), Vel(0., 0., 0.))),
+        )
+        .to_vec();
+    world.delete(entities[3]);
+    world.delete(entities[12]);
+
+    let mut query = <(Read<Pos>, Write<Vel>)>::query();
+    for chunk in query.iter_chunks(&mut world) {
+        let positions = unsafe { chunk.components_padded::<Pos>() }.unwrap();
+        assert_eq!(11, chunk.entities().len());
+        assert_eq!(16, positions.len());
+        assert!(positions[..11].iter().all(|pos| pos.0 > 0.));
+        assert!(positions[11..].iter().all(|pos| *pos == Pos(0., 0., 0.)));
+        assert_eq!(*chunk.components::<Pos>().unwrap(), &positions[..11]);
+    }
+}
+
+#[test]
+fn query_on_changed_per_chunk() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let a = world.insert((Model(1),), vec![(Pos(1., 0., 0.),)])[0];
+    let b = world.insert((Model(2),), vec![(Pos(2., 0., 0.),)])[0];
+
+    let mut first = Read::<Pos>::query().filter(changed::<Pos>());
+    let mut second = Read::<Pos>::query().filter(changed::<Pos>());
+    assert_eq!(2, first.iter(&mut world).count());
+    assert_eq!(0, first.iter(&mut world).count());
+
+    // write the chunk which is visited last before the chunk which is visited first, such that
+    // the chunks' versions are in the opposite order to the order in which they are checked
+    *world.get_component_mut::<Pos>(b).unwrap() = Pos(20., 0., 0.);
+    *world.get_component_mut::<Pos>(a).unwrap() = Pos(10., 0., 0.);
+
+    let mut changed = first.iter(&mut world).map(|pos| pos.0).collect::<Vec<_>>();
+    changed.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    assert_eq!(vec![10., 20.], changed);
+
+    // each filter observes changes independently of any other
+    assert_eq!(2, second.iter(&mut world).count());
+    assert_eq!(0, second.iter(&mut world).count());
+}
+
+#[test]
+fn query_on_changed_for_reader() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let a = world.insert((Model(1),), vec![(Pos(1., 0., 0.),)])[0];
+    let b = world.insert((Model(2),), vec![(Pos(2., 0., 0.),)])[0];
+
+    let physics = world.change_reader("physics");
+    let audio = world.change_reader("audio");
+    assert_eq!("physics", world.change_reader("physics").name());
+
+    // queries sharing a reader share its observed changes
+    let mut first = Read::<Pos>::query().filter(changed_for::<Pos>(&physics));
+    let mut second = Read::<Pos>::query().filter(changed_for::<Pos>(&physics));
+    assert_eq!(2, first.iter(&mut world).count());
+    assert_eq!(0, second.iter(&mut world).count());
+
+    // marking a reader unchanged does not hide changes from other readers
+    *world.get_component_mut::<Pos>(a).unwrap() = Pos(10., 0., 0.);
+    physics.mark_unchanged();
+    assert!(!world.get_component_changed::<Pos>(a, &physics));
+    assert!(world.get_component_changed::<Pos>(a, &audio));
+    assert!(world.get_component_changed::<Pos>(b, &audio));
+    assert!(!world.get_component_changed::<Rot>(a, &audio));
+
+    let mut audio_query = Read::<Pos>::query().filter(changed_for::<Pos>(&audio));
+    assert_eq!(2, audio_query.iter(&mut world).count());
+    assert!(!world.get_component_changed::<Pos>(a, &audio));
+
+    *world.get_component_mut::<Pos>(b).unwrap() = Pos(20., 0., 0.);
+    assert!(!world.get_component_changed::<Pos>(a, &physics));
+    assert!(world.get_component_changed::<Pos>(b, &physics));
+    let changed = first.iter(&mut world).map(|pos| *pos).collect::<Vec<_>>();
+    assert_eq!(vec![Pos(20., 0., 0.)], changed);
+}
+
+#[test]
+fn query_chunk_tags() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1), Static), vec![(Pos(1., 0., 0.),)]);
+    world.insert((Model(2), Static), vec![(Pos(2., 0., 0.),)]);
+    world.insert((Model(3),), vec![(Pos(3., 0., 0.),)]);
+
+    let mut query = Read::<Pos>::query();
+    let mut models = Vec::new();
+    for chunk in query.iter_chunks(&mut world) {
+        let pos = chunk.components::<Pos>().unwrap()[0];
+        match chunk.tags::<(Static, Model)>() {
+            Some((_, model)) => models.push((model.0, pos.0)),
+            None => assert_eq!(Some(&Model(3)), chunk.tag::<Model>()),
+        }
+        assert_eq!(chunk.tag::<Model>(), chunk.tags::<(Model,)>().map(|(m,)| m));
+    }
+
+    models.sort_by_key(|(model, _)| *model);
+    assert_eq!(vec![(1, 1.), (2, 2.)], models);
+}
+
+#[test]
+fn query_component_set_filters() {
+    use legion::storage::ComponentTypeId;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((), vec![(Pos(1., 0., 0.),)]);
+    world.insert((), vec![(Pos(2., 0., 0.), Rot(0., 0., 0.))]);
+    world.insert((), vec![(Pos(3., 0., 0.), Rot(0., 0., 0.), Vel(0., 0., 0.))]);
+
+    let pos = ComponentTypeId::of::<Pos>();
+    let rot = ComponentTypeId::of::<Rot>();
+    let vel = ComponentTypeId::of::<Vel>();
+
+    let mut matching = |filter| {
+        let mut query = Read::<Pos>::query().filter(filter);
+        let mut found = query.iter(&mut world).map(|p| p.0).collect::<Vec<_>>();
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        found
+    };
+
+    assert_eq!(vec![2., 3.], matching(contains_all(&[rot, pos])));
+    assert_eq!(vec![1., 2., 3.], matching(contains_all(&[])));
+    assert_eq!(vec![1.], matching(contains_none(&[rot, vel])));
+    assert_eq!(vec![2.], matching(exact(&[rot, pos, rot])));
+    assert_eq!(vec![3.], matching(exact(&[pos, rot, vel])));
+    assert_eq!(Vec::<f32>::new(), matching(exact(&[rot, vel])));
+}
+
+#[test]
+fn query_closure_filters() {
+    use legion::filter::ChunkTagsView;
+    use legion::filter::EntityFilter;
+    use legion::storage::ArchetypeDescription;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    for i in 0..4 {
+        world.insert((Model(i),), vec![(Pos(i as f32, 0., 0.),)]);
+    }
+    world.insert((Model(9),), vec![(Pos(9., 0., 0.), Rot(0., 0., 0.))]);
+    world.insert((), vec![(Pos(10., 0., 0.),)]);
+
+    fn matching<F: EntityFilter>(world: &mut World, mut query: Query<Read<Pos>, F>) -> Vec<f32> {
+        let mut found = query.iter(world).map(|p| p.0).collect::<Vec<_>>();
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        found
+    }
+
+    let odd = |tags: &ChunkTagsView| tags.get::<Model>().map_or(false, |m| m.0 % 2 == 1);
+    let query = Read::<Pos>::query().filter(chunkset_where(odd));
+    assert_eq!(vec![1., 3., 9.], matching(&mut world, query));
+
+    let with_rot = |arch: &ArchetypeDescription| arch.has_component::<Rot>();
+    let query = Read::<Pos>::query().filter(archetype_where(with_rot));
+    assert_eq!(vec![9.], matching(&mut world, query));
+
+    let query = Read::<Pos>::query().filter(!archetype_where(with_rot) & chunkset_where(odd));
+    assert_eq!(vec![1., 3.], matching(&mut world, query));
+
+    let first_sets = chunkset_where(|tags: &ChunkTagsView| tags.index() < 4);
+    let query = Read::<Pos>::query().filter(tag::<Model>() & first_sets);
+    assert_eq!(vec![0., 1., 2., 3., 9.], matching(&mut world, query));
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position(Pos);
+
+unsafe impl legion::storage::ComponentAlias for Position {
+    type Target = Pos;
+}
+
+#[test]
+fn query_component_alias() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((), vec![(Pos(1., 0., 0.), Vel(1., 0., 0.))]);
+    world.insert((), vec![(Pos(2., 0., 0.),)]);
+    world.insert((), vec![(Vel(3., 0., 0.),)]);
+
+    let mut query = <(WriteAs<Position>, Read<Vel>)>::query();
+    for (mut pos, vel) in query.iter(&mut world) {
+        (pos.0).0 += vel.0;
+    }
+
+    let mut query = ReadAs::<Position>::query();
+    let mut found = query.iter(&mut world).map(|p| (p.0).0).collect::<Vec<_>>();
+    found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(vec![2., 2.], found);
+
+    // writes through an alias are visible through the target type
+    let mut query = Read::<Pos>::query().filter(changed::<Pos>());
+    assert_eq!(2, query.iter(&mut world).count());
+}
+
+#[test]
+#[should_panic(expected = "invalid view")]
+fn query_component_alias_conflict() {
+    let _ = <(Read<Pos>, WriteAs<Position>)>::query();
+}
+
+#[test]
+fn query_on_added() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entities = world
+        .insert((), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)])
+        .to_vec();
+
+    let mut query = Read::<Pos>::query().filter(added::<Pos>());
+    assert_eq!(2, query.iter(&mut world).count());
+    assert_eq!(0, query.iter(&mut world).count());
+
+    // modifying existing components does not count as adding them
+    *world.get_component_mut::<Pos>(entities[0]).unwrap() = Pos(0., 0., 0.);
+    assert_eq!(0, query.iter(&mut world).count());
+
+    // moving an entity into a new archetype does not re-add its existing components
+    world.add_component(entities[0], Rot(0., 0., 0.));
+    assert_eq!(0, query.iter(&mut world).count());
+
+    let mut rot_query = Read::<Rot>::query().filter(added::<Rot>());
+    assert_eq!(1, rot_query.iter(&mut world).count());
+
+    // the archetype's column is reported when an entity is inserted into it
+    world.insert((), vec![(Pos(7., 8., 9.), Rot(0., 0., 0.))]);
+    assert_eq!(2, query.iter(&mut world).count());
+}
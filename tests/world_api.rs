@@ -71,6 +71,40 @@ fn get_component_wrong_type() {
     assert!(world.get_component::<i32>(entity).is_none());
 }
 
+#[test]
+fn get_component_pair_mut() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.), Vel(1., 1., 1.))])[0];
+    let other = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+
+    {
+        let (mut pos, vel) = world.get_component_pair_mut::<Pos, Vel>(entity).unwrap();
+        pos.0 += vel.0;
+        pos.1 += vel.1;
+    }
+    assert_eq!(Pos(2., 3., 3.), *world.get_component(entity).unwrap());
+
+    assert!(world.get_component_pair_mut::<Pos, Vel>(other).is_none());
+    world.delete(entity);
+    assert!(world.get_component_pair_mut::<Pos, Vel>(entity).is_none());
+}
+
+#[test]
+#[should_panic(expected = "same component type twice")]
+fn get_component_pair_mut_same_type() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    let _ = world.get_component_pair_mut::<Pos, Pos>(entity);
+}
+
 #[test]
 fn get_shared() {
     let _ = tracing_subscriber::fmt::try_init();
@@ -135,6 +169,50 @@ fn delete() {
     }
 }
 
+#[test]
+fn entity_count() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    assert!(world.is_empty());
+
+    let shared = (Static, Model(5));
+    let components = vec![
+        (Pos(1., 2., 3.), Rot(0.1, 0.2, 0.3)),
+        (Pos(4., 5., 6.), Rot(0.4, 0.5, 0.6)),
+        (Pos(7., 8., 9.), Rot(0.7, 0.8, 0.9)),
+    ];
+    let entities = world.insert(shared, components).to_vec();
+    world.insert((), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)]);
+    assert_eq!(5, world.len());
+
+    world.delete(entities[0]);
+    assert_eq!(4, world.len());
+
+    let mut query = <(Read<Pos>, Read<Rot>)>::query();
+    let chunk = query.iter_chunks(&mut world).next().unwrap();
+    assert_eq!(2, chunk.archetype().entity_count());
+    assert_eq!(2, chunk.chunkset().entity_count());
+
+    world.delete_all(component::<Pos>());
+    assert!(world.is_empty());
+}
+
+#[test]
+fn world_without_universe() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut world = World::default();
+    let entities = world.insert((), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)]).to_vec();
+    assert_eq!(Some(Pos(4., 5., 6.)), world.get_component::<Pos>(entities[1]).map(|p| *p));
+
+    let debug = format!("{:?}", world);
+    assert!(debug.starts_with("World {"));
+    assert!(debug.contains("archetypes: 1"));
+    assert!(debug.contains("entities: 2"));
+}
+
 #[test]
 fn delete_last() {
     let _ = tracing_subscriber::fmt::try_init();
@@ -241,6 +319,38 @@ fn merge() {
     }
 }
 
+#[test]
+fn merge_into_existing_archetype() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world_1 = universe.create_world();
+    let mut world_2 = universe.create_world();
+
+    let a = world_1.insert((Model(1),), vec![(Pos(1., 0., 0.),)])[0];
+    let b = world_2.insert((Model(2),), vec![(Pos(2., 0., 0.),)])[0];
+    let c = world_2.insert((Model(1),), vec![(Pos(3., 0., 0.),)])[0];
+    let d = world_2.insert((Model(3),), vec![(Rot(4., 0., 0.),)])[0];
+
+    world_1.merge(world_2);
+
+    assert_eq!(Pos(1., 0., 0.), *world_1.get_component::<Pos>(a).unwrap());
+    assert_eq!(Pos(2., 0., 0.), *world_1.get_component::<Pos>(b).unwrap());
+    assert_eq!(Pos(3., 0., 0.), *world_1.get_component::<Pos>(c).unwrap());
+    assert_eq!(Rot(4., 0., 0.), *world_1.get_component::<Rot>(d).unwrap());
+    assert_eq!(Some(&Model(2)), world_1.get_tag::<Model>(b));
+    assert_eq!(Some(&Model(1)), world_1.get_tag::<Model>(c));
+
+    // merged chunks belong to the world they were merged into
+    let (archetype, _, chunk) = world_1.entity_archetype(d).unwrap();
+    assert!(world_1.archetype(archetype).is_some());
+    assert_eq!(&[d], world_1.chunk(chunk).unwrap().entities());
+
+    world_1.delete(a);
+    world_1.defrag(None);
+    assert_eq!(Pos(3., 0., 0.), *world_1.get_component::<Pos>(c).unwrap());
+}
+
 #[test]
 fn mutate_add_component() {
     let _ = tracing_subscriber::fmt::try_init();
@@ -415,3 +525,1995 @@ fn mutate_change_tag() {
 
     assert_eq!(2, query_model_5.iter(&mut world).count());
 }
+
+#[test]
+fn insert_with_defaults() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Health(u32);
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.registry.register_with_default::<Health>();
+
+    let components = vec![
+        (Pos(1., 2., 3.), Rot(0.1, 0.2, 0.3)),
+        (Pos(4., 5., 6.), Rot(0.4, 0.5, 0.6)),
+    ];
+
+    let entities = world
+        .insert_with_defaults(
+            (Static,),
+            components.clone(),
+            &[legion::storage::ComponentTypeId::of::<Health>()],
+        )
+        .to_vec();
+
+    for (i, e) in entities.iter().enumerate() {
+        assert_eq!(components[i].0, *world.get_component::<Pos>(*e).unwrap());
+        assert_eq!(Health(0), *world.get_component::<Health>(*e).unwrap());
+    }
+
+    let mut query = <(Read<Pos>, Read<Rot>, Read<Health>)>::query();
+    assert_eq!(2, query.iter(&mut world).count());
+}
+
+#[test]
+fn add_component_default() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Health(u32);
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    world.add_component_default::<Health>(entity);
+
+    assert_eq!(Health(0), *world.get_component::<Health>(entity).unwrap());
+}
+
+#[test]
+fn non_sync_components() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    use std::rc::Rc;
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.registry.register_non_sync::<Rc<u32>>();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    let shared = Rc::new(5u32);
+    world.add_non_sync_component(entity, shared.clone());
+
+    assert_eq!(5, **world.get_non_sync_component::<Rc<u32>>(entity).unwrap());
+    assert_eq!(2, Rc::strong_count(&shared));
+
+    world.delete(entity);
+    assert_eq!(1, Rc::strong_count(&shared));
+}
+
+#[test]
+#[should_panic]
+fn non_sync_components_unregistered() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    world.add_non_sync_component(entity, std::rc::Rc::new(5u32));
+}
+
+#[test]
+#[cfg(not(feature = "single-thread"))]
+fn non_sync_components_other_thread() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.registry.register_non_sync::<std::rc::Rc<u32>>();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    world.add_non_sync_component(entity, std::rc::Rc::new(5u32));
+
+    let result = std::thread::spawn(move || {
+        let world = world;
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            world.get_non_sync_component::<std::rc::Rc<u32>>(entity);
+        }))
+        .is_err();
+        (panicked, world.is_owner_thread())
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!((true, false), result);
+}
+
+#[test]
+#[cfg(not(feature = "single-thread"))]
+fn replace_with_non_sync_other_thread() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.registry.register_non_sync::<std::rc::Rc<u32>>();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    world.add_non_sync_component(entity, std::rc::Rc::new(5u32));
+    let level = universe.create_world();
+
+    let result = std::thread::spawn(move || {
+        let mut level = level;
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            level.replace_with(world);
+        }))
+        .is_err();
+        (panicked, level.is_alive(entity))
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!((true, false), result);
+}
+
+#[test]
+fn split_off() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let kept = world
+        .insert(
+            (Model(1),),
+            (0..10000).map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.))),
+        )
+        .to_vec();
+    let moved = world
+        .insert(
+            (Model(2),),
+            (0..10000).map(|i| (Pos(i as f32, 1., 0.), Rot(0., 0., 0.))),
+        )
+        .to_vec();
+
+    let (split, remap) = world.split_off_with_remap(tag_value(&Model(2)));
+    assert_eq!(moved.len(), remap.len());
+
+    for (i, e) in kept.iter().enumerate() {
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component::<Pos>(*e).unwrap());
+        assert_eq!(Model(1), *world.get_tag::<Model>(*e).unwrap());
+    }
+
+    for (i, e) in moved.iter().enumerate() {
+        assert!(!world.is_alive(*e));
+        assert_eq!(Pos(i as f32, 1., 0.), *split.get_component::<Pos>(remap[e]).unwrap());
+        assert_eq!(Model(2), *split.get_tag::<Model>(remap[e]).unwrap());
+    }
+
+    let mut query = <(Read<Pos>, Tagged<Model>)>::query();
+    assert_eq!(10000, query.iter_immutable(&world).count());
+
+    let mut count = 0;
+    for (e, (pos, model)) in query.iter_entities_immutable(&split) {
+        assert_eq!(Model(2), *model);
+        assert_eq!(*pos, *split.get_component::<Pos>(e).unwrap());
+        assert_eq!(1., pos.1);
+        count += 1;
+    }
+    assert_eq!(10000, count);
+    assert_ne!(world.id(), split.id());
+}
+
+#[test]
+fn tag_interning() {
+    use std::sync::Arc;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Mesh(Arc<String>);
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    assert!(!world.tag_interning());
+    world.set_tag_interning(true);
+
+    let first = Arc::new("cube".to_string());
+    let duplicate = Arc::new("cube".to_string());
+    let a = world.insert((Mesh(first.clone()),), vec![(Pos(0., 0., 0.),)])[0];
+    let b = world.insert(
+        (Mesh(duplicate.clone()), Model(1)),
+        vec![(Pos(0., 0., 0.), Rot(0., 0., 0.))],
+    )[0];
+    let c = world.insert((Mesh(Arc::new("sphere".to_string())),), vec![(Pos(0., 0., 0.),)])[0];
+
+    // the duplicate was replaced by, and dropped in favour of, the first value
+    assert!(Arc::ptr_eq(&first, &world.get_tag::<Mesh>(b).unwrap().0));
+    assert_eq!(1, Arc::strong_count(&duplicate));
+    assert_eq!(3, Arc::strong_count(&first));
+    assert_eq!("sphere", world.get_tag::<Mesh>(c).unwrap().0.as_str());
+
+    // chunk sets created by structural changes are interned too
+    world.add_component(a, Vel(0., 0., 0.));
+    assert!(Arc::ptr_eq(&first, &world.get_tag::<Mesh>(a).unwrap().0));
+    assert_eq!(4, Arc::strong_count(&first));
+
+    drop(world);
+    assert_eq!(1, Arc::strong_count(&first));
+}
+
+#[test]
+fn delete_deterministic_order() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.set_deterministic_order(true);
+
+    let components = (0..5).map(|i| (Pos(i as f32, 0., 0.),)).collect::<Vec<_>>();
+    let entities = world.insert((), components).to_vec();
+
+    assert!(world.delete(entities[1]));
+
+    let mut query = Read::<Pos>::query();
+    let remaining = query
+        .iter_immutable(&world)
+        .map(|pos| pos.0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![0., 2., 3., 4.], remaining);
+
+    for (i, e) in entities.iter().enumerate().filter(|(i, _)| *i != 1) {
+        assert_eq!(
+            Pos(i as f32, 0., 0.),
+            *world.get_component::<Pos>(*e).unwrap()
+        );
+    }
+}
+
+#[test]
+fn inspect() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    struct Health {
+        current: u32,
+        max: u32,
+    }
+
+    legion::impl_reflect!(Health { current, max });
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.registry.register_reflect::<Health>();
+
+    let entity = world.insert(
+        (),
+        vec![(Pos(1., 2., 3.), Health { current: 5, max: 10 })],
+    )[0];
+
+    let changed = world.reflect_component_mut(
+        entity,
+        legion::storage::ComponentTypeId::of::<Health>(),
+        |health| health.set("current", 7u32),
+    );
+    assert_eq!(Some(true), changed);
+
+    let tree = world.inspect(entity).unwrap();
+    assert_eq!(1, tree.len());
+    assert_eq!(std::any::type_name::<Health>(), tree[0].name);
+    assert_eq!("current", tree[0].children[0].name);
+    assert_eq!(Some("7".to_owned()), tree[0].children[0].value);
+    assert_eq!(Some("10".to_owned()), tree[0].children[1].value);
+}
+
+#[test]
+#[cfg(feature = "serialize")]
+fn apply_json_patch() {
+    use legion::reflect::PatchError;
+    use serde_json::json;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    struct Health {
+        current: u32,
+        max: u32,
+        label: String,
+    }
+
+    legion::impl_reflect!(Health { current, max, label });
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    let mut registry = legion::registry::Registry::new();
+    registry.register_reflect::<Health>();
+
+    let health = Health {
+        current: 5,
+        max: 10,
+        label: "player".to_owned(),
+    };
+    let entity = world.insert((), vec![(Pos(1., 2., 3.), health)])[0];
+
+    let patch = json!({ "Health": { "current": 7, "label": "boss" } });
+    assert_eq!(Ok(()), world.apply_json_patch(entity, &patch, &registry));
+    let name = std::any::type_name::<Health>();
+    let patch = json!({ name: { "max": 20 } });
+    assert_eq!(Ok(()), world.apply_json_patch(entity, &patch, &registry));
+    {
+        let health = world.get_component::<Health>(entity).unwrap();
+        assert_eq!((7, 20), (health.current, health.max));
+        assert_eq!("boss", health.label);
+    }
+
+    assert_eq!(
+        Err(PatchError::UnknownComponent("Pos".to_owned())),
+        world.apply_json_patch(entity, &json!({ "Pos": { "x": 1 } }), &registry)
+    );
+    assert_eq!(
+        Err(PatchError::UnknownField("Health.min".to_owned())),
+        world.apply_json_patch(entity, &json!({ "Health": { "min": 1 } }), &registry)
+    );
+    assert_eq!(
+        Err(PatchError::InvalidValue("Health.current".to_owned())),
+        world.apply_json_patch(entity, &json!({ "Health": { "current": -1 } }), &registry)
+    );
+    assert_eq!(7, world.get_component::<Health>(entity).unwrap().current);
+
+    world.delete(entity);
+    assert!(matches!(
+        world.apply_json_patch(entity, &json!({}), &registry),
+        Err(PatchError::Entity(_))
+    ));
+}
+
+#[test]
+fn get_tag_mut() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let a = world
+        .insert((Model(1),), vec![(Pos(1., 0., 0.),), (Pos(2., 0., 0.),)])
+        .to_vec();
+    let b = world
+        .insert((Model(2),), vec![(Pos(3., 0., 0.),), (Pos(4., 0., 0.),)])
+        .to_vec();
+
+    // move into a new chunkset
+    world.get_tag_mut::<Model>(b[0]).unwrap().0 = 3;
+    // move into an existing chunkset
+    world.get_tag_mut::<Model>(a[1]).unwrap().0 = 2;
+    // unchanged values stay in place
+    let _ = world.get_tag_mut::<Model>(a[0]).unwrap();
+
+    assert_eq!(Some(&Model(1)), world.get_tag::<Model>(a[0]));
+    assert_eq!(Some(&Model(2)), world.get_tag::<Model>(a[1]));
+    assert_eq!(Some(&Model(3)), world.get_tag::<Model>(b[0]));
+    assert_eq!(Some(&Model(2)), world.get_tag::<Model>(b[1]));
+    assert_eq!(Pos(2., 0., 0.), *world.get_component::<Pos>(a[1]).unwrap());
+    assert_eq!(Pos(3., 0., 0.), *world.get_component::<Pos>(b[0]).unwrap());
+
+    let mut query = Read::<Pos>::query().filter(tag_value(&Model(2)));
+    assert_eq!(2, query.iter(&mut world).count());
+
+    assert!(world.get_tag_mut::<Static>(a[0]).is_none());
+}
+
+#[test]
+fn fixed_capacity() {
+    use legion::world::CapacityError;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.reserve((Model(1),), Vec::<(Pos, Rot)>::new(), 2000);
+    world.set_fixed_capacity(true);
+
+    let report = world.capacity_report();
+    assert_eq!(1, report.archetypes.len());
+    let capacity = report.archetypes[0].chunks * report.archetypes[0].chunk_capacity;
+    assert!(capacity >= 2000);
+    assert!(report.free_entity_slots >= capacity);
+
+    let components = vec![(Pos(0., 0., 0.), Rot(0., 0., 0.)); 2000];
+    let entities = world
+        .try_insert((Model(1),), components.clone())
+        .unwrap()
+        .to_vec();
+    assert_eq!(2000, entities.len());
+
+    // chunks keep their memory when emptied
+    for entity in entities.iter() {
+        assert!(world.delete(*entity));
+    }
+    assert_eq!(report.archetypes[0].chunks, world.capacity_report().archetypes[0].chunks);
+    assert_eq!(2000, world.try_insert((Model(1),), components).unwrap().len());
+
+    assert_eq!(
+        Err(CapacityError::Archetype),
+        world.try_insert((Model(1),), vec![(Pos(0., 0., 0.),)]).map(|e| e.len())
+    );
+    assert_eq!(
+        Err(CapacityError::Chunkset),
+        world
+            .try_insert((Model(2),), vec![(Pos(0., 0., 0.), Rot(0., 0., 0.))])
+            .map(|e| e.len())
+    );
+
+    let overflow = vec![(Pos(0., 0., 0.), Rot(0., 0., 0.)); capacity - 2000 + 1];
+    match world.try_insert((Model(1),), overflow) {
+        Err(CapacityError::Chunks { available, .. }) => assert_eq!(capacity - 2000, available),
+        _ => panic!("expected chunk capacity error"),
+    }
+    assert_eq!(2000, world.capacity_report().entities);
+}
+
+#[test]
+fn describe() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1),), vec![(Pos(0., 0., 0.), Rot(0., 0., 0.)); 3]);
+    world.insert((Model(2),), vec![(Pos(0., 0., 0.), Rot(0., 0., 0.)); 1]);
+    world.insert((), vec![(Pos(0., 0., 0.),)]);
+
+    let report = world.describe();
+    assert_eq!(5, report.entities);
+    assert_eq!(2, report.archetypes.len());
+
+    let tagged = &report.archetypes[0];
+    assert_eq!(1, tagged.tags.len());
+    assert!(tagged.tags[0].ends_with("Model"));
+    assert_eq!(2, tagged.components.len());
+    assert_eq!(2, tagged.chunksets);
+    assert_eq!(vec![3, 1], tagged.chunk_occupancy);
+    assert_eq!(4, tagged.entities);
+
+    let slots = 2 * tagged.chunk_capacity;
+    let expected = (slots - 4) as f32 / slots as f32 * 100.0;
+    assert!((tagged.fragmentation - expected).abs() < 0.001);
+
+    let text = report.to_string();
+    assert!(text.starts_with("2 archetypes, 5 entities"));
+    assert!(text.contains(&format!(
+        " 3/{} 1/{}",
+        tagged.chunk_capacity, tagged.chunk_capacity
+    )));
+}
+
+#[test]
+fn universe_shrink() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::with_entity_block_size(64);
+    assert_eq!(64, universe.entity_block_size());
+
+    let mut stale = Vec::new();
+    for _ in 0..4 {
+        let mut world = universe.create_world();
+        stale.extend(
+            world
+                .insert((), vec![(Pos(0., 0., 0.),); 100])
+                .iter()
+                .copied(),
+        );
+        assert_eq!(2, world.entity_stats().blocks_in_use);
+    }
+    assert_eq!(2, universe.shrink());
+    assert_eq!(0, universe.shrink());
+
+    let mut world = universe.create_world();
+    let entities = world.insert((), vec![(Pos(0., 0., 0.),); 200]).to_vec();
+    assert!(stale.iter().all(|e| !world.is_alive(*e)));
+    assert!(entities.iter().all(|e| !stale.contains(e)));
+}
+
+#[test]
+fn create_world_with_id() {
+    use legion::world::WorldId;
+    use legion::world::WorldIdInUse;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let zone = universe.create_world_with_id(WorldId::new(1)).unwrap();
+    assert_eq!(WorldId::new(1), zone.id());
+    assert_eq!(
+        Err(WorldIdInUse(WorldId::new(1))),
+        universe.create_world_with_id(WorldId::new(1)).map(|w| w.id())
+    );
+
+    // automatically assigned IDs skip those already in use
+    let a = universe.create_world();
+    let b = universe.create_world();
+    assert_eq!(WorldId::new(0), a.id());
+    assert_eq!(WorldId::new(2), b.id());
+    assert_eq!(
+        vec![WorldId::new(0), WorldId::new(1), WorldId::new(2)],
+        universe.worlds()
+    );
+
+    drop(zone);
+    assert!(!universe.contains_world(WorldId::new(1)));
+    assert_eq!(vec![WorldId::new(0), WorldId::new(2)], universe.worlds());
+    assert!(universe.create_world_with_id(WorldId::new(1)).is_ok());
+}
+
+#[test]
+fn foreign_entities() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut a = Universe::new().create_world();
+    let mut b = Universe::new().create_world();
+
+    let a_entities = a.insert((Model(1),), vec![(Pos(1., 0., 0.),)]).to_vec();
+    let b_entities = b.insert((Model(2),), vec![(Pos(2., 0., 0.),)]).to_vec();
+    assert_eq!(a_entities, b_entities);
+
+    // entities from another universe are not confused with this world's entities
+    assert!(!b.is_alive(a_entities[0]));
+    assert!(b.get_component::<Pos>(a_entities[0]).is_none());
+    assert!(b.get_tag::<Model>(a_entities[0]).is_none());
+    assert!(!b.delete(a_entities[0]));
+
+    assert!(b.is_alive(b_entities[0]));
+    assert_eq!(
+        Pos(2., 0., 0.),
+        *b.get_component::<Pos>(b_entities[0]).unwrap()
+    );
+    assert!(a.delete(a_entities[0]));
+}
+
+#[test]
+#[should_panic(expected = "is not alive in this world")]
+fn add_component_to_deleted_entity() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut world = Universe::new().create_world();
+    let entities = world.insert((), vec![(Pos(1., 0., 0.),); 2]).to_vec();
+    world.delete(entities[0]);
+    world.add_component(entities[0], Rot(0., 0., 0.));
+}
+
+#[test]
+fn removed() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.track_removed::<Pos>();
+
+    let entities = world
+        .insert((), vec![(Pos(0., 0., 0.), Rot(0., 0., 0.)); 4])
+        .to_vec();
+    world.remove_component::<Pos>(entities[0]);
+    world.remove_component::<Pos>(entities[0]);
+    world.remove_component::<Rot>(entities[1]);
+    world.take_component::<Pos>(entities[1]);
+    world.delete(entities[2]);
+
+    assert_eq!(
+        entities[..3].to_vec(),
+        world.removed::<Pos>().collect::<Vec<_>>()
+    );
+    assert_eq!(0, world.removed::<Rot>().count());
+
+    // removals remain visible through the following update
+    world.update_removed();
+    world.remove_entity(entities[3]);
+    assert_eq!(entities, world.removed::<Pos>().collect::<Vec<_>>());
+
+    world.update_removed();
+    assert_eq!(
+        vec![entities[3]],
+        world.removed::<Pos>().collect::<Vec<_>>()
+    );
+    world.update_removed();
+    assert_eq!(0, world.removed::<Pos>().count());
+
+    // systems observe removals made by command buffers in the previous frame
+    let entity = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    let remover = SystemBuilder::<()>::new("remover")
+        .build(move |commands, _, _, _| commands.remove_component::<Pos>(entity));
+    let observed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let observer = SystemBuilder::<()>::new("observer").build({
+        let observed = observed.clone();
+        move |_, world, _, _| observed.lock().unwrap().extend(world.removed::<Pos>())
+    });
+    let mut executor = StageExecutor::new(vec![observer, remover]);
+
+    executor.execute(&mut world);
+    world.update_removed();
+    executor.execute(&mut world);
+    assert_eq!(vec![entity], *observed.lock().unwrap());
+}
+
+#[test]
+fn for_each_raw() {
+    use legion::storage::ComponentTypeId;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1),), vec![(1u32, 2u8), (3u32, 4u8)]);
+    world.insert((), vec![(5u32,)]);
+    let deleted = world.insert((), vec![(6u16,)])[0];
+    world.delete(deleted);
+
+    // the component types contain no padding
+    let mut columns = Vec::new();
+    world.for_each_raw(|bytes, type_id, element_size, count| {
+        assert_eq!(element_size * count, bytes.len());
+        let bytes = bytes
+            .iter()
+            .map(|b| unsafe { b.assume_init() })
+            .collect::<Vec<_>>();
+        columns.push((type_id, bytes));
+    });
+
+    let u32s = columns
+        .iter()
+        .filter(|(t, _)| *t == ComponentTypeId::of::<u32>())
+        .flat_map(|(_, bytes)| bytes.chunks(4))
+        .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![1, 3, 5], u32s);
+
+    let u8s = columns
+        .iter()
+        .filter(|(t, _)| *t == ComponentTypeId::of::<u8>())
+        .flat_map(|(_, bytes)| bytes.iter().copied())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![2, 4], u8s);
+
+    assert_eq!(3, columns.len());
+}
+
+#[test]
+fn checksum() {
+    use legion::error::Error;
+    use legion::storage::ComponentTypeId;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut a = World::new();
+    let mut b = World::new();
+    a.registry.register_hash::<String>();
+    b.registry.register_hash::<String>();
+    a.registry.register_pod::<u32>();
+    b.registry.register_pod::<u32>();
+
+    let data = vec![(1u32, "x".to_owned()), (2u32, "y".to_owned())];
+    let entities = a.insert((), data.clone()).to_vec();
+    let b_entities = b.insert((), data).to_vec();
+
+    let checksum = a.checksum(&a.registry).unwrap();
+    assert_eq!(checksum, b.checksum(&b.registry).unwrap());
+
+    *a.get_component_mut::<u32>(entities[1]).unwrap() = 3;
+    assert_ne!(checksum, a.checksum(&a.registry).unwrap());
+    *a.get_component_mut::<u32>(entities[1]).unwrap() = 2;
+    assert_eq!(checksum, a.checksum(&a.registry).unwrap());
+
+    // moving entities between chunks does not change the checksum
+    a.add_tag(entities[0], Model(1));
+    a.remove_tag::<Model>(entities[0]);
+    assert_eq!(checksum, a.checksum(&a.registry).unwrap());
+
+    // registered types are hashed by value rather than by their bytes
+    a.get_component_mut::<String>(entities[0]).unwrap().reserve(64);
+    assert_eq!(checksum, a.checksum(&a.registry).unwrap());
+
+    b.delete(b_entities[0]);
+    assert_ne!(checksum, b.checksum(&b.registry).unwrap());
+
+    // unregistered types cannot be hashed
+    a.insert((), vec![(1u16,)]);
+    assert_eq!(
+        Err(Error::NotHashable(ComponentTypeId::of::<u16>())),
+        a.checksum(&a.registry)
+    );
+}
+
+#[test]
+fn find_by_name() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entities = world
+        .insert(
+            (),
+            vec![
+                (Name::new("enemy"), Pos(0., 0., 0.)),
+                (Name::new("player"), Pos(1., 0., 0.)),
+                (Name::new("enemy".to_owned()), Pos(2., 0., 0.)),
+            ],
+        )
+        .to_vec();
+
+    let mut enemies = world.find_by_name("enemy").collect::<Vec<_>>();
+    enemies.sort_by_key(|e| entities.iter().position(|x| x == e));
+    assert_eq!(vec![entities[0], entities[2]], enemies);
+    assert_eq!(vec![entities[1]], world.find_by_name("player").collect::<Vec<_>>());
+    assert_eq!(0, world.find_by_name("missing").count());
+
+    // renames
+    *world.get_component_mut::<Name>(entities[0]).unwrap() = Name::new("boss");
+    assert_eq!(vec![entities[2]], world.find_by_name("enemy").collect::<Vec<_>>());
+    assert_eq!(vec![entities[0]], world.find_by_name("boss").collect::<Vec<_>>());
+
+    // renames through queries
+    for mut name in Write::<Name>::query().iter(&mut world) {
+        if name.as_str() == "player" {
+            *name = Name::new("hero");
+        }
+    }
+    assert_eq!(vec![entities[1]], world.find_by_name("hero").collect::<Vec<_>>());
+
+    // moves between archetypes and deletions
+    world.add_tag(entities[1], Model(1));
+    assert_eq!(vec![entities[1]], world.find_by_name("hero").collect::<Vec<_>>());
+    world.delete(entities[2]);
+    assert_eq!(0, world.find_by_name("enemy").count());
+    world.remove_component::<Name>(entities[0]);
+    assert_eq!(0, world.find_by_name("boss").count());
+}
+
+#[test]
+fn add_component_to_filtered() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let many = (0..3000)
+        .map(|i| (Pos(i as f32, 0., 0.),))
+        .collect::<Vec<_>>();
+    let plain = world.insert((), many).to_vec();
+    let tagged = world
+        .insert((Model(1),), vec![(Pos(-1., 0., 0.),), (Pos(-2., 0., 0.),)])
+        .to_vec();
+    let existing = world
+        .insert((), vec![(Pos(-3., 0., 0.), Vel(0., 0., 0.))])
+        .to_vec();
+    let ignored = world.insert((), vec![(Rot(0., 0., 0.),)]).to_vec();
+
+    world.add_component_to_filtered(component::<Pos>(), |e| {
+        let index = plain.iter().position(|p| *p == e).unwrap_or(0) as f32;
+        Vel(index, 1., 0.)
+    });
+
+    for (i, entity) in plain.iter().enumerate() {
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component::<Pos>(*entity).unwrap());
+        assert_eq!(Vel(i as f32, 1., 0.), *world.get_component::<Vel>(*entity).unwrap());
+    }
+    for entity in tagged.iter() {
+        assert_eq!(Some(&Model(1)), world.get_tag::<Model>(*entity));
+        assert_eq!(Vel(0., 1., 0.), *world.get_component::<Vel>(*entity).unwrap());
+    }
+    assert_eq!(Vel(0., 1., 0.), *world.get_component::<Vel>(existing[0]).unwrap());
+    assert!(world.get_component::<Vel>(ignored[0]).is_none());
+
+    let mut query = <(Read<Pos>, Read<Vel>)>::query();
+    assert_eq!(3003, query.iter(&mut world).count());
+}
+
+#[test]
+fn migrate_component() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let many = (0..3000)
+        .map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.)))
+        .collect::<Vec<_>>();
+    let plain = world.insert((), many).to_vec();
+    let tagged = world
+        .insert((Model(1),), vec![(Pos(-1., 0., 0.),), (Pos(-2., 0., 0.),)])
+        .to_vec();
+    let existing = world
+        .insert((), vec![(Pos(-3., 0., 0.), Vel(0., 0., 0.))])
+        .to_vec();
+    let ignored = world.insert((), vec![(Rot(0., 0., 0.),)]).to_vec();
+
+    let migrated = world.migrate_component(|Pos(x, y, z)| Vel(*x, *y, *z));
+    assert_eq!(3003, migrated);
+
+    for (i, entity) in plain.iter().enumerate() {
+        assert!(world.get_component::<Pos>(*entity).is_none());
+        assert_eq!(Vel(i as f32, 0., 0.), *world.get_component::<Vel>(*entity).unwrap());
+        assert!(world.get_component::<Rot>(*entity).is_some());
+    }
+    for (i, entity) in tagged.iter().enumerate() {
+        assert_eq!(Some(&Model(1)), world.get_tag::<Model>(*entity));
+        assert_eq!(Vel(-1. - i as f32, 0., 0.), *world.get_component::<Vel>(*entity).unwrap());
+    }
+    assert_eq!(Vel(-3., 0., 0.), *world.get_component::<Vel>(existing[0]).unwrap());
+    assert!(world.get_component::<Vel>(ignored[0]).is_none());
+    assert_eq!(0, Read::<Pos>::query().iter(&mut world).count());
+
+    // old components are dropped once converted
+    let names = world
+        .insert((), vec![("a".to_owned(),), ("b".to_owned(),)])
+        .to_vec();
+    world.migrate_component(|name: &String| name.clone().into_bytes());
+    assert_eq!(b"b", &world.get_component::<Vec<u8>>(names[1]).unwrap()[..]);
+    assert!(world.delete(names[0]));
+
+    // a panicking conversion leaves the entity being converted unchanged
+    let entity = world.insert((), vec![(Rot(1., 2., 3.),)])[0];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        world.migrate_component(|_: &Rot| -> Scale { panic!("conversion failed") });
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rot(1., 2., 3.), *world.get_component::<Rot>(entity).unwrap());
+    assert!(world.get_component::<Scale>(entity).is_none());
+}
+
+#[test]
+fn insert_from_fn() {
+    use legion::world::from_fn;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entities = world
+        .insert((), from_fn(2000, |i| (Pos(i as f32, 0., 0.), Rot(0., i as f32, 0.))))
+        .to_vec();
+
+    assert_eq!(2000, entities.len());
+    for (i, entity) in entities.iter().enumerate() {
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component::<Pos>(*entity).unwrap());
+        assert_eq!(Rot(0., i as f32, 0.), *world.get_component::<Rot>(*entity).unwrap());
+    }
+
+    // the number of entities is known before any are inserted
+    world.set_fixed_capacity(true);
+    let result = world
+        .try_insert((), from_fn(100_000, |_| (Pos(0., 0., 0.), Rot(0., 0., 0.))))
+        .map(|e| e.len());
+    assert!(result.is_err());
+    assert_eq!(2000, world.capacity_report().entities);
+}
+
+#[test]
+fn insert_dynamic() {
+    use legion::world::DynamicEntityBuilder;
+    use std::sync::Arc;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let existing = world.insert((Model(1),), vec![(Pos(0., 0., 0.),)])[0];
+
+    let counter = Arc::new(());
+    let builders = (0..10).map(|i| {
+        let mut builder = DynamicEntityBuilder::new().with_component(Pos(i as f32, 0., 0.));
+        if i % 2 == 0 {
+            builder = builder.with_component(Rot(0., i as f32, 0.));
+        }
+        if i % 3 == 0 {
+            builder = builder
+                .with_component(counter.clone())
+                .with_tag(Model(i % 2));
+        }
+        builder
+    });
+    let entities = world.insert_dynamic(builders);
+
+    assert_eq!(10, entities.len());
+    for (i, entity) in entities.iter().enumerate() {
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component::<Pos>(*entity).unwrap());
+        assert_eq!(i % 2 == 0, world.get_component::<Rot>(*entity).is_some());
+        assert_eq!(i % 3 == 0, world.get_component::<Arc<()>>(*entity).is_some());
+        if i % 3 == 0 {
+            assert_eq!(Some(&Model(i as u32 % 2)), world.get_tag::<Model>(*entity));
+        } else {
+            assert!(world.get_tag::<Model>(*entity).is_none());
+        }
+    }
+
+    // entities join existing chunks with the same layout
+    let location = world.insert_dynamic(vec![
+        DynamicEntityBuilder::new().with_component(Pos(1., 1., 1.)).with_tag(Model(1)),
+    ])[0];
+    let mut query = Read::<Pos>::query().filter(tag_value(&Model(1)) & !component::<Arc<()>>());
+    assert_eq!(1, query.iter_chunks(&mut world).count());
+    assert_eq!(
+        vec![existing, location],
+        query.iter_entities(&mut world).map(|(e, _)| e).collect::<Vec<_>>()
+    );
+
+    // values are moved rather than copied, and unused values are dropped
+    assert_eq!(5, Arc::strong_count(&counter));
+    let _ = DynamicEntityBuilder::new()
+        .with_component(counter.clone())
+        .with_component(counter.clone());
+    assert_eq!(5, Arc::strong_count(&counter));
+    drop(world);
+    assert_eq!(1, Arc::strong_count(&counter));
+}
+
+#[test]
+fn extend() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.extend((0..10).map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.))));
+    world.extend(vec![(Pos(10., 0., 0.),)]);
+    world.extend_tagged((Model(1),), (0..5).map(|i| (Pos(i as f32, 1., 0.),)));
+
+    assert_eq!(16, Read::<Pos>::query().iter(&mut world).count());
+    assert_eq!(10, Read::<Rot>::query().iter(&mut world).count());
+
+    let mut query = Read::<Pos>::query().filter(tag_value(&Model(1)));
+    assert!(query.iter(&mut world).all(|pos| pos.1 == 1.));
+    assert_eq!(5, query.iter(&mut world).count());
+}
+
+#[test]
+#[cfg(not(feature = "single-thread"))]
+fn read_view() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entities = world
+        .insert(
+            (Model(1),),
+            (0..100).map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.))),
+        )
+        .to_vec();
+
+    let view = world.read_view();
+    let (sum, count) = std::thread::scope(|scope| {
+        let sum = scope.spawn(move || {
+            let mut query = Read::<Pos>::query();
+            view.iter(&mut query).map(|pos| pos.0).sum::<f32>()
+        });
+        let count = scope.spawn(move || {
+            let mut query = <(Read<Pos>, Read<Rot>)>::query().filter(tag_value(&Model(1)));
+            view.iter_entities(&mut query).count()
+        });
+        (sum.join().unwrap(), count.join().unwrap())
+    });
+
+    assert_eq!(4950., sum);
+    assert_eq!(100, count);
+    assert_eq!(world.id(), view.id());
+    assert!(view.is_alive(entities[5]));
+    assert_eq!(Pos(5., 0., 0.), *view.get_component::<Pos>(entities[5]).unwrap());
+    assert_eq!(Some(&Model(1)), view.get_tag::<Model>(entities[5]));
+}
+
+#[test]
+fn insert_spanning_chunks() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((), (0..10).map(|i| (Pos(i as f32, 0., 0.),)));
+    let removed = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    world.delete(removed);
+
+    let inserted = world.insert((), (0..5000).map(|i| (Pos(i as f32, 1., 0.),)));
+    assert_eq!(5000, inserted.len());
+    assert_eq!(5000, inserted.iter().len());
+    assert_eq!(Some(&inserted[4999]), inserted.iter().last());
+    assert!(inserted.get(5000).is_none());
+
+    let entities = inserted.to_vec();
+    let chunks = Read::<Pos>::query().iter_chunks(&mut world).count();
+    assert!(chunks > 1);
+    for (i, entity) in entities.iter().enumerate() {
+        assert_eq!(Pos(i as f32, 1., 0.), *world.get_component::<Pos>(*entity).unwrap());
+    }
+}
+
+#[test]
+fn remove_entity() {
+    use std::sync::Arc;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let item = Arc::new(5u32);
+    let entities = world
+        .insert(
+            (Model(3),),
+            vec![
+                (Pos(1., 2., 3.), item.clone()),
+                (Pos(4., 5., 6.), item.clone()),
+                (Pos(7., 8., 9.), item.clone()),
+            ],
+        )
+        .to_vec();
+    assert_eq!(4, Arc::strong_count(&item));
+
+    // taken components are moved out of the world without being cloned
+    let mut bag = world.remove_entity(entities[0]).unwrap();
+    assert_eq!(entities[0], bag.entity());
+    assert!(!world.is_alive(entities[0]));
+    assert!(world.remove_entity(entities[0]).is_none());
+    assert_eq!(4, Arc::strong_count(&item));
+
+    let taken = bag.take::<Arc<u32>>().unwrap();
+    assert!(!bag.contains::<Arc<u32>>());
+    assert!(bag.take::<Arc<u32>>().is_none());
+    assert_eq!(Some(Pos(1., 2., 3.)), bag.take::<Pos>());
+    drop(bag);
+    assert_eq!(4, Arc::strong_count(&item));
+    drop(taken);
+    assert_eq!(3, Arc::strong_count(&item));
+
+    // components left in the bag are dropped with it
+    drop(world.remove_entity(entities[1]).unwrap());
+    assert_eq!(2, Arc::strong_count(&item));
+
+    // the remaining entity was moved to fill the gap
+    assert_eq!(Pos(7., 8., 9.), *world.get_component::<Pos>(entities[2]).unwrap());
+
+    // bags can be re-inserted
+    let bag = world.remove_entity(entities[2]).unwrap();
+    let moved = world.insert_dynamic(vec![bag.into_builder()])[0];
+    assert_eq!(Pos(7., 8., 9.), *world.get_component::<Pos>(moved).unwrap());
+    assert_eq!(Some(&Model(3)), world.get_tag::<Model>(moved));
+    assert_eq!(2, Arc::strong_count(&item));
+}
+
+#[test]
+fn take_and_replace_component() {
+    use std::sync::Arc;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let value = Arc::new(1u32);
+    let entities = world
+        .insert(
+            (),
+            vec![
+                (Pos(1., 2., 3.), value.clone()),
+                (Pos(4., 5., 6.), value.clone()),
+            ],
+        )
+        .to_vec();
+
+    // replacing swaps the value in place
+    let before = world.get_component::<Pos>(entities[0]).map(|c| &*c as *const Pos);
+    let replaced = world.replace_component(entities[0], Arc::new(2u32)).unwrap();
+    assert!(Arc::ptr_eq(&value, &replaced));
+    assert_eq!(2, **world.get_component::<Arc<u32>>(entities[0]).unwrap().as_ref());
+    assert_eq!(before, world.get_component::<Pos>(entities[0]).map(|c| &*c as *const Pos));
+    assert!(world.replace_component(entities[0], Rot(0., 0., 0.)).is_none());
+    drop(replaced);
+    assert_eq!(2, Arc::strong_count(&value));
+
+    // taking moves the value out of the entity
+    let taken = world.take_component::<Arc<u32>>(entities[1]).unwrap();
+    assert!(Arc::ptr_eq(&value, &taken));
+    assert_eq!(2, Arc::strong_count(&value));
+    assert!(world.get_component::<Arc<u32>>(entities[1]).is_none());
+    assert!(world.take_component::<Arc<u32>>(entities[1]).is_none());
+    assert_eq!(Pos(4., 5., 6.), *world.get_component::<Pos>(entities[1]).unwrap());
+    assert_eq!(Pos(1., 2., 3.), *world.get_component::<Pos>(entities[0]).unwrap());
+
+    drop(taken);
+    assert_eq!(1, Arc::strong_count(&value));
+}
+
+#[test]
+fn sort_chunk_entities() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Key(u32);
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let keys = [3, 1, 2, 1, 0];
+    let entities = world
+        .insert(
+            (Model(1),),
+            keys.iter().map(|k| (Key(*k), Pos(*k as f32, 0., 0.))),
+        )
+        .to_vec();
+    let other = world.insert(
+        (Model(2),),
+        vec![(Key(2), Pos(2., 0., 0.)), (Key(1), Pos(1., 0., 0.))],
+    )[0];
+
+    world.sort_chunk_entities::<Key, _>(tag_value(&Model(1)));
+
+    let sorted = <(Read<Key>, Read<Pos>)>::query()
+        .filter(tag_value(&Model(1)))
+        .iter(&mut world)
+        .map(|(k, p)| (k.0, p.0))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![(0, 0.), (1, 1.), (1, 1.), (2, 2.), (3, 3.)], sorted);
+
+    // the stable sort keeps the relative order of equal keys
+    let ordered = <Read<Key>>::query()
+        .filter(tag_value(&Model(1)))
+        .iter_entities(&mut world)
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    let expected = [4, 1, 3, 2, 0]
+        .iter()
+        .map(|i| entities[*i])
+        .collect::<Vec<_>>();
+    assert_eq!(expected, ordered);
+
+    // locations are updated
+    for (entity, key) in entities.iter().zip(keys.iter()) {
+        let found = world.get_component::<Key>(*entity).map(|k| *k);
+        assert_eq!(Some(Key(*key)), found);
+    }
+
+    // chunks which do not match the filter are untouched
+    assert_eq!(Some(Key(2)), world.get_component::<Key>(other).map(|k| *k));
+    let unsorted = Read::<Key>::query()
+        .filter(tag_value(&Model(2)))
+        .iter(&mut world)
+        .map(|k| k.0)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![2, 1], unsorted);
+}
+
+#[test]
+fn checked_operations() {
+    use legion::error::{EntityError, Error};
+    use legion::storage::{ComponentTypeId, TagTypeId};
+    use legion::world::CapacityError;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entity = world
+        .insert_checked((Model(1),), vec![(Pos(1., 2., 3.),)])
+        .unwrap()[0];
+
+    assert_eq!(Ok(()), world.add_component_checked(entity, Rot(0., 0., 0.)));
+    assert_eq!(Ok(()), world.remove_component_checked::<Rot>(entity));
+    assert_eq!(
+        Err(Error::Entity(EntityError::MissingComponent(
+            entity,
+            ComponentTypeId::of::<Rot>()
+        ))),
+        world.remove_component_checked::<Rot>(entity)
+    );
+
+    assert_eq!(Ok(()), world.delete_checked(entity));
+    let dead = Err(Error::Entity(EntityError::NotAlive(entity)));
+    assert_eq!(dead, world.delete_checked(entity));
+    assert_eq!(dead, world.add_component_checked(entity, Rot(0., 0., 0.)));
+    assert_eq!(dead, world.remove_component_checked::<Pos>(entity));
+
+    let duplicate = world
+        .insert_checked((), vec![(Pos(0., 0., 0.), Pos(1., 1., 1.))])
+        .map(|e| e.len());
+    assert_eq!(
+        Err(Error::DuplicateComponent(ComponentTypeId::of::<Pos>())),
+        duplicate
+    );
+    let duplicate = world
+        .insert_checked((Model(1), Model(2)), vec![(Pos(0., 0., 0.),)])
+        .map(|e| e.len());
+    assert_eq!(
+        Err(Error::DuplicateTag(TagTypeId::of::<Model>())),
+        duplicate
+    );
+
+    world.set_fixed_capacity(true);
+    let exhausted = world
+        .insert_checked((Model(3),), vec![(Pos(0., 0., 0.),)])
+        .map(|e| e.len());
+    assert_eq!(Err(Error::Capacity(CapacityError::Chunkset)), exhausted);
+}
+
+#[test]
+fn adopt_world() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    struct Gravity(f32);
+
+    let offline = Universe::new();
+    let mut level = offline.create_world();
+    level.resources.insert(Gravity(9.8));
+    let mut built = level
+        .insert(
+            (Model(0),),
+            vec![
+                (Pos(1., 2., 3.), Rot(0., 0., 0.)),
+                (Pos(4., 5., 6.), Rot(1., 1., 1.)),
+            ],
+        )
+        .to_vec();
+    built.extend(level.insert((Static,), vec![(Pos(7., 8., 9.),)]));
+
+    let universe = Universe::new();
+    let mut existing = universe.create_world();
+    let existing_entities = existing.insert((), vec![(Pos(0., 0., 0.),); 3]).to_vec();
+
+    let (world, remap) = universe.adopt_world_with_remap(level);
+    assert_eq!(3, remap.len());
+
+    for (entity, pos) in built.iter().zip(&[1., 4., 7.]) {
+        let adopted = remap[entity];
+        assert!(world.is_alive(adopted));
+        assert!(!existing_entities.contains(&adopted));
+        assert_eq!(*pos, world.get_component::<Pos>(adopted).unwrap().0);
+    }
+    assert_eq!(Some(&Model(0)), world.get_tag::<Model>(remap[&built[1]]));
+    assert_eq!(9.8, world.resources.get::<Gravity>().unwrap().0);
+
+    // the universe continues to allocate unique IDs
+    let more = existing.insert((), vec![(Pos(0., 0., 0.),); 3]).to_vec();
+    assert!(more.iter().all(|e| remap.values().all(|a| a != e)));
+}
+
+#[test]
+fn snapshot_rollback() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    use legion::error::Error;
+    use legion::storage::ComponentTypeId;
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    let entities = world
+        .insert(
+            (Model(1),),
+            (0..3).map(|i| (Pos(i as f32, 0., 0.), format!("{}", i))),
+        )
+        .to_vec();
+
+    assert_eq!(
+        Err(Error::NotCloneable(ComponentTypeId::of::<Pos>())),
+        world.snapshot().map(|_| ())
+    );
+
+    world.registry.register_pod::<Pos>();
+    world.registry.register_clone::<String>();
+    let snapshot = world.snapshot().unwrap();
+
+    *world.get_component_mut::<Pos>(entities[0]).unwrap() = Pos(10., 0., 0.);
+    world.get_component_mut::<String>(entities[1]).unwrap().push('!');
+    world.delete(entities[2]);
+    let created = world.insert((Model(1),), vec![(Pos(3., 0., 0.), String::new())])[0];
+
+    // taking a snapshot of a modified world does not disturb earlier snapshots
+    let modified = world.snapshot().unwrap();
+
+    world.rollback(snapshot);
+    assert!(!world.is_alive(created));
+    for (i, entity) in entities.iter().enumerate() {
+        assert!(world.is_alive(*entity));
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component::<Pos>(*entity).unwrap());
+        assert_eq!(format!("{}", i), *world.get_component::<String>(*entity).unwrap());
+        assert_eq!(Some(&Model(1)), world.get_tag::<Model>(*entity));
+    }
+
+    let mut changed = Read::<Pos>::query().filter(changed::<Pos>());
+    assert_eq!(3, changed.iter(&mut world).count());
+
+    world.rollback(modified);
+    assert!(!world.is_alive(entities[2]));
+    assert!(world.is_alive(created));
+    assert_eq!(Pos(10., 0., 0.), *world.get_component::<Pos>(entities[0]).unwrap());
+    assert_eq!("1!", *world.get_component::<String>(entities[1]).unwrap());
+}
+
+#[test]
+fn apply_deltas() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    use legion::world::EntityDelta;
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.track_removed::<Rot>();
+    let entities = world
+        .insert(
+            (Model(0),),
+            (0..6).map(|i| (Pos(i as f32, 0., 0.), Rot(0., 0., 0.), format!("{}", i))),
+        )
+        .to_vec();
+    let dead = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    world.delete(dead);
+
+    let deltas = entities
+        .iter()
+        .enumerate()
+        .map(|(i, e)| match i % 3 {
+            0 => (*e, EntityDelta::new().with_component(Vel(i as f32, 0., 0.))),
+            1 => (*e, EntityDelta::new().without_component::<Rot>()),
+            _ => (*e, EntityDelta::new().with_component(format!("updated {}", i))),
+        })
+        .chain(vec![
+            (dead, EntityDelta::new().with_component(Vel(0., 0., 0.))),
+            // later deltas for the same entity are applied after earlier ones
+            (
+                entities[0],
+                EntityDelta::new()
+                    .without_component::<Vel>()
+                    .with_component(Scale(1., 1., 1.)),
+            ),
+            (entities[3], EntityDelta::new().with_component(Vel(30., 0., 0.))),
+        ])
+        .collect::<Vec<_>>();
+    assert_eq!(6, world.apply_deltas(deltas));
+
+    assert!(world.get_component::<Vel>(entities[0]).is_none());
+    assert_eq!(Some(Scale(1., 1., 1.)), world.get_component::<Scale>(entities[0]).map(|s| *s));
+    assert_eq!(Vel(30., 0., 0.), *world.get_component::<Vel>(entities[3]).unwrap());
+    for i in &[1, 4] {
+        assert!(world.get_component::<Rot>(entities[*i]).is_none());
+    }
+    for i in &[2, 5] {
+        let name = world.get_component::<String>(entities[*i]).unwrap();
+        assert_eq!(format!("updated {}", i), *name);
+    }
+
+    // untouched components and tags are carried over
+    for (i, entity) in entities.iter().enumerate() {
+        assert_eq!(i as f32, world.get_component::<Pos>(*entity).unwrap().0);
+        assert_eq!(Some(&Model(0)), world.get_tag::<Model>(*entity));
+    }
+    assert_eq!("0", *world.get_component::<String>(entities[0]).unwrap());
+
+    world.update_removed();
+    let removed = world.removed::<Rot>().collect::<Vec<_>>();
+    assert_eq!(2, removed.len());
+    assert!(removed.contains(&entities[1]) && removed.contains(&entities[4]));
+}
+
+#[test]
+fn get_component_raw() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entities = world
+        .insert((), vec![(Pos(1., 2., 3.),), (Pos(4., 5., 6.),)])
+        .to_vec();
+
+    let pos_id = legion::storage::ComponentTypeId::of::<Pos>();
+    let rot_id = legion::storage::ComponentTypeId::of::<Rot>();
+
+    {
+        let (_borrow, ptr, size) = world.get_component_raw(entities[1], pos_id).unwrap();
+        assert_eq!(std::mem::size_of::<Pos>(), size);
+        assert_eq!(Pos(4., 5., 6.), unsafe { *(ptr as *const Pos) });
+    }
+
+    assert!(world.get_component_raw(entities[0], rot_id).is_none());
+
+    world.delete(entities[0]);
+    assert!(world.get_component_raw(entities[0], pos_id).is_none());
+}
+
+#[test]
+fn add_remove_component_raw() {
+    use legion::storage::ComponentMeta;
+    use legion::storage::ComponentTypeId;
+    use std::ptr::NonNull;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let entity = world.insert((), vec![(Pos(1., 2., 3.),)])[0];
+    let string_id = ComponentTypeId::of::<String>();
+
+    let mut value = std::mem::ManuallyDrop::new("added".to_string());
+    unsafe {
+        world.add_component_raw(
+            entity,
+            string_id,
+            ComponentMeta::of::<String>(),
+            NonNull::from(&mut *value).cast(),
+        );
+    }
+    assert_eq!("added", *world.get_component::<String>(entity).unwrap());
+    assert_eq!(Pos(1., 2., 3.), *world.get_component::<Pos>(entity).unwrap());
+
+    let mut value = std::mem::ManuallyDrop::new("replaced".to_string());
+    unsafe {
+        world.add_component_raw(
+            entity,
+            string_id,
+            ComponentMeta::of::<String>(),
+            NonNull::from(&mut *value).cast(),
+        );
+    }
+    assert_eq!("replaced", *world.get_component::<String>(entity).unwrap());
+
+    assert!(world.remove_component_raw(entity, string_id));
+    assert!(!world.remove_component_raw(entity, string_id));
+    assert!(world.get_component::<String>(entity).is_none());
+    assert_eq!(Pos(1., 2., 3.), *world.get_component::<Pos>(entity).unwrap());
+}
+
+#[test]
+fn spawn_builder() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let a = world
+        .spawn()
+        .with(Pos(1., 2., 3.))
+        .with(Rot(0.1, 0.2, 0.3))
+        .with(Scale(1., 1., 1.))
+        .with(Vel(0., 1., 0.))
+        .with(Accel(0., 0., 1.))
+        .with("named".to_string())
+        .with_tag(Model(1))
+        .with_tag(Static)
+        .build();
+    let b = world.spawn().with(Pos(4., 5., 6.)).with(Pos(7., 8., 9.)).build();
+
+    assert_eq!(Pos(1., 2., 3.), *world.get_component::<Pos>(a).unwrap());
+    assert_eq!(Accel(0., 0., 1.), *world.get_component::<Accel>(a).unwrap());
+    assert_eq!("named", *world.get_component::<String>(a).unwrap());
+    assert_eq!(Some(&Model(1)), world.get_tag::<Model>(a));
+    assert_eq!(Some(&Static), world.get_tag::<Static>(a));
+
+    assert_eq!(Pos(7., 8., 9.), *world.get_component::<Pos>(b).unwrap());
+    assert!(world.get_component::<Rot>(b).is_none());
+}
+
+#[test]
+fn delete_all() {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) { self.0.fetch_add(1, Ordering::SeqCst); }
+    }
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let doomed = world
+        .insert(
+            (Model(1),),
+            (0..3000).map(|_| (Counted(drops.clone()), Pos(0., 0., 0.))),
+        )
+        .to_vec();
+    let kept = world
+        .insert(
+            (Model(2),),
+            (0..10).map(|_| (Counted(drops.clone()), Pos(1., 0., 0.))),
+        )
+        .to_vec();
+
+    assert_eq!(3000, world.delete_all(tag_value(&Model(1))));
+    assert_eq!(3000, drops.load(Ordering::SeqCst));
+    assert!(doomed.iter().all(|e| !world.is_alive(*e)));
+    assert!(kept.iter().all(|e| world.is_alive(*e)));
+
+    let mut query = Read::<Pos>::query();
+    assert_eq!(10, query.iter(&mut world).count());
+    for e in &kept {
+        assert_eq!(Pos(1., 0., 0.), *world.get_component::<Pos>(*e).unwrap());
+    }
+
+    assert_eq!(0, world.delete_all(tag_value(&Model(1))));
+    drop(world);
+    assert_eq!(3010, drops.load(Ordering::SeqCst));
+}
+
+#[test]
+fn delete_all_entities() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    for deterministic in &[false, true] {
+        let universe = Universe::new();
+        let mut world = universe.create_world();
+        world.set_deterministic_order(*deterministic);
+
+        let mut entities = world
+            .insert(
+                (Model(1),),
+                (0..3000).map(|i| (Pos(i as f32, 0., 0.),)),
+            )
+            .to_vec();
+        entities.extend(
+            world
+                .insert((Model(2),), (0..10).map(|i| (Pos(i as f32, 1., 0.),)))
+                .iter(),
+        );
+
+        let dead = entities[5];
+        assert!(world.delete(dead));
+
+        let doomed = entities
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(i, _)| i % 3 == 0)
+            .map(|(_, e)| e)
+            .collect::<Vec<_>>();
+        let mut request = doomed.clone();
+        request.push(dead);
+        request.push(doomed[0]);
+        request.reverse();
+
+        assert_eq!(doomed.len(), world.delete_all_entities(&request));
+
+        for (i, e) in entities.iter().enumerate() {
+            let alive = i % 3 != 0 && *e != dead;
+            assert_eq!(alive, world.is_alive(*e));
+            if alive {
+                let y = if i < 3000 { 0. } else { 1. };
+                let x = if i < 3000 { i } else { i - 3000 };
+                assert_eq!(
+                    Pos(x as f32, y, 0.),
+                    *world.get_component::<Pos>(*e).unwrap()
+                );
+            }
+        }
+
+        let mut query = Read::<Pos>::query();
+        assert_eq!(
+            entities.len() - doomed.len() - 1,
+            query.iter(&mut world).count()
+        );
+    }
+}
+
+#[test]
+fn maintain() {
+    #[cfg(feature = "events")]
+    use legion::event::WorldEvent;
+    use legion::world::DefragPolicy;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.track_removed::<Pos>();
+    world.set_defrag_policy(DefragPolicy::Full);
+    #[cfg(feature = "events")]
+    let listener = world.world_channel().bind_listener(4);
+
+    let entities = world
+        .insert((), (0..10).map(|i| (Pos(i as f32, 0., 0.),)))
+        .to_vec();
+
+    let commands = CommandBuffer::default();
+    commands.delete(entities[0]);
+    commands.add_component(entities[1], Vel(1., 0., 0.));
+    world.queue_commands(commands);
+    assert!(world.is_alive(entities[0]));
+
+    let before = world.maintained_version();
+    world.maintain();
+    assert!(world.maintained_version() > before);
+    assert!(!world.is_alive(entities[0]));
+    assert_eq!(Vel(1., 0., 0.), *world.get_component::<Vel>(entities[1]).unwrap());
+    assert_eq!(vec![entities[0]], world.removed::<Pos>().collect::<Vec<_>>());
+    #[cfg(feature = "events")]
+    assert_eq!(
+        Some(WorldEvent::Maintained(world.id())),
+        world.world_channel().read(listener)
+    );
+
+    // removals made by flushed commands are visible until the following update
+    world.maintain();
+    assert_eq!(0, world.removed::<Pos>().count());
+
+    for (i, e) in entities.iter().enumerate().skip(1) {
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component::<Pos>(*e).unwrap());
+    }
+}
+
+#[test]
+fn archetype_user_data() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    struct Batch(u32);
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    world.insert((Model(1),), vec![(Pos(1., 0., 0.),)]);
+    world.insert((Model(2),), vec![(Pos(2., 0., 0.),)]);
+
+    let mut query = Read::<Pos>::query();
+    let archetype = query
+        .iter_chunks(&mut world)
+        .next()
+        .unwrap()
+        .id()
+        .archetype_id();
+
+    assert!(world
+        .set_archetype_user_data(archetype, Box::new(Batch(1)))
+        .is_none());
+    world.set_chunkset_user_data(archetype, 1, Box::new(Batch(20)));
+    world
+        .archetype_user_data_mut(archetype)
+        .unwrap()
+        .downcast_mut::<Batch>()
+        .unwrap()
+        .0 += 1;
+
+    let mut seen = Vec::new();
+    for chunk in query.iter_chunks(&mut world) {
+        let batch = chunk.archetype().user_data().unwrap();
+        assert_eq!(2, batch.downcast_ref::<Batch>().unwrap().0);
+        let set = chunk
+            .chunkset()
+            .user_data()
+            .map(|data| data.downcast_ref::<Batch>().unwrap().0);
+        seen.push((chunk.tag::<Model>().unwrap().0, set));
+    }
+    seen.sort();
+    assert_eq!(vec![(1, None), (2, Some(20))], seen);
+
+    let previous = world.set_archetype_user_data(archetype, Box::new(Batch(3)));
+    assert_eq!(2, previous.unwrap().downcast_ref::<Batch>().unwrap().0);
+    assert!(world.chunkset_user_data_mut(archetype, 0).is_none());
+    assert!(world.chunkset_user_data_mut(archetype, 5).is_none());
+}
+
+#[test]
+fn drain_new_and_freed_chunks() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    assert!(world.drain_new_chunks().is_empty());
+    assert!(world.drain_freed_chunks().is_empty());
+
+    let a = world.insert((Model(1),), vec![(Pos(1., 0., 0.),)])[0];
+    let b = world.insert((Model(2),), vec![(Pos(2., 0., 0.),)])[0];
+
+    let created = world.drain_new_chunks();
+    assert_eq!(2, created.len());
+    assert_eq!(&[a], world.chunk(created[0]).unwrap().entities());
+    assert_eq!(&[b], world.chunk(created[1]).unwrap().entities());
+    assert!(world.drain_new_chunks().is_empty());
+    assert!(world.drain_freed_chunks().is_empty());
+
+    world.delete(a);
+    assert_eq!(vec![created[0]], world.drain_freed_chunks());
+    assert!(world.drain_freed_chunks().is_empty());
+    assert!(world.drain_new_chunks().is_empty());
+
+    // a chunk which has been reported as freed is reported as new again once reallocated
+    let c = world.insert((Model(1),), vec![(Pos(3., 0., 0.),)])[0];
+    assert_eq!(vec![created[0]], world.drain_new_chunks());
+    assert_eq!(&[c], world.chunk(created[0]).unwrap().entities());
+
+    let other = universe.create_world();
+    assert!(other.chunk(created[0]).is_none());
+}
+
+#[test]
+fn entity_archetype() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let a = world.insert((Model(1),), vec![(Pos(1., 0., 0.),)])[0];
+    let b = world.insert((Model(2),), vec![(Pos(2., 0., 0.),)])[0];
+    let c = world.insert((Model(1),), vec![(Pos(3., 0., 0.), Rot(0., 0., 0.))])[0];
+
+    let (archetype_a, set_a, chunk_a) = world.entity_archetype(a).unwrap();
+    let (archetype_b, set_b, chunk_b) = world.entity_archetype(b).unwrap();
+    let (archetype_c, _, _) = world.entity_archetype(c).unwrap();
+    assert_eq!(archetype_a, archetype_b);
+    assert_ne!(set_a, set_b);
+    assert_ne!(chunk_a, chunk_b);
+    assert_ne!(archetype_a, archetype_c);
+    assert_eq!(&[a], world.chunk(chunk_a).unwrap().entities());
+
+    let description = world.entity_description(c).unwrap();
+    assert!(description.has_component::<Pos>());
+    assert!(description.has_component::<Rot>());
+    assert!(!description.has_component::<Vel>());
+    assert!(description.has_tag::<Model>());
+    assert!(!description.has_tag::<Static>());
+
+    // moving an entity between archetypes is reflected in its location
+    world.add_component(a, Rot(0., 0., 0.));
+    assert_eq!(archetype_c, world.entity_archetype(a).unwrap().0);
+
+    world.delete(b);
+    assert_eq!(None, world.entity_archetype(b));
+    assert!(world.entity_description(b).is_none());
+}
+
+#[test]
+fn replace_with() {
+    use legion::world::DefragPolicy;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.set_defrag_policy(DefragPolicy::Full);
+    let old = world.insert((Model(1),), vec![(Pos(1., 0., 0.),)])[0];
+    let reader = world.change_reader("render");
+
+    let mut level = universe.create_world();
+    let new = level.insert((Model(2),), vec![(Pos(2., 0., 0.), Rot(0., 0., 0.))])[0];
+
+    let mut previous = world.replace_with(level);
+    assert!(!world.is_alive(old));
+    assert!(world.is_alive(new));
+    assert_eq!(DefragPolicy::Full, world.defrag_policy());
+    assert!(world.get_component_changed::<Pos>(new, &reader));
+
+    // archetype and chunk IDs belong to the world which now holds them
+    let (archetype, _, chunk) = world.entity_archetype(new).unwrap();
+    assert!(world.archetype(archetype).is_some());
+    assert_eq!(&[new], world.chunk(chunk).unwrap().entities());
+
+    assert!(previous.is_alive(old));
+    assert!(!previous.is_alive(new));
+    assert_eq!(Pos(1., 0., 0.), *previous.get_component::<Pos>(old).unwrap());
+
+    // both worlds continue to function after the swap
+    world.delete(new);
+    previous.insert((Model(1),), vec![(Pos(3., 0., 0.),)]);
+    let mut query = Read::<Pos>::query();
+    assert_eq!(0, query.iter(&mut world).count());
+    assert_eq!(2, query.iter(&mut previous).count());
+}
+
+#[test]
+#[should_panic(expected = "different universe")]
+fn replace_with_foreign_world() {
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.replace_with(Universe::new().create_world());
+}
+
+#[test]
+fn on_relocated() {
+    use std::sync::{Arc, Mutex};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let sink = batches.clone();
+    world.on_relocated(move |relocations| sink.lock().unwrap().push(relocations.to_vec()));
+
+    let entities = world
+        .insert((), vec![(Pos(0., 0., 0.), Rot(0., 0., 0.)); 4])
+        .to_vec();
+    world.maintain();
+    assert!(batches.lock().unwrap().is_empty());
+
+    // entities[3] is swapped into the deleted entity's slot, then moved again
+    world.delete(entities[0]);
+    world.add_component(entities[3], Vel(0., 0., 0.));
+    world.add_component(entities[1], Vel(0., 0., 0.));
+    world.delete(entities[1]);
+    assert!(batches.lock().unwrap().is_empty());
+
+    world.maintain();
+    let batches = std::mem::take(&mut *batches.lock().unwrap());
+    assert_eq!(1, batches.len());
+    let moved = batches[0].iter().map(|r| r.entity).collect::<Vec<_>>();
+    assert_eq!(vec![entities[2], entities[3]], moved);
+
+    for relocation in &batches[0] {
+        let chunk = world.entity_archetype(relocation.entity).unwrap().2;
+        assert_eq!(chunk, relocation.chunk);
+    }
+    assert_eq!(0, batches[0][0].index);
+    assert_eq!(0, batches[0][1].index);
+}
+
+#[test]
+fn defrag_report() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+
+    let first = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    let (_, _, chunk) = world.entity_archetype(first).unwrap();
+    let capacity = world.chunk(chunk).unwrap().capacity();
+
+    // fill the first chunk, spill four entities into a second, then open four gaps
+    let entities = world
+        .insert((), vec![(Pos(0., 0., 0.),); capacity + 3])
+        .to_vec();
+    world.delete(first);
+    for entity in &entities[..3] {
+        world.delete(*entity);
+    }
+
+    let report = world.defrag(Some(2));
+    assert_eq!(2, report.entities_moved);
+    assert_eq!(0, report.chunks_freed);
+    assert_eq!(0, report.archetypes_completed);
+    assert!(!report.finished);
+
+    let report = world.defrag(Some(10));
+    assert_eq!(2, report.entities_moved);
+    assert_eq!(1, report.chunks_freed);
+    assert_eq!(1, report.archetypes_completed);
+    assert!(report.finished);
+
+    let report = world.defrag(None);
+    assert_eq!(0, report.entities_moved);
+    assert!(report.finished);
+}
+
+#[test]
+fn small_chunks() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.set_small_chunk_capacity(Some(4));
+
+    let mut entities = world
+        .insert(
+            (Model(0),),
+            (0..3).map(|i| (Pos(i as f32, 0., 0.), format!("{}", i))),
+        )
+        .to_vec();
+    let (_, _, chunk) = world.entity_archetype(entities[0]).unwrap();
+    assert_eq!(4, world.chunk(chunk).unwrap().capacity());
+
+    // exceeding the small capacity grows the first chunk rather than creating another
+    entities.extend(
+        world
+            .insert(
+                (Model(0),),
+                (3..10).map(|i| (Pos(i as f32, 0., 0.), format!("{}", i))),
+            )
+            .iter(),
+    );
+    assert!(world.chunk(chunk).unwrap().capacity() > 4);
+    for (i, entity) in entities.iter().enumerate() {
+        assert_eq!(chunk, world.entity_archetype(*entity).unwrap().2);
+        assert_eq!(Pos(i as f32, 0., 0.), *world.get_component(*entity).unwrap());
+        assert_eq!(format!("{}", i), *world.get_component::<String>(*entity).unwrap());
+    }
+
+    // archetypes created before the capacity is changed are unaffected
+    world.set_small_chunk_capacity(None);
+    let other = world.insert((Model(1),), vec![(Pos(0., 0., 0.), String::new())])[0];
+    let (_, _, other_chunk) = world.entity_archetype(other).unwrap();
+    assert_eq!(4, world.chunk(other_chunk).unwrap().capacity());
+
+    let full = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    let (_, _, full_chunk) = world.entity_archetype(full).unwrap();
+    assert!(world.chunk(full_chunk).unwrap().capacity() > 4);
+}
+
+#[test]
+fn try_clone_zero_sized_drop() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct Token;
+
+    impl Drop for Token {
+        fn drop(&mut self) { DROPS.fetch_add(1, Ordering::SeqCst); }
+    }
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    world.insert((), vec![(Pos(0., 0., 0.), Token), (Pos(1., 0., 0.), Token)]);
+    world.insert((), vec![(Static,)]);
+    DROPS.store(0, Ordering::SeqCst);
+
+    // zero sized components are only required to be cloneable if they have drop glue
+    let mut registry = world.registry.clone();
+    registry.register_pod::<Pos>();
+    assert!(world.try_clone(&registry).is_err());
+
+    registry.register_clone::<Token>();
+    let fork = world.try_clone(&registry).unwrap();
+    drop(fork);
+    assert_eq!(2, DROPS.load(Ordering::SeqCst));
+    drop(world);
+    assert_eq!(4, DROPS.load(Ordering::SeqCst));
+}
+
+#[test]
+fn try_clone() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let universe = Universe::new();
+    let mut world = universe.create_world();
+    let entities = world
+        .insert(
+            (Model(1),),
+            (0..4).map(|i| (Pos(i as f32, 0., 0.), format!("{}", i))),
+        )
+        .to_vec();
+
+    let mut registry = world.registry.clone();
+    registry.register_pod::<Pos>();
+    assert!(world.try_clone(&registry).is_err());
+
+    registry.register_clone::<String>();
+    let mut fork = world.try_clone(&registry).unwrap();
+    assert_ne!(world.id(), fork.id());
+    for (i, entity) in entities.iter().enumerate() {
+        assert!(fork.is_alive(*entity));
+        assert_eq!(Pos(i as f32, 0., 0.), *fork.get_component(*entity).unwrap());
+        assert_eq!(format!("{}", i), *fork.get_component::<String>(*entity).unwrap());
+        assert_eq!(Some(&Model(1)), fork.get_tag::<Model>(*entity));
+    }
+
+    // changes to the fork do not affect the original
+    *fork.get_component_mut::<Pos>(entities[0]).unwrap() = Pos(9., 9., 9.);
+    *fork.get_component_mut::<String>(entities[1]).unwrap() = "changed".to_string();
+    fork.delete(entities[2]);
+    let created = fork.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    assert!(!world.is_alive(created));
+    assert!(fork.is_alive(created));
+
+    // both worlds allocate new entities from separate blocks
+    let original = world.insert((), vec![(Pos(0., 0., 0.),)])[0];
+    assert_ne!(created, original);
+    assert!(!fork.is_alive(original));
+    assert_eq!(Pos(0., 0., 0.), *world.get_component(entities[0]).unwrap());
+    assert_eq!("1", *world.get_component::<String>(entities[1]).unwrap());
+    assert!(world.is_alive(entities[2]));
+
+    // dropping the fork does not release the original's entities
+    drop(fork);
+    let other = universe.create_world();
+    assert!(entities.iter().all(|e| world.is_alive(*e)));
+    assert!(entities.iter().all(|e| !other.is_alive(*e)));
+}